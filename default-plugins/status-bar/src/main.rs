@@ -17,8 +17,8 @@ use zellij_tile_utils::{palette_match, style};
 use first_line::first_line;
 use second_line::{
     floating_panes_are_visible, fullscreen_panes_to_hide, keybinds,
-    locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide, system_clipboard_error,
-    text_copied_hint,
+    locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide, pending_key_sequence,
+    system_clipboard_error, text_copied_hint,
 };
 use tip::utils::get_cached_tip_name;
 
@@ -294,6 +294,8 @@ impl State {
             text_copied_hint(&self.mode_info.style.colors, copy_destination)
         } else if self.display_system_clipboard_failure {
             system_clipboard_error(&self.mode_info.style.colors)
+        } else if !self.mode_info.pending_key_sequence.is_empty() {
+            pending_key_sequence(&self.mode_info)
         } else if let Some(active_tab) = active_tab {
             if active_tab.is_fullscreen_active {
                 match self.mode_info.mode {
@@ -112,6 +112,8 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
     use Direction as Dir;
     use actions::SearchDirection as SDir;
     use actions::SearchOption as SOpt;
+    use actions::CopyModeMotion as CMotion;
+    use actions::CopyModeSelection as CSel;
 
     let mut old_keymap = mi.get_mode_keybinds();
     let s = |string: &str| string.to_string();
@@ -219,8 +221,11 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
             action_key_group(&km, &[&[Action::PageScrollDown], &[Action::PageScrollUp]])),
         (s("Scroll half page"), s("Scroll"),
             action_key_group(&km, &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]])),
+        (s("Previous / next prompt"), s("Prompt"),
+            action_key_group(&km, &[&[A::ScrollToPreviousPrompt], &[A::ScrollToNextPrompt]])),
         (s("Edit scrollback in default editor"), s("Edit"),
             action_key(&km, &[Action::EditScrollback, TO_NORMAL])),
+        (s("Copy mode"), s("Copy"), action_key(&km, &[A::SwitchToMode(IM::Copy)])),
         (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::EnterSearch { vec![
         (s("When done"), s("Done"), action_key(&km, &[A::SwitchToMode(IM::Search)])),
@@ -243,6 +248,22 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<Key>)> {
             action_key(&km, &[A::SearchToggleOption(SOpt::Wrap)])),
         (s("Whole words"), s("Whole"),
             action_key(&km, &[A::SearchToggleOption(SOpt::WholeWord)])),
+        (s("Regex"), s("Regex"),
+            action_key(&km, &[A::SearchToggleOption(SOpt::Regex)])),
+        (s("Copy mode"), s("Copy"), action_key(&km, &[A::SwitchToMode(IM::Copy)])),
+    ]} else if mi.mode == IM::Copy { vec![
+        (s("Move"), s("Move"), action_key_group(&km, &[
+            &[A::CopyModeMotion(CMotion::Left)], &[A::CopyModeMotion(CMotion::Down)],
+            &[A::CopyModeMotion(CMotion::Up)], &[A::CopyModeMotion(CMotion::Right)]])),
+        (s("Word"), s("Word"), action_key_group(&km, &[
+            &[A::CopyModeMotion(CMotion::WordForward)], &[A::CopyModeMotion(CMotion::WordBackward)],
+            &[A::CopyModeMotion(CMotion::WordEnd)]])),
+        (s("Select"), s("Select"), action_key_group(&km, &[
+            &[A::CopyModeToggleSelection(CSel::Char)], &[A::CopyModeToggleSelection(CSel::Line)],
+            &[A::CopyModeToggleSelection(CSel::Block)]])),
+        (s("Yank"), s("Yank"), action_key(&km, &[A::CopyModeYank, TO_NORMAL])),
+        (s("Search"), s("Search"),
+            action_key(&km, &[A::SwitchToMode(IM::EnterSearch), A::SearchInput(vec![0])])),
     ]} else if mi.mode == IM::Session { vec![
         (s("Detach"), s("Detach"), action_key(&km, &[Action::Detach])),
         (s("Session Manager"), s("Manager"), action_key(&km, &[A::LaunchOrFocusPlugin(Default::default(), true, true, false, false), TO_NORMAL])), // not entirely accurate
@@ -365,6 +386,25 @@ pub fn text_copied_hint(palette: &Palette, copy_destination: CopyDestination) ->
     }
 }
 
+pub fn pending_key_sequence(mode_info: &ModeInfo) -> LinePart {
+    let palette = mode_info.style.colors;
+    let text_color = palette_match!(match palette.theme_hue {
+        ThemeHue::Dark => palette.white,
+        ThemeHue::Light => palette.black,
+    });
+    let green_color = palette_match!(palette.green);
+    let keys = format!("<{}>", mode_info.pending_key_sequence.join(" "));
+    let waiting = " waiting for the next key...";
+    LinePart {
+        part: format!(
+            " {}{}",
+            Style::new().fg(green_color).bold().paint(&keys),
+            Style::new().fg(text_color).paint(waiting),
+        ),
+        len: 1 + keys.chars().count() + waiting.chars().count(),
+    }
+}
+
 pub fn system_clipboard_error(palette: &Palette) -> LinePart {
     let hint = " Error using the system clipboard.";
     let red_color = palette_match!(palette.red);
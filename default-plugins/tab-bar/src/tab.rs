@@ -33,6 +33,11 @@ pub fn render_tab(
     };
     let background_color = if tab.active {
         palette.green
+    } else if tab.bell_alert {
+        // the alerts of the active tab are shown on the frames of its panes instead
+        palette.red
+    } else if tab.activity_alert || tab.silence_alert {
+        palette.orange
     } else if is_alternate_tab {
         alternate_tab_color
     } else {
@@ -419,6 +419,8 @@ pub(crate) fn start_client(opts: CliArgs) {
                     force_run_commands: false,
                     index: None,
                     options: None,
+                    read_only: false,
+                    follow: None,
                 }));
             } else {
                 opts.command = None;
@@ -475,6 +477,8 @@ pub(crate) fn start_client(opts: CliArgs) {
             force_run_commands,
             index,
             options,
+            read_only,
+            follow,
         })) = opts.command.clone()
         {
             let config_options = match options.as_deref() {
@@ -507,6 +511,20 @@ pub(crate) fn start_client(opts: CliArgs) {
                 }
             };
 
+            let client = if read_only {
+                match client {
+                    ClientInfo::Attach(session_name, config_options) => {
+                        ClientInfo::AttachReadOnly(session_name, config_options, follow)
+                    },
+                    _ => {
+                        eprintln!("A read-only client can only attach to a running session.");
+                        process::exit(1);
+                    },
+                }
+            } else {
+                client
+            };
+
             if let Ok(val) = std::env::var(envs::SESSION_NAME_ENV_KEY) {
                 if val == *client.get_session_name() {
                     panic!("You are trying to attach to the current session (\"{}\"). This is not supported.", val);
@@ -514,7 +532,7 @@ pub(crate) fn start_client(opts: CliArgs) {
             }
 
             let attach_layout = match &client {
-                ClientInfo::Attach(_, _) => None,
+                ClientInfo::Attach(_, _) | ClientInfo::AttachReadOnly(..) => None,
                 ClientInfo::New(_) => Some(layout),
                 ClientInfo::Resurrect(_session_name, layout_to_resurrect) => {
                     Some(layout_to_resurrect.clone())
@@ -575,7 +593,7 @@ pub(crate) fn start_client(opts: CliArgs) {
                                 true,
                             );
                             let attach_layout = match &client {
-                                ClientInfo::Attach(_, _) => None,
+                                ClientInfo::Attach(_, _) | ClientInfo::AttachReadOnly(..) => None,
                                 ClientInfo::New(_) => Some(layout),
                                 ClientInfo::Resurrect(_, resurrection_layout) => {
                                     Some(resurrection_layout.clone())
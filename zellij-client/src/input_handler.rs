@@ -3,14 +3,16 @@ use crate::{
     os_input_output::ClientOsApi, stdin_ansi_parser::AnsiStdinInstruction, ClientId,
     ClientInstruction, CommandIsExecuting, InputInstruction,
 };
+use std::time::{Duration, Instant};
 use zellij_utils::{
-    channels::{Receiver, SenderWithContext, OPENCALLS},
-    data::{InputMode, Key},
+    channels::{Receiver, RecvError, RecvTimeoutError, SenderWithContext, OPENCALLS},
+    data::{InputMode, Key, KeyWithModifier},
     errors::{ContextType, ErrorContext, FatalError},
     input::{
         actions::Action,
-        cast_termwiz_key,
+        cast_termwiz_key_with_modifier,
         config::Config,
+        keybinds::{KeySequenceMatch, Keybinds},
         mouse::{MouseButton, MouseEvent},
         options::Options,
     },
@@ -18,6 +20,8 @@ use zellij_utils::{
     termwiz::input::InputEvent,
 };
 
+const DEFAULT_KEY_SEQUENCE_TIMEOUT_MS: u64 = 1000;
+
 #[derive(Debug, Clone, Copy)]
 enum HeldMouseButton {
     Left,
@@ -45,6 +49,10 @@ struct InputHandler {
     receive_input_instructions: Receiver<(InputInstruction, ErrorContext)>,
     holding_mouse: Option<HeldMouseButton>,
     mouse_mode_active: bool,
+    /// The keys typed so far of a multi-key keybinding (with their raw bytes and whether those
+    /// are encoded with the kitty keyboard protocol), until it completes or is abandoned
+    pending_key_sequence: Vec<(KeyWithModifier, Vec<u8>, bool)>,
+    pending_key_sequence_deadline: Option<Instant>,
 }
 
 impl InputHandler {
@@ -69,6 +77,8 @@ impl InputHandler {
             receive_input_instructions,
             holding_mouse: None,
             mouse_mode_active: false,
+            pending_key_sequence: vec![],
+            pending_key_sequence_deadline: None,
         }
     }
 
@@ -87,19 +97,31 @@ impl InputHandler {
             if self.should_exit {
                 break;
             }
-            match self.receive_input_instructions.recv() {
+            let received = match self.pending_key_sequence_deadline {
+                Some(deadline) => match self.receive_input_instructions.recv_deadline(deadline) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.abandon_key_sequence();
+                        continue;
+                    },
+                    received => received.map_err(|_| RecvError),
+                },
+                None => self.receive_input_instructions.recv(),
+            };
+            match received {
                 Ok((InputInstruction::KeyEvent(input_event, raw_bytes), _error_context)) => {
                     match input_event {
                         InputEvent::Key(key_event) => {
-                            let key = cast_termwiz_key(key_event, &raw_bytes);
-                            self.handle_key(&key, raw_bytes);
+                            let key = cast_termwiz_key_with_modifier(key_event, &raw_bytes);
+                            self.handle_key(key.as_ref(), raw_bytes, false);
                         },
                         InputEvent::Mouse(mouse_event) => {
+                            self.abandon_key_sequence();
                             let mouse_event =
                                 zellij_utils::input::mouse::MouseEvent::from(mouse_event);
                             self.handle_mouse_event(&mouse_event);
                         },
                         InputEvent::Paste(pasted_text) => {
+                            self.abandon_key_sequence();
                             if self.mode == InputMode::Normal || self.mode == InputMode::Locked {
                                 self.dispatch_action(
                                     Action::Write(bracketed_paste_start.clone()),
@@ -136,9 +158,24 @@ impl InputHandler {
                         _ => {},
                     }
                 },
+                Ok((
+                    InputInstruction::KeyWithModifierEvent(key_with_modifier, raw_bytes),
+                    _error_context,
+                )) => {
+                    self.handle_key(Some(&key_with_modifier), raw_bytes, true);
+                },
                 Ok((InputInstruction::SwitchToMode(input_mode), _error_context)) => {
+                    if input_mode != self.mode {
+                        // the keys typed so far are handled in the mode they were typed in
+                        self.abandon_key_sequence();
+                    }
                     self.mode = input_mode;
                 },
+                Ok((InputInstruction::Reconfigure(keybinds, options), _error_context)) => {
+                    // the keys typed so far were typed against the previous keybinds
+                    self.abandon_key_sequence();
+                    self.reconfigure(keybinds, *options);
+                },
                 Ok((
                     InputInstruction::AnsiStdinInstructions(ansi_stdin_instructions),
                     _error_context,
@@ -164,11 +201,144 @@ impl InputHandler {
             }
         }
     }
-    fn handle_key(&mut self, key: &Key, raw_bytes: Vec<u8>) {
-        let keybinds = &self.config.keybinds;
-        for action in
-            keybinds.get_actions_for_key_in_mode_or_default_action(&self.mode, key, raw_bytes)
+    fn reconfigure(&mut self, keybinds: Keybinds, options: Options) {
+        let mouse_mode = options.mouse_mode.unwrap_or(true);
+        if mouse_mode != self.options.mouse_mode.unwrap_or(true) {
+            if mouse_mode {
+                self.os_input.enable_mouse().non_fatal();
+            } else {
+                self.os_input.disable_mouse().non_fatal();
+            }
+            self.mouse_mode_active = mouse_mode;
+        }
+        self.config.keybinds = keybinds;
+        self.options = options;
+    }
+    fn handle_key(
+        &mut self,
+        key_with_modifier: Option<&KeyWithModifier>,
+        raw_bytes: Vec<u8>,
+        is_kitty_keyboard_protocol: bool,
+    ) {
+        let key_with_modifier = match key_with_modifier {
+            Some(key_with_modifier) => *key_with_modifier,
+            None => {
+                self.abandon_key_sequence();
+                let action = self.config.keybinds.default_action_for_mode(
+                    &self.mode,
+                    None,
+                    raw_bytes,
+                    is_kitty_keyboard_protocol,
+                );
+                self.dispatch_actions(vec![action]);
+                return;
+            },
+        };
+        let mut keys: Vec<Key> = self
+            .pending_key_sequence
+            .iter()
+            .map(|(pending_key, ..)| Key::from(*pending_key))
+            .collect();
+        keys.push(Key::from(key_with_modifier));
+        match self.config.keybinds.match_key_sequence(&self.mode, &keys) {
+            KeySequenceMatch::Complete(actions) => {
+                self.clear_key_sequence();
+                self.dispatch_actions(actions);
+            },
+            KeySequenceMatch::Partial
+                if self.pending_key_sequence.is_empty()
+                    && !self.options.prefer_key_sequences.unwrap_or(false)
+                    && self
+                        .config
+                        .keybinds
+                        .get_actions_for_key_in_mode(&self.mode, &keys[0])
+                        .is_some() =>
+            {
+                // the key is also bound on its own, so rather than holding it back until we know
+                // whether a sequence follows, it acts right away
+                let actions = self
+                    .config
+                    .keybinds
+                    .get_actions_for_key_in_mode_or_default_action(
+                        &self.mode,
+                        &key_with_modifier,
+                        raw_bytes,
+                        is_kitty_keyboard_protocol,
+                    );
+                self.dispatch_actions(actions);
+            },
+            KeySequenceMatch::Partial => {
+                let timeout = self
+                    .options
+                    .key_sequence_timeout
+                    .unwrap_or(DEFAULT_KEY_SEQUENCE_TIMEOUT_MS);
+                self.pending_key_sequence.push((
+                    key_with_modifier,
+                    raw_bytes,
+                    is_kitty_keyboard_protocol,
+                ));
+                self.pending_key_sequence_deadline =
+                    Some(Instant::now() + Duration::from_millis(timeout));
+                self.show_pending_key_sequence(keys);
+            },
+            KeySequenceMatch::NoMatch if !self.pending_key_sequence.is_empty() => {
+                // the keys typed so far fall through, and this one might begin a new sequence
+                self.abandon_key_sequence();
+                self.handle_key(
+                    Some(&key_with_modifier),
+                    raw_bytes,
+                    is_kitty_keyboard_protocol,
+                );
+            },
+            KeySequenceMatch::NoMatch => {
+                let actions = self
+                    .config
+                    .keybinds
+                    .get_actions_for_key_in_mode_or_default_action(
+                        &self.mode,
+                        &key_with_modifier,
+                        raw_bytes,
+                        is_kitty_keyboard_protocol,
+                    );
+                self.dispatch_actions(actions);
+            },
+        }
+    }
+    /// Handles the keys typed so far of a multi-key keybinding as if they were typed on their own
+    /// (eg. passing them on to the pane if they are not bound)
+    fn abandon_key_sequence(&mut self) {
+        for (key_with_modifier, raw_bytes, is_kitty_keyboard_protocol) in self.clear_key_sequence()
         {
+            let actions = self
+                .config
+                .keybinds
+                .get_actions_for_key_in_mode_or_default_action(
+                    &self.mode,
+                    &key_with_modifier,
+                    raw_bytes,
+                    is_kitty_keyboard_protocol,
+                );
+            self.dispatch_actions(actions);
+        }
+    }
+    fn clear_key_sequence(&mut self) -> Vec<(KeyWithModifier, Vec<u8>, bool)> {
+        self.pending_key_sequence_deadline = None;
+        let pending_key_sequence = std::mem::take(&mut self.pending_key_sequence);
+        if !pending_key_sequence.is_empty() {
+            // this has to happen before dispatching any actions, lest it undo a mode switch
+            self.show_pending_key_sequence(vec![]);
+        }
+        pending_key_sequence
+    }
+    fn show_pending_key_sequence(&mut self, keys: Vec<Key>) {
+        self.os_input.send_to_server(ClientToServerMsg::Action(
+            Action::PendingKeySequence(self.mode, keys),
+            None,
+            None,
+        ));
+    }
+    fn dispatch_actions(&mut self, actions: Vec<Action>) {
+        for action in actions {
             let should_exit = self.dispatch_action(action, None);
             if should_exit {
                 self.should_exit = true;
@@ -202,6 +372,10 @@ impl InputHandler {
                     .send(ClientInstruction::SetSynchronizedOutput(enabled))
                     .unwrap();
             },
+            AnsiStdinInstruction::KittyGraphicsSupport => {
+                self.os_input
+                    .send_to_server(ClientToServerMsg::TerminalSupportsKittyGraphics);
+            },
         }
     }
     fn handle_mouse_event(&mut self, mouse_event: &MouseEvent) {
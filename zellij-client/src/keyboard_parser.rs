@@ -0,0 +1,144 @@
+//! Keys as reported by terminal emulators that support the kitty keyboard protocol (or CSI-u),
+//! which termwiz does not understand.
+//!
+//! See: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+
+use zellij_utils::data::{BareKey, KeyModifiers, KeyWithModifier};
+
+// pushed on startup, this asks the terminal to "disambiguate escape codes" - ie. to send CSI u
+// sequences for keys that have no unambiguous legacy encoding (eg. Ctrl Shift t or Esc), the
+// terminal ignores it if it does not support the protocol
+pub const ENABLE_KITTY_KEYBOARD_PROTOCOL: &str = "\u{1b}[>1u";
+pub const DISABLE_KITTY_KEYBOARD_PROTOCOL: &str = "\u{1b}[<1u";
+
+/// A chunk of what was read from STDIN, see `split_kitty_keys`
+#[derive(Debug, PartialEq)]
+pub enum StdinChunk<'a> {
+    KittyKey(KeyWithModifier, &'a [u8]), // the raw bytes of the key
+    Other(&'a [u8]),
+}
+
+/// Splits `bytes` into the kitty keys they contain and the input around them, which is left for
+/// termwiz to parse. A single read can hold several keys (eg. when typing fast or pasting).
+pub fn split_kitty_keys(bytes: &[u8]) -> Vec<StdinChunk> {
+    let mut chunks = vec![];
+    let mut other_start = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        if let Some(sequence_len) = csi_sequence_len(&bytes[index..]) {
+            let sequence = &bytes[index..index + sequence_len];
+            if let Some(key_with_modifier) = parse_kitty_key(sequence) {
+                if other_start < index {
+                    chunks.push(StdinChunk::Other(&bytes[other_start..index]));
+                }
+                chunks.push(StdinChunk::KittyKey(key_with_modifier, sequence));
+                other_start = index + sequence_len;
+            }
+            index += sequence_len;
+        } else {
+            index += 1;
+        }
+    }
+    if other_start < bytes.len() {
+        chunks.push(StdinChunk::Other(&bytes[other_start..]));
+    }
+    chunks
+}
+
+// the length of the CSI sequence at the start of `bytes`, if there is a complete one:
+// ESC [, parameter bytes (0x30-0x3f), intermediate bytes (0x20-0x2f) and a final byte (0x40-0x7e)
+fn csi_sequence_len(bytes: &[u8]) -> Option<usize> {
+    if !bytes.starts_with(b"\x1b[") {
+        return None;
+    }
+    let body_len = bytes[2..]
+        .iter()
+        .position(|byte| !(0x20..=0x3f).contains(byte))?;
+    let final_byte = bytes[2 + body_len];
+    if (0x40..=0x7e).contains(&final_byte) {
+        Some(2 + body_len + 1)
+    } else {
+        None
+    }
+}
+
+/// Parses `bytes` if they are exactly one key in one of the forms the kitty keyboard protocol
+/// uses: `CSI code[:alternates] [; modifiers[:event]] [; text] u`, `CSI 1 ; modifiers {ABCDFHPQS}`
+/// or `CSI number [; modifiers] ~`. Anything else is left for termwiz to parse.
+pub fn parse_kitty_key(bytes: &[u8]) -> Option<KeyWithModifier> {
+    let sequence = std::str::from_utf8(bytes).ok()?.strip_prefix("\u{1b}[")?;
+    let terminator = sequence.chars().last()?;
+    let params: Vec<&str> = sequence[..sequence.len() - terminator.len_utf8()]
+        .split(';')
+        .collect();
+    let number = first_sub_param(params.get(0))?;
+    let key_modifiers = first_sub_param(params.get(1))?
+        .map(|encoded| KeyModifiers::from_bits_truncate(encoded.saturating_sub(1) as u8))
+        .unwrap_or(KeyModifiers::NONE);
+    let bare_key = match terminator {
+        'u' => bare_key_from_codepoint(number?)?,
+        '~' => match number? {
+            2 => BareKey::Insert,
+            3 => BareKey::Delete,
+            5 => BareKey::PageUp,
+            6 => BareKey::PageDown,
+            7 => BareKey::Home,
+            8 => BareKey::End,
+            11 => BareKey::F(1),
+            12 => BareKey::F(2),
+            13 => BareKey::F(3),
+            14 => BareKey::F(4),
+            15 => BareKey::F(5),
+            17 => BareKey::F(6),
+            18 => BareKey::F(7),
+            19 => BareKey::F(8),
+            20 => BareKey::F(9),
+            21 => BareKey::F(10),
+            23 => BareKey::F(11),
+            24 => BareKey::F(12),
+            _ => return None,
+        },
+        'A' => BareKey::Up,
+        'B' => BareKey::Down,
+        'C' => BareKey::Right,
+        'D' => BareKey::Left,
+        'F' => BareKey::End,
+        'H' => BareKey::Home,
+        'P' => BareKey::F(1),
+        'Q' => BareKey::F(2),
+        // F3 is only sent as CSI 13 ~ because CSI R is the cursor position report
+        'S' => BareKey::F(4),
+        _ => return None,
+    };
+    Some(KeyWithModifier::new(bare_key, key_modifiers))
+}
+
+// we only need the first sub-parameter of each parameter: the key code (not its shifted or base
+// layout alternates) and the modifiers (not the event type)
+// returns None if the parameter is malformed and Some(None) if it is absent
+fn first_sub_param(param: Option<&&str>) -> Option<Option<u32>> {
+    let first_sub_param = param
+        .and_then(|param| param.split(':').next())
+        .unwrap_or("");
+    if first_sub_param.is_empty() {
+        Some(None)
+    } else {
+        first_sub_param.parse().ok().map(Some)
+    }
+}
+
+fn bare_key_from_codepoint(codepoint: u32) -> Option<BareKey> {
+    match codepoint {
+        9 => Some(BareKey::Tab),
+        13 => Some(BareKey::Enter),
+        27 => Some(BareKey::Esc),
+        127 => Some(BareKey::Backspace),
+        // the private use area is where the protocol places keys such as the keypad or media keys
+        0..=31 | 57344..=63743 => None,
+        _ => char::from_u32(codepoint).map(BareKey::Char),
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/keyboard_parser_tests.rs"]
+mod keyboard_parser_tests;
@@ -3,6 +3,7 @@ pub mod os_input_output;
 pub mod cli_client;
 mod command_is_executing;
 mod input_handler;
+mod keyboard_parser;
 pub mod old_config_converter;
 mod stdin_ansi_parser;
 mod stdin_handler;
@@ -18,17 +19,21 @@ use zellij_utils::errors::FatalError;
 
 use crate::stdin_ansi_parser::{AnsiStdinInstruction, StdinAnsiParser, SyncOutput};
 use crate::{
-    command_is_executing::CommandIsExecuting, input_handler::input_loop,
-    os_input_output::ClientOsApi, stdin_handler::stdin_loop,
+    command_is_executing::CommandIsExecuting,
+    input_handler::input_loop,
+    keyboard_parser::{DISABLE_KITTY_KEYBOARD_PROTOCOL, ENABLE_KITTY_KEYBOARD_PROTOCOL},
+    os_input_output::ClientOsApi,
+    stdin_handler::stdin_loop,
 };
 use zellij_utils::{
     channels::{self, ChannelWithContext, SenderWithContext},
     consts::{set_permissions, ZELLIJ_SOCK_DIR},
-    data::{ClientId, ConnectToSession, InputMode, Style},
+    data::{ClientId, ConnectToSession, InputMode, KeyWithModifier, Style},
     envs,
     errors::{ClientContext, ContextType, ErrorInstruction},
-    input::{config::Config, options::Options},
-    ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg},
+    input::{config::Config, keybinds::Keybinds, options::Options},
+    ipc::{ClientAttributes, ClientToServerMsg, ColorDepth, ExitReason, ServerToClientMsg},
+    termwiz::caps::{Capabilities, ColorLevel},
     termwiz::input::InputEvent,
 };
 use zellij_utils::{cli::CliArgs, input::layout::Layout};
@@ -51,6 +56,7 @@ pub(crate) enum ClientInstruction {
     SetSynchronizedOutput(Option<SyncOutput>),
     UnblockCliPipeInput(String),   // String -> pipe name
     CliPipeOutput(String, String), // String -> pipe name, String -> output
+    Reconfigure(Keybinds, Box<Options>),
 }
 
 impl From<ServerToClientMsg> for ClientInstruction {
@@ -75,6 +81,9 @@ impl From<ServerToClientMsg> for ClientInstruction {
             ServerToClientMsg::CliPipeOutput(pipe_name, output) => {
                 ClientInstruction::CliPipeOutput(pipe_name, output)
             },
+            ServerToClientMsg::Reconfigure(keybinds, config_options) => {
+                ClientInstruction::Reconfigure(keybinds, config_options)
+            },
         }
     }
 }
@@ -97,6 +106,7 @@ impl From<&ClientInstruction> for ClientContext {
             ClientInstruction::SetSynchronizedOutput(..) => ClientContext::SetSynchronisedOutput,
             ClientInstruction::UnblockCliPipeInput(..) => ClientContext::UnblockCliPipeInput,
             ClientInstruction::CliPipeOutput(..) => ClientContext::CliPipeOutput,
+            ClientInstruction::Reconfigure(..) => ClientContext::Reconfigure,
         }
     }
 }
@@ -131,6 +141,7 @@ fn spawn_server(socket_path: &Path, debug: bool) -> io::Result<()> {
 #[derive(Debug, Clone)]
 pub enum ClientInfo {
     Attach(String, Options),
+    AttachReadOnly(String, Options, Option<ClientId>), // ClientId is the client to follow
     New(String),
     Resurrect(String, Layout),
 }
@@ -139,6 +150,7 @@ impl ClientInfo {
     pub fn get_session_name(&self) -> &str {
         match self {
             Self::Attach(ref name, _) => name,
+            Self::AttachReadOnly(ref name, _, _) => name,
             Self::New(ref name) => name,
             Self::Resurrect(ref name, _) => name,
         }
@@ -148,13 +160,32 @@ impl ClientInfo {
 #[derive(Debug, Clone)]
 pub(crate) enum InputInstruction {
     KeyEvent(InputEvent, Vec<u8>),
+    KeyWithModifierEvent(KeyWithModifier, Vec<u8>), // parsed from the kitty keyboard protocol
     SwitchToMode(InputMode),
+    Reconfigure(Keybinds, Box<Options>),
     AnsiStdinInstructions(Vec<AnsiStdinInstruction>),
     StartedParsing,
     DoneParsing,
     Exit,
 }
 
+/// Reads how many colours the terminal supports from COLORTERM and its terminfo entry, assuming
+/// truecolor if we can't tell so as not to dull the colours of terminals that do support it (the
+/// color_depth option overrides this for terminals that get it wrong)
+fn detect_color_depth() -> ColorDepth {
+    match Capabilities::new_from_env() {
+        Ok(capabilities) => match capabilities.color_level() {
+            ColorLevel::TrueColor => ColorDepth::TrueColor,
+            ColorLevel::TwoFiftySix => ColorDepth::Indexed256,
+            ColorLevel::Sixteen | ColorLevel::MonoChrome => ColorDepth::Ansi16,
+        },
+        Err(e) => {
+            log::error!("Failed to detect terminal color depth: {}", e);
+            ColorDepth::TrueColor
+        },
+    }
+}
+
 pub fn start_client(
     mut os_input: Box<dyn ClientOsApi>,
     opts: CliArgs,
@@ -203,6 +234,9 @@ pub fn start_client(
             hide_session_name: config.ui.pane_frames.hide_session_name,
         },
         keybinds: config.keybinds.clone(),
+        color_depth: config_options
+            .color_depth
+            .unwrap_or_else(detect_color_depth),
     };
 
     let create_ipc_pipe = || -> std::path::PathBuf {
@@ -225,6 +259,25 @@ pub fn start_client(
                     config_options,
                     tab_position_to_focus,
                     pane_id_to_focus,
+                    false,
+                    None,
+                ),
+                ipc_pipe,
+            )
+        },
+        ClientInfo::AttachReadOnly(name, config_options, client_to_follow) => {
+            envs::set_session_name(name.clone());
+            os_input.update_session_name(name);
+            let ipc_pipe = create_ipc_pipe();
+
+            (
+                ClientToServerMsg::AttachClient(
+                    client_attributes,
+                    config_options,
+                    tab_position_to_focus,
+                    pane_id_to_focus,
+                    true,
+                    client_to_follow,
                 ),
                 ipc_pipe,
             )
@@ -259,6 +312,17 @@ pub fn start_client(
         .get_stdout_writer()
         .write(bracketed_paste.as_bytes())
         .unwrap();
+    let support_kitty_keyboard_protocol = config_options
+        .support_kitty_keyboard_protocol
+        .unwrap_or(true);
+    if support_kitty_keyboard_protocol {
+        if let Err(e) = os_input
+            .get_stdout_writer()
+            .write(ENABLE_KITTY_KEYBOARD_PROTOCOL.as_bytes())
+        {
+            log::error!("Failed to enable the kitty keyboard protocol: {}", e);
+        }
+    }
 
     let (send_client_instructions, receive_client_instructions): ChannelWithContext<
         ClientInstruction,
@@ -381,6 +445,11 @@ pub fn start_client(
         let goto_start_of_last_line = format!("\u{1b}[{};{}H", full_screen_ws.rows, 1);
         let restore_snapshot = "\u{1b}[?1049l";
         os_input.disable_mouse().non_fatal();
+        if support_kitty_keyboard_protocol {
+            let _ = os_input
+                .get_stdout_writer()
+                .write(DISABLE_KITTY_KEYBOARD_PROTOCOL.as_bytes());
+        }
         let error = format!(
             "{}\n{}{}\n",
             restore_snapshot, goto_start_of_last_line, backtrace
@@ -481,6 +550,11 @@ pub fn start_client(
                     .send(InputInstruction::SwitchToMode(input_mode))
                     .unwrap();
             },
+            ClientInstruction::Reconfigure(keybinds, config_options) => {
+                send_input_instructions
+                    .send(InputInstruction::Reconfigure(keybinds, config_options))
+                    .unwrap();
+            },
             ClientInstruction::Log(lines_to_log) => {
                 for line in lines_to_log {
                     log::info!("{line}");
@@ -505,6 +579,14 @@ pub fn start_client(
 
     router_thread.join().unwrap();
 
+    if support_kitty_keyboard_protocol {
+        // we push the flags again when reconnecting to another session
+        let mut stdout = os_input.get_stdout_writer();
+        if let Err(e) = stdout.write(DISABLE_KITTY_KEYBOARD_PROTOCOL.as_bytes()) {
+            log::error!("Failed to disable the kitty keyboard protocol: {}", e);
+        }
+    }
+
     if reconnect_to_session.is_none() {
         let reset_style = "\u{1b}[m";
         let show_cursor = "\u{1b}[?25h";
@@ -1,6 +1,7 @@
 use std::time::{Duration, Instant};
 
 const STARTUP_PARSE_DEADLINE_MS: u64 = 500;
+const KITTY_GRAPHICS_QUERY: &str = "\u{1b}_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\u{1b}\\";
 use zellij_utils::{
     consts::ZELLIJ_STDIN_CACHE_FILE, ipc::PixelDimensions, lazy_static::lazy_static,
     pane_size::SizeInPixels, regex::Regex,
@@ -64,9 +65,11 @@ impl StdinAnsiParser {
         // <ESC>]11;?<ESC>\ => get background color
         // <ESC>]10;?<ESC>\ => get foreground color
         // <ESC>[?2026$p => get synchronised output mode
+        // <ESC>_Gi=31,...;AAAA<ESC>\ => check for kitty graphics support (with a 1x1 image)
         let mut query_string = String::from(
             "\u{1b}[14t\u{1b}[16t\u{1b}]11;?\u{1b}\u{5c}\u{1b}]10;?\u{1b}\u{5c}\u{1b}[?2026$p",
         );
+        query_string.push_str(KITTY_GRAPHICS_QUERY);
 
         // query colors
         // eg. <ESC>]4;5;?<ESC>\ => query color register number 5
@@ -147,7 +150,14 @@ impl StdinAnsiParser {
             }
         } else if byte == b'\\' {
             self.raw_buffer.push(byte);
-            if let Ok(ansi_sequence) = AnsiStdinInstruction::bg_or_fg_from_bytes(&self.raw_buffer) {
+            if let Some(ansi_sequence) =
+                AnsiStdinInstruction::kitty_graphics_support_from_bytes(&self.raw_buffer)
+            {
+                self.pending_events.push(ansi_sequence);
+                self.raw_buffer.clear();
+            } else if let Ok(ansi_sequence) =
+                AnsiStdinInstruction::bg_or_fg_from_bytes(&self.raw_buffer)
+            {
                 self.pending_events.push(ansi_sequence);
                 self.raw_buffer.clear();
             } else if let Ok((color_register, color_sequence)) =
@@ -180,6 +190,7 @@ pub enum AnsiStdinInstruction {
     ForegroundColor(String),
     ColorRegisters(Vec<(usize, String)>),
     SynchronizedOutput(Option<SyncOutput>),
+    KittyGraphicsSupport,
 }
 
 impl AnsiStdinInstruction {
@@ -281,6 +292,18 @@ impl AnsiStdinInstruction {
             None
         }
     }
+    pub fn kitty_graphics_support_from_bytes(bytes: &[u8]) -> Option<Self> {
+        // eg. <ESC>_Gi=31;OK<ESC>\
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"_Gi=31;OK\u{1b}\\$").unwrap();
+        }
+        let key_string = String::from_utf8_lossy(bytes);
+        if RE.is_match(&key_string) {
+            Some(AnsiStdinInstruction::KittyGraphicsSupport)
+        } else {
+            None
+        }
+    }
 }
 
 fn color_sequence_from_bytes(bytes: &[u8]) -> Result<(usize, String), &'static str> {
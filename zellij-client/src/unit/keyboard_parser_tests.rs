@@ -0,0 +1,107 @@
+use super::{parse_kitty_key, split_kitty_keys, StdinChunk};
+use zellij_utils::data::{BareKey, KeyModifiers, KeyWithModifier};
+
+#[test]
+pub fn parse_csi_u_key_with_modifiers() {
+    assert_eq!(
+        parse_kitty_key("\u{1b}[116;6u".as_bytes()),
+        Some(KeyWithModifier::new(
+            BareKey::Char('t'),
+            KeyModifiers::CTRL | KeyModifiers::SHIFT
+        )),
+        "Ctrl Shift t"
+    );
+    assert_eq!(
+        parse_kitty_key("\u{1b}[13;5u".as_bytes()),
+        Some(KeyWithModifier::new(BareKey::Enter, KeyModifiers::CTRL)),
+        "Ctrl Enter"
+    );
+    assert_eq!(
+        parse_kitty_key("\u{1b}[27u".as_bytes()),
+        Some(KeyWithModifier::new(BareKey::Esc, KeyModifiers::NONE)),
+        "Esc"
+    );
+}
+
+#[test]
+pub fn parse_csi_u_key_ignores_alternates_event_type_and_lock_modifiers() {
+    // Super a, with the shifted alternate key, a press event and Num Lock on
+    assert_eq!(
+        parse_kitty_key("\u{1b}[97:65;137:1u".as_bytes()),
+        Some(KeyWithModifier::new(
+            BareKey::Char('a'),
+            KeyModifiers::SUPER
+        )),
+    );
+}
+
+#[test]
+pub fn parse_functional_keys_with_modifiers() {
+    assert_eq!(
+        parse_kitty_key("\u{1b}[1;3D".as_bytes()),
+        Some(KeyWithModifier::new(BareKey::Left, KeyModifiers::ALT)),
+        "Alt Left"
+    );
+    assert_eq!(
+        parse_kitty_key("\u{1b}[5;5~".as_bytes()),
+        Some(KeyWithModifier::new(BareKey::PageUp, KeyModifiers::CTRL)),
+        "Ctrl PageUp"
+    );
+    assert_eq!(
+        parse_kitty_key("\u{1b}[13;2~".as_bytes()),
+        Some(KeyWithModifier::new(BareKey::F(3), KeyModifiers::SHIFT)),
+        "Shift F3"
+    );
+}
+
+#[test]
+pub fn leave_other_sequences_to_termwiz() {
+    assert_eq!(parse_kitty_key("a".as_bytes()), None, "plain text");
+    assert_eq!(
+        parse_kitty_key("\u{1b}[200~".as_bytes()),
+        None,
+        "bracketed paste"
+    );
+    assert_eq!(
+        parse_kitty_key("\u{1b}[<0;12;5M".as_bytes()),
+        None,
+        "mouse event"
+    );
+    assert_eq!(
+        parse_kitty_key("\u{1b}[116;5u\u{1b}[116;5u".as_bytes()),
+        None,
+        "more than one key"
+    );
+}
+
+#[test]
+pub fn split_several_kitty_keys_read_at_once() {
+    let bytes = "\u{1b}[116;6u\u{1b}[13;5uab\u{1b}[<0;3;4M\u{1b}[27u".as_bytes();
+    assert_eq!(
+        split_kitty_keys(bytes),
+        vec![
+            StdinChunk::KittyKey(
+                KeyWithModifier::new(BareKey::Char('t'), KeyModifiers::CTRL | KeyModifiers::SHIFT),
+                "\u{1b}[116;6u".as_bytes()
+            ),
+            StdinChunk::KittyKey(
+                KeyWithModifier::new(BareKey::Enter, KeyModifiers::CTRL),
+                "\u{1b}[13;5u".as_bytes()
+            ),
+            // the mouse event is not a key, so it is left for termwiz along with what precedes it
+            StdinChunk::Other("ab\u{1b}[<0;3;4M".as_bytes()),
+            StdinChunk::KittyKey(
+                KeyWithModifier::new(BareKey::Esc, KeyModifiers::NONE),
+                "\u{1b}[27u".as_bytes()
+            ),
+        ]
+    );
+}
+
+#[test]
+pub fn split_input_without_kitty_keys() {
+    assert_eq!(
+        split_kitty_keys("abc\u{1b}[".as_bytes()),
+        vec![StdinChunk::Other("abc\u{1b}[".as_bytes())]
+    );
+}
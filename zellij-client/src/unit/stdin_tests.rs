@@ -2,7 +2,9 @@ use super::input_loop;
 use crate::stdin_ansi_parser::StdinAnsiParser;
 use crate::stdin_loop;
 use zellij_utils::anyhow::Result;
-use zellij_utils::data::{Direction, InputMode, Palette};
+use zellij_utils::data::{
+    BareKey, CharOrArrow, Direction, InputMode, Key, KeyModifiers, KeyWithModifier, Palette,
+};
 use zellij_utils::input::actions::Action;
 use zellij_utils::input::config::Config;
 use zellij_utils::input::options::Options;
@@ -318,3 +320,364 @@ pub fn move_focus_left_in_normal_mode() {
         "All actions sent to server properly"
     );
 }
+
+fn config_with_key_sequences() -> Config {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" {
+                    bind "n" { GoToNextTab; }
+                }
+            }
+        }
+    "#;
+    Config::from_kdl(
+        config_contents,
+        Some(Config::from_default_assets().unwrap()),
+    )
+    .unwrap()
+}
+
+#[test]
+pub fn key_sequence_in_normal_mode() {
+    let stdin_events = vec![
+        (
+            vec![1], // ctrl-a
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('a'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+        (
+            vec![110], // n
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('n'),
+                modifiers: Modifiers::NONE,
+            }),
+        ),
+        (
+            commands::QUIT.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+    ];
+
+    let events_sent_to_server = Arc::new(Mutex::new(vec![]));
+    let command_is_executing = CommandIsExecuting::new();
+    let client_os_api = Box::new(FakeClientOsApi::new(
+        events_sent_to_server.clone(),
+        command_is_executing.clone(),
+    ));
+    let config = config_with_key_sequences();
+    let options = Options::default();
+
+    let (send_client_instructions, _receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::bounded(50);
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+
+    let (send_input_instructions, receive_input_instructions): ChannelWithContext<
+        InputInstruction,
+    > = channels::bounded(50);
+    let send_input_instructions = SenderWithContext::new(send_input_instructions);
+    for event in stdin_events {
+        send_input_instructions
+            .send(InputInstruction::KeyEvent(event.1, event.0))
+            .unwrap();
+    }
+
+    let default_mode = InputMode::Normal;
+    input_loop(
+        client_os_api,
+        config,
+        options,
+        command_is_executing,
+        send_client_instructions,
+        default_mode,
+        receive_input_instructions,
+    );
+    let expected_actions_sent_to_server = vec![
+        Action::PendingKeySequence(InputMode::Normal, vec![Key::Ctrl('a')]),
+        Action::PendingKeySequence(InputMode::Normal, vec![]),
+        Action::GoToNextTab,
+        Action::Quit,
+    ];
+    let received_actions = extract_actions_sent_to_server(events_sent_to_server);
+    assert_eq!(
+        expected_actions_sent_to_server, received_actions,
+        "All actions sent to server properly"
+    );
+}
+
+#[test]
+pub fn unmatched_key_sequence_falls_through_to_pane() {
+    let stdin_events = vec![
+        (
+            vec![1], // ctrl-a
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('a'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+        (
+            vec![120], // x
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('x'),
+                modifiers: Modifiers::NONE,
+            }),
+        ),
+        (
+            commands::QUIT.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+    ];
+
+    let events_sent_to_server = Arc::new(Mutex::new(vec![]));
+    let command_is_executing = CommandIsExecuting::new();
+    let client_os_api = Box::new(FakeClientOsApi::new(
+        events_sent_to_server.clone(),
+        command_is_executing.clone(),
+    ));
+    let config = config_with_key_sequences();
+    let options = Options::default();
+
+    let (send_client_instructions, _receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::bounded(50);
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+
+    let (send_input_instructions, receive_input_instructions): ChannelWithContext<
+        InputInstruction,
+    > = channels::bounded(50);
+    let send_input_instructions = SenderWithContext::new(send_input_instructions);
+    for event in stdin_events {
+        send_input_instructions
+            .send(InputInstruction::KeyEvent(event.1, event.0))
+            .unwrap();
+    }
+
+    let default_mode = InputMode::Normal;
+    input_loop(
+        client_os_api,
+        config,
+        options,
+        command_is_executing,
+        send_client_instructions,
+        default_mode,
+        receive_input_instructions,
+    );
+    let expected_actions_sent_to_server = vec![
+        Action::PendingKeySequence(InputMode::Normal, vec![Key::Ctrl('a')]),
+        Action::PendingKeySequence(InputMode::Normal, vec![]),
+        Action::WriteKey(
+            KeyWithModifier::new(BareKey::Char('a'), KeyModifiers::CTRL),
+            vec![1],
+            false,
+        ),
+        Action::WriteKey(
+            KeyWithModifier::new(BareKey::Char('x'), KeyModifiers::NONE),
+            vec![120],
+            false,
+        ),
+        Action::Quit,
+    ];
+    let received_actions = extract_actions_sent_to_server(events_sent_to_server);
+    assert_eq!(
+        expected_actions_sent_to_server, received_actions,
+        "All actions sent to server properly"
+    );
+}
+
+#[test]
+pub fn key_sequence_falls_through_to_pane_after_timeout() {
+    let events_sent_to_server = Arc::new(Mutex::new(vec![]));
+    let command_is_executing = CommandIsExecuting::new();
+    let client_os_api = Box::new(FakeClientOsApi::new(
+        events_sent_to_server.clone(),
+        command_is_executing.clone(),
+    ));
+    let config = config_with_key_sequences();
+    let options = Options {
+        key_sequence_timeout: Some(10),
+        ..Default::default()
+    };
+
+    let (send_client_instructions, _receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::bounded(50);
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+
+    let (send_input_instructions, receive_input_instructions): ChannelWithContext<
+        InputInstruction,
+    > = channels::bounded(50);
+    let send_input_instructions = SenderWithContext::new(send_input_instructions);
+    send_input_instructions
+        .send(InputInstruction::KeyEvent(
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('a'),
+                modifiers: Modifiers::CTRL,
+            }),
+            vec![1], // ctrl-a
+        ))
+        .unwrap();
+    let quit_after_timeout = thread::spawn(move || {
+        thread::sleep(std::time::Duration::from_millis(200));
+        send_input_instructions
+            .send(InputInstruction::KeyEvent(
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('q'),
+                    modifiers: Modifiers::CTRL,
+                }),
+                commands::QUIT.to_vec(),
+            ))
+            .unwrap();
+    });
+
+    let default_mode = InputMode::Normal;
+    input_loop(
+        client_os_api,
+        config,
+        options,
+        command_is_executing,
+        send_client_instructions,
+        default_mode,
+        receive_input_instructions,
+    );
+    quit_after_timeout.join().unwrap();
+    let expected_actions_sent_to_server = vec![
+        Action::PendingKeySequence(InputMode::Normal, vec![Key::Ctrl('a')]),
+        Action::PendingKeySequence(InputMode::Normal, vec![]),
+        Action::WriteKey(
+            KeyWithModifier::new(BareKey::Char('a'), KeyModifiers::CTRL),
+            vec![1],
+            false,
+        ),
+        Action::Quit,
+    ];
+    let received_actions = extract_actions_sent_to_server(events_sent_to_server);
+    assert_eq!(
+        expected_actions_sent_to_server, received_actions,
+        "All actions sent to server properly"
+    );
+}
+
+fn config_with_key_sequence_beginning_with_a_bound_key() -> Config {
+    // Alt h is also bound on its own by default (to move the focus left)
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Alt h" {
+                    bind "n" { GoToNextTab; }
+                }
+            }
+        }
+    "#;
+    Config::from_kdl(
+        config_contents,
+        Some(Config::from_default_assets().unwrap()),
+    )
+    .unwrap()
+}
+
+fn actions_for_alt_h_n_with_options(options: Options) -> Vec<Action> {
+    let stdin_events = vec![
+        (
+            commands::MOVE_FOCUS_LEFT_IN_NORMAL_MODE.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('h'),
+                modifiers: Modifiers::ALT,
+            }),
+        ),
+        (
+            vec![110], // n
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('n'),
+                modifiers: Modifiers::NONE,
+            }),
+        ),
+        (
+            commands::QUIT.to_vec(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('q'),
+                modifiers: Modifiers::CTRL,
+            }),
+        ),
+    ];
+
+    let events_sent_to_server = Arc::new(Mutex::new(vec![]));
+    let command_is_executing = CommandIsExecuting::new();
+    let client_os_api = Box::new(FakeClientOsApi::new(
+        events_sent_to_server.clone(),
+        command_is_executing.clone(),
+    ));
+    let config = config_with_key_sequence_beginning_with_a_bound_key();
+
+    let (send_client_instructions, _receive_client_instructions): ChannelWithContext<
+        ClientInstruction,
+    > = channels::bounded(50);
+    let send_client_instructions = SenderWithContext::new(send_client_instructions);
+
+    let (send_input_instructions, receive_input_instructions): ChannelWithContext<
+        InputInstruction,
+    > = channels::bounded(50);
+    let send_input_instructions = SenderWithContext::new(send_input_instructions);
+    for event in stdin_events {
+        send_input_instructions
+            .send(InputInstruction::KeyEvent(event.1, event.0))
+            .unwrap();
+    }
+
+    let default_mode = InputMode::Normal;
+    input_loop(
+        client_os_api,
+        config,
+        options,
+        command_is_executing,
+        send_client_instructions,
+        default_mode,
+        receive_input_instructions,
+    );
+    extract_actions_sent_to_server(events_sent_to_server)
+}
+
+#[test]
+pub fn key_bound_on_its_own_acts_right_away_even_if_it_begins_a_key_sequence() {
+    let expected_actions_sent_to_server = vec![
+        Action::MoveFocusOrTab(Direction::Left),
+        Action::WriteKey(
+            KeyWithModifier::new(BareKey::Char('n'), KeyModifiers::NONE),
+            vec![110],
+            false,
+        ),
+        Action::Quit,
+    ];
+    assert_eq!(
+        expected_actions_sent_to_server,
+        actions_for_alt_h_n_with_options(Options::default()),
+        "All actions sent to server properly"
+    );
+}
+
+#[test]
+pub fn key_bound_on_its_own_waits_for_the_key_sequence_it_begins_if_preferred() {
+    let options = Options {
+        prefer_key_sequences: Some(true),
+        ..Default::default()
+    };
+    let expected_actions_sent_to_server = vec![
+        Action::PendingKeySequence(InputMode::Normal, vec![Key::Alt(CharOrArrow::Char('h'))]),
+        Action::PendingKeySequence(InputMode::Normal, vec![]),
+        Action::GoToNextTab,
+        Action::Quit,
+    ];
+    assert_eq!(
+        expected_actions_sent_to_server,
+        actions_for_alt_h_n_with_options(options),
+        "All actions sent to server properly"
+    );
+}
@@ -10,8 +10,10 @@ use zellij_utils::surf::{
     RequestBuilder,
 };
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
 use std::os::unix::fs::FileTypeExt;
 use std::path::PathBuf;
@@ -21,7 +23,7 @@ use std::sync::{
 };
 use std::time::{Duration, Instant};
 
-use crate::panes::PaneId;
+use crate::panes::{KittyGraphicsFileRead, PaneId};
 use crate::plugins::{PluginId, PluginInstruction};
 use crate::screen::ScreenInstruction;
 use crate::thread_bus::Bus;
@@ -35,6 +37,7 @@ pub enum BackgroundJob {
     ReadAllSessionInfosOnMachine,                         // u32 - plugin_id
     ReportSessionInfo(String, SessionInfo),               // String - session name
     ReportLayoutInfo((String, BTreeMap<String, String>)), // BTreeMap<file_name, pane_contents>
+    ReportPanesMonitoredForSilence(bool),
     RunCommand(
         PluginId,
         ClientId,
@@ -53,6 +56,7 @@ pub enum BackgroundJob {
         Vec<u8>,                  // body
         BTreeMap<String, String>, // context
     ),
+    ReadKittyGraphicsFile(u32, KittyGraphicsFileRead), // u32 - the pid of the terminal pane
     Exit,
 }
 
@@ -69,8 +73,12 @@ impl From<&BackgroundJob> for BackgroundJobContext {
             },
             BackgroundJob::ReportSessionInfo(..) => BackgroundJobContext::ReportSessionInfo,
             BackgroundJob::ReportLayoutInfo(..) => BackgroundJobContext::ReportLayoutInfo,
+            BackgroundJob::ReportPanesMonitoredForSilence(..) => {
+                BackgroundJobContext::ReportPanesMonitoredForSilence
+            },
             BackgroundJob::RunCommand(..) => BackgroundJobContext::RunCommand,
             BackgroundJob::WebRequest(..) => BackgroundJobContext::WebRequest,
+            BackgroundJob::ReadKittyGraphicsFile(..) => BackgroundJobContext::ReadKittyGraphicsFile,
             BackgroundJob::Exit => BackgroundJobContext::Exit,
         }
     }
@@ -80,6 +88,7 @@ static FLASH_DURATION_MS: u64 = 1000;
 static PLUGIN_ANIMATION_OFFSET_DURATION_MD: u64 = 500;
 static SESSION_READ_DURATION: u64 = 1000;
 static DEFAULT_SERIALIZATION_INTERVAL: u64 = 60000;
+static TERMINAL_PROCESS_INFO_INTERVAL: u64 = 2000;
 
 pub(crate) fn background_jobs_main(
     bus: Bus<BackgroundJob>,
@@ -91,6 +100,8 @@ pub(crate) fn background_jobs_main(
     let current_session_name = Arc::new(Mutex::new(String::default()));
     let current_session_info = Arc::new(Mutex::new(SessionInfo::default()));
     let current_session_layout = Arc::new(Mutex::new((String::new(), BTreeMap::new())));
+    let session_layout_changed = Arc::new(AtomicBool::new(false));
+    let panes_monitored_for_silence = Arc::new(AtomicBool::new(false));
     let last_serialization_time = Arc::new(Mutex::new(Instant::now()));
     let serialization_interval = serialization_interval.map(|s| s * 1000); // convert to
                                                                            // milliseconds
@@ -153,6 +164,11 @@ pub(crate) fn background_jobs_main(
             },
             BackgroundJob::ReportLayoutInfo(session_layout) => {
                 *current_session_layout.lock().unwrap() = session_layout;
+                session_layout_changed.store(true, Ordering::SeqCst);
+            },
+            BackgroundJob::ReportPanesMonitoredForSilence(panes_are_monitored_for_silence) => {
+                panes_monitored_for_silence
+                    .store(panes_are_monitored_for_silence, Ordering::SeqCst);
             },
             BackgroundJob::ReadAllSessionInfosOnMachine => {
                 // this job should only be run once and it keeps track of other sessions (as well
@@ -167,18 +183,29 @@ pub(crate) fn background_jobs_main(
                     let current_session_info = current_session_info.clone();
                     let current_session_name = current_session_name.clone();
                     let current_session_layout = current_session_layout.clone();
+                    let session_layout_changed = session_layout_changed.clone();
+                    let panes_monitored_for_silence = panes_monitored_for_silence.clone();
                     let last_serialization_time = last_serialization_time.clone();
+                    let mut last_terminal_process_info_time = Instant::now();
+                    let mut written_layout_files = HashMap::new();
                     async move {
                         loop {
                             let current_session_name =
                                 current_session_name.lock().unwrap().to_string();
                             let current_session_info = current_session_info.lock().unwrap().clone();
+                            // the layout (along with the pane contents) is only written when it
+                            // was reported again since the last time
                             let current_session_layout =
-                                current_session_layout.lock().unwrap().clone();
+                                if session_layout_changed.swap(false, Ordering::SeqCst) {
+                                    Some(current_session_layout.lock().unwrap().clone())
+                                } else {
+                                    None
+                                };
                             write_session_state_to_disk(
                                 current_session_name.clone(),
                                 current_session_info,
                                 current_session_layout,
+                                &mut written_layout_files,
                             );
                             let session_infos_on_machine =
                                 read_other_live_session_states(&current_session_name);
@@ -200,6 +227,18 @@ pub(crate) fn background_jobs_main(
                                 let _ = senders.send_to_screen(ScreenInstruction::DumpLayoutToHd);
                                 *last_serialization_time.lock().unwrap() = Instant::now();
                             }
+                            if last_terminal_process_info_time.elapsed().as_millis()
+                                >= TERMINAL_PROCESS_INFO_INTERVAL.into()
+                            {
+                                let _ = senders
+                                    .send_to_plugin(PluginInstruction::RefreshTerminalProcessInfo);
+                                last_terminal_process_info_time = Instant::now();
+                            }
+                            // a pane going silent doesn't cause anything else to happen
+                            if panes_monitored_for_silence.load(Ordering::SeqCst) {
+                                let _ =
+                                    senders.send_to_screen(ScreenInstruction::CheckPaneMonitors);
+                            }
                             task::sleep(std::time::Duration::from_millis(SESSION_READ_DURATION))
                                 .await;
                         }
@@ -315,6 +354,20 @@ pub(crate) fn background_jobs_main(
                     }
                 });
             },
+            BackgroundJob::ReadKittyGraphicsFile(pid, file_read) => {
+                // the file may be on a slow file system, so it is read on the blocking pool
+                task::spawn_blocking({
+                    let senders = bus.senders.clone();
+                    move || {
+                        let data = file_read.read();
+                        let _ = senders.send_to_screen(ScreenInstruction::KittyGraphicsFileRead(
+                            pid,
+                            file_read.id,
+                            data,
+                        ));
+                    }
+                });
+            },
             BackgroundJob::Exit => {
                 for loading_plugin in loading_plugins.values() {
                     loading_plugin.store(false, Ordering::SeqCst);
@@ -349,18 +402,24 @@ fn job_already_running(
     }
 }
 
+// written_layout_files: <file_name, hash of the contents last written to it> - so that the pane
+// contents (which can include their whole scrollback) are only rewritten when they changed
 fn write_session_state_to_disk(
     current_session_name: String,
     current_session_info: SessionInfo,
-    current_session_layout: (String, BTreeMap<String, String>),
+    current_session_layout: Option<(String, BTreeMap<String, String>)>,
+    written_layout_files: &mut HashMap<String, u64>,
 ) {
     let metadata_cache_file_name = session_info_cache_file_name(&current_session_name);
-    let (current_session_layout, layout_files_to_write) = current_session_layout;
     let _wrote_metadata_file =
         std::fs::create_dir_all(session_info_folder_for_session(&current_session_name).as_path())
             .and_then(|_| std::fs::File::create(metadata_cache_file_name))
             .and_then(|mut f| write!(f, "{}", current_session_info.to_string()));
 
+    let (current_session_layout, layout_files_to_write) = match current_session_layout {
+        Some(current_session_layout) => current_session_layout,
+        None => return,
+    };
     if !current_session_layout.is_empty() {
         let layout_cache_file_name = session_layout_cache_file_name(&current_session_name);
         let _wrote_layout_file = std::fs::create_dir_all(
@@ -370,12 +429,25 @@ fn write_session_state_to_disk(
         .and_then(|mut f| write!(f, "{}", current_session_layout))
         .and_then(|_| {
             let session_info_folder = session_info_folder_for_session(&current_session_name);
+            written_layout_files
+                .retain(|file_name, _| layout_files_to_write.contains_key(file_name));
             for (external_file_name, external_file_contents) in layout_files_to_write {
-                std::fs::File::create(session_info_folder.join(external_file_name))
+                let mut hasher = DefaultHasher::new();
+                external_file_contents.hash(&mut hasher);
+                let contents_hash = hasher.finish();
+                if written_layout_files.get(&external_file_name) == Some(&contents_hash) {
+                    continue;
+                }
+                match std::fs::File::create(session_info_folder.join(&external_file_name))
                     .and_then(|mut f| write!(f, "{}", external_file_contents))
-                    .unwrap_or_else(|e| {
+                {
+                    Ok(_) => {
+                        written_layout_files.insert(external_file_name, contents_hash);
+                    },
+                    Err(e) => {
                         log::error!("Failed to write layout metadata file: {:?}", e);
-                    });
+                    },
+                }
             }
             Ok(())
         });
@@ -0,0 +1,55 @@
+use crate::ServerInstruction;
+use std::path::Path;
+use std::time::Duration;
+
+use zellij_utils::channels::SenderWithContext;
+use zellij_utils::errors::prelude::Result;
+use zellij_utils::notify_debouncer_full::{
+    new_debouncer,
+    notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher},
+    DebounceEventResult, Debouncer, FileIdMap,
+};
+
+const DEBOUNCE_DURATION_MS: u64 = 400;
+
+/// Reloads the session's config whenever the config file changes.
+///
+/// We watch the directory of the config file rather than the file itself, because many editors
+/// save by writing a new file and renaming it over the old one, which would end a watch on the
+/// old file.
+pub fn watch_config_file(
+    config_file_path: &Path,
+    to_server: SenderWithContext<ServerInstruction>,
+) -> Result<Debouncer<RecommendedWatcher, FileIdMap>> {
+    let config_file_name = config_file_path.file_name().map(|f| f.to_owned());
+    let config_dir = match config_file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => Path::new(".").to_path_buf(),
+    };
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(DEBOUNCE_DURATION_MS),
+        None,
+        move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                let config_file_changed = events.iter().any(|event| {
+                    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                        && event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name().map(|f| f.to_owned()) == config_file_name)
+                });
+                if config_file_changed {
+                    let _ = to_server.send(ServerInstruction::ReloadConfig);
+                }
+            },
+            Err(errors) => errors
+                .iter()
+                .for_each(|error| log::error!("config watch error: {error:?}")),
+        },
+    )?;
+
+    debouncer
+        .watcher()
+        .watch(&config_dir, RecursiveMode::NonRecursive)?;
+    Ok(debouncer)
+}
@@ -4,6 +4,7 @@ pub mod panes;
 pub mod tab;
 
 mod background_jobs;
+mod config_watcher;
 mod logging_pipe;
 mod plugins;
 mod pty;
@@ -11,11 +12,13 @@ mod pty_writer;
 mod route;
 mod screen;
 mod session_layout_metadata;
+mod session_listing;
 mod terminal_bytes;
 mod thread_bus;
 mod ui;
 
 use background_jobs::{background_jobs_main, BackgroundJob};
+use config_watcher::watch_config_file;
 use log::info;
 use pty_writer::{pty_writer_main, PtyWriteInstruction};
 use std::collections::{HashMap, HashSet};
@@ -32,6 +35,7 @@ use wasmer::Store;
 
 use crate::{
     os_input_output::ServerOsApi,
+    panes::{alacritty_functions::xparse_color, terminal_character::AnsiCode},
     plugins::{plugin_thread_main, PluginInstruction},
     pty::{get_default_shell, pty_thread_main, Pty, PtyInstruction},
     screen::{screen_thread_main, ScreenInstruction},
@@ -47,12 +51,17 @@ use zellij_utils::{
     home::{default_layout_dir, get_default_data_dir},
     input::{
         command::{RunCommand, TerminalAction},
+        config::Config,
         get_mode_info,
+        keybinds::Keybinds,
         layout::Layout,
         options::Options,
         plugins::PluginsConfig,
+        theme::Themes,
     },
     ipc::{ClientAttributes, ExitReason, ServerToClientMsg},
+    notify_debouncer_full::{notify::RecommendedWatcher, Debouncer, FileIdMap},
+    setup::Setup,
 };
 
 pub type ClientId = u16;
@@ -80,6 +89,8 @@ pub enum ServerInstruction {
         Options,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane_id to focus
+        bool,                // is read-only
+        Option<ClientId>,    // client whose focus a read-only client follows
         ClientId,
     ),
     ConnStatus(ClientId),
@@ -94,6 +105,11 @@ pub enum ServerInstruction {
         client_id: ClientId,
     },
     DisconnectAllClientsExcept(ClientId),
+    ListClients(bool, ClientId), // bool is json
+    ReloadConfig,
+    SwitchTheme(String, Option<ClientId>, ClientId), // String is the theme name, Option<ClientId>
+    // is the client to switch (None => all clients), ClientId is the client that asked for it
+    TerminalBackgroundColor(String, ClientId), // String is the color reported by the terminal
 }
 
 impl From<&ServerInstruction> for ServerContext {
@@ -121,6 +137,12 @@ impl From<&ServerInstruction> for ServerContext {
             ServerInstruction::DisconnectAllClientsExcept(..) => {
                 ServerContext::DisconnectAllClientsExcept
             },
+            ServerInstruction::ListClients(..) => ServerContext::ListClients,
+            ServerInstruction::ReloadConfig => ServerContext::ReloadConfig,
+            ServerInstruction::SwitchTheme(..) => ServerContext::SwitchTheme,
+            ServerInstruction::TerminalBackgroundColor(..) => {
+                ServerContext::TerminalBackgroundColor
+            },
         }
     }
 }
@@ -138,6 +160,11 @@ pub(crate) struct SessionMetaData {
     pub default_shell: Option<TerminalAction>,
     pub layout: Box<Layout>,
     pub config_options: Box<Options>,
+    cli_args: Box<CliArgs>, // used to find and re-read the config file
+    themes: Themes,         // the themes clients can switch to at runtime
+    background_themes: HashMap<ClientId, String>, // the theme last picked from each client's terminal background
+    explicit_themes: HashSet<Option<ClientId>>, // set with set-theme (None for the whole session), not overridden by the background
+    _config_file_watcher: Option<Debouncer<RecommendedWatcher, FileIdMap>>, // kept for as long as the session lives
     screen_thread: Option<thread::JoinHandle<()>>,
     pty_thread: Option<thread::JoinHandle<()>>,
     plugin_thread: Option<thread::JoinHandle<()>>,
@@ -145,6 +172,76 @@ pub(crate) struct SessionMetaData {
     background_jobs_thread: Option<thread::JoinHandle<()>>,
 }
 
+impl SessionMetaData {
+    /// Re-reads the config file and applies it to the session, returning the keybinds and options
+    /// the clients should now use or a report of why the config could not be read
+    fn reload_config(&mut self) -> std::result::Result<(Keybinds, Options), String> {
+        // rebuilt from scratch rather than merged over the running options, so that options
+        // removed from the file go back to their defaults
+        let (config, config_options) =
+            Setup::reload_config(&self.cli_args).map_err(|e| e.to_report())?;
+        if let Some(palette) = config.theme_config(&config_options) {
+            self.client_attributes.style.colors = palette;
+        }
+        self.client_attributes.style.rounded_corners = config.ui.pane_frames.rounded_corners;
+        self.client_attributes.style.hide_session_name = config.ui.pane_frames.hide_session_name;
+        self.client_attributes.keybinds = config.keybinds.clone();
+        self.config_options = Box::new(config_options.clone());
+        self.themes = config.themes;
+        Ok((config.keybinds, config_options))
+    }
+    /// Switches one client (or all of them if `target_client_id` is None) to one of the loaded
+    /// themes, returning false if there is no theme by this name
+    fn switch_theme(&mut self, theme_name: &str, target_client_id: Option<ClientId>) -> bool {
+        match self.themes.get_theme(theme_name).map(|theme| theme.palette) {
+            Some(palette) => {
+                if target_client_id.is_none() {
+                    self.client_attributes.style.colors = palette;
+                }
+                let _ = self
+                    .senders
+                    .send_to_screen(ScreenInstruction::SwitchTheme(palette, target_client_id));
+                true
+            },
+            None => false,
+        }
+    }
+    /// Switches a client to the light or dark theme matching the background color its terminal
+    /// reported. This happens when it attaches and whenever the background changes between light
+    /// and dark (it is reported again on every resize), unless a theme was set explicitly.
+    fn apply_background_color(&mut self, background_color: &str, client_id: ClientId) {
+        if self.explicit_themes.contains(&None) || self.explicit_themes.contains(&Some(client_id)) {
+            return;
+        }
+        if let Some(theme_name) = self.theme_for_background_color(background_color) {
+            if self.background_themes.get(&client_id) == Some(&theme_name) {
+                return;
+            }
+            if self.switch_theme(&theme_name, Some(client_id)) {
+                self.background_themes.insert(client_id, theme_name);
+            } else {
+                log::error!("Theme \"{}\" not found", theme_name);
+            }
+        }
+    }
+    /// The light or dark theme (if configured) matching the background color reported by a
+    /// client's terminal
+    fn theme_for_background_color(&self, background_color: &str) -> Option<String> {
+        match xparse_color(background_color.as_bytes()) {
+            Some(AnsiCode::RgbCode((r, g, b))) => {
+                // perceived brightness, see: https://www.w3.org/TR/AERT/#color-contrast
+                let brightness = (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000;
+                if brightness > 127 {
+                    self.config_options.light_theme.clone()
+                } else {
+                    self.config_options.dark_theme.clone()
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
 impl Drop for SessionMetaData {
     fn drop(&mut self) {
         let _ = self.senders.send_to_pty(PtyInstruction::Exit);
@@ -205,6 +302,7 @@ macro_rules! send_to_client {
 pub(crate) struct SessionState {
     clients: HashMap<ClientId, Option<Size>>,
     pipes: HashMap<String, ClientId>, // String => pipe_id
+    read_only_clients: HashSet<ClientId>,
 }
 
 impl SessionState {
@@ -212,6 +310,7 @@ impl SessionState {
         SessionState {
             clients: HashMap::new(),
             pipes: HashMap::new(),
+            read_only_clients: HashSet::new(),
         }
     }
     pub fn new_client(&mut self) -> ClientId {
@@ -233,23 +332,32 @@ impl SessionState {
     pub fn remove_client(&mut self, client_id: ClientId) {
         self.clients.remove(&client_id);
         self.pipes.retain(|_p_id, c_id| c_id != &client_id);
+        self.read_only_clients.remove(&client_id);
+    }
+    pub fn set_client_read_only(&mut self, client_id: ClientId) {
+        self.read_only_clients.insert(client_id);
     }
     pub fn set_client_size(&mut self, client_id: ClientId, size: Size) {
         self.clients.insert(client_id, Some(size));
     }
     pub fn min_client_terminal_size(&self) -> Option<Size> {
         // None if there are no client sizes
-        let mut rows: Vec<usize> = self
+        // read-only clients only decide the size of the session when they are the only ones
+        // left, so that a spectator cannot shrink it for everyone else
+        let sizes_of_clients_who_can_write: Vec<Size> = self
             .clients
-            .values()
-            .filter_map(|size| size.map(|size| size.rows))
+            .iter()
+            .filter(|(client_id, _size)| !self.read_only_clients.contains(client_id))
+            .filter_map(|(_client_id, size)| *size)
             .collect();
+        let sizes: Vec<Size> = if sizes_of_clients_who_can_write.is_empty() {
+            self.clients.values().filter_map(|size| *size).collect()
+        } else {
+            sizes_of_clients_who_can_write
+        };
+        let mut rows: Vec<usize> = sizes.iter().map(|size| size.rows).collect();
         rows.sort_unstable();
-        let mut cols: Vec<usize> = self
-            .clients
-            .values()
-            .filter_map(|size| size.map(|size| size.cols))
-            .collect();
+        let mut cols: Vec<usize> = sizes.iter().map(|size| size.cols).collect();
         cols.sort_unstable();
         let min_rows = rows.first();
         let min_cols = cols.first();
@@ -264,6 +372,9 @@ impl SessionState {
     pub fn client_ids(&self) -> Vec<ClientId> {
         self.clients.keys().copied().collect()
     }
+    pub fn client_sizes(&self) -> HashMap<ClientId, Option<Size>> {
+        self.clients.clone()
+    }
     pub fn active_clients_are_connected(&self) -> bool {
         let ids_of_pipe_clients: HashSet<ClientId> = self.pipes.values().copied().collect();
         let mut active_clients_connected = false;
@@ -384,6 +495,17 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .write()
                     .unwrap()
                     .set_client_size(client_id, client_attributes.size);
+                session_data
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .senders
+                    .send_to_screen(ScreenInstruction::ClientColorDepth(
+                        client_id,
+                        client_attributes.color_depth,
+                    ))
+                    .unwrap();
 
                 let default_shell = config_options.default_shell.map(|shell| {
                     TerminalAction::RunCommand(RunCommand {
@@ -464,10 +586,18 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 options,
                 tab_position_to_focus,
                 pane_id_to_focus,
+                is_read_only,
+                client_to_follow,
                 client_id,
             ) => {
                 let rlock = session_data.read().unwrap();
                 let session_data = rlock.as_ref().unwrap();
+                if is_read_only {
+                    session_state
+                        .write()
+                        .unwrap()
+                        .set_client_read_only(client_id);
+                }
                 session_state
                     .write()
                     .unwrap()
@@ -481,6 +611,13 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .senders
                     .send_to_screen(ScreenInstruction::TerminalResize(min_size))
                     .unwrap();
+                session_data
+                    .senders
+                    .send_to_screen(ScreenInstruction::ClientColorDepth(
+                        client_id,
+                        attrs.color_depth,
+                    ))
+                    .unwrap();
                 session_data
                     .senders
                     .send_to_screen(ScreenInstruction::AddClient(
@@ -493,8 +630,18 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .senders
                     .send_to_plugin(PluginInstruction::AddClient(client_id))
                     .unwrap();
+                if let Some(client_to_follow) = client_to_follow {
+                    session_data
+                        .senders
+                        .send_to_screen(ScreenInstruction::FollowClient(
+                            client_id,
+                            client_to_follow,
+                        ))
+                        .unwrap();
+                }
                 let default_mode = options.default_mode.unwrap_or_default();
-                let mode_info = get_mode_info(default_mode, &attrs, session_data.capabilities);
+                let mut mode_info = get_mode_info(default_mode, &attrs, session_data.capabilities);
+                mode_info.is_read_only = is_read_only;
                 let mode = mode_info.mode;
                 session_data
                     .senders
@@ -619,6 +766,10 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
             },
             ServerInstruction::RemoveClient(client_id) => {
                 remove_client!(client_id, os_input, session_state);
+                if let Some(session_data) = session_data.write().unwrap().as_mut() {
+                    session_data.background_themes.remove(&client_id);
+                    session_data.explicit_themes.remove(&Some(client_id));
+                }
                 if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
                     session_data
                         .write()
@@ -753,6 +904,89 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     session_state
                 );
             },
+            ServerInstruction::ListClients(json, client_id) => {
+                let client_sizes = session_state.read().unwrap().client_sizes();
+                session_data
+                    .write()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap()
+                    .senders
+                    .send_to_screen(ScreenInstruction::ListClients(
+                        json,
+                        client_sizes,
+                        client_id,
+                    ))
+                    .unwrap();
+            },
+            ServerInstruction::ReloadConfig => {
+                let mut session_data = session_data.write().unwrap();
+                let session_data = match session_data.as_mut() {
+                    Some(session_data) => session_data,
+                    None => continue,
+                };
+                match session_data.reload_config() {
+                    Ok((keybinds, config_options)) => {
+                        session_data
+                            .senders
+                            .send_to_screen(ScreenInstruction::Reconfigure(
+                                keybinds.clone(),
+                                session_data.client_attributes.style,
+                                Box::new(config_options.clone()),
+                            ))
+                            .unwrap();
+                        let client_ids = session_state.read().unwrap().client_ids();
+                        for client_id in client_ids {
+                            send_to_client!(
+                                client_id,
+                                os_input,
+                                ServerToClientMsg::Reconfigure(
+                                    keybinds.clone(),
+                                    Box::new(config_options.clone())
+                                ),
+                                session_state
+                            );
+                        }
+                    },
+                    Err(report) => {
+                        log::error!("Failed to reload config: {}", report);
+                        session_data
+                            .senders
+                            .send_to_screen(ScreenInstruction::ShowConfigError(format!(
+                                "Failed to reload config, keeping the previous one:\n{}",
+                                report
+                            )))
+                            .unwrap();
+                    },
+                }
+            },
+            ServerInstruction::SwitchTheme(theme_name, target_client_id, client_id) => {
+                let mut session_data = session_data.write().unwrap();
+                let session_data = match session_data.as_mut() {
+                    Some(session_data) => session_data,
+                    None => continue,
+                };
+                if session_data.switch_theme(&theme_name, target_client_id) {
+                    session_data.explicit_themes.insert(target_client_id);
+                } else {
+                    let error = format!("Theme \"{}\" not found", theme_name);
+                    log::error!("{}", error);
+                    send_to_client!(
+                        client_id,
+                        os_input,
+                        ServerToClientMsg::LogError(vec![error]),
+                        session_state
+                    );
+                }
+            },
+            ServerInstruction::TerminalBackgroundColor(background_color, client_id) => {
+                let mut session_data = session_data.write().unwrap();
+                let session_data = match session_data.as_mut() {
+                    Some(session_data) => session_data,
+                    None => continue,
+                };
+                session_data.apply_background_color(&background_color, client_id);
+            },
             ServerInstruction::Log(lines_to_log, client_id) => {
                 send_to_client!(
                     client_id,
@@ -874,6 +1108,24 @@ fn init_session(
         channels::unbounded();
     let to_background_jobs = SenderWithContext::new(to_background_jobs);
 
+    let config_file_watcher = Config::config_file_path(&opts).and_then(|config_file_path| {
+        match watch_config_file(&config_file_path, to_server.clone()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                log::error!("Failed to watch config file: {:?}", e);
+                None
+            },
+        }
+    });
+    let cli_args = opts.clone();
+    let themes = match Setup::reload_config(&opts) {
+        Ok((config, _config_options)) => config.themes,
+        Err(e) => {
+            log::error!("Failed to load the themes clients can switch to: {}", e);
+            Themes::default()
+        },
+    };
+
     // Determine and initialize the data directory
     let data_dir = opts.data_dir.unwrap_or_else(get_default_data_dir);
 
@@ -1037,6 +1289,11 @@ fn init_session(
         client_attributes,
         layout,
         config_options: config_options.clone(),
+        cli_args,
+        themes,
+        background_themes: HashMap::new(),
+        explicit_themes: HashSet::new(),
+        _config_file_watcher: config_file_watcher,
         screen_thread: Some(screen_thread),
         pty_thread: Some(pty_thread),
         plugin_thread: Some(plugin_thread),
@@ -337,6 +337,7 @@ fn spawn_terminal(
                 cwd,
                 hold_on_close: false,
                 hold_on_start: false,
+                originating_plugin: None,
             }
         },
         TerminalAction::RunCommand(command) => command,
@@ -0,0 +1,233 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::panes::terminal_character::{AnsiCode, CharacterStyles, NamedColor};
+use zellij_utils::ipc::ColorDepth;
+
+// the default xterm colours, the first 16 colours are usually themed but we have to assume
+// something to match against
+const ANSI_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+const NAMED_COLORS: [NamedColor; 16] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+];
+const COLOR_CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+const FIRST_COLOR_CUBE_INDEX: u8 = 16;
+const FIRST_GRAYSCALE_INDEX: u8 = 232;
+const MAX_CACHED_COLORS: usize = 4096;
+
+thread_local! {
+    // a session rarely uses more than a few dozen distinct colours, so we only look for the
+    // nearest match of each of them once
+    static NEAREST_COLORS: RefCell<HashMap<((u8, u8, u8), ColorDepth), u8>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Brings the colours of these styles down to ones the terminal of the client can display
+pub fn downsample_styles(styles: &mut CharacterStyles, color_depth: ColorDepth) {
+    if color_depth == ColorDepth::TrueColor {
+        return;
+    }
+    styles.foreground = styles.foreground.map(|c| downsample_color(c, color_depth));
+    styles.background = styles.background.map(|c| downsample_color(c, color_depth));
+    styles.underline_color =
+        styles
+            .underline_color
+            .map(|c| match downsample_color(c, color_depth) {
+                // there is no named form of the underline colour sequence
+                AnsiCode::NamedColor(named_color) => AnsiCode::ColorIndex(named_color as u8),
+                c => c,
+            });
+}
+
+pub fn downsample_color(ansi_code: AnsiCode, color_depth: ColorDepth) -> AnsiCode {
+    match (color_depth, ansi_code) {
+        (ColorDepth::Indexed256, AnsiCode::RgbCode(rgb)) => {
+            AnsiCode::ColorIndex(nearest_color_index(rgb, color_depth))
+        },
+        (ColorDepth::Ansi16, AnsiCode::RgbCode(rgb)) => {
+            AnsiCode::NamedColor(NAMED_COLORS[nearest_color_index(rgb, color_depth) as usize])
+        },
+        (ColorDepth::Ansi16, AnsiCode::ColorIndex(color_index)) => {
+            let color_index = if color_index < FIRST_COLOR_CUBE_INDEX {
+                color_index
+            } else {
+                nearest_color_index(color_index_to_rgb(color_index), color_depth)
+            };
+            AnsiCode::NamedColor(NAMED_COLORS[color_index as usize])
+        },
+        _ => ansi_code,
+    }
+}
+
+fn nearest_color_index(rgb: (u8, u8, u8), color_depth: ColorDepth) -> u8 {
+    NEAREST_COLORS.with(|nearest_colors| {
+        let mut nearest_colors = nearest_colors.borrow_mut();
+        if let Some(color_index) = nearest_colors.get(&(rgb, color_depth)) {
+            return *color_index;
+        }
+        // we don't match against the first 16 colours of the 256 colour palette, because they
+        // are themed and might look nothing like the colour we want
+        let candidates = match color_depth {
+            ColorDepth::Ansi16 => 0..=FIRST_COLOR_CUBE_INDEX - 1,
+            _ => FIRST_COLOR_CUBE_INDEX..=u8::MAX,
+        };
+        let lab = rgb_to_lab(rgb);
+        let color_index = candidates
+            .map(|color_index| {
+                let distance = lab_distance(lab, rgb_to_lab(color_index_to_rgb(color_index)));
+                (color_index, distance)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(color_index, _)| color_index)
+            .unwrap_or(0);
+        if nearest_colors.len() >= MAX_CACHED_COLORS {
+            nearest_colors.clear();
+        }
+        nearest_colors.insert((rgb, color_depth), color_index);
+        color_index
+    })
+}
+
+fn color_index_to_rgb(color_index: u8) -> (u8, u8, u8) {
+    if color_index < FIRST_COLOR_CUBE_INDEX {
+        ANSI_COLORS[color_index as usize]
+    } else if color_index < FIRST_GRAYSCALE_INDEX {
+        let cube_index = (color_index - FIRST_COLOR_CUBE_INDEX) as usize;
+        (
+            COLOR_CUBE_LEVELS[cube_index / 36],
+            COLOR_CUBE_LEVELS[(cube_index / 6) % 6],
+            COLOR_CUBE_LEVELS[cube_index % 6],
+        )
+    } else {
+        let level = 8 + (color_index - FIRST_GRAYSCALE_INDEX) * 10;
+        (level, level, level)
+    }
+}
+
+/// The colour in the CIELAB colour space, where the distance between two colours is close to
+/// how different they look
+fn rgb_to_lab((r, g, b): (u8, u8, u8)) -> (f32, f32, f32) {
+    fn linear(channel: u8) -> f32 {
+        let channel = channel as f32 / 255.0;
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    fn f(t: f32) -> f32 {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    }
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    // relative to the D65 white point
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+fn lab_distance(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    (a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::panes::DEFAULT_STYLES;
+
+    #[test]
+    fn rgb_colors_are_matched_to_the_256_color_palette() {
+        assert_eq!(
+            downsample_color(AnsiCode::RgbCode((255, 0, 0)), ColorDepth::Indexed256),
+            AnsiCode::ColorIndex(196)
+        );
+        assert_eq!(
+            downsample_color(AnsiCode::RgbCode((130, 130, 130)), ColorDepth::Indexed256),
+            AnsiCode::ColorIndex(244)
+        );
+        assert_eq!(
+            downsample_color(AnsiCode::RgbCode((95, 135, 175)), ColorDepth::Indexed256),
+            AnsiCode::ColorIndex(67)
+        );
+    }
+
+    #[test]
+    fn colors_are_matched_to_the_16_color_palette() {
+        assert_eq!(
+            downsample_color(AnsiCode::RgbCode((250, 10, 10)), ColorDepth::Ansi16),
+            AnsiCode::NamedColor(NamedColor::BrightRed)
+        );
+        assert_eq!(
+            downsample_color(AnsiCode::RgbCode((0, 190, 200)), ColorDepth::Ansi16),
+            AnsiCode::NamedColor(NamedColor::Cyan)
+        );
+        assert_eq!(
+            downsample_color(AnsiCode::ColorIndex(4), ColorDepth::Ansi16),
+            AnsiCode::NamedColor(NamedColor::Blue)
+        );
+        assert_eq!(
+            downsample_color(AnsiCode::ColorIndex(231), ColorDepth::Ansi16),
+            AnsiCode::NamedColor(NamedColor::BrightWhite)
+        );
+    }
+
+    #[test]
+    fn colors_are_kept_as_they_are_in_truecolor() {
+        let rgb = AnsiCode::RgbCode((12, 34, 56));
+        assert_eq!(downsample_color(rgb, ColorDepth::TrueColor), rgb);
+        assert_eq!(
+            downsample_color(AnsiCode::Reset, ColorDepth::Ansi16),
+            AnsiCode::Reset
+        );
+    }
+
+    #[test]
+    fn underline_colors_are_downsampled_to_color_indices() {
+        let mut styles = DEFAULT_STYLES
+            .foreground(Some(AnsiCode::RgbCode((255, 255, 255))))
+            .underline_color(Some(AnsiCode::RgbCode((0, 0, 0))));
+        downsample_styles(&mut styles, ColorDepth::Ansi16);
+        assert_eq!(
+            styles.foreground,
+            Some(AnsiCode::NamedColor(NamedColor::BrightWhite))
+        );
+        assert_eq!(styles.underline_color, Some(AnsiCode::ColorIndex(0)));
+    }
+}
@@ -1,3 +1,5 @@
+mod color_depth;
+
 use std::collections::VecDeque;
 
 use crate::panes::selection::Selection;
@@ -9,6 +11,7 @@ use crate::{
     panes::{LinkHandler, TerminalCharacter, DEFAULT_STYLES, EMPTY_TERMINAL_CHARACTER},
     ClientId,
 };
+use color_depth::downsample_styles;
 use std::cell::RefCell;
 use std::fmt::Write;
 use std::rc::Rc;
@@ -17,6 +20,7 @@ use std::{
     str,
 };
 use zellij_utils::errors::prelude::*;
+use zellij_utils::ipc::ColorDepth;
 use zellij_utils::pane_size::PaneGeom;
 use zellij_utils::pane_size::SizeInPixels;
 
@@ -61,13 +65,15 @@ fn write_changed_styles(
     current_character_styles: CharacterStyles,
     chunk_changed_colors: Option<[Option<AnsiCode>; 256]>,
     link_handler: Option<&std::cell::Ref<LinkHandler>>,
+    color_depth: ColorDepth,
     vte_output: &mut String,
 ) -> Result<()> {
     let err_context = "failed to format changed styles to VTE string";
 
-    if let Some(new_styles) =
+    if let Some(mut new_styles) =
         character_styles.update_and_return_diff(&current_character_styles, chunk_changed_colors)
     {
+        downsample_styles(&mut new_styles, color_depth);
         if let Some(osc8_link) =
             link_handler.and_then(|l_h| l_h.output_osc8(new_styles.link_anchor))
         {
@@ -106,26 +112,54 @@ fn serialize_chunks_with_newlines(
                 current_character_styles,
                 chunk_changed_colors,
                 link_handler.as_ref(),
+                ColorDepth::TrueColor,
                 &mut vte_output,
             )
             .with_context(err_context)?;
             chunk_width += t_character.width();
-            vte_output.push(t_character.character);
+            t_character.character.push_to(&mut vte_output);
         }
     }
     Ok(vte_output)
 }
+#[allow(clippy::too_many_arguments)]
 fn serialize_chunks(
     character_chunks: Vec<CharacterChunk>,
     sixel_chunks: Option<&Vec<SixelImageChunk>>,
     link_handler: Option<&mut Rc<RefCell<LinkHandler>>>,
-    sixel_image_store: Option<&mut SixelImageStore>,
+    mut sixel_image_store: Option<&mut SixelImageStore>,
     styled_underlines: bool,
+    character_cell_size: Option<SizeInPixels>,
+    kitty_graphics_client: Option<ClientId>, // the client, if its terminal supports kitty graphics
+    color_depth: ColorDepth,
 ) -> Result<String> {
     let err_context = || "failed to serialize input chunks".to_string();
 
     let mut vte_output = String::new();
     let mut sixel_vte: Option<String> = None;
+    if let (Some(sixel_image_store), Some(character_cell_size), Some(client_id)) = (
+        sixel_image_store.as_deref_mut(),
+        character_cell_size,
+        kitty_graphics_client,
+    ) {
+        // text does not hide kitty images in the terminal, so we delete the parts of them that
+        // it is drawn over
+        let covered_cells: Vec<(usize, usize, usize)> = character_chunks
+            .iter()
+            .map(|c| {
+                let width = c.terminal_characters.iter().map(|t| t.width()).sum();
+                (c.x, c.y, width)
+            })
+            .collect();
+        let kitty_deletions = sixel_image_store.uncover_kitty_placements(
+            client_id,
+            &covered_cells,
+            character_cell_size,
+        );
+        if !kitty_deletions.is_empty() {
+            sixel_vte = Some(kitty_deletions);
+        }
+    }
     let link_handler = link_handler.map(|l_h| l_h.borrow());
     for character_chunk in character_chunks {
         let chunk_changed_colors = character_chunk.changed_colors();
@@ -145,23 +179,41 @@ fn serialize_chunks(
                 current_character_styles,
                 chunk_changed_colors,
                 link_handler.as_ref(),
+                color_depth,
                 &mut vte_output,
             )
             .with_context(err_context)?;
             chunk_width += t_character.width();
-            vte_output.push(t_character.character);
+            t_character.character.push_to(&mut vte_output);
         }
     }
     if let Some(sixel_image_store) = sixel_image_store {
         if let Some(sixel_chunks) = sixel_chunks {
             for sixel_chunk in sixel_chunks {
-                let serialized_sixel_image = sixel_image_store.serialize_image(
-                    sixel_chunk.sixel_image_id,
-                    sixel_chunk.sixel_image_pixel_x,
-                    sixel_chunk.sixel_image_pixel_y,
-                    sixel_chunk.sixel_image_pixel_width,
-                    sixel_chunk.sixel_image_pixel_height,
-                );
+                let serialized_sixel_image = match character_cell_size {
+                    Some(character_cell_size)
+                        if sixel_image_store.is_kitty_placement(sixel_chunk.sixel_image_id) =>
+                    {
+                        match kitty_graphics_client {
+                            Some(client_id) => sixel_image_store.serialize_kitty_placement(
+                                client_id,
+                                sixel_chunk,
+                                character_cell_size,
+                            ),
+                            None => sixel_image_store.serialize_kitty_placement_fallback(
+                                sixel_chunk,
+                                character_cell_size,
+                            ),
+                        }
+                    },
+                    _ => sixel_image_store.serialize_image(
+                        sixel_chunk.sixel_image_id,
+                        sixel_chunk.sixel_image_pixel_x,
+                        sixel_chunk.sixel_image_pixel_y,
+                        sixel_chunk.sixel_image_pixel_width,
+                        sixel_chunk.sixel_image_pixel_height,
+                    ),
+                };
                 if let Some(serialized_sixel_image) = serialized_sixel_image {
                     let sixel_vte = sixel_vte.get_or_insert_with(String::new);
                     vte_goto_instruction(sixel_chunk.cell_x, sixel_chunk.cell_y, sixel_vte)
@@ -246,6 +298,8 @@ pub struct Output {
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     floating_panes_stack: Option<FloatingPanesStack>,
     styled_underlines: bool,
+    kitty_graphics_clients: HashSet<ClientId>,
+    client_color_depths: HashMap<ClientId, ColorDepth>,
 }
 
 impl Output {
@@ -253,11 +307,15 @@ impl Output {
         sixel_image_store: Rc<RefCell<SixelImageStore>>,
         character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
         styled_underlines: bool,
+        kitty_graphics_clients: HashSet<ClientId>,
+        client_color_depths: HashMap<ClientId, ColorDepth>,
     ) -> Self {
         Output {
             sixel_image_store,
             character_cell_size,
             styled_underlines,
+            kitty_graphics_clients,
+            client_color_depths,
             ..Default::default()
         }
     }
@@ -421,6 +479,12 @@ impl Output {
                     self.link_handler.as_mut(),
                     Some(&mut self.sixel_image_store.borrow_mut()),
                     self.styled_underlines,
+                    *self.character_cell_size.borrow(),
+                    Some(client_id).filter(|c| self.kitty_graphics_clients.contains(c)),
+                    self.client_color_depths
+                        .get(&client_id)
+                        .copied()
+                        .unwrap_or_default(),
                 )
                 .with_context(err_context)?,
             ); // TODO: less allocations?
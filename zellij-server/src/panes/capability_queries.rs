@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+// nothing we answer to comes close to this, longer queries are most likely garbage
+const MAX_DCS_QUERY_LENGTH: usize = 1024;
+
+/// A query for a capability or a setting of the terminal sent in a DCS string, the payload of
+/// which is collected until the string ends
+pub enum DcsQuery {
+    /// XTGETTCAP (DCS + q Pt ST), asking for terminfo capabilities by their hex encoded names
+    Termcap(Vec<u8>),
+    /// DECRQSS (DCS $ q Pt ST), asking for the current value of a setting
+    Setting(Vec<u8>),
+}
+
+impl DcsQuery {
+    pub fn new(intermediates: &[u8], c: char) -> Option<Self> {
+        match (intermediates, c) {
+            ([b'+'], 'q') => Some(DcsQuery::Termcap(vec![])),
+            ([b'$'], 'q') => Some(DcsQuery::Setting(vec![])),
+            _ => None,
+        }
+    }
+    pub fn push(&mut self, byte: u8) {
+        let payload = match self {
+            DcsQuery::Termcap(payload) | DcsQuery::Setting(payload) => payload,
+        };
+        if payload.len() < MAX_DCS_QUERY_LENGTH {
+            payload.push(byte);
+        }
+    }
+}
+
+enum Capability {
+    Flag,
+    Value(&'static str),
+}
+
+/// The terminfo capabilities of the terminal we emulate, by their terminfo or termcap name
+fn capability(name: &str, styled_underlines: bool) -> Option<Capability> {
+    let capability = match name {
+        "Co" | "colors" => Capability::Value("256"),
+        "RGB" | "Tc" => Capability::Flag,
+        "setrgbf" => Capability::Value("\u{1b}[38;2;%p1%d;%p2%d;%p3%dm"),
+        "setrgbb" => Capability::Value("\u{1b}[48;2;%p1%d;%p2%d;%p3%dm"),
+        "Smulx" if styled_underlines => Capability::Value("\u{1b}[4:%p1%dm"),
+        "Setulc" if styled_underlines => {
+            Capability::Value("\u{1b}[58;2;%p1%{65536}%/%d;%p1%{256}%/%{255}%&%d;%p1%{255}%&%dm")
+        },
+        "Ss" => Capability::Value("\u{1b}[%p1%d q"),
+        "Se" => Capability::Value("\u{1b}[0 q"),
+        "Sync" => Capability::Value("\u{1b}[?2026%?%p1%{1}%-%tl%eh%;"),
+        "Ms" => Capability::Value("\u{1b}]52;%p1%s;%p2%s\u{7}"),
+        _ => return None,
+    };
+    Some(capability)
+}
+
+/// Answers each of the capabilities asked for in an XTGETTCAP query in a DCS string of its own,
+/// with the value of the ones we have and as invalid the ones we don't
+pub fn termcap_response(payload: &[u8], styled_underlines: bool) -> String {
+    let mut response = String::new();
+    for hex_name in payload.split(|byte| *byte == b';') {
+        let hex_name = String::from_utf8_lossy(hex_name);
+        let capability =
+            hex_decode(&hex_name).and_then(|name| capability(&name, styled_underlines));
+        let _ = match capability {
+            Some(Capability::Flag) => write!(response, "\u{1b}P1+r{}\u{1b}\\", hex_name),
+            Some(Capability::Value(value)) => write!(
+                response,
+                "\u{1b}P1+r{}={}\u{1b}\\",
+                hex_name,
+                hex_encode(value)
+            ),
+            None => write!(response, "\u{1b}P0+r{}\u{1b}\\", hex_name),
+        };
+    }
+    response
+}
+
+/// Answers a DECRQSS query with the setting, if we know it, or as invalid
+pub fn setting_response(setting: Option<String>) -> String {
+    match setting {
+        Some(setting) => format!("\u{1b}P1$r{}\u{1b}\\", setting),
+        None => "\u{1b}P0$r\u{1b}\\".to_owned(),
+    }
+}
+
+fn hex_decode(hex: &str) -> Option<String> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+fn hex_encode(text: &str) -> String {
+    text.bytes().map(|byte| format!("{:02X}", byte)).collect()
+}
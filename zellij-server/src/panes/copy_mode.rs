@@ -0,0 +1,495 @@
+use crate::panes::selection::Selection;
+use crate::panes::{Grid, Row};
+use std::borrow::Cow;
+use zellij_utils::input::actions::{CopyModeMotion, CopyModeSelection, SearchDirection};
+use zellij_utils::position::Position;
+
+// Word motions stop wherever the class of character changes, like vi's lowercase word motions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharacterClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+impl CharacterClass {
+    fn of(character: Option<char>) -> Self {
+        match character {
+            Some(c) if c.is_alphanumeric() || c == '_' => CharacterClass::Word,
+            Some(c) if !c.is_whitespace() => CharacterClass::Punctuation,
+            _ => CharacterClass::Whitespace,
+        }
+    }
+}
+
+/// The keyboard cursor and selection anchor of a pane in copy mode.
+///
+/// Both positions are relative to the viewport and are moved along with it when it scrolls, so
+/// the anchor of a selection can end up above or below the visible lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyMode {
+    pub cursor: Position,
+    anchor: Option<(Position, CopyModeSelection)>,
+}
+
+impl CopyMode {
+    pub fn new(cursor: Position) -> Self {
+        CopyMode {
+            cursor,
+            anchor: None,
+        }
+    }
+
+    pub fn move_up(&mut self, lines: usize) {
+        self.cursor.line.0 -= lines as isize;
+        if let Some((anchor, _)) = self.anchor.as_mut() {
+            anchor.line.0 -= lines as isize;
+        }
+    }
+
+    pub fn move_down(&mut self, lines: usize) {
+        self.cursor.line.0 += lines as isize;
+        if let Some((anchor, _)) = self.anchor.as_mut() {
+            anchor.line.0 += lines as isize;
+        }
+    }
+}
+
+impl Grid {
+    pub fn start_copy_mode(&mut self) {
+        let active_search_result = self.search_results.active.map(|active| active.start);
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            // we are coming back from searching inside copy mode
+            if let Some(search_result) = active_search_result {
+                copy_mode.cursor = search_result;
+            }
+        } else {
+            let cursor = active_search_result.unwrap_or_else(|| {
+                if self.is_scrolled {
+                    Position::new(self.viewport.len().saturating_sub(1) as i32, 0)
+                } else {
+                    Position::new(self.cursor.y as i32, self.cursor.x as u16)
+                }
+            });
+            self.copy_mode = Some(CopyMode::new(cursor));
+        }
+        // we freeze the pane while in copy mode so that incoming output does not move the text
+        // out from under the cursor
+        self.is_scrolled = true;
+        self.copy_mode_adjust_cursor();
+        self.copy_mode_update_selection();
+        self.output_buffer.update_all_lines();
+    }
+
+    pub fn exit_copy_mode(&mut self) {
+        if let Some(copy_mode) = self.copy_mode.take() {
+            if copy_mode.anchor.is_some() {
+                self.reset_selection();
+            }
+        }
+        if self.lines_below.is_empty() {
+            self.is_scrolled = false;
+        }
+        self.output_buffer.update_all_lines();
+    }
+
+    pub fn copy_mode_motion(&mut self, motion: CopyModeMotion) {
+        if self.copy_mode.is_none() {
+            return;
+        }
+        match motion {
+            CopyModeMotion::Left => self.copy_mode_move_left(),
+            CopyModeMotion::Right => self.copy_mode_move_right(),
+            CopyModeMotion::Up => self.copy_mode_move_up(),
+            CopyModeMotion::Down => self.copy_mode_move_down(),
+            CopyModeMotion::WordForward => self.copy_mode_word_forward(),
+            CopyModeMotion::WordBackward => self.copy_mode_word_backward(),
+            CopyModeMotion::WordEnd => self.copy_mode_word_end(),
+            CopyModeMotion::LineStart => {
+                let cursor = self.copy_mode_cursor();
+                self.set_copy_mode_cursor(Position::new(cursor.line() as i32, 0));
+            },
+            CopyModeMotion::LineEnd => {
+                let cursor = self.copy_mode_cursor();
+                let row_width = self
+                    .viewport
+                    .get(cursor.line() as usize)
+                    .map(|row| row.width())
+                    .unwrap_or(0);
+                self.set_copy_mode_cursor(Position::new(
+                    cursor.line() as i32,
+                    row_width.saturating_sub(1) as u16,
+                ));
+            },
+            CopyModeMotion::Top => {
+                self.scroll_to_top();
+                self.set_copy_mode_cursor(Position::new(0, 0));
+            },
+            CopyModeMotion::Bottom => {
+                self.scroll_to_bottom();
+                let last_line = self.viewport.len().saturating_sub(1);
+                self.set_copy_mode_cursor(Position::new(last_line as i32, 0));
+            },
+            CopyModeMotion::SearchNext => self.copy_mode_search(SearchDirection::Down),
+            CopyModeMotion::SearchPrevious => self.copy_mode_search(SearchDirection::Up),
+        }
+        self.copy_mode_adjust_cursor();
+        self.copy_mode_update_selection();
+        self.output_buffer.update_all_lines();
+    }
+
+    pub fn copy_mode_toggle_selection(&mut self, selection: CopyModeSelection) {
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            copy_mode.anchor = match copy_mode.anchor {
+                Some((_, current_selection)) if current_selection == selection => None,
+                Some((anchor, _)) => Some((anchor, selection)),
+                None => Some((copy_mode.cursor, selection)),
+            };
+            self.copy_mode_update_selection();
+            self.output_buffer.update_all_lines();
+        }
+    }
+
+    pub fn copy_mode_clear_selection(&mut self) {
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            copy_mode.anchor = None;
+            self.reset_selection();
+            self.output_buffer.update_all_lines();
+        }
+    }
+
+    /// The part of the block selection (if any) that is on the given viewport row
+    pub(crate) fn copy_mode_block_in_row(&self, row: usize) -> Option<Selection> {
+        let block = self.copy_mode_block()?;
+        if !block.contains_row(row) {
+            return None;
+        }
+        let mut row_selection = Selection::default();
+        row_selection.start(Position::new(row as i32, block.start.column() as u16));
+        row_selection.end(Position::new(row as i32, block.end.column() as u16));
+        Some(row_selection)
+    }
+
+    pub(crate) fn copy_mode_block_text(&self) -> Option<String> {
+        let block = self.copy_mode_block()?;
+        let (start_column, end_column) = (block.start.column(), block.end.column());
+        let lines: Vec<String> = block
+            .line_indices()
+            .filter_map(|line| self.copy_mode_row(line))
+            .map(|row| {
+                let mut line_selection = String::new();
+                let mut terminal_col = 0;
+                for terminal_character in &row.columns {
+                    if (start_column..end_column).contains(&terminal_col) {
+                        terminal_character.character.push_to(&mut line_selection);
+                    }
+                    terminal_col += terminal_character.width();
+                }
+                line_selection.trim_end().to_owned()
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    fn copy_mode_block(&self) -> Option<Selection> {
+        let copy_mode = self.copy_mode?;
+        match copy_mode.anchor? {
+            (anchor, CopyModeSelection::Block) => {
+                let cursor = copy_mode.cursor;
+                let mut block = Selection::default();
+                block.start(Position::new(
+                    anchor.line().min(cursor.line()) as i32,
+                    anchor.column().min(cursor.column()) as u16,
+                ));
+                block.end(Position::new(
+                    anchor.line().max(cursor.line()) as i32,
+                    (anchor.column().max(cursor.column()) + 1) as u16,
+                ));
+                Some(block)
+            },
+            _ => None,
+        }
+    }
+
+    fn copy_mode_row(&self, line: isize) -> Option<Cow<'_, Row>> {
+        if line < 0 {
+            self.lines_above
+                .len()
+                .checked_sub(line.unsigned_abs())
+                .and_then(|index| self.lines_above.get(index))
+        } else if (line as usize) < self.viewport.len() {
+            self.viewport.get(line as usize).map(Cow::Borrowed)
+        } else {
+            self.lines_below
+                .get(line as usize - self.viewport.len())
+                .map(Cow::Borrowed)
+        }
+    }
+
+    fn copy_mode_update_selection(&mut self) {
+        let copy_mode = match self.copy_mode {
+            Some(copy_mode) => copy_mode,
+            None => return,
+        };
+        let cursor = copy_mode.cursor;
+        match copy_mode.anchor {
+            Some((anchor, CopyModeSelection::Char)) => {
+                let (start, end) = if anchor <= cursor {
+                    (anchor, cursor)
+                } else {
+                    (cursor, anchor)
+                };
+                // the selection includes the character under its end
+                let end_width = if end.line() >= 0 {
+                    self.copy_mode_character_at(end.line() as usize, end.column())
+                        .map(|(_, _, width)| width)
+                        .unwrap_or(1)
+                } else {
+                    1
+                };
+                self.selection.start(start);
+                self.selection.end(Position::new(
+                    end.line() as i32,
+                    (end.column() + end_width) as u16,
+                ));
+            },
+            Some((anchor, CopyModeSelection::Line)) => {
+                let start_line = anchor.line().min(cursor.line());
+                let end_line = anchor.line().max(cursor.line());
+                self.selection.start(Position::new(start_line as i32, 0));
+                self.selection
+                    .end(Position::new(end_line as i32, self.width as u16));
+            },
+            Some((_, CopyModeSelection::Block)) | None => self.selection.reset(),
+        }
+    }
+
+    fn copy_mode_cursor(&self) -> Position {
+        self.copy_mode
+            .map(|copy_mode| copy_mode.cursor)
+            .unwrap_or_else(|| Position::new(0, 0))
+    }
+
+    fn set_copy_mode_cursor(&mut self, cursor: Position) {
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            copy_mode.cursor = cursor;
+        }
+    }
+
+    fn copy_mode_can_scroll_up(&self) -> bool {
+        !self.lines_above.is_empty() && self.viewport.len() == self.height
+    }
+
+    fn copy_mode_can_scroll_down(&self) -> bool {
+        !self.lines_below.is_empty() && self.viewport.len() == self.height
+    }
+
+    // keep the cursor inside the viewport and on the first column of wide characters
+    fn copy_mode_adjust_cursor(&mut self) {
+        let cursor = self.copy_mode_cursor();
+        let last_line = self.viewport.len().saturating_sub(1);
+        let line = (cursor.line().max(0) as usize).min(last_line);
+        let column = cursor.column().min(self.width.saturating_sub(1));
+        let column = self
+            .copy_mode_character_at(line, column)
+            .map(|(_, start, _)| start)
+            .unwrap_or(column);
+        self.set_copy_mode_cursor(Position::new(line as i32, column as u16));
+    }
+
+    /// The character covering `column` on the viewport line `line`, along with the column it
+    /// starts at and its width
+    fn copy_mode_character_at(&self, line: usize, column: usize) -> Option<(char, usize, usize)> {
+        let row = self.viewport.get(line)?;
+        let mut start = 0;
+        for terminal_character in &row.columns {
+            let width = terminal_character.width();
+            if column < start + width {
+                return Some((terminal_character.character.base_char(), start, width));
+            }
+            start += width;
+        }
+        None
+    }
+
+    fn copy_mode_class_at(&self, position: Position) -> CharacterClass {
+        if position.line() < 0 {
+            return CharacterClass::Whitespace;
+        }
+        CharacterClass::of(
+            self.copy_mode_character_at(position.line() as usize, position.column())
+                .map(|(character, _, _)| character),
+        )
+    }
+
+    fn copy_mode_move_left(&mut self) {
+        let cursor = self.copy_mode_cursor();
+        let column = cursor.column().saturating_sub(1);
+        let column = self
+            .copy_mode_character_at(cursor.line() as usize, column)
+            .map(|(_, start, _)| start)
+            .unwrap_or(column);
+        self.set_copy_mode_cursor(Position::new(cursor.line() as i32, column as u16));
+    }
+
+    fn copy_mode_move_right(&mut self) {
+        let cursor = self.copy_mode_cursor();
+        let column = self
+            .copy_mode_character_at(cursor.line() as usize, cursor.column())
+            .map(|(_, start, width)| start + width)
+            .unwrap_or(cursor.column() + 1);
+        self.set_copy_mode_cursor(Position::new(cursor.line() as i32, column as u16));
+    }
+
+    fn copy_mode_move_up(&mut self) {
+        if self.copy_mode_cursor().line() == 0 && self.copy_mode_can_scroll_up() {
+            self.scroll_up_one_line();
+        }
+        let cursor = self.copy_mode_cursor();
+        if cursor.line() > 0 {
+            self.set_copy_mode_cursor(Position::new(
+                cursor.line() as i32 - 1,
+                cursor.column() as u16,
+            ));
+        }
+    }
+
+    fn copy_mode_move_down(&mut self) {
+        if self.copy_mode_cursor().line() as usize + 1 >= self.viewport.len()
+            && self.copy_mode_can_scroll_down()
+        {
+            self.scroll_down_one_line();
+        }
+        let cursor = self.copy_mode_cursor();
+        if (cursor.line() as usize) + 1 < self.viewport.len() {
+            self.set_copy_mode_cursor(Position::new(
+                cursor.line() as i32 + 1,
+                cursor.column() as u16,
+            ));
+        }
+    }
+
+    /// The position after the cursor, scrolling the viewport down if the cursor is on its last
+    /// line. The returned bool indicates whether a canonical line break was crossed to get there.
+    fn copy_mode_next_position(&mut self) -> Option<(Position, bool)> {
+        let cursor = self.copy_mode_cursor();
+        let line = cursor.line() as usize;
+        let next_column = self
+            .copy_mode_character_at(line, cursor.column())
+            .map(|(_, start, width)| start + width)
+            .unwrap_or(cursor.column() + 1);
+        let row_width = self.viewport.get(line).map(|row| row.width()).unwrap_or(0);
+        if next_column < row_width {
+            return Some((Position::new(line as i32, next_column as u16), false));
+        }
+        if line + 1 >= self.viewport.len() && self.copy_mode_can_scroll_down() {
+            self.scroll_down_one_line();
+        }
+        let line = self.copy_mode_cursor().line() as usize;
+        self.viewport
+            .get(line + 1)
+            .map(|row| (Position::new(line as i32 + 1, 0), row.is_canonical))
+    }
+
+    /// The position before the cursor, scrolling the viewport up if the cursor is on its first
+    /// line. The returned bool indicates whether a canonical line break was crossed to get there.
+    fn copy_mode_previous_position(&mut self) -> Option<(Position, bool)> {
+        let cursor = self.copy_mode_cursor();
+        let line = cursor.line() as usize;
+        if cursor.column() > 0 {
+            let column = self
+                .copy_mode_character_at(line, cursor.column() - 1)
+                .map(|(_, start, _)| start)
+                .unwrap_or(cursor.column() - 1);
+            return Some((Position::new(line as i32, column as u16), false));
+        }
+        if line == 0 && self.copy_mode_can_scroll_up() {
+            self.scroll_up_one_line();
+        }
+        let line = self.copy_mode_cursor().line() as usize;
+        if line == 0 {
+            return None;
+        }
+        let crossed_line_break = self
+            .viewport
+            .get(line)
+            .map(|row| row.is_canonical)
+            .unwrap_or(true);
+        let previous_row_width = self.viewport[line - 1].width();
+        let column = self
+            .copy_mode_character_at(line - 1, previous_row_width.saturating_sub(1))
+            .map(|(_, start, _)| start)
+            .unwrap_or(0);
+        Some((
+            Position::new(line as i32 - 1, column as u16),
+            crossed_line_break,
+        ))
+    }
+
+    fn copy_mode_word_forward(&mut self) {
+        let mut class = self.copy_mode_class_at(self.copy_mode_cursor());
+        while let Some((position, crossed_line_break)) = self.copy_mode_next_position() {
+            let next_class = self.copy_mode_class_at(position);
+            self.set_copy_mode_cursor(position);
+            if next_class != CharacterClass::Whitespace
+                && (crossed_line_break || next_class != class)
+            {
+                break;
+            }
+            class = next_class;
+        }
+    }
+
+    fn copy_mode_word_end(&mut self) {
+        match self.copy_mode_next_position() {
+            Some((position, _)) => self.set_copy_mode_cursor(position),
+            None => return,
+        }
+        while self.copy_mode_class_at(self.copy_mode_cursor()) == CharacterClass::Whitespace {
+            match self.copy_mode_next_position() {
+                Some((position, _)) => self.set_copy_mode_cursor(position),
+                None => return,
+            }
+        }
+        let class = self.copy_mode_class_at(self.copy_mode_cursor());
+        while let Some((position, crossed_line_break)) = self.copy_mode_next_position() {
+            if crossed_line_break || self.copy_mode_class_at(position) != class {
+                break;
+            }
+            self.set_copy_mode_cursor(position);
+        }
+    }
+
+    fn copy_mode_word_backward(&mut self) {
+        match self.copy_mode_previous_position() {
+            Some((position, _)) => self.set_copy_mode_cursor(position),
+            None => return,
+        }
+        while self.copy_mode_class_at(self.copy_mode_cursor()) == CharacterClass::Whitespace {
+            match self.copy_mode_previous_position() {
+                Some((position, _)) => self.set_copy_mode_cursor(position),
+                None => return,
+            }
+        }
+        let class = self.copy_mode_class_at(self.copy_mode_cursor());
+        while let Some((position, crossed_line_break)) = self.copy_mode_previous_position() {
+            if crossed_line_break || self.copy_mode_class_at(position) != class {
+                break;
+            }
+            self.set_copy_mode_cursor(position);
+        }
+    }
+
+    fn copy_mode_search(&mut self, direction: SearchDirection) {
+        if self.search_results.needle.is_empty() {
+            return;
+        }
+        match direction {
+            SearchDirection::Down => self.search_down(),
+            SearchDirection::Up => self.search_up(),
+        }
+        if let Some(active) = self.search_results.active {
+            self.set_copy_mode_cursor(active.start);
+        }
+    }
+}
@@ -289,6 +289,13 @@ impl FloatingPanes {
     pub fn first_active_floating_pane_id(&self) -> Option<PaneId> {
         self.active_panes.values().next().copied()
     }
+    pub fn reconfigure(&mut self, style: Style, default_mode_info: ModeInfo) {
+        self.style = style;
+        self.default_mode_info = default_mode_info;
+        for pane in self.panes.values_mut() {
+            pane.update_style(style);
+        }
+    }
     pub fn set_force_render(&mut self) {
         for pane in self.panes.values_mut() {
             pane.set_should_render(true);
@@ -351,14 +358,18 @@ impl FloatingPanes {
                 true,
             );
             for client_id in &connected_clients {
-                let client_mode = self
-                    .mode_info
-                    .borrow()
-                    .get(client_id)
-                    .unwrap_or(&self.default_mode_info)
-                    .mode;
+                let (client_mode, client_style) = {
+                    let mode_infos = self.mode_info.borrow();
+                    let mode_info = mode_infos.get(client_id).unwrap_or(&self.default_mode_info);
+                    (mode_info.mode, mode_info.style)
+                };
                 pane_contents_and_ui
-                    .render_pane_frame(*client_id, client_mode, self.session_is_mirrored)
+                    .render_pane_frame(
+                        *client_id,
+                        client_mode,
+                        client_style,
+                        self.session_is_mirrored,
+                    )
                     .with_context(err_context)?;
                 if let PaneId::Plugin(..) = kind {
                     pane_contents_and_ui
@@ -404,16 +415,30 @@ impl FloatingPanes {
     pub fn resize_active_pane(
         &mut self,
         client_id: ClientId,
+        os_api: &mut Box<dyn ServerOsApi>,
+        strategy: &ResizeStrategy,
+    ) -> Result<bool> {
+        // true => successfully resized
+        match self.active_panes.get(&client_id).copied() {
+            Some(active_floating_pane_id) => {
+                self.resize_pane_with_id(active_floating_pane_id, os_api, strategy)
+            },
+            None => Ok(false),
+        }
+    }
+
+    pub fn resize_pane_with_id(
+        &mut self,
+        pane_id: PaneId,
         _os_api: &mut Box<dyn ServerOsApi>,
         strategy: &ResizeStrategy,
     ) -> Result<bool> {
         // true => successfully resized
-        let err_context =
-            || format!("failed to {strategy} for active floating pane for client {client_id}");
+        let err_context = || format!("failed to {strategy} for floating pane {pane_id:?}");
 
         let display_area = *self.display_area.borrow();
         let viewport = *self.viewport.borrow();
-        if let Some(active_floating_pane_id) = self.active_panes.get(&client_id) {
+        if self.panes.contains_key(&pane_id) {
             let mut floating_pane_grid = FloatingPaneGrid::new(
                 &mut self.panes,
                 &mut self.desired_pane_positions,
@@ -422,7 +447,7 @@ impl FloatingPanes {
             );
             floating_pane_grid
                 .change_pane_size(
-                    active_floating_pane_id,
+                    &pane_id,
                     strategy,
                     (RESIZE_INCREMENT_WIDTH, RESIZE_INCREMENT_HEIGHT),
                 )
@@ -807,6 +832,9 @@ impl FloatingPanes {
     pub fn get_active_pane_id(&self, client_id: ClientId) -> Option<PaneId> {
         self.active_panes.get(&client_id).copied()
     }
+    pub fn get_panes_mut(&mut self) -> impl Iterator<Item = (&PaneId, &mut Box<dyn Pane>)> {
+        self.panes.iter_mut()
+    }
     pub fn get_panes(&self) -> impl Iterator<Item = (&PaneId, &Box<dyn Pane>)> {
         self.panes.iter()
     }
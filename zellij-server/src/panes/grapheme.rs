@@ -0,0 +1,322 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::FromIterator;
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+// clusters made of a character followed by one of these (the most common ones by far: letters
+// with a combining accent, emoji with a presentation selector or a skin tone, flags and the start
+// of zwj sequences) are kept inline, the other clusters of more than one character are interned,
+// and the rest of them (almost all of them) are single chars - so that a cell is as small as it
+// was when it held a single char
+const INLINE_SECOND_CHARACTERS: &[(char, char)] = &[
+    ('\u{300}', '\u{36F}'),     // combining diacritical marks
+    ('\u{200D}', '\u{200D}'),   // zero width joiner
+    ('\u{FE0E}', '\u{FE0F}'),   // text and emoji presentation selectors
+    ('\u{1F1E6}', '\u{1F1FF}'), // regional indicators
+    ('\u{1F3FB}', '\u{1F3FF}'), // skin tone modifiers
+];
+const FIRST_INTERNED_INDEX: u32 = char::MAX as u32 + 1;
+const FIRST_INLINE_INDEX: u32 = 1 << 31; // the base char in the low bits, the second one above
+const CHAR_BITS: u32 = 21;
+const MAX_INTERNED_GRAPHEMES: usize = 1 << 16;
+// longer clusters (eg. a character followed by a pile of combining marks) are cut here
+const MAX_GRAPHEME_LENGTH: usize = 64;
+// no character below this one can join another one below it into a grapheme cluster, so
+// checking this is enough for the common case of ascii text
+const FIRST_COMBINING_CHARACTER: char = '\u{300}';
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{FE0F}';
+
+thread_local! {
+    // like the scrollback, all the grids of a session live on the screen thread
+    static INTERNED_GRAPHEMES: RefCell<InternedGraphemes> = RefCell::new(InternedGraphemes::default());
+}
+
+/// The clusters that cannot be kept inline in a `Grapheme`.
+///
+/// Interned clusters are never evicted: cells only hold their index and are freely copied, so
+/// there is no telling when one is no longer used. They are only freed along with the thread
+/// (rather than leaked for the life of the process). Since the common clusters are kept inline
+/// there are few distinct ones in practice, and at most MAX_INTERNED_GRAPHEMES of them
+#[derive(Default)]
+struct InternedGraphemes {
+    clusters: Vec<Rc<str>>,
+    indices: HashMap<Rc<str>, u32>,
+    is_full: bool, // so that we only warn about it once
+}
+
+/// An extended grapheme cluster, the text of one terminal cell: a single character or a
+/// sequence of them that is displayed as one, like an emoji with a skin tone modifier, a flag or
+/// a letter followed by combining marks.
+///
+/// This is 4 bytes like a char. Clusters that cannot be kept inline are interned on the thread
+/// that created them (for as long as it lives), and should not be used on another one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Grapheme(u32);
+
+enum GraphemeKind {
+    Char(char),
+    Inline(char, char),
+    Interned(Rc<str>),
+}
+
+impl Grapheme {
+    #[inline]
+    pub const fn from_char(character: char) -> Self {
+        Grapheme(character as u32)
+    }
+    /// Falls back to the first character of the cluster if it cannot be interned, and to a space
+    /// if it is empty
+    pub fn new(cluster: &str) -> Self {
+        match cluster.chars().next() {
+            Some(character) => {
+                Grapheme::from_cluster(cluster).unwrap_or(Grapheme::from_char(character))
+            },
+            None => Grapheme::from_char(' '),
+        }
+    }
+    // every cluster must always end up with the same representation, since they're compared by it
+    fn from_cluster(cluster: &str) -> Option<Self> {
+        let mut characters = cluster.chars();
+        match (characters.next(), characters.next(), characters.next()) {
+            (Some(character), None, _) => Some(Grapheme::from_char(character)),
+            (Some(character), Some(second_character), None) => {
+                Grapheme::inline(character, second_character).or_else(|| Grapheme::intern(cluster))
+            },
+            (Some(_), Some(_), Some(_)) => Grapheme::intern(cluster),
+            (None, ..) => None,
+        }
+    }
+    fn inline(character: char, second_character: char) -> Option<Self> {
+        let mut offset = 0;
+        for (first, last) in INLINE_SECOND_CHARACTERS {
+            if (*first..=*last).contains(&second_character) {
+                let index = offset + (second_character as u32 - *first as u32);
+                return Some(Grapheme(
+                    FIRST_INLINE_INDEX | (index << CHAR_BITS) | character as u32,
+                ));
+            }
+            offset += *last as u32 - *first as u32 + 1;
+        }
+        None
+    }
+    fn intern(cluster: &str) -> Option<Self> {
+        if cluster.len() > MAX_GRAPHEME_LENGTH {
+            return None;
+        }
+        INTERNED_GRAPHEMES.with(|interned_graphemes| {
+            let mut interned_graphemes = interned_graphemes.borrow_mut();
+            if let Some(index) = interned_graphemes.indices.get(cluster) {
+                return Some(Grapheme(FIRST_INTERNED_INDEX + index));
+            }
+            if interned_graphemes.clusters.len() >= MAX_INTERNED_GRAPHEMES {
+                if !interned_graphemes.is_full {
+                    log::warn!(
+                        "Too many distinct grapheme clusters, not joining characters anymore"
+                    );
+                    interned_graphemes.is_full = true;
+                }
+                return None;
+            }
+            let index = interned_graphemes.clusters.len() as u32;
+            let cluster: Rc<str> = Rc::from(cluster);
+            interned_graphemes.clusters.push(cluster.clone());
+            interned_graphemes.indices.insert(cluster, index);
+            Some(Grapheme(FIRST_INTERNED_INDEX + index))
+        })
+    }
+    fn kind(self) -> GraphemeKind {
+        if self.0 < FIRST_INTERNED_INDEX {
+            GraphemeKind::Char(char::from_u32(self.0).unwrap_or(' '))
+        } else if self.0 < FIRST_INLINE_INDEX {
+            let cluster = INTERNED_GRAPHEMES.with(|interned_graphemes| {
+                interned_graphemes
+                    .borrow()
+                    .clusters
+                    .get((self.0 - FIRST_INTERNED_INDEX) as usize)
+                    .cloned()
+            });
+            GraphemeKind::Interned(cluster.unwrap_or_else(|| Rc::from(" ")))
+        } else {
+            let character = char::from_u32(self.0 & ((1 << CHAR_BITS) - 1)).unwrap_or(' ');
+            let mut index = (self.0 & !FIRST_INLINE_INDEX) >> CHAR_BITS;
+            for (first, last) in INLINE_SECOND_CHARACTERS {
+                let range_length = *last as u32 - *first as u32 + 1;
+                if index < range_length {
+                    let second_character = char::from_u32(*first as u32 + index).unwrap_or(' ');
+                    return GraphemeKind::Inline(character, second_character);
+                }
+                index -= range_length;
+            }
+            GraphemeKind::Char(character)
+        }
+    }
+    /// The character, if this cluster is made of a single one
+    #[inline]
+    pub fn as_char(self) -> Option<char> {
+        if self.0 < FIRST_INTERNED_INDEX {
+            char::from_u32(self.0)
+        } else {
+            None
+        }
+    }
+    /// The last character of the cluster, which decides what may still join it
+    fn last_char(self) -> char {
+        match self.kind() {
+            GraphemeKind::Char(character) | GraphemeKind::Inline(_, character) => character,
+            GraphemeKind::Interned(cluster) => cluster.chars().last().unwrap_or(' '),
+        }
+    }
+    /// The first character of the cluster, which decides what kind of text it is (eg. a letter or
+    /// whitespace)
+    pub fn base_char(self) -> char {
+        match self.kind() {
+            GraphemeKind::Char(character) | GraphemeKind::Inline(character, _) => character,
+            GraphemeKind::Interned(cluster) => cluster.chars().next().unwrap_or(' '),
+        }
+    }
+    #[inline]
+    pub fn push_to(self, string: &mut String) {
+        match self.as_char() {
+            Some(character) => string.push(character),
+            None => match self.kind() {
+                GraphemeKind::Char(character) => string.push(character),
+                GraphemeKind::Inline(character, second_character) => {
+                    string.push(character);
+                    string.push(second_character);
+                },
+                GraphemeKind::Interned(cluster) => string.push_str(&cluster),
+            },
+        }
+    }
+    /// How many columns the cluster takes up: the width of its first character, unless it's a
+    /// symbol asked to be displayed as an emoji or a flag, which are always wide
+    pub fn width(self) -> usize {
+        let (base_char, is_flag, has_emoji_presentation_selector) = match self.kind() {
+            GraphemeKind::Char(character) => return character.width().unwrap_or(0),
+            GraphemeKind::Inline(character, second_character) => (
+                character,
+                is_regional_indicator(character) && is_regional_indicator(second_character),
+                second_character == EMOJI_PRESENTATION_SELECTOR,
+            ),
+            GraphemeKind::Interned(cluster) => (
+                cluster.chars().next().unwrap_or(' '),
+                cluster.chars().all(is_regional_indicator),
+                cluster.contains(EMOJI_PRESENTATION_SELECTOR),
+            ),
+        };
+        let is_emoji_presentation = has_emoji_presentation_selector && !base_char.is_alphabetic();
+        if is_flag || is_emoji_presentation {
+            2
+        } else {
+            base_char.width().unwrap_or(0)
+        }
+    }
+    /// The cluster with `character` appended, if it is still a single grapheme cluster
+    pub fn extended_with(self, character: char) -> Option<Self> {
+        if character.is_ascii()
+            || (character < FIRST_COMBINING_CHARACTER && self.0 < FIRST_COMBINING_CHARACTER as u32)
+        {
+            return None;
+        }
+        let always_extends = always_extends_a_cluster(character);
+        if always_extends {
+            // the common case of a single character followed by eg. a combining accent, no need
+            // to segment it
+            if let Some(grapheme) = self
+                .as_char()
+                .and_then(|base_char| Grapheme::inline(base_char, character))
+            {
+                return Some(grapheme);
+            }
+        }
+        if !always_extends && !may_join(self.last_char(), character) {
+            return None;
+        }
+        let mut cluster = String::new();
+        self.push_to(&mut cluster);
+        cluster.push(character);
+        if !always_extends && cluster.graphemes(true).nth(1).is_some() {
+            return None;
+        }
+        Grapheme::from_cluster(&cluster)
+    }
+    pub fn to_ascii_lowercase(self) -> Self {
+        match self.as_char() {
+            Some(character) => Grapheme::from_char(character.to_ascii_lowercase()),
+            None => self,
+        }
+    }
+}
+
+/// Characters that join whatever (printable) character comes before them into a cluster
+fn always_extends_a_cluster(character: char) -> bool {
+    ('\u{300}'..='\u{36F}').contains(&character)
+        || character == '\u{200D}'
+        || ('\u{FE00}'..='\u{FE0F}').contains(&character)
+        || ('\u{1F3FB}'..='\u{1F3FF}').contains(&character)
+}
+
+/// Whether `character` may join a cluster ending with `last_character`, without allocating:
+/// segmenting them as a pair on the stack looks up their grapheme break properties (whether
+/// `character` is an extending character, a zero width joiner, a spacing mark or a regional
+/// indicator, or `last_character` is a prepended one). The rules that look further back (emoji
+/// zwj sequences and indic conjuncts) only join after a zero width character, so those clusters
+/// have to be segmented whole
+fn may_join(last_character: char, character: char) -> bool {
+    if last_character.width().unwrap_or(0) == 0 {
+        return true;
+    }
+    let mut pair = [0; 8];
+    let first_length = last_character.encode_utf8(&mut pair).len();
+    let pair_length = first_length + character.encode_utf8(&mut pair[first_length..]).len();
+    match std::str::from_utf8(&pair[..pair_length]) {
+        Ok(pair) => pair.graphemes(true).nth(1).is_none(),
+        Err(_) => true,
+    }
+}
+
+fn is_regional_indicator(character: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&character)
+}
+
+impl From<char> for Grapheme {
+    #[inline]
+    fn from(character: char) -> Self {
+        Grapheme::from_char(character)
+    }
+}
+
+impl PartialEq<char> for Grapheme {
+    fn eq(&self, other: &char) -> bool {
+        self.0 == *other as u32
+    }
+}
+
+impl Display for Grapheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut cluster = String::new();
+        self.push_to(&mut cluster);
+        f.write_str(&cluster)
+    }
+}
+
+impl Debug for Grapheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut cluster = String::new();
+        self.push_to(&mut cluster);
+        write!(f, "{:?}", cluster)
+    }
+}
+
+impl FromIterator<Grapheme> for String {
+    fn from_iter<I: IntoIterator<Item = Grapheme>>(graphemes: I) -> Self {
+        let mut string = String::new();
+        for grapheme in graphemes {
+            grapheme.push_to(&mut string);
+        }
+        string
+    }
+}
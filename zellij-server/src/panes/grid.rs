@@ -24,15 +24,29 @@ use zellij_utils::{
 
 const TABSTOP_WIDTH: usize = 8; // TODO: is this always right?
 pub const MAX_TITLE_STACK_SIZE: usize = 1000;
+const RESET_STYLES: &str = "\u{1b}[m";
+/// The most we write to disk for the scrollback of a single pane, the oldest lines are dropped
+/// beyond this
+const MAX_SERIALIZED_SCROLLBACK_BYTES: usize = 4 * 1024 * 1024;
 
 use vte::{Params, Perform};
 use zellij_utils::{consts::VERSION, shared::version_number};
 
 use crate::output::{CharacterChunk, OutputBuffer, SixelImageChunk};
 use crate::panes::alacritty_functions::{parse_number, xparse_color};
+use crate::panes::capability_queries::{setting_response, termcap_response, DcsQuery};
+use crate::panes::copy_mode::CopyMode;
+use crate::panes::kitty_graphics::{
+    KittyGraphicsAction, KittyGraphicsCommand, KittyGraphicsFileRead, KittyGraphicsState,
+    KittyImage, KittyPlacement,
+};
+use crate::panes::kitty_keyboard::KittyKeyboardFlags;
 use crate::panes::link_handler::LinkHandler;
+use crate::panes::reported_cwd::ReportedCwd;
+use crate::panes::scrollback::Scrollback;
 use crate::panes::search::SearchResult;
 use crate::panes::selection::Selection;
+use crate::panes::semantic_prompt::{SemanticPromptMark, SemanticPromptMarks};
 use crate::panes::terminal_character::{
     AnsiCode, CharsetIndex, Cursor, CursorShape, RcCharacterStyles, StandardCharset,
     TerminalCharacter, EMPTY_TERMINAL_CHARACTER,
@@ -56,20 +70,16 @@ fn get_top_non_canonical_rows(rows: &mut Vec<Row>) -> Vec<Row> {
     }
 }
 
-fn get_lines_above_bottom_canonical_row_and_wraps(rows: &mut VecDeque<Row>) -> Vec<Row> {
-    let mut index_of_last_non_canonical_row = None;
-    for (i, row) in rows.iter().enumerate().rev() {
-        index_of_last_non_canonical_row = Some(i);
-        if row.is_canonical {
+fn get_lines_above_bottom_canonical_row_and_wraps(rows: &mut Scrollback) -> Vec<Row> {
+    let mut canonical_row_and_wraps = VecDeque::new();
+    while let Some(row) = rows.pop_back() {
+        let is_canonical = row.is_canonical;
+        canonical_row_and_wraps.push_front(row);
+        if is_canonical {
             break;
         }
     }
-    match index_of_last_non_canonical_row {
-        Some(index_of_last_non_canonical_row) => {
-            rows.drain(index_of_last_non_canonical_row..).collect()
-        },
-        None => vec![],
-    }
+    canonical_row_and_wraps.into()
 }
 
 fn get_viewport_bottom_canonical_row_and_wraps(viewport: &mut Vec<Row>) -> Vec<Row> {
@@ -116,7 +126,7 @@ fn get_top_canonical_row_and_wraps(rows: &mut Vec<Row>) -> Vec<Row> {
 }
 
 fn transfer_rows_from_lines_above_to_viewport(
-    lines_above: &mut VecDeque<Row>,
+    lines_above: &mut Scrollback,
     viewport: &mut Vec<Row>,
     sixel_grid: &mut SixelGrid,
     count: usize,
@@ -160,7 +170,7 @@ fn transfer_rows_from_lines_above_to_viewport(
 
 fn transfer_rows_from_viewport_to_lines_above(
     viewport: &mut Vec<Row>,
-    lines_above: &mut VecDeque<Row>,
+    lines_above: &mut Scrollback,
     sixel_grid: &mut SixelGrid,
     count: usize,
     max_viewport_width: usize,
@@ -226,16 +236,19 @@ fn transfer_rows_from_lines_below_to_viewport(
     }
 }
 
-fn bounded_push(vec: &mut VecDeque<Row>, sixel_grid: &mut SixelGrid, value: Row) -> Option<usize> {
+fn bounded_push(
+    lines_above: &mut Scrollback,
+    sixel_grid: &mut SixelGrid,
+    value: Row,
+) -> Option<usize> {
     let mut dropped_line_width = None;
-    if vec.len() >= *SCROLL_BUFFER_SIZE.get().unwrap() {
-        let line = vec.pop_front();
-        if let Some(line) = line {
+    if lines_above.len() >= *SCROLL_BUFFER_SIZE.get().unwrap() {
+        dropped_line_width = lines_above.drop_oldest_line();
+        if dropped_line_width.is_some() {
             sixel_grid.offset_grid_top();
-            dropped_line_width = Some(line.width());
         }
     }
-    vec.push_back(value);
+    lines_above.push_back(value);
     dropped_line_width
 }
 
@@ -252,7 +265,7 @@ pub fn create_horizontal_tabstops(columns: usize) -> BTreeSet<usize> {
     horizontal_tabstops
 }
 
-fn calculate_row_display_height(row_width: usize, viewport_width: usize) -> usize {
+pub(crate) fn calculate_row_display_height(row_width: usize, viewport_width: usize) -> usize {
     if row_width <= viewport_width {
         return 1;
     }
@@ -311,12 +324,12 @@ fn utf8_mouse_coordinates(column: usize, line: isize) -> Vec<u8> {
 
 #[derive(Clone)]
 pub struct Grid {
-    pub(crate) lines_above: VecDeque<Row>,
+    pub(crate) lines_above: Scrollback,
     pub(crate) viewport: Vec<Row>,
     pub(crate) lines_below: Vec<Row>,
     horizontal_tabstops: BTreeSet<usize>,
     alternate_screen_state: Option<AlternateScreenState>,
-    cursor: Cursor,
+    pub(crate) cursor: Cursor,
     cursor_is_hidden: bool,
     saved_cursor_position: Option<Cursor>,
     // FIXME: change scroll_region to be (usize, usize) - where the top line is always the first
@@ -332,6 +345,7 @@ pub struct Grid {
     title_stack: Vec<String>,
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     sixel_grid: SixelGrid,
+    kitty_graphics: KittyGraphicsState,
     pub changed_colors: Option<[Option<AnsiCode>; 256]>,
     pub should_render: bool,
     pub lock_renders: bool,
@@ -346,18 +360,26 @@ pub struct Grid {
     pub width: usize,
     pub height: usize,
     pub pending_messages_to_pty: Vec<Vec<u8>>,
+    pub pending_kitty_graphics_file_reads: Vec<KittyGraphicsFileRead>,
     pub selection: Selection,
     pub title: Option<String>,
     pub is_scrolled: bool,
     pub link_handler: Rc<RefCell<LinkHandler>>,
     pub ring_bell: bool,
+    pub rang_bell_since_last_output: bool, // unlike ring_bell, this is not cleared by rendering
     scrollback_buffer_lines: usize,
     pub mouse_mode: MouseMode,
     pub mouse_tracking: MouseTracking,
     pub focus_event_tracking: bool,
     pub search_results: SearchResult,
+    pub copy_mode: Option<CopyMode>,
+    pub last_command_exit_status: Option<i32>,
     pub pending_clipboard_update: Option<String>,
+    pub pending_cwd_update: Option<ReportedCwd>,
+    pub committed_lines: Option<Vec<String>>, // None if no one is watching the output
+    kitty_keyboard_flags: KittyKeyboardFlags,
     ui_component_bytes: Option<Vec<u8>>,
+    dcs_query: Option<DcsQuery>,
     style: Style,
     debug: bool,
     arrow_fonts: bool,
@@ -458,7 +480,7 @@ impl Grid {
         // I don't know why this needs to be a OneCell, but whatevs
         let _ = SCROLL_BUFFER_SIZE.set(DEFAULT_SCROLL_BUFFER_SIZE);
         Grid {
-            lines_above: VecDeque::new(),
+            lines_above: Scrollback::new(),
             viewport: vec![Row::new().canonical()],
             lines_below: vec![],
             horizontal_tabstops: create_horizontal_tabstops(columns),
@@ -481,6 +503,7 @@ impl Grid {
             clear_viewport_before_rendering: false,
             active_charset: Default::default(),
             pending_messages_to_pty: vec![],
+            pending_kitty_graphics_file_reads: vec![],
             terminal_emulator_colors,
             terminal_emulator_color_codes,
             output_buffer: Default::default(),
@@ -491,15 +514,23 @@ impl Grid {
             is_scrolled: false,
             link_handler,
             ring_bell: false,
+            rang_bell_since_last_output: false,
             scrollback_buffer_lines: 0,
             mouse_mode: MouseMode::default(),
             mouse_tracking: MouseTracking::default(),
             focus_event_tracking: false,
             character_cell_size,
             search_results: Default::default(),
+            copy_mode: None,
+            last_command_exit_status: None,
             sixel_grid,
+            kitty_graphics: KittyGraphicsState::default(),
             pending_clipboard_update: None,
+            pending_cwd_update: None,
+            committed_lines: None,
+            kitty_keyboard_flags: KittyKeyboardFlags::default(),
             ui_component_bytes: None,
+            dcs_query: None,
             style,
             debug,
             arrow_fonts,
@@ -561,8 +592,7 @@ impl Grid {
 
     fn recalculate_scrollback_buffer_count(&self) -> usize {
         let mut scrollback_buffer_count = 0;
-        for row in &self.lines_above {
-            let row_width = row.width();
+        for row_width in self.lines_above.line_widths() {
             // rows in lines_above are unwrapped, so we need to account for that
             if row_width > self.width {
                 scrollback_buffer_count += calculate_row_display_height(row_width, self.width);
@@ -573,6 +603,20 @@ impl Grid {
         scrollback_buffer_count
     }
 
+    /// Accounts for the lines of the scrollback that were dropped to keep the session under its
+    /// scrollback memory budget (possibly while another pane was being written to)
+    /// Returns true if any lines were dropped
+    pub fn drop_lines_evicted_from_scrollback(&mut self) -> bool {
+        let evicted_line_widths = self.lines_above.take_evicted_line_widths();
+        for width in &evicted_line_widths {
+            self.sixel_grid.offset_grid_top();
+            self.scrollback_buffer_lines = self
+                .scrollback_buffer_lines
+                .saturating_sub(calculate_row_display_height(*width, self.width));
+        }
+        !evicted_line_widths.is_empty()
+    }
+
     fn set_horizontal_tabstop(&mut self) {
         self.horizontal_tabstops.insert(self.cursor.x);
     }
@@ -680,6 +724,9 @@ impl Grid {
                 .saturating_sub(transferred_rows_height);
 
             self.selection.move_down(1);
+            if let Some(copy_mode) = self.copy_mode.as_mut() {
+                copy_mode.move_down(1);
+            }
             // Move all search-selections down one line as well
             found_something = self
                 .search_results
@@ -722,17 +769,128 @@ impl Grid {
             );
 
             self.selection.move_up(1);
+            if let Some(copy_mode) = self.copy_mode.as_mut() {
+                copy_mode.move_up(1);
+            }
             // Move all search-selections up one line as well
             found_something =
                 self.search_results
                     .move_up(1, &self.viewport, &self.lines_below, self.height);
             self.output_buffer.update_all_lines();
         }
-        if self.lines_below.is_empty() {
+        if self.lines_below.is_empty() && self.copy_mode.is_none() {
             self.is_scrolled = false;
         }
         found_something
     }
+    /// Scrolls up to the top of the scrollback at once, rather than a line at a time
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_up_by(usize::MAX);
+    }
+    /// Scrolls down to the bottom of the scrollback at once, rather than a line at a time
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_down_by(usize::MAX);
+    }
+    /// Scrolls up by this many rows (or as many as there are above the viewport) at once, rather
+    /// than a line at a time
+    pub fn scroll_up_by(&mut self, count: usize) {
+        if count == 0 || self.lines_above.is_empty() || self.viewport.len() != self.height {
+            return;
+        }
+        let mut lines_above = vec![];
+        let mut rows_above = 0;
+        while rows_above < count {
+            match self.lines_above.pop_back() {
+                Some(line) => {
+                    rows_above += calculate_row_display_height(line.width(), self.width);
+                    lines_above.push(line);
+                },
+                None => break,
+            }
+        }
+        let mut rows = vec![];
+        for mut line in lines_above.into_iter().rev() {
+            rows.append(&mut line.split_to_rows_of_length(self.width));
+        }
+        let lines_scrolled = count.min(rows.len());
+        let mut rows_in_view = rows.split_off(rows.len() - lines_scrolled);
+        if !rows.is_empty() {
+            // the part of the topmost line that is still above the viewport
+            bounded_push(
+                &mut self.lines_above,
+                &mut self.sixel_grid,
+                Row::from_rows(rows),
+            );
+        }
+        rows_in_view.append(&mut self.viewport);
+        let mut rows_below_view = rows_in_view.split_off(self.height);
+        rows_below_view.append(&mut self.lines_below);
+        self.lines_below = rows_below_view;
+        self.viewport = rows_in_view;
+        self.scrollback_buffer_lines = self.scrollback_buffer_lines.saturating_sub(lines_scrolled);
+        self.is_scrolled = true;
+
+        self.selection.move_down(lines_scrolled);
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            copy_mode.move_down(lines_scrolled);
+        }
+        self.search_viewport_after_jump(lines_scrolled as isize);
+        self.output_buffer.update_all_lines();
+    }
+    /// Scrolls down by this many rows (or as many as there are below the viewport) at once,
+    /// rather than a line at a time
+    pub fn scroll_down_by(&mut self, count: usize) {
+        if count == 0 || self.lines_below.is_empty() || self.viewport.len() != self.height {
+            return;
+        }
+        let mut lines_below = 0;
+        let mut rows_below = 0;
+        while rows_below < count && lines_below < self.lines_below.len() {
+            rows_below +=
+                calculate_row_display_height(self.lines_below[lines_below].width(), self.width);
+            lines_below += 1;
+        }
+        let mut rows_from_viewport_down = std::mem::take(&mut self.viewport);
+        rows_from_viewport_down.extend(self.lines_below.drain(..lines_below));
+        // the rows below the viewport are not necessarily wrapped to its width
+        let mut rows = Vec::with_capacity(rows_from_viewport_down.len());
+        let mut canonical_line = vec![];
+        for row in rows_from_viewport_down {
+            if row.is_canonical && !canonical_line.is_empty() {
+                rows.append(
+                    &mut Row::from_rows(std::mem::take(&mut canonical_line))
+                        .split_to_rows_of_length(self.width),
+                );
+            }
+            canonical_line.push(row);
+        }
+        rows.append(&mut Row::from_rows(canonical_line).split_to_rows_of_length(self.width));
+        let lines_scrolled = count.min(rows.len().saturating_sub(self.height));
+        let mut rows_in_view = rows.split_off(lines_scrolled);
+        let mut rows_below_view = rows_in_view.split_off(self.height.min(rows_in_view.len()));
+        rows_below_view.append(&mut self.lines_below);
+        self.lines_below = rows_below_view;
+        self.viewport = rows_in_view;
+        let transferred_rows_count = transfer_rows_from_viewport_to_lines_above(
+            &mut rows,
+            &mut self.lines_above,
+            &mut self.sixel_grid,
+            lines_scrolled,
+            self.width,
+        );
+        self.scrollback_buffer_lines =
+            subtract_isize_from_usize(self.scrollback_buffer_lines, transferred_rows_count);
+        if self.lines_below.is_empty() && self.copy_mode.is_none() {
+            self.is_scrolled = false;
+        }
+
+        self.selection.move_up(lines_scrolled);
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            copy_mode.move_up(lines_scrolled);
+        }
+        self.search_viewport_after_jump(-(lines_scrolled as isize));
+        self.output_buffer.update_all_lines();
+    }
     pub fn force_change_size(&mut self, new_rows: usize, new_columns: usize) {
         // this is an ugly hack - it's here because sometimes we need to change_size to the
         // existing size (eg. when resizing an alternative_grid to the current height/width) and
@@ -818,6 +976,7 @@ impl Grid {
             let mut new_viewport_rows = vec![];
             for mut canonical_line in viewport_canonical_lines {
                 let mut canonical_line_parts: Vec<Row> = vec![];
+                let semantic_prompt_marks = canonical_line.semantic_prompt_marks.take();
                 if canonical_line.columns.is_empty() {
                     canonical_line_parts.push(Row::new().canonical());
                 }
@@ -839,6 +998,9 @@ impl Grid {
                     };
                     canonical_line_parts.push(row);
                 }
+                if let Some(first_part) = canonical_line_parts.first_mut() {
+                    first_part.semantic_prompt_marks = semantic_prompt_marks;
+                }
                 new_viewport_rows.append(&mut canonical_line_parts);
             }
 
@@ -1051,7 +1213,7 @@ impl Grid {
                 };
                 let mut to_serialize = vec![];
                 for line in self.lines_above.iter().skip(first_index) {
-                    to_serialize.push(line.clone());
+                    to_serialize.push(line.into_owned());
                 }
                 for line in &self.viewport {
                     to_serialize.push(line.clone())
@@ -1061,6 +1223,35 @@ impl Grid {
             None => self.output_buffer.serialize(&self.viewport).ok(),
         }
     }
+    /// The scrollback in a compact form suitable for persisting it: one line of text per
+    /// canonical line, styled with ANSI escape sequences and without trailing padding or empty
+    /// lines at the end. Replaying this into a new pane restores the scrollback.
+    ///
+    /// Only the newest `scrollback_lines_to_serialize` lines are kept (all of them if it is None
+    /// or 0), and no more than MAX_SERIALIZED_SCROLLBACK_BYTES of them.
+    pub fn serialize_scrollback(
+        &self,
+        scrollback_lines_to_serialize: Option<usize>,
+    ) -> Option<String> {
+        let mut lines = self.pane_contents(true, true);
+        while lines.last().map(|l| l == RESET_STYLES).unwrap_or(false) {
+            lines.pop();
+        }
+        let mut first_index = match scrollback_lines_to_serialize {
+            Some(line_count) if line_count > 0 => lines.len().saturating_sub(line_count),
+            _ => 0,
+        };
+        let mut serialized_len: usize = lines[first_index..].iter().map(|l| l.len() + 2).sum();
+        while serialized_len > MAX_SERIALIZED_SCROLLBACK_BYTES && first_index < lines.len() {
+            serialized_len -= lines[first_index].len() + 2;
+            first_index += 1;
+        }
+        if first_index >= lines.len() {
+            None
+        } else {
+            Some(lines[first_index..].join("\r\n"))
+        }
+    }
     pub fn render(
         &mut self,
         content_x: usize,
@@ -1072,9 +1263,25 @@ impl Grid {
         }
         let mut raw_vte_output = String::new();
 
+        self.drop_lines_evicted_from_scrollback();
         let (mut character_chunks, sixel_image_chunks) = self.read_changes(content_x, content_y);
         for character_chunk in character_chunks.iter_mut() {
             character_chunk.add_changed_colors(self.changed_colors);
+            if let Some(block_selection) =
+                self.copy_mode_block_in_row(character_chunk.y.saturating_sub(content_y))
+            {
+                let background_color = match style.colors.bg {
+                    PaletteColor::Rgb(rgb) => AnsiCode::RgbCode(rgb),
+                    PaletteColor::EightBit(col) => AnsiCode::ColorIndex(col),
+                };
+                character_chunk.add_selection_and_colors(
+                    block_selection,
+                    background_color,
+                    None,
+                    content_x,
+                    content_y,
+                );
+            }
             if self
                 .selection
                 .contains_row(character_chunk.y.saturating_sub(content_y))
@@ -1130,7 +1337,9 @@ impl Grid {
         )));
     }
     pub fn cursor_coordinates(&self) -> Option<(usize, usize)> {
-        if self.cursor_is_hidden {
+        if let Some(copy_mode) = &self.copy_mode {
+            Some((copy_mode.cursor.column(), copy_mode.cursor.line() as usize))
+        } else if self.cursor_is_hidden {
             None
         } else {
             Some((self.cursor.x, self.cursor.y))
@@ -1158,6 +1367,64 @@ impl Grid {
         scrollback.push_str(&viewport);
         scrollback
     }
+    /// The canonical lines of the viewport (or of the whole scrollback), with wrapped lines
+    /// joined and trailing whitespace trimmed, optionally styled with ANSI escape sequences
+    pub fn pane_contents(&self, include_scrollback: bool, with_ansi: bool) -> Vec<String> {
+        let rows: Vec<Cow<Row>> = if include_scrollback {
+            self.lines_above
+                .iter()
+                .chain(self.viewport.iter().map(Cow::Borrowed))
+                .chain(self.lines_below.iter().map(Cow::Borrowed))
+                .collect()
+        } else {
+            self.viewport.iter().map(Cow::Borrowed).collect()
+        };
+        let mut canonical_lines: Vec<Row> = vec![];
+        for row in rows {
+            match canonical_lines.last_mut() {
+                Some(canonical_line) if !row.is_canonical => {
+                    canonical_line.append(&mut row.columns.clone())
+                },
+                _ => canonical_lines.push(Row::from_columns(row.columns.clone())),
+            }
+        }
+        canonical_lines
+            .iter_mut()
+            .map(|canonical_line| {
+                while canonical_line
+                    .columns
+                    .back()
+                    .map(|c| {
+                        // padding, as opposed to spaces with a visible background
+                        c.character == ' '
+                            && matches!(c.styles.background, None | Some(AnsiCode::Reset))
+                    })
+                    .unwrap_or(false)
+                {
+                    canonical_line.columns.pop_back();
+                }
+                canonical_line.width = None;
+                if with_ansi {
+                    let mut line = self
+                        .output_buffer
+                        .serialize(std::slice::from_ref(canonical_line))
+                        .unwrap_or_default()
+                        .trim_start_matches("\n\r")
+                        .to_owned();
+                    line.push_str(RESET_STYLES);
+                    line
+                } else {
+                    canonical_line
+                        .columns
+                        .iter()
+                        .map(|c| c.character)
+                        .collect::<String>()
+                        .trim_end()
+                        .to_owned()
+                }
+            })
+            .collect()
+    }
     pub fn move_viewport_up(&mut self, count: usize) {
         for _ in 0..count {
             self.scroll_up_one_line();
@@ -1354,10 +1621,9 @@ impl Grid {
     }
     pub fn add_character(&mut self, terminal_character: TerminalCharacter) {
         let character_width = terminal_character.width();
-        // Drop zero-width Unicode/UTF-8 codepoints, like for example Variation Selectors.
-        // This breaks unicode grapheme segmentation, and is the reason why some characters
-        // aren't displayed correctly. Refer to this issue for more information:
-        //     https://github.com/zellij-org/zellij/issues/1538
+        // Drop zero-width Unicode/UTF-8 codepoints that did not join the grapheme cluster of the
+        // character before them (see extend_preceding_grapheme), like for example a Variation
+        // Selector at the start of a line
         if character_width == 0 {
             return;
         }
@@ -1370,6 +1636,48 @@ impl Grid {
         self.add_character_at_cursor_position(terminal_character, false);
         self.move_cursor_forward_until_edge(character_width);
     }
+    /// Joins `character` to the cluster of the character printed right before it, if it is part
+    /// of it (eg. a combining mark, a skin tone modifier or the second half of a flag). The cell
+    /// becomes wide if the cluster is, when there is room for it on the line.
+    fn extend_preceding_grapheme(&mut self, character: char) -> bool {
+        let preceding_char = match &self.preceding_char {
+            Some(preceding_char) => preceding_char,
+            None => return false,
+        };
+        let grapheme = match preceding_char.character.extended_with(character) {
+            Some(grapheme) => grapheme,
+            None => return false,
+        };
+        // the cursor is right after the preceding character, unless it was moved since
+        let preceding_width = preceding_char.width();
+        let x = match self.cursor.x.checked_sub(preceding_width) {
+            Some(x) => x,
+            None => return false,
+        };
+        let row = match self.viewport.get_mut(self.cursor.y) {
+            Some(row) => row,
+            None => return false,
+        };
+        let index = row.absolute_character_index(x);
+        if row.columns.get(index) != Some(preceding_char) {
+            return false;
+        }
+        let width = if grapheme.width() > preceding_width && x + grapheme.width() <= self.width {
+            grapheme.width()
+        } else {
+            preceding_width
+        };
+        let terminal_character = TerminalCharacter::new_styled_with_width(
+            grapheme,
+            preceding_char.styles.clone(),
+            width as u8,
+        );
+        row.add_character_at(terminal_character.clone(), x);
+        self.cursor.x = x + width;
+        self.set_preceding_character(terminal_character);
+        self.output_buffer.update_line(self.cursor.y);
+        true
+    }
     pub fn get_character_under_cursor(&self) -> Option<TerminalCharacter> {
         let absolute_x_in_line = self.get_absolute_character_index(self.cursor.x, self.cursor.y);
         self.viewport
@@ -1566,6 +1874,10 @@ impl Grid {
     pub fn show_cursor(&mut self) {
         self.cursor_is_hidden = false;
     }
+    pub fn update_style(&mut self, style: Style) {
+        self.style = style;
+        self.mark_for_rerender();
+    }
     pub fn set_scroll_region(&mut self, top_line_index: usize, bottom_line_index: Option<usize>) {
         let bottom_line_index = bottom_line_index.unwrap_or(self.height);
         self.scroll_region = Some((top_line_index, bottom_line_index));
@@ -1680,14 +1992,53 @@ impl Grid {
         }
     }
     fn add_newline(&mut self) {
+        self.commit_current_line();
         self.add_canonical_line();
         self.mark_for_rerender();
     }
+    /// Record the canonical line the cursor is on as printed output, if someone is watching it.
+    /// We assume the line will not change once the cursor moved on to the next one.
+    fn commit_current_line(&mut self) {
+        if self.committed_lines.is_none() || self.alternate_screen_state.is_some() {
+            // full screen applications redraw the screen rather than print lines
+            return;
+        }
+        if self.viewport.is_empty() {
+            return;
+        }
+        let cursor_y = self.cursor.y.min(self.viewport.len() - 1);
+        let mut canonical_line_y = cursor_y;
+        while canonical_line_y > 0 && !self.viewport[canonical_line_y].is_canonical {
+            canonical_line_y -= 1;
+        }
+        let mut rows: Vec<Cow<Row>> = vec![];
+        if !self.viewport[canonical_line_y].is_canonical {
+            // the beginning of the line has already scrolled off the top of the viewport
+            let line_start = self
+                .lines_above
+                .iter()
+                .rposition(|row| row.is_canonical)
+                .unwrap_or(0);
+            rows.extend(self.lines_above.iter().skip(line_start));
+        }
+        rows.extend(
+            self.viewport[canonical_line_y..=cursor_y]
+                .iter()
+                .map(Cow::Borrowed),
+        );
+        let line: String = rows
+            .iter()
+            .flat_map(|row| row.columns.iter().map(|c| c.character))
+            .collect();
+        if let Some(committed_lines) = self.committed_lines.as_mut() {
+            committed_lines.push(line.trim_end().to_owned());
+        }
+    }
     pub fn mark_for_rerender(&mut self) {
         self.should_render = true;
     }
     pub fn reset_terminal_state(&mut self) {
-        self.lines_above = VecDeque::new();
+        self.lines_above = Scrollback::new();
         self.lines_below = vec![];
         self.viewport = vec![Row::new().canonical()];
         self.alternate_screen_state = None;
@@ -1705,14 +2056,17 @@ impl Grid {
         self.changed_colors = None;
         self.scrollback_buffer_lines = 0;
         self.search_results = Default::default();
+        self.last_command_exit_status = None;
         self.sixel_scrolling = false;
         self.mouse_mode = MouseMode::NoEncoding;
         self.mouse_tracking = MouseTracking::Off;
         self.focus_event_tracking = false;
         self.cursor_is_hidden = false;
+        self.kitty_keyboard_flags = KittyKeyboardFlags::default();
         if let Some(images_to_reap) = self.sixel_grid.clear() {
             self.sixel_grid.reap_images(images_to_reap);
         }
+        self.kitty_graphics.reset();
     }
     fn set_preceding_character(&mut self, terminal_character: TerminalCharacter) {
         self.preceding_char = Some(terminal_character);
@@ -1744,6 +2098,9 @@ impl Grid {
         self.mark_for_rerender();
     }
     pub fn get_selected_text(&self) -> Option<String> {
+        if let Some(block_text) = self.copy_mode_block_text() {
+            return Some(block_text);
+        }
         if self.selection.is_empty() {
             return None;
         }
@@ -1776,17 +2133,21 @@ impl Grid {
             // get the row from lines_above, viewport, or lines below depending on index
             let row = if l < 0 && self.lines_above.len() > l.abs() as usize {
                 let offset_from_end = l.abs();
-                &self.lines_above[self
-                    .lines_above
-                    .len()
-                    .saturating_sub(offset_from_end as usize)]
+                match self.lines_above.get(
+                    self.lines_above
+                        .len()
+                        .saturating_sub(offset_from_end as usize),
+                ) {
+                    Some(row) => row,
+                    None => continue,
+                }
             } else if l >= 0 && (l as usize) < self.viewport.len() {
-                &self.viewport[l as usize]
+                Cow::Borrowed(&self.viewport[l as usize])
             } else if (l as usize) < self.height {
                 // index is in viewport but there is no line
-                &empty_row
+                Cow::Borrowed(&empty_row)
             } else if self.lines_below.len() > (l as usize).saturating_sub(self.viewport.len()) {
-                &self.lines_below[(l as usize) - self.viewport.len()]
+                Cow::Borrowed(&self.lines_below[(l as usize) - self.viewport.len()])
             } else {
                 // can't find the line, this probably it's on the pane border
                 // is on the pane border
@@ -1796,7 +2157,7 @@ impl Grid {
             let mut terminal_col = 0;
             for terminal_character in &row.columns {
                 if (start_column..end_column).contains(&terminal_col) {
-                    line_selection.push(terminal_character.character);
+                    terminal_character.character.push_to(&mut line_selection);
                 }
 
                 terminal_col += terminal_character.width();
@@ -1912,6 +2273,191 @@ impl Grid {
             }
         }
     }
+    pub fn handle_kitty_graphics_command(&mut self, bytes: &[u8]) {
+        let command = match KittyGraphicsCommand::parse(bytes) {
+            Some(command) => command,
+            None => return,
+        };
+        let mut command = match self.kitty_graphics.collect_chunks(command) {
+            Some(command) => command,
+            None => return,
+        };
+        let result = match command.action() {
+            KittyGraphicsAction::Transmit
+            | KittyGraphicsAction::TransmitAndPlace
+            | KittyGraphicsAction::Query
+                if command.is_in_file() =>
+            {
+                // the transmission goes on in finish_kitty_graphics_file_read once it's read
+                match self.kitty_graphics.start_file_read(&command) {
+                    Ok(file_read) => {
+                        self.pending_kitty_graphics_file_reads.push(file_read);
+                        return;
+                    },
+                    Err(error) => Err(error),
+                }
+            },
+            KittyGraphicsAction::Transmit
+            | KittyGraphicsAction::TransmitAndPlace
+            | KittyGraphicsAction::Query => self.transmit_kitty_image(&mut command),
+            KittyGraphicsAction::Place => match self.kitty_graphics.image(command.image_id()) {
+                Some(image) => self.add_kitty_placement(image, &command),
+                None => Err("ENOENT:no such image".to_owned()),
+            },
+            KittyGraphicsAction::Delete => {
+                self.delete_kitty_placements(&command);
+                return;
+            },
+            KittyGraphicsAction::Unsupported => Err("EINVAL:unsupported action".to_owned()),
+        };
+        if let Some(response) = command.response(&result) {
+            self.pending_messages_to_pty.push(response.into_bytes());
+        }
+    }
+    /// Goes on with a transmission once the file holding its image was read in the background
+    pub fn finish_kitty_graphics_file_read(
+        &mut self,
+        file_read_id: u32,
+        data: Result<Vec<u8>, String>,
+    ) {
+        let mut command = match self.kitty_graphics.take_file_read(file_read_id) {
+            Some(command) => command,
+            None => return,
+        };
+        let result = match data {
+            Ok(data) => {
+                command.set_file_data(data);
+                self.transmit_kitty_image(&mut command)
+            },
+            Err(error) => Err(error),
+        };
+        if let Some(response) = command.response(&result) {
+            self.pending_messages_to_pty.push(response.into_bytes());
+        }
+    }
+    fn transmit_kitty_image(&mut self, command: &mut KittyGraphicsCommand) -> Result<(), String> {
+        // we can only place images if we know the pixel size of each character cell
+        if self.character_cell_size.borrow().is_none() {
+            return Err("ENOTSUPPORTED:the size of the terminal cells is unknown".to_owned());
+        }
+        let kitty_image_id = self
+            .sixel_grid
+            .sixel_image_store
+            .borrow_mut()
+            .next_kitty_image_id();
+        let image = Rc::new(KittyImage::new(kitty_image_id, command)?);
+        if command.action() == KittyGraphicsAction::Query {
+            return Ok(());
+        }
+        if command.image_id() != 0 {
+            self.kitty_graphics
+                .add_image(command.image_id(), image.clone());
+        }
+        if command.action() == KittyGraphicsAction::TransmitAndPlace {
+            self.add_kitty_placement(image, command)?;
+        }
+        Ok(())
+    }
+    fn add_kitty_placement(
+        &mut self,
+        image: Rc<KittyImage>,
+        command: &KittyGraphicsCommand,
+    ) -> Result<(), String> {
+        let character_cell_size = match *self.character_cell_size.borrow() {
+            Some(character_cell_size) => character_cell_size,
+            None => {
+                return Err("ENOTSUPPORTED:the size of the terminal cells is unknown".to_owned())
+            },
+        };
+        let kitty_placement =
+            KittyPlacement::new(image, command, character_cell_size, self.width, self.height)?;
+        let (width, height) = kitty_placement.size();
+        if command.placement_id() != 0 {
+            // placing an image again with the same placement id moves it
+            let sixel_image_store = self.sixel_grid.sixel_image_store.clone();
+            let previous_placements: Vec<usize> = self
+                .sixel_grid
+                .image_coordinates()
+                .map(|(image_id, _)| image_id)
+                .filter(|image_id| {
+                    sixel_image_store
+                        .borrow()
+                        .kitty_placement(*image_id)
+                        .map(|p| p.pane_ids() == (command.image_id(), command.placement_id()))
+                        .unwrap_or(false)
+                })
+                .collect();
+            for image_id in previous_placements {
+                self.sixel_grid.remove_image(image_id);
+            }
+        }
+        if let Some((x_pixel_coordinates, y_pixel_coordinates)) =
+            self.current_cursor_pixel_coordinates()
+        {
+            let new_image_id = self.sixel_grid.next_image_id();
+            self.sixel_grid.new_kitty_placement(
+                new_image_id,
+                x_pixel_coordinates,
+                y_pixel_coordinates,
+                kitty_placement,
+            );
+        }
+        if command.moves_cursor() {
+            // to the cell after the bottom right corner of the image
+            let columns = (width + character_cell_size.width - 1) / character_cell_size.width;
+            let rows = (height + character_cell_size.height - 1) / character_cell_size.height;
+            let cursor_x = self.cursor.x;
+            // only an image displayed at its own size can be taller than the pane, the cursor
+            // is moved down by no more than the pane's height for those rather than scrolling
+            // line after blank line through the scrollback
+            for _ in 1..std::cmp::min(rows, self.height + 1) {
+                self.add_canonical_line();
+            }
+            self.cursor.x = std::cmp::min(cursor_x + columns, self.width.saturating_sub(1));
+        }
+        self.render_full_viewport();
+        Ok(())
+    }
+    fn delete_kitty_placements(&mut self, command: &KittyGraphicsCommand) {
+        let character_cell_size = match *self.character_cell_size.borrow() {
+            Some(character_cell_size) => character_cell_size,
+            None => return,
+        };
+        let scrollback_height = self.lines_above.len();
+        let cursor = (self.cursor.x, (scrollback_height + self.cursor.y) as isize);
+        let sixel_image_store = self.sixel_grid.sixel_image_store.clone();
+        let mut deleted_placements = vec![];
+        for (image_id, pixel_rect) in self.sixel_grid.image_coordinates() {
+            if let Some(kitty_placement) = sixel_image_store.borrow().kitty_placement(image_id) {
+                let first_column = pixel_rect.x / character_cell_size.width;
+                let last_column = (pixel_rect.x + pixel_rect.width + character_cell_size.width - 1)
+                    / character_cell_size.width;
+                let cell_height = character_cell_size.height as isize;
+                let first_row = pixel_rect.y.div_euclid(cell_height);
+                let last_row = (pixel_rect.y + pixel_rect.height as isize + cell_height - 1)
+                    .div_euclid(cell_height);
+                if command.deletes(
+                    kitty_placement,
+                    first_column..last_column,
+                    first_row..last_row,
+                    cursor,
+                    scrollback_height,
+                ) {
+                    deleted_placements.push((image_id, kitty_placement.pane_ids().0));
+                }
+            }
+        }
+        for (image_id, pane_image_id) in deleted_placements {
+            self.sixel_grid.remove_image(image_id);
+            if command.frees_image_data() {
+                self.kitty_graphics.remove_image(pane_image_id);
+            }
+        }
+        if command.frees_image_data() && command.image_id() != 0 {
+            self.kitty_graphics.remove_image(command.image_id());
+        }
+        self.render_full_viewport();
+    }
     pub fn mouse_left_click_signal(&self, position: &Position, is_held: bool) -> Option<String> {
         let utf8_event = || -> Option<String> {
             let button_code = if is_held { b'@' } else { b' ' };
@@ -2118,6 +2664,11 @@ impl Grid {
     pub fn is_alternate_mode_active(&self) -> bool {
         self.alternate_screen_state.is_some()
     }
+    /// Whether the application asked to receive keys with the kitty keyboard protocol
+    pub fn supports_kitty_keyboard_protocol(&self) -> bool {
+        self.kitty_keyboard_flags
+            .disambiguate_escape_codes(self.is_alternate_mode_active())
+    }
     pub fn focus_event(&self) -> Option<String> {
         if self.focus_event_tracking {
             Some("\u{1b}[I".into())
@@ -2146,11 +2697,34 @@ impl Grid {
     pub fn unlock_renders(&mut self) {
         self.lock_renders = false;
     }
+    fn respond_to_dcs_query(&mut self, dcs_query: DcsQuery) {
+        let response = match dcs_query {
+            DcsQuery::Termcap(payload) => termcap_response(&payload, self.styled_underlines),
+            DcsQuery::Setting(setting) => {
+                let setting = match setting.as_slice() {
+                    b"m" => Some(format!("{}m", self.cursor.pending_styles.sgr_params())),
+                    b" q" => Some(format!("{} q", self.cursor.get_shape().decscusr_param())),
+                    b"r" => {
+                        let (top, bottom) = self
+                            .scroll_region
+                            .unwrap_or((0, self.height.saturating_sub(1)));
+                        Some(format!("{};{}r", top + 1, (bottom + 1).min(self.height)))
+                    },
+                    _ => None,
+                };
+                setting_response(setting)
+            },
+        };
+        self.pending_messages_to_pty.push(response.into_bytes());
+    }
 }
 
 impl Perform for Grid {
     fn print(&mut self, c: char) {
         let c = self.cursor.charsets[self.active_charset].map(c);
+        if self.extend_preceding_grapheme(c) {
+            return;
+        }
 
         let terminal_character =
             TerminalCharacter::new_styled(c, self.cursor.pending_styles.clone());
@@ -2162,6 +2736,7 @@ impl Perform for Grid {
         match byte {
             7 => {
                 self.ring_bell = true;
+                self.rang_bell_since_last_output = true;
             },
             8 => {
                 // backspace
@@ -2196,7 +2771,10 @@ impl Perform for Grid {
     }
 
     fn hook(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, c: char) {
-        if c == 'q' {
+        if let Some(dcs_query) = DcsQuery::new(intermediates, c) {
+            // XTGETTCAP or DECRQSS
+            self.dcs_query = Some(dcs_query);
+        } else if c == 'q' {
             // we only process sixel images if we know the pixel size of each character cell,
             // otherwise we can't reliably display them
             if self.current_cursor_pixel_coordinates().is_some() {
@@ -2226,6 +2804,8 @@ impl Perform for Grid {
             self.should_render = false;
         } else if let Some(ui_component_bytes) = self.ui_component_bytes.as_mut() {
             ui_component_bytes.push(byte);
+        } else if let Some(dcs_query) = self.dcs_query.as_mut() {
+            dcs_query.push(byte);
         }
     }
 
@@ -2239,6 +2819,8 @@ impl Perform for Grid {
             UiComponentParser::new(self, style, arrow_fonts)
                 .parse(component_bytes.collect())
                 .non_fatal();
+        } else if let Some(dcs_query) = self.dcs_query.take() {
+            self.respond_to_dcs_query(dcs_query);
         }
         self.mark_for_rerender();
     }
@@ -2385,6 +2967,20 @@ impl Perform for Grid {
                 }
             },
 
+            // Current working directory.
+            b"7" => {
+                if let Some(reported_cwd) = ReportedCwd::from_osc_params(params) {
+                    self.pending_cwd_update = Some(reported_cwd);
+                }
+            },
+
+            // Semantic prompt (shell integration) marks.
+            b"133" => {
+                if let Some(mark) = SemanticPromptMark::from_osc_params(params) {
+                    self.mark_semantic_prompt(mark);
+                }
+            },
+
             // Reset color index.
             b"104" => {
                 // Reset all color indexes when no parameters are given.
@@ -2629,7 +3225,7 @@ impl Perform for Grid {
                         1049 => {
                             // enter alternate buffer
                             let current_lines_above =
-                                std::mem::replace(&mut self.lines_above, VecDeque::new());
+                                std::mem::replace(&mut self.lines_above, Scrollback::new());
                             let current_viewport =
                                 std::mem::replace(&mut self.viewport, vec![Row::new().canonical()]);
                             let current_cursor = std::mem::replace(
@@ -2715,7 +3311,10 @@ impl Perform for Grid {
                 for param in params_iter.map(|param| param[0]) {
                     match param {
                         2026 => {
-                            let response = "\u{1b}[2026;2$y";
+                            // DECRQM - synchronized output is supported, and set while renders
+                            // are locked
+                            let mode = if self.lock_renders { 1 } else { 2 };
+                            let response = format!("\u{1b}[?2026;{}$y", mode);
                             self.pending_messages_to_pty
                                 .push(response.as_bytes().to_vec());
                         },
@@ -2826,7 +3425,34 @@ impl Perform for Grid {
         } else if c == 's' {
             self.save_cursor_position();
         } else if c == 'u' {
-            self.restore_cursor_position();
+            let alternate_screen = self.is_alternate_mode_active();
+            match intermediates.get(0) {
+                Some(b'>') => {
+                    // push kitty keyboard protocol flags
+                    let flags = next_param_or(0) as u8;
+                    self.kitty_keyboard_flags.push(flags, alternate_screen);
+                },
+                Some(b'<') => {
+                    // pop kitty keyboard protocol flags
+                    let count = next_param_or(1);
+                    self.kitty_keyboard_flags.pop(count, alternate_screen);
+                },
+                Some(b'=') => {
+                    // set kitty keyboard protocol flags
+                    let flags = next_param_or(0) as u8;
+                    let mode = next_param_or(1);
+                    self.kitty_keyboard_flags.set(flags, mode, alternate_screen);
+                },
+                Some(b'?') => {
+                    // query kitty keyboard protocol flags
+                    let flags = self.kitty_keyboard_flags.current(alternate_screen);
+                    self.pending_messages_to_pty
+                        .push(format!("\u{1b}[?{}u", flags).into_bytes());
+                },
+                _ => {
+                    self.restore_cursor_position();
+                },
+            }
         } else if c == '@' {
             let count = next_param_or(1);
             for _ in 0..count {
@@ -2873,9 +3499,9 @@ impl Perform for Grid {
                 if let Some(cursor_shape) = shape {
                     self.cursor.change_shape(cursor_shape);
                 }
-            } else if matches!(intermediates.get(0), Some(b'>')) {
-                let version = version_number(VERSION);
-                let xtversion = format!("\u{1b}P>|Zellij({})\u{1b}\\", version);
+            } else if matches!(intermediates.get(0), Some(b'>')) && next_param_or(0) == 0 {
+                // XTVERSION
+                let xtversion = format!("\u{1b}P>|Zellij({})\u{1b}\\", VERSION);
                 self.pending_messages_to_pty
                     .push(xtversion.as_bytes().to_vec());
             }
@@ -2893,7 +3519,7 @@ impl Perform for Grid {
                     self.pending_messages_to_pty
                         .push(terminal_capabilities.as_bytes().to_vec());
                 },
-                Some(b'>') => {
+                Some(b'>') if next_param_or(0) == 0 => {
                     // secondary device attributes
                     let version = version_number(VERSION);
                     let text = format!("\u{1b}[>0;{};1c", version);
@@ -3066,7 +3692,7 @@ impl Perform for Grid {
             },
             (b'8', Some(b'#')) => {
                 let mut fill_character = EMPTY_TERMINAL_CHARACTER;
-                fill_character.character = 'E';
+                fill_character.character = 'E'.into();
                 self.fill_viewport(fill_character);
             },
             _ => {
@@ -3080,14 +3706,14 @@ impl Perform for Grid {
 
 #[derive(Clone)]
 pub struct AlternateScreenState {
-    lines_above: VecDeque<Row>,
+    lines_above: Scrollback,
     viewport: Vec<Row>,
     cursor: Cursor,
     sixel_grid: SixelGrid,
 }
 impl AlternateScreenState {
     pub fn new(
-        lines_above: VecDeque<Row>,
+        lines_above: Scrollback,
         viewport: Vec<Row>,
         cursor: Cursor,
         sixel_grid: SixelGrid,
@@ -3101,7 +3727,7 @@ impl AlternateScreenState {
     }
     pub fn apply_contents_to(
         &mut self,
-        lines_above: &mut VecDeque<Row>,
+        lines_above: &mut Scrollback,
         viewport: &mut Vec<Row>,
         cursor: &mut Cursor,
         sixel_grid: &mut SixelGrid,
@@ -3118,6 +3744,8 @@ pub struct Row {
     pub columns: VecDeque<TerminalCharacter>,
     pub is_canonical: bool,
     width: Option<usize>,
+    // boxed because the vast majority of lines are not part of a shell prompt
+    semantic_prompt_marks: Option<Box<SemanticPromptMarks>>,
 }
 
 impl Debug for Row {
@@ -3135,6 +3763,7 @@ impl Row {
             columns: VecDeque::new(),
             is_canonical: false,
             width: None,
+            semantic_prompt_marks: None,
         }
     }
     pub fn from_columns(columns: VecDeque<TerminalCharacter>) -> Self {
@@ -3142,6 +3771,7 @@ impl Row {
             columns,
             is_canonical: false,
             width: None,
+            semantic_prompt_marks: None,
         }
     }
     pub fn from_rows(mut rows: Vec<Row>) -> Self {
@@ -3164,6 +3794,23 @@ impl Row {
         self.is_canonical = true;
         self
     }
+    pub fn semantic_prompt_marks(&self) -> Option<&SemanticPromptMarks> {
+        self.semantic_prompt_marks.as_deref()
+    }
+    pub fn semantic_prompt_marks_mut(&mut self) -> &mut SemanticPromptMarks {
+        self.semantic_prompt_marks
+            .get_or_insert_with(Default::default)
+    }
+    pub(crate) fn take_semantic_prompt_marks(&mut self) -> Option<Box<SemanticPromptMarks>> {
+        self.semantic_prompt_marks.take()
+    }
+    pub(crate) fn with_semantic_prompt_marks(
+        mut self,
+        semantic_prompt_marks: Option<Box<SemanticPromptMarks>>,
+    ) -> Self {
+        self.semantic_prompt_marks = semantic_prompt_marks;
+        self
+    }
     pub fn width_cached(&mut self) -> usize {
         if self.width.is_some() {
             self.width.unwrap()
@@ -3443,6 +4090,8 @@ impl Row {
         }
         if parts.is_empty() {
             parts.push(self.clone());
+        } else if let Some(part) = parts.get_mut(0) {
+            part.semantic_prompt_marks = self.semantic_prompt_marks.take();
         }
         self.width = None;
         parts
@@ -0,0 +1,1106 @@
+use crate::output::SixelImageChunk;
+use crate::panes::grid::Grid;
+use crate::panes::sixel::PixelRect;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use zellij_utils::nix::fcntl::OFlag;
+use zellij_utils::pane_size::SizeInPixels;
+use zellij_utils::vte;
+
+const ESC: u8 = 0x1b;
+// larger commands (or transmissions made of several of them) and images are dropped
+const MAX_COMMAND_SIZE: usize = 64 * 1024 * 1024;
+const MAX_IMAGE_PIXELS: usize = 10_000 * 10_000;
+// once the images a pane transmitted take up more than this, the oldest ones are forgotten (those
+// that are placed stay on screen until they are deleted or scroll away)
+const MAX_STORED_IMAGES_SIZE: usize = 320 * 1024 * 1024;
+// the size of the base64 chunks images are transmitted to the clients in
+const TRANSMISSION_CHUNK_SIZE: usize = 4096;
+// images transmitted in files are read in the background, further transmissions in files are
+// refused while this many of them are being read for a pane
+const MAX_PENDING_FILE_READS: usize = 8;
+// applications are not allowed to have us read from these, only from regular files
+const FORBIDDEN_DIRECTORIES: &[&str] = &["/proc", "/sys", "/dev"];
+const PLACEHOLDER: &str = "[image]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InterceptorState {
+    Ground,
+    Escape,
+    Apc,
+    ApcEscape,
+}
+
+/// Picks the kitty graphics commands (`ESC _ G ... ESC \`) out of the bytes a pane receives from
+/// its pty and passes everything else on to the vte parser, which drops APC sequences.
+#[derive(Debug)]
+pub struct KittyGraphicsInterceptor {
+    state: InterceptorState,
+    apc: Vec<u8>,
+}
+
+impl Default for KittyGraphicsInterceptor {
+    fn default() -> Self {
+        KittyGraphicsInterceptor {
+            state: InterceptorState::Ground,
+            apc: vec![],
+        }
+    }
+}
+
+impl KittyGraphicsInterceptor {
+    pub fn advance(&mut self, vte_parser: &mut vte::Parser, grid: &mut Grid, byte: u8) {
+        match self.state {
+            InterceptorState::Ground if byte == ESC => self.state = InterceptorState::Escape,
+            InterceptorState::Ground => vte_parser.advance(grid, byte),
+            InterceptorState::Escape if byte == b'_' => {
+                self.state = InterceptorState::Apc;
+                self.apc.clear();
+            },
+            InterceptorState::Escape => {
+                self.state = InterceptorState::Ground;
+                vte_parser.advance(grid, ESC);
+                self.advance(vte_parser, grid, byte);
+            },
+            InterceptorState::Apc if byte == ESC => self.state = InterceptorState::ApcEscape,
+            InterceptorState::Apc => {
+                if self.apc.len() < MAX_COMMAND_SIZE {
+                    self.apc.push(byte);
+                }
+            },
+            InterceptorState::ApcEscape => {
+                self.state = InterceptorState::Ground;
+                let apc = std::mem::take(&mut self.apc);
+                if byte == b'\\' {
+                    if let Some(command) = apc.strip_prefix(b"G") {
+                        grid.handle_kitty_graphics_command(command);
+                    }
+                } else {
+                    // the sequence was cut short by another one
+                    vte_parser.advance(grid, ESC);
+                    self.advance(vte_parser, grid, byte);
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KittyGraphicsAction {
+    Transmit,
+    TransmitAndPlace,
+    Query,
+    Place,
+    Delete,
+    Unsupported, // eg. animation frames
+}
+
+/// A command of the kitty graphics protocol
+/// (https://sw.kovidgoyal.net/kitty/graphics-protocol/), with its payload decoded
+#[derive(Debug, Clone)]
+pub struct KittyGraphicsCommand {
+    action: KittyGraphicsAction,
+    quiet: u8,
+    image_id: u32,
+    placement_id: u32,
+    format: u32,
+    medium: u8,
+    compression: Option<u8>,
+    data_width: usize,
+    data_height: usize,
+    data_size: usize,
+    data_offset: usize,
+    more_chunks: bool,
+    // the part of the image to place, or the cell to delete placements at (x, y)
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    columns: usize,
+    rows: usize,
+    z_index: i32,
+    move_cursor: bool,
+    delete: u8,
+    payload: Vec<u8>,
+}
+
+impl Default for KittyGraphicsCommand {
+    fn default() -> Self {
+        KittyGraphicsCommand {
+            action: KittyGraphicsAction::Transmit,
+            quiet: 0,
+            image_id: 0,
+            placement_id: 0,
+            format: 32,
+            medium: b'd',
+            compression: None,
+            data_width: 0,
+            data_height: 0,
+            data_size: 0,
+            data_offset: 0,
+            more_chunks: false,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            columns: 0,
+            rows: 0,
+            z_index: 0,
+            move_cursor: true,
+            delete: b'a',
+            payload: vec![],
+        }
+    }
+}
+
+impl KittyGraphicsCommand {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let (control_data, payload) = match bytes.iter().position(|b| *b == b';') {
+            Some(separator) => (&bytes[..separator], &bytes[separator + 1..]),
+            None => (bytes, &[][..]),
+        };
+        let mut command = KittyGraphicsCommand::default();
+        for key_value in control_data.split(|b| *b == b',') {
+            let (key, value) = match key_value {
+                [key, b'=', value @ ..] => (*key, value),
+                _ => continue,
+            };
+            let character = value.first().copied().unwrap_or(0);
+            match key {
+                b'a' => {
+                    command.action = match character {
+                        b't' => KittyGraphicsAction::Transmit,
+                        b'T' => KittyGraphicsAction::TransmitAndPlace,
+                        b'q' => KittyGraphicsAction::Query,
+                        b'p' => KittyGraphicsAction::Place,
+                        b'd' => KittyGraphicsAction::Delete,
+                        _ => KittyGraphicsAction::Unsupported,
+                    }
+                },
+                b'q' => command.quiet = number(value)?,
+                b'i' => command.image_id = number(value)?,
+                b'p' => command.placement_id = number(value)?,
+                b'f' => command.format = number(value)?,
+                b't' => command.medium = character,
+                b'o' => command.compression = Some(character),
+                b's' => command.data_width = number(value)?,
+                b'v' => command.data_height = number(value)?,
+                b'S' => command.data_size = number(value)?,
+                b'O' => command.data_offset = number(value)?,
+                b'm' => command.more_chunks = number::<u8>(value)? == 1,
+                b'x' => command.x = number(value)?,
+                b'y' => command.y = number(value)?,
+                b'w' => command.width = number(value)?,
+                b'h' => command.height = number(value)?,
+                b'c' => command.columns = number(value)?,
+                b'r' => command.rows = number(value)?,
+                b'z' => command.z_index = number(value)?,
+                b'C' => command.move_cursor = number::<u8>(value)? != 1,
+                b'd' => command.delete = character,
+                _ => {}, // eg. image numbers or unicode placeholders, which we do not support
+            }
+        }
+        if !payload.is_empty() {
+            command.payload = base64::decode(payload).ok()?;
+        }
+        Some(command)
+    }
+    pub fn action(&self) -> KittyGraphicsAction {
+        self.action
+    }
+    pub fn image_id(&self) -> u32 {
+        self.image_id
+    }
+    pub fn placement_id(&self) -> u32 {
+        self.placement_id
+    }
+    pub fn moves_cursor(&self) -> bool {
+        self.move_cursor
+    }
+    /// The reply to the application, which only gets one if it gave the image an id and did not
+    /// ask us to be quiet
+    pub fn response(&self, result: &Result<(), String>) -> Option<String> {
+        if self.image_id == 0 {
+            return None;
+        }
+        let message = match result {
+            Ok(()) if self.quiet == 0 => "OK",
+            Err(error) if self.quiet < 2 => error,
+            _ => return None,
+        };
+        let mut response = format!("\u{1b}_Gi={}", self.image_id);
+        if self.placement_id != 0 {
+            let _ = write!(response, ",p={}", self.placement_id);
+        }
+        let _ = write!(response, ";{}\u{1b}\\", message);
+        Some(response)
+    }
+    /// Whether this delete command deletes `placement`, which covers the `columns` and `rows`
+    /// (counted in cells from the top of the scrollback)
+    pub fn deletes(
+        &self,
+        placement: &KittyPlacement,
+        columns: Range<usize>,
+        rows: Range<isize>,
+        cursor: (usize, isize),
+        scrollback_height: usize,
+    ) -> bool {
+        // the coordinates in the command are 1-based and relative to the viewport
+        let column = self.x.saturating_sub(1);
+        let row = (self.y.saturating_sub(1) + scrollback_height) as isize;
+        match self.delete.to_ascii_lowercase() {
+            b'a' => true,
+            b'i' => {
+                placement.pane_image_id == self.image_id
+                    && (self.placement_id == 0 || placement.pane_placement_id == self.placement_id)
+            },
+            b'c' => columns.contains(&cursor.0) && rows.contains(&cursor.1),
+            b'p' => columns.contains(&column) && rows.contains(&row),
+            b'q' => {
+                columns.contains(&column)
+                    && rows.contains(&row)
+                    && placement.z_index == self.z_index
+            },
+            b'x' => columns.contains(&column),
+            b'y' => rows.contains(&row),
+            b'z' => placement.z_index == self.z_index,
+            b'r' => (self.x as u32..=self.y as u32).contains(&placement.pane_image_id),
+            _ => false,
+        }
+    }
+    /// Whether the images of the deleted placements should be forgotten too
+    pub fn frees_image_data(&self) -> bool {
+        self.delete.is_ascii_uppercase()
+    }
+    /// Whether the image data is in a file, which has to be read before the image is transmitted
+    pub fn is_in_file(&self) -> bool {
+        self.medium == b'f' || self.medium == b't'
+    }
+    /// Goes on with a transmission in a file once it is read, as if its data was sent directly
+    pub fn set_file_data(&mut self, data: Vec<u8>) {
+        self.payload = data;
+        self.medium = b'd';
+    }
+    fn read_data(&mut self) -> Result<Vec<u8>, String> {
+        match self.medium {
+            b'd' => Ok(std::mem::take(&mut self.payload)),
+            _ => Err("EINVAL:unsupported transmission medium".to_owned()),
+        }
+    }
+}
+
+/// An image file a transmission asked us to read, which is read in the background (by
+/// `BackgroundJob::ReadKittyGraphicsFile`) so that a slow file system does not hold up the screen
+/// thread, the transmission then goes on with `Grid::finish_kitty_graphics_file_read`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KittyGraphicsFileRead {
+    pub id: u32,
+    path: PathBuf,
+    delete_after_reading: bool,
+    data_offset: usize,
+    data_size: usize,
+}
+
+impl KittyGraphicsFileRead {
+    pub fn read(&self) -> Result<Vec<u8>, String> {
+        read_file(
+            &self.path,
+            self.delete_after_reading,
+            self.data_offset,
+            self.data_size,
+        )
+    }
+}
+
+fn number<T: std::str::FromStr>(value: &[u8]) -> Option<T> {
+    std::str::from_utf8(value).ok()?.parse().ok()
+}
+
+/// Reads `data_size` bytes (all of them if it is 0) of the file from `data_offset`, as long as it
+/// is a regular file, deleting it afterwards if asked to and it is a temporary file
+fn read_file(
+    path: &Path,
+    delete_after_reading: bool,
+    data_offset: usize,
+    data_size: usize,
+) -> Result<Vec<u8>, String> {
+    let failed_to_read =
+        |e: std::io::Error| format!("EBADF:failed to read {}: {}", path.display(), e);
+    // so that the checks below (and the temporary file check) are about the file itself and not
+    // about a symlink to it
+    let canonical_path = fs::canonicalize(path).map_err(failed_to_read)?;
+    if is_in_forbidden_directory(&canonical_path) {
+        return Err(format!("EPERM:not allowed to read {}", path.display()));
+    }
+    // no following symlinks swapped in since, and no blocking on a fifo until we know what it is
+    let mut file = OpenOptions::new()
+        .read(true)
+        .custom_flags((OFlag::O_NOFOLLOW | OFlag::O_NONBLOCK).bits())
+        .open(&canonical_path)
+        .map_err(failed_to_read)?;
+    let metadata = file.metadata().map_err(failed_to_read)?;
+    if !metadata.is_file() {
+        return Err(format!("EBADF:{} is not a regular file", path.display()));
+    }
+    let size_to_read = match data_size {
+        0 => (metadata.len() as usize).saturating_sub(data_offset),
+        data_size => data_size,
+    };
+    if size_to_read > MAX_COMMAND_SIZE {
+        return Err("EFBIG:the file is too large".to_owned());
+    }
+    let mut data = vec![];
+    file.seek(SeekFrom::Start(data_offset as u64))
+        .and_then(|_| file.take(size_to_read as u64).read_to_end(&mut data))
+        .map_err(failed_to_read)?;
+    if delete_after_reading && is_temporary_file(&canonical_path) {
+        let _ = fs::remove_file(&canonical_path);
+    }
+    Ok(data)
+}
+
+fn is_in_forbidden_directory(canonical_path: &Path) -> bool {
+    FORBIDDEN_DIRECTORIES
+        .iter()
+        .any(|directory| canonical_path.starts_with(directory))
+        && !canonical_path.starts_with("/dev/shm")
+}
+
+// files transmitted with t=t are deleted once they are read, but only if they look like they were
+// made for this, so that an application cannot have us delete arbitrary files (the path is
+// canonicalized, so that eg. /tmp/../home/... is not taken for a temporary file)
+fn is_temporary_file(canonical_path: &Path) -> bool {
+    let temp_dir = std::env::temp_dir();
+    let in_temporary_directory = [
+        fs::canonicalize(&temp_dir).unwrap_or(temp_dir),
+        PathBuf::from("/tmp"),
+        PathBuf::from("/dev/shm"),
+    ]
+    .iter()
+    .any(|directory| canonical_path.starts_with(directory));
+    let is_named_like_one = canonical_path
+        .file_name()
+        .map(|file_name| {
+            file_name
+                .to_string_lossy()
+                .contains("tty-graphics-protocol")
+        })
+        .unwrap_or(false);
+    in_temporary_directory && is_named_like_one
+}
+
+fn png_size(data: &[u8]) -> Option<(usize, usize)> {
+    // the IHDR chunk, which holds the size of the image, is always the first one
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if data.len() < 24 || !data.starts_with(PNG_SIGNATURE) || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
+    let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
+    Some((width as usize, height as usize))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageFormat {
+    Rgb,
+    Rgba,
+    Png,
+}
+
+/// An image transmitted by an application
+#[derive(Debug)]
+pub struct KittyImage {
+    // the id the image is transmitted to the clients with, which is unique across panes
+    id: u32,
+    format: ImageFormat,
+    compressed: bool,
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl KittyImage {
+    /// Reads the image data of a transmit command, `id` being the id it will have on the clients
+    pub fn new(id: u32, command: &mut KittyGraphicsCommand) -> Result<Self, String> {
+        let format = match command.format {
+            24 => ImageFormat::Rgb,
+            32 => ImageFormat::Rgba,
+            100 => ImageFormat::Png,
+            format => return Err(format!("EINVAL:unknown image format {}", format)),
+        };
+        let compressed = match command.compression {
+            None => false,
+            Some(b'z') => true,
+            Some(_) => return Err("EINVAL:unknown compression".to_owned()),
+        };
+        let data = command.read_data()?;
+        if data.is_empty() {
+            return Err("ENODATA:no image data".to_owned());
+        }
+        let (width, height) = match format {
+            ImageFormat::Png if !compressed => {
+                png_size(&data).ok_or_else(|| "EBADPNG:not a png image".to_owned())?
+            },
+            _ => (command.data_width, command.data_height),
+        };
+        if width == 0 || height == 0 {
+            return Err("EINVAL:the size of the image is missing".to_owned());
+        }
+        if width.saturating_mul(height) > MAX_IMAGE_PIXELS {
+            return Err("EFBIG:the image is too large".to_owned());
+        }
+        let bytes_per_pixel = match format {
+            ImageFormat::Rgb => 3,
+            ImageFormat::Rgba => 4,
+            ImageFormat::Png => 0,
+        };
+        if !compressed && data.len() < width * height * bytes_per_pixel {
+            return Err("ENODATA:insufficient image data".to_owned());
+        }
+        Ok(KittyImage {
+            id,
+            format,
+            compressed,
+            width,
+            height,
+            data,
+        })
+    }
+    fn data_size(&self) -> usize {
+        self.data.len()
+    }
+    // only uncompressed rgb(a) images can be drawn with sixel, as we do not decode the others
+    fn has_raw_pixels(&self) -> bool {
+        !self.compressed && self.format != ImageFormat::Png
+    }
+    // the color of a pixel, or None if it's transparent
+    fn pixel(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        let bytes_per_pixel = match self.format {
+            ImageFormat::Rgb => 3,
+            ImageFormat::Rgba => 4,
+            ImageFormat::Png => return None,
+        };
+        let offset = (y * self.width + x) * bytes_per_pixel;
+        let pixel = self.data.get(offset..offset + bytes_per_pixel)?;
+        if bytes_per_pixel == 4 && pixel[3] < 128 {
+            None
+        } else {
+            Some([pixel[0], pixel[1], pixel[2]])
+        }
+    }
+}
+
+/// The images a pane transmitted, by the ids it gave them
+#[derive(Debug, Clone, Default)]
+pub struct KittyGraphicsState {
+    images: HashMap<u32, Rc<KittyImage>>,
+    // a transmission made of several chunks that is still being received
+    pending_command: Option<KittyGraphicsCommand>,
+    // the transmissions waiting for their file to be read, by the id of the read
+    pending_file_reads: HashMap<u32, KittyGraphicsCommand>,
+    next_file_read_id: u32,
+}
+
+impl KittyGraphicsState {
+    /// Adds the chunk of a transmission to the previous ones, returning the whole command once
+    /// its last chunk arrives
+    pub fn collect_chunks(
+        &mut self,
+        mut command: KittyGraphicsCommand,
+    ) -> Option<KittyGraphicsCommand> {
+        if let Some(mut pending_command) = self.pending_command.take() {
+            if pending_command.payload.len() + command.payload.len() > MAX_COMMAND_SIZE {
+                log::error!("Kitty graphics transmission is too large, dropping it");
+                return None;
+            }
+            pending_command.payload.append(&mut command.payload);
+            pending_command.more_chunks = command.more_chunks;
+            command = pending_command;
+        }
+        if command.more_chunks {
+            self.pending_command = Some(command);
+            None
+        } else {
+            Some(command)
+        }
+    }
+    /// Puts a transmission in a file aside until the file is read, returning what to read
+    pub fn start_file_read(
+        &mut self,
+        command: &KittyGraphicsCommand,
+    ) -> Result<KittyGraphicsFileRead, String> {
+        if self.pending_file_reads.len() >= MAX_PENDING_FILE_READS {
+            return Err("EBUSY:too many files are being read".to_owned());
+        }
+        let id = self.next_file_read_id;
+        self.next_file_read_id = self.next_file_read_id.wrapping_add(1);
+        let file_read = KittyGraphicsFileRead {
+            id,
+            path: Path::new(OsStr::from_bytes(&command.payload)).to_path_buf(),
+            delete_after_reading: command.medium == b't',
+            data_offset: command.data_offset,
+            data_size: command.data_size,
+        };
+        self.pending_file_reads.insert(id, command.clone());
+        Ok(file_read)
+    }
+    /// Forgets the images and transmissions, but not which file reads were started (so that one
+    /// still going on is not taken for one started after this)
+    pub fn reset(&mut self) {
+        *self = KittyGraphicsState {
+            next_file_read_id: self.next_file_read_id,
+            ..Default::default()
+        };
+    }
+    /// The transmission a file was read for (unless the pane was reset since)
+    pub fn take_file_read(&mut self, file_read_id: u32) -> Option<KittyGraphicsCommand> {
+        self.pending_file_reads.remove(&file_read_id)
+    }
+    pub fn image(&self, image_id: u32) -> Option<Rc<KittyImage>> {
+        self.images.get(&image_id).cloned()
+    }
+    pub fn add_image(&mut self, image_id: u32, image: Rc<KittyImage>) {
+        self.images.insert(image_id, image);
+        while self.images.values().map(|i| i.data_size()).sum::<usize>() > MAX_STORED_IMAGES_SIZE {
+            let oldest_image = self
+                .images
+                .iter()
+                .min_by_key(|(_, image)| image.id)
+                .map(|(image_id, _)| *image_id);
+            match oldest_image {
+                Some(oldest_image) => drop(self.images.remove(&oldest_image)),
+                None => break,
+            }
+        }
+    }
+    pub fn remove_image(&mut self, image_id: u32) {
+        self.images.remove(&image_id);
+    }
+}
+
+/// An image placed in a pane, which is positioned and clipped like a sixel image
+#[derive(Debug, Clone)]
+pub struct KittyPlacement {
+    image: Rc<KittyImage>,
+    pane_image_id: u32,
+    pane_placement_id: u32,
+    // the part of the image that is displayed, in image pixels
+    source_x: usize,
+    source_y: usize,
+    source_width: usize,
+    source_height: usize,
+    // the size it is displayed at, in screen pixels
+    width: usize,
+    height: usize,
+    z_index: i32,
+    // the cells (relative to the placement) that text was written over since
+    cut_out_cells: HashSet<(usize, usize)>,
+    sixel_cache: HashMap<PixelRect, String>,
+}
+
+impl KittyPlacement {
+    /// `max_columns` and `max_rows` being the size of the pane, which is as large as the image can
+    /// be asked to be displayed
+    pub fn new(
+        image: Rc<KittyImage>,
+        command: &KittyGraphicsCommand,
+        character_cell_size: SizeInPixels,
+        max_columns: usize,
+        max_rows: usize,
+    ) -> Result<Self, String> {
+        if character_cell_size.width == 0 || character_cell_size.height == 0 {
+            return Err("ENOTSUPPORTED:the size of the terminal cells is unknown".to_owned());
+        }
+        let source_x = std::cmp::min(command.x, image.width);
+        let source_y = std::cmp::min(command.y, image.height);
+        let source_width = match command.width {
+            0 => image.width - source_x,
+            width => std::cmp::min(width, image.width - source_x),
+        };
+        let source_height = match command.height {
+            0 => image.height - source_y,
+            height => std::cmp::min(height, image.height - source_y),
+        };
+        if source_width == 0 || source_height == 0 {
+            return Err("EINVAL:the part of the image to display is empty".to_owned());
+        }
+        let columns = std::cmp::min(command.columns, max_columns);
+        let rows = std::cmp::min(command.rows, max_rows);
+        let max_width = max_columns.saturating_mul(character_cell_size.width);
+        let max_height = max_rows.saturating_mul(character_cell_size.height);
+        // size * numerator / denominator
+        let scale = |size: usize, numerator: usize, denominator: usize| {
+            size.checked_mul(numerator)
+                .map(|size| size / denominator)
+                .ok_or_else(|| "EINVAL:the image is displayed too large".to_owned())
+        };
+        // when only one of the columns and rows is given, the image keeps its aspect ratio (and
+        // is made smaller if it does not fit in the pane that way)
+        let (width, height) = match (columns, rows) {
+            (0, 0) => (source_width, source_height),
+            (columns, 0) => {
+                let width = columns.saturating_mul(character_cell_size.width);
+                match scale(source_height, width, source_width)? {
+                    height if height > max_height => {
+                        (scale(source_width, max_height, source_height)?, max_height)
+                    },
+                    height => (width, height),
+                }
+            },
+            (0, rows) => {
+                let height = rows.saturating_mul(character_cell_size.height);
+                match scale(source_width, height, source_height)? {
+                    width if width > max_width => {
+                        (max_width, scale(source_height, max_width, source_width)?)
+                    },
+                    width => (width, height),
+                }
+            },
+            (columns, rows) => (
+                columns.saturating_mul(character_cell_size.width),
+                rows.saturating_mul(character_cell_size.height),
+            ),
+        };
+        if width == 0 || height == 0 {
+            return Err("EINVAL:the image is displayed with no size".to_owned());
+        }
+        Ok(KittyPlacement {
+            image,
+            pane_image_id: command.image_id,
+            pane_placement_id: command.placement_id,
+            source_x,
+            source_y,
+            source_width,
+            source_height,
+            width,
+            height,
+            z_index: command.z_index,
+            cut_out_cells: HashSet::new(),
+            sixel_cache: HashMap::new(),
+        })
+    }
+    /// The size the placement is displayed at, in pixels (width, height)
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+    pub fn pane_ids(&self) -> (u32, u32) {
+        (self.pane_image_id, self.pane_placement_id)
+    }
+    pub fn image_id(&self) -> u32 {
+        self.image.id
+    }
+    fn is_scaled(&self) -> bool {
+        self.width != self.source_width || self.height != self.source_height
+    }
+    pub fn cut_out(&mut self, rect: &PixelRect, character_cell_size: SizeInPixels) {
+        let first_column = rect.x / character_cell_size.width;
+        let first_row = rect.y.max(0) as usize / character_cell_size.height;
+        let columns = (rect.width + character_cell_size.width - 1) / character_cell_size.width;
+        let rows = (rect.height + character_cell_size.height - 1) / character_cell_size.height;
+        for row in first_row..first_row + rows {
+            for column in first_column..first_column + columns {
+                self.cut_out_cells.insert((column, row));
+            }
+        }
+        self.sixel_cache.clear();
+    }
+    fn is_cut_out(&self, column: usize, row: usize) -> bool {
+        self.cut_out_cells.contains(&(column, row))
+    }
+    // clips a part of the placement to its size
+    fn clip(&self, rect: PixelRect) -> PixelRect {
+        PixelRect {
+            width: std::cmp::min(rect.width, self.width.saturating_sub(rect.x)),
+            height: std::cmp::min(rect.height, self.height.saturating_sub(rect.y as usize)),
+            ..rect
+        }
+    }
+    // the part of the image that a part of the placement displays
+    fn source_rect(&self, rect: &PixelRect) -> PixelRect {
+        let scale_x = |x: usize| x * self.source_width / self.width;
+        let scale_y = |y: usize| y * self.source_height / self.height;
+        PixelRect {
+            x: self.source_x + scale_x(rect.x),
+            y: (self.source_y + scale_y(rect.y as usize)) as isize,
+            width: std::cmp::max(scale_x(rect.width), 1),
+            height: std::cmp::max(scale_y(rect.height), 1),
+        }
+    }
+    fn serialize_as_sixel(
+        &mut self,
+        rect: PixelRect,
+        character_cell_size: SizeInPixels,
+    ) -> Option<String> {
+        if !self.image.has_raw_pixels() {
+            return None;
+        }
+        let rect = self.clip(rect);
+        if let Some(serialized_image) = self.sixel_cache.get(&rect) {
+            return Some(serialized_image.clone());
+        }
+        let serialized_image = encode_sixel(rect.width, rect.height, |x, y| {
+            let x = rect.x + x;
+            let y = rect.y as usize + y;
+            if self.is_cut_out(
+                x / character_cell_size.width,
+                y / character_cell_size.height,
+            ) {
+                return None;
+            }
+            self.image.pixel(
+                self.source_x + x * self.source_width / self.width,
+                self.source_y + y * self.source_height / self.height,
+            )
+        });
+        self.sixel_cache.insert(rect, serialized_image.clone());
+        Some(serialized_image)
+    }
+    /// The image in its sixel form if it can be encoded as one, and a placeholder otherwise, for
+    /// clients whose terminal does not support kitty graphics
+    pub fn serialize_fallback(
+        &mut self,
+        chunk: &SixelImageChunk,
+        character_cell_size: SizeInPixels,
+    ) -> String {
+        let rect = PixelRect::new(
+            chunk.sixel_image_pixel_x,
+            chunk.sixel_image_pixel_y,
+            chunk.sixel_image_pixel_height,
+            chunk.sixel_image_pixel_width,
+        );
+        match self.serialize_as_sixel(rect, character_cell_size) {
+            Some(serialized_image) => serialized_image,
+            None if chunk.sixel_image_pixel_y == 0 => {
+                let columns = chunk.sixel_image_pixel_width / character_cell_size.width;
+                PLACEHOLDER.chars().take(columns).collect()
+            },
+            None => String::new(),
+        }
+    }
+}
+
+// encodes an image with the colors of the 6x6x6 color cube, leaving transparent pixels alone
+fn encode_sixel(
+    width: usize,
+    height: usize,
+    pixel: impl Fn(usize, usize) -> Option<[u8; 3]>,
+) -> String {
+    let color_index = |[r, g, b]: [u8; 3]| {
+        let level = |c: u8| (c as usize * 5 + 127) / 255;
+        level(r) * 36 + level(g) * 6 + level(b)
+    };
+    let mut sixel = format!("\u{1b}P0;1;0q\"1;1;{};{}", width, height);
+    for color in 0..216 {
+        let (r, g, b) = (color / 36, color / 6 % 6, color % 6);
+        let _ = write!(sixel, "#{};2;{};{};{}", color, r * 20, g * 20, b * 20);
+    }
+    for band_top in (0..height).step_by(6) {
+        let mut bands: BTreeMap<usize, Vec<u8>> = BTreeMap::new();
+        for y in band_top..std::cmp::min(band_top + 6, height) {
+            for x in 0..width {
+                if let Some(rgb) = pixel(x, y) {
+                    let band = bands
+                        .entry(color_index(rgb))
+                        .or_insert_with(|| vec![0; width]);
+                    band[x] |= 1 << (y - band_top);
+                }
+            }
+        }
+        for (i, (color, band)) in bands.iter().enumerate() {
+            if i > 0 {
+                sixel.push('$'); // back to the start of the band, to draw the next color over it
+            }
+            let _ = write!(sixel, "#{}", color);
+            let mut x = 0;
+            while x < band.len() {
+                let run = band[x..].iter().take_while(|b| **b == band[x]).count();
+                let character = (63 + band[x]) as char;
+                if run > 3 {
+                    let _ = write!(sixel, "!{}{}", run, character);
+                } else {
+                    sixel.push_str(&character.to_string().repeat(run));
+                }
+                x += run;
+            }
+        }
+        sixel.push('-');
+    }
+    sixel.push_str("\u{1b}\\");
+    sixel
+}
+
+fn transmission(image: &KittyImage) -> String {
+    let format = match image.format {
+        ImageFormat::Rgb => 24,
+        ImageFormat::Rgba => 32,
+        ImageFormat::Png => 100,
+    };
+    let encoded_data = base64::encode(&image.data);
+    let chunks: Vec<&[u8]> = encoded_data
+        .as_bytes()
+        .chunks(TRANSMISSION_CHUNK_SIZE)
+        .collect();
+    let mut transmission = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more_chunks = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            let _ = write!(
+                transmission,
+                "\u{1b}_Ga=t,q=2,i={},f={},s={},v={}",
+                image.id, format, image.width, image.height
+            );
+            if image.compressed {
+                transmission.push_str(",o=z");
+            }
+            let _ = write!(transmission, ",m={}", more_chunks);
+        } else {
+            let _ = write!(transmission, "\u{1b}_Gq=2,m={}", more_chunks);
+        }
+        transmission.push(';');
+        transmission.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        transmission.push_str("\u{1b}\\");
+    }
+    transmission
+}
+
+// a part of a placement one cell high, which a client displays as a placement of its own so that
+// text can be drawn next to it
+#[derive(Debug, Clone, Copy)]
+struct KittySlice {
+    placement: usize,
+    image_id: u32,
+    placement_id: u32,
+    cell_x: usize,
+    cell_y: usize,
+    columns: usize,
+    rect: PixelRect, // relative to the placement
+}
+
+impl KittySlice {
+    fn covers(&self, x: usize, y: usize, width: usize) -> bool {
+        self.cell_y == y && self.cell_x < x + width && x < self.cell_x + self.columns
+    }
+}
+
+/// What a client whose terminal supports kitty graphics is displaying
+///
+/// Text does not hide the images under it in such terminals, so rather than drawing images whole
+/// we place them one row of cells at a time, and delete the parts of rows that text is drawn over.
+#[derive(Debug, Clone, Default)]
+pub struct KittyGraphicsClient {
+    transmitted_images: HashSet<u32>,
+    slices: Vec<KittySlice>,
+    next_placement_id: u32,
+    // deletions of images that are gone, sent with the next render
+    pending_deletions: String,
+}
+
+impl KittyGraphicsClient {
+    pub fn serialize_chunk(
+        &mut self,
+        placement_key: usize,
+        placement: &KittyPlacement,
+        chunk: &SixelImageChunk,
+        character_cell_size: SizeInPixels,
+    ) -> String {
+        let mut output = String::new();
+        if self.transmitted_images.insert(placement.image_id()) {
+            output.push_str(&transmission(&placement.image));
+        }
+        let SizeInPixels {
+            width: cell_width,
+            height: cell_height,
+        } = character_cell_size;
+        let right_edge = std::cmp::min(
+            chunk.sixel_image_pixel_x + chunk.sixel_image_pixel_width,
+            placement.width,
+        );
+        let bottom_edge = std::cmp::min(
+            chunk.sixel_image_pixel_y + chunk.sixel_image_pixel_height,
+            placement.height,
+        );
+        let first_column = chunk.sixel_image_pixel_x / cell_width;
+        let first_row = chunk.sixel_image_pixel_y / cell_height;
+        let last_column = (right_edge + cell_width - 1) / cell_width;
+        let last_row = (bottom_edge + cell_height - 1) / cell_height;
+        for row in first_row..last_row {
+            let mut column = first_column;
+            while column < last_column {
+                if placement.is_cut_out(column, row) {
+                    column += 1;
+                    continue;
+                }
+                let run_start = column;
+                while column < last_column && !placement.is_cut_out(column, row) {
+                    column += 1;
+                }
+                let x = run_start * cell_width;
+                let y = row * cell_height;
+                let rect = PixelRect::new(
+                    x,
+                    y,
+                    std::cmp::min(cell_height, bottom_edge - y),
+                    std::cmp::min(column * cell_width, right_edge) - x,
+                );
+                self.place_slice(
+                    &mut output,
+                    placement_key,
+                    placement,
+                    (
+                        chunk.cell_x + run_start - first_column,
+                        chunk.cell_y + row - first_row,
+                    ),
+                    column - run_start,
+                    rect,
+                );
+            }
+        }
+        output
+    }
+    /// Deletes the parts of images displayed where text is drawn (`width` cells from `x`, `y`)
+    pub fn uncover(
+        &mut self,
+        placements: &HashMap<usize, KittyPlacement>,
+        x: usize,
+        y: usize,
+        width: usize,
+        character_cell_size: SizeInPixels,
+    ) -> String {
+        let mut output = String::new();
+        let covered_slices = self.delete_slices(&mut output, |slice| slice.covers(x, y, width));
+        for slice in covered_slices {
+            let placement = match placements.get(&slice.placement) {
+                Some(placement) => placement,
+                None => continue,
+            };
+            if slice.cell_x < x {
+                let columns = x - slice.cell_x;
+                let rect = PixelRect {
+                    width: std::cmp::min(columns * character_cell_size.width, slice.rect.width),
+                    ..slice.rect
+                };
+                self.place_slice(
+                    &mut output,
+                    slice.placement,
+                    placement,
+                    (slice.cell_x, y),
+                    columns,
+                    rect,
+                );
+            }
+            let slice_end = slice.cell_x + slice.columns;
+            if x + width < slice_end {
+                let skipped_width = (x + width - slice.cell_x) * character_cell_size.width;
+                let rect = PixelRect {
+                    x: slice.rect.x + skipped_width,
+                    width: slice.rect.width.saturating_sub(skipped_width),
+                    ..slice.rect
+                };
+                if rect.width > 0 {
+                    self.place_slice(
+                        &mut output,
+                        slice.placement,
+                        placement,
+                        (x + width, y),
+                        slice_end - (x + width),
+                        rect,
+                    );
+                }
+            }
+        }
+        output
+    }
+    pub fn forget_placement(&mut self, placement_key: usize, image_id: u32, image_is_placed: bool) {
+        let mut deletions = std::mem::take(&mut self.pending_deletions);
+        self.delete_slices(&mut deletions, |slice| slice.placement == placement_key);
+        if !image_is_placed && self.transmitted_images.remove(&image_id) {
+            let _ = write!(deletions, "\u{1b}_Ga=d,d=I,q=2,i={}\u{1b}\\", image_id);
+        }
+        self.pending_deletions = deletions;
+    }
+    pub fn drain_pending_deletions(&mut self) -> String {
+        std::mem::take(&mut self.pending_deletions)
+    }
+    fn place_slice(
+        &mut self,
+        output: &mut String,
+        placement_key: usize,
+        placement: &KittyPlacement,
+        (cell_x, cell_y): (usize, usize),
+        columns: usize,
+        rect: PixelRect,
+    ) {
+        // a slice that is displayed in the same cells is replaced in place, and the other ones
+        // of the same placement overlapping them are deleted
+        let placement_id = match self.slices.iter().position(|slice| {
+            slice.placement == placement_key
+                && slice.cell_x == cell_x
+                && slice.cell_y == cell_y
+                && slice.columns == columns
+        }) {
+            Some(existing_slice) => self.slices.remove(existing_slice).placement_id,
+            None => {
+                self.next_placement_id = self.next_placement_id.wrapping_add(1).max(1);
+                self.next_placement_id
+            },
+        };
+        self.delete_slices(output, |slice| {
+            slice.placement == placement_key && slice.covers(cell_x, cell_y, columns)
+        });
+        let source = placement.source_rect(&rect);
+        let _ = write!(
+            output,
+            "\u{1b}[{};{}H\u{1b}_Ga=p,q=2,i={},p={},x={},y={},w={},h={},z={},C=1",
+            cell_y + 1,
+            cell_x + 1,
+            placement.image_id(),
+            placement_id,
+            source.x,
+            source.y,
+            source.width,
+            source.height,
+            placement.z_index,
+        );
+        if placement.is_scaled() {
+            let _ = write!(output, ",c={},r=1", columns);
+        }
+        output.push_str("\u{1b}\\");
+        self.slices.push(KittySlice {
+            placement: placement_key,
+            image_id: placement.image_id(),
+            placement_id,
+            cell_x,
+            cell_y,
+            columns,
+            rect,
+        });
+    }
+    fn delete_slices(
+        &mut self,
+        output: &mut String,
+        should_delete: impl Fn(&KittySlice) -> bool,
+    ) -> Vec<KittySlice> {
+        let (deleted_slices, slices): (Vec<KittySlice>, Vec<KittySlice>) = self
+            .slices
+            .drain(..)
+            .partition(|slice| should_delete(slice));
+        self.slices = slices;
+        for slice in &deleted_slices {
+            let _ = write!(
+                output,
+                "\u{1b}_Ga=d,d=i,q=2,i={},p={}\u{1b}\\",
+                slice.image_id, slice.placement_id
+            );
+        }
+        deleted_slices
+    }
+}
@@ -0,0 +1,63 @@
+// the only progressive enhancement of the kitty keyboard protocol we support - we receive our
+// input with just this one enabled, so we cannot provide the others (eg. key release events)
+const DISAMBIGUATE_ESCAPE_CODES: u8 = 0b1;
+
+// so that an application pushing without ever popping does not grow the stack forever
+const MAX_STACK_SIZE: usize = 64;
+
+/// The kitty keyboard protocol flags applications pushed with `CSI > flags u`, with the stacks of
+/// the main and alternate screens kept separately as the protocol requires
+#[derive(Debug, Clone, Default)]
+pub struct KittyKeyboardFlags {
+    main_screen: Vec<u8>,
+    alternate_screen: Vec<u8>,
+}
+
+impl KittyKeyboardFlags {
+    pub fn push(&mut self, flags: u8, alternate_screen: bool) {
+        let stack = self.stack_mut(alternate_screen);
+        if stack.len() >= MAX_STACK_SIZE {
+            stack.remove(0);
+        }
+        stack.push(flags);
+    }
+    pub fn pop(&mut self, count: usize, alternate_screen: bool) {
+        let stack = self.stack_mut(alternate_screen);
+        let new_len = stack.len().saturating_sub(count);
+        stack.truncate(new_len);
+    }
+    /// `CSI = flags ; mode u` - 1 sets the current flags to `flags`, 2 adds `flags` to them and 3
+    /// removes `flags` from them
+    pub fn set(&mut self, flags: u8, mode: usize, alternate_screen: bool) {
+        let stack = self.stack_mut(alternate_screen);
+        let current = stack.last().copied().unwrap_or(0);
+        let updated = match mode {
+            2 => current | flags,
+            3 => current & !flags,
+            _ => flags,
+        };
+        match stack.last_mut() {
+            Some(top) => *top = updated,
+            None => stack.push(updated),
+        }
+    }
+    /// The current flags, as reported to the application when it queries them with `CSI ? u`
+    pub fn current(&self, alternate_screen: bool) -> u8 {
+        let stack = if alternate_screen {
+            &self.alternate_screen
+        } else {
+            &self.main_screen
+        };
+        stack.last().copied().unwrap_or(0) & DISAMBIGUATE_ESCAPE_CODES
+    }
+    pub fn disambiguate_escape_codes(&self, alternate_screen: bool) -> bool {
+        self.current(alternate_screen) & DISAMBIGUATE_ESCAPE_CODES != 0
+    }
+    fn stack_mut(&mut self, alternate_screen: bool) -> &mut Vec<u8> {
+        if alternate_screen {
+            &mut self.alternate_screen
+        } else {
+            &mut self.main_screen
+        }
+    }
+}
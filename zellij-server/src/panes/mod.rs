@@ -6,18 +6,35 @@ pub mod sixel;
 pub mod terminal_character;
 
 mod active_panes;
+mod capability_queries;
+mod copy_mode;
 mod floating_panes;
+mod grapheme;
+mod kitty_graphics;
+mod kitty_keyboard;
+mod pane_monitor;
+mod pane_output_pipe;
 mod plugin_pane;
+mod reported_cwd;
+mod scrollback;
 mod search;
+mod semantic_prompt;
 mod terminal_pane;
 mod tiled_panes;
 
 pub use active_panes::*;
 pub use alacritty_functions::*;
 pub use floating_panes::*;
+pub use grapheme::Grapheme;
 pub use grid::*;
+pub use kitty_graphics::KittyGraphicsFileRead;
 pub use link_handler::*;
+pub use pane_monitor::{PaneAlerts, PaneMonitor};
 pub(crate) use plugin_pane::*;
+pub use reported_cwd::ReportedCwd;
+pub use scrollback::{
+    set_scrollback_memory_budget, take_scrollback_evictions, Scrollback, ScrollbackIter,
+};
 pub use sixel::*;
 pub(crate) use terminal_character::*;
 pub use terminal_pane::*;
@@ -0,0 +1,186 @@
+use std::time::{Duration, Instant};
+
+use zellij_utils::input::layout::PaneMonitors;
+
+/// The alerts raised by the monitors of a pane, until a client focuses it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PaneAlerts {
+    pub bell: bool,
+    pub activity: bool,
+    pub silence: bool,
+}
+
+impl PaneAlerts {
+    pub fn any(&self) -> bool {
+        self.bell || self.activity || self.silence
+    }
+    pub fn or(self, other: PaneAlerts) -> Self {
+        PaneAlerts {
+            bell: self.bell || other.bell,
+            activity: self.activity || other.activity,
+            silence: self.silence || other.silence,
+        }
+    }
+}
+
+/// Keeps track of the output of a pane to raise the alerts of the monitors it has, none of them
+/// are on unless they're turned on
+pub struct PaneMonitor {
+    monitors: PaneMonitors, // the ones from the layout, falling back to the defaults
+    alerts: PaneAlerts,
+    rang_bell: bool,
+    had_output: bool,
+    last_output_at: Instant,
+    is_silence_reported: bool, // silence is reported once until there's output again
+}
+
+impl Default for PaneMonitor {
+    fn default() -> Self {
+        PaneMonitor {
+            monitors: PaneMonitors::default(),
+            alerts: PaneAlerts::default(),
+            rang_bell: false,
+            had_output: false,
+            last_output_at: Instant::now(),
+            is_silence_reported: false,
+        }
+    }
+}
+
+impl PaneMonitor {
+    pub fn set_monitors(&mut self, monitors: PaneMonitors) {
+        self.monitors = monitors;
+    }
+    pub fn monitors(&self) -> PaneMonitors {
+        self.monitors
+    }
+    pub fn is_monitoring_silence(&self, defaults: PaneMonitors) -> bool {
+        self.monitors
+            .or(defaults)
+            .silence
+            .map(|silence| silence > 0)
+            .unwrap_or(false)
+    }
+    pub fn output(&mut self, rang_bell: bool) {
+        self.rang_bell |= rang_bell;
+        self.had_output = true;
+        self.last_output_at = Instant::now();
+        self.is_silence_reported = false;
+    }
+    /// Raises the alerts of the monitors triggered since the last time this was called and
+    /// returns the ones that were not raised already
+    pub fn new_alerts(&mut self, defaults: PaneMonitors, now: Instant) -> PaneAlerts {
+        let monitors = self.monitors.or(defaults);
+        let rang_bell = std::mem::take(&mut self.rang_bell) && monitors.bell.unwrap_or(false);
+        let had_output = std::mem::take(&mut self.had_output) && monitors.activity.unwrap_or(false);
+        let went_silent = match monitors.silence {
+            Some(silence) if silence > 0 && !self.is_silence_reported => {
+                now.saturating_duration_since(self.last_output_at) >= Duration::from_secs(silence)
+            },
+            _ => false,
+        };
+        if went_silent {
+            self.is_silence_reported = true;
+        }
+        let new_alerts = PaneAlerts {
+            bell: rang_bell && !self.alerts.bell,
+            activity: had_output && !self.alerts.activity,
+            silence: went_silent && !self.alerts.silence,
+        };
+        self.alerts = self.alerts.or(new_alerts);
+        new_alerts
+    }
+    /// Returns whether there were any alerts to clear
+    pub fn clear_alerts(&mut self) -> bool {
+        let had_alerts = self.alerts.any();
+        self.alerts = PaneAlerts::default();
+        had_alerts
+    }
+    pub fn alerts(&self) -> PaneAlerts {
+        self.alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_monitored_by_default() {
+        let mut pane_monitor = PaneMonitor::default();
+        pane_monitor.output(true);
+        let new_alerts = pane_monitor.new_alerts(PaneMonitors::default(), Instant::now());
+        assert!(!new_alerts.any());
+        assert!(!pane_monitor.is_monitoring_silence(PaneMonitors::default()));
+    }
+
+    #[test]
+    fn bell_is_reported_until_it_is_cleared() {
+        let mut pane_monitor = PaneMonitor::default();
+        let defaults = PaneMonitors {
+            bell: Some(true),
+            ..Default::default()
+        };
+        pane_monitor.output(true);
+        let new_alerts = pane_monitor.new_alerts(defaults, Instant::now());
+        assert_eq!(
+            new_alerts,
+            PaneAlerts {
+                bell: true,
+                ..Default::default()
+            }
+        );
+        pane_monitor.output(true);
+        let new_alerts = pane_monitor.new_alerts(defaults, Instant::now());
+        assert!(
+            !new_alerts.any(),
+            "bell is only reported until it is cleared"
+        );
+        assert!(pane_monitor.clear_alerts());
+        assert!(!pane_monitor.alerts().any());
+    }
+
+    #[test]
+    fn monitors_in_the_layout_override_the_defaults() {
+        let mut pane_monitor = PaneMonitor::default();
+        pane_monitor.set_monitors(PaneMonitors {
+            bell: Some(false),
+            activity: Some(true),
+            silence: None,
+        });
+        let defaults = PaneMonitors {
+            bell: Some(true),
+            activity: Some(false),
+            silence: None,
+        };
+        pane_monitor.output(true);
+        let new_alerts = pane_monitor.new_alerts(defaults, Instant::now());
+        assert_eq!(
+            new_alerts,
+            PaneAlerts {
+                activity: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn silence_is_reported_once_until_there_is_output() {
+        let mut pane_monitor = PaneMonitor::default();
+        let defaults = PaneMonitors {
+            silence: Some(5),
+            ..Default::default()
+        };
+        assert!(pane_monitor.is_monitoring_silence(defaults));
+        let start = Instant::now();
+        pane_monitor.output(false);
+        assert!(!pane_monitor.new_alerts(defaults, start).silence);
+        let later = start + Duration::from_secs(6);
+        assert!(pane_monitor.new_alerts(defaults, later).silence);
+        pane_monitor.clear_alerts();
+        assert!(!pane_monitor.new_alerts(defaults, later).silence);
+        pane_monitor.output(false);
+        let much_later = Instant::now() + Duration::from_secs(6);
+        assert!(pane_monitor.new_alerts(defaults, much_later).silence);
+    }
+}
@@ -0,0 +1,160 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use zellij_utils::errors::prelude::*;
+use zellij_utils::input::actions::PipePaneTarget;
+use zellij_utils::vte::{Parser, Perform};
+
+/// How many chunks of output can be waiting to be written before we start dropping them
+const MAX_PENDING_CHUNKS: usize = 1024;
+/// How often the writer thread checks whether the command it writes to has exited while the pane
+/// has no output
+const COMMAND_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Tees the bytes a terminal pane receives from its pty to a file or to the stdin of a command.
+///
+/// They are written on a thread of their own, so that a slow target never holds up the screen
+/// thread (if it falls too far behind, the output is dropped rather than buffered without bound).
+/// Dropping the pipe stops it (and closes the stdin of the command, so that it can exit).
+pub struct PaneOutputPipe {
+    sender: SyncSender<Vec<u8>>,
+    is_writing: Arc<AtomicBool>, // false once the writer thread is done (eg. the command exited)
+    dropped_bytes: usize,        // since the writer last caught up
+}
+
+impl PaneOutputPipe {
+    pub fn new(target: PipePaneTarget, strip_ansi: bool) -> Result<Self> {
+        let err_context = || format!("failed to pipe pane output to {target:?}");
+
+        let (mut writer, mut child): (Box<dyn Write + Send>, Option<Child>) = match &target {
+            PipePaneTarget::File(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(err_context)?;
+                (Box::new(file), None)
+            },
+            PipePaneTarget::Command(command) => {
+                let mut child = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .with_context(err_context)?;
+                let stdin = child
+                    .stdin
+                    .take()
+                    .context("could not get stdin")
+                    .with_context(err_context)?;
+                (Box::new(stdin), Some(child))
+            },
+        };
+        let (sender, receiver) = sync_channel::<Vec<u8>>(MAX_PENDING_CHUNKS);
+        let is_writing = Arc::new(AtomicBool::new(true));
+        thread::Builder::new()
+            .name("pipe_pane".to_string())
+            .spawn({
+                let is_writing = is_writing.clone();
+                move || {
+                    let mut vte_parser = Parser::new();
+                    loop {
+                        let bytes = match receiver.recv_timeout(COMMAND_EXIT_POLL_INTERVAL) {
+                            Ok(bytes) => bytes,
+                            Err(RecvTimeoutError::Timeout) => {
+                                let command_exited = child
+                                    .as_mut()
+                                    .map(|child| matches!(child.try_wait(), Ok(Some(_))))
+                                    .unwrap_or(false);
+                                if command_exited {
+                                    break;
+                                }
+                                continue;
+                            },
+                            Err(RecvTimeoutError::Disconnected) => break,
+                        };
+                        let bytes = if strip_ansi {
+                            strip_ansi_escape_sequences(&mut vte_parser, &bytes)
+                        } else {
+                            bytes
+                        };
+                        if let Err(e) = writer.write_all(&bytes).and_then(|_| writer.flush()) {
+                            log::error!("Failed to pipe pane output: {}", e);
+                            break;
+                        }
+                    }
+                    is_writing.store(false, Ordering::SeqCst);
+                    drop(writer);
+                    if let Some(child) = child.as_mut() {
+                        let _ = child.wait();
+                    }
+                }
+            })
+            .with_context(err_context)?;
+        Ok(PaneOutputPipe {
+            sender,
+            is_writing,
+            dropped_bytes: 0,
+        })
+    }
+    /// Returns false if the pipe is closed (its target can no longer be written to), in which case
+    /// it should be dropped
+    pub fn write(&mut self, bytes: &[u8]) -> bool {
+        match self.sender.try_send(bytes.to_vec()) {
+            Ok(()) => {
+                if self.dropped_bytes > 0 {
+                    log::warn!(
+                        "Dropped {} bytes of pane output that could not be piped fast enough",
+                        self.dropped_bytes
+                    );
+                    self.dropped_bytes = 0;
+                }
+                self.is_open()
+            },
+            Err(TrySendError::Full(_)) => {
+                self.dropped_bytes += bytes.len();
+                self.is_open()
+            },
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+    pub fn is_open(&self) -> bool {
+        self.is_writing.load(Ordering::SeqCst)
+    }
+}
+
+/// Keeps only the printable characters and the line breaks and tabs, dropping escape sequences
+/// (the parser holds on to sequences that are cut in the middle until the rest of them arrives)
+fn strip_ansi_escape_sequences(vte_parser: &mut Parser, bytes: &[u8]) -> Vec<u8> {
+    let mut plain_text = PlainText::default();
+    for byte in bytes {
+        vte_parser.advance(&mut plain_text, *byte);
+    }
+    plain_text.bytes
+}
+
+#[derive(Default)]
+struct PlainText {
+    bytes: Vec<u8>,
+}
+
+impl Perform for PlainText {
+    fn print(&mut self, c: char) {
+        let mut buf = [0; 4];
+        self.bytes
+            .extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    }
+    fn execute(&mut self, byte: u8) {
+        if matches!(byte, b'\n' | b'\r' | b'\t') {
+            self.bytes.push(byte);
+        }
+    }
+}
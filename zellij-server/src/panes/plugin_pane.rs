@@ -562,6 +562,13 @@ impl Pane for PluginPane {
     fn borderless(&self) -> bool {
         self.borderless
     }
+    fn update_style(&mut self, style: Style) {
+        self.style = style;
+        for grid in self.grids.values_mut() {
+            grid.update_style(style);
+        }
+        self.set_should_render(true);
+    }
     fn set_exclude_from_sync(&mut self, exclude_from_sync: bool) {
         self.exclude_from_sync = exclude_from_sync;
     }
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::str;
+use url::Url;
+use zellij_utils::nix::unistd::gethostname;
+
+/// The working directory of a terminal pane, as reported by the shell running inside it with
+/// OSC 7 (eg. `ESC ] 7 ; file://hostname/path/to/dir BEL`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportedCwd {
+    pub path: PathBuf,
+    pub hostname: Option<String>, // None if the report did not include one
+}
+
+impl ReportedCwd {
+    pub fn from_osc_params(params: &[&[u8]]) -> Option<Self> {
+        // the path itself might contain semicolons
+        let uri = params
+            .get(1..)?
+            .iter()
+            .map(|param| str::from_utf8(param).ok())
+            .collect::<Option<Vec<&str>>>()?
+            .join(";");
+        let url = Url::parse(&uri).ok()?;
+        if url.scheme() != "file" {
+            return None;
+        }
+        let hostname = url
+            .host_str()
+            .filter(|hostname| !hostname.is_empty())
+            .map(|hostname| hostname.to_owned());
+        // the path is only converted if the url points to the local machine, but we want to
+        // record it regardless (the hostname is kept separately)
+        let path = Url::parse(&format!("file://{}", url.path()))
+            .ok()?
+            .to_file_path()
+            .ok()?;
+        Some(ReportedCwd { path, hostname })
+    }
+    /// The path, if it is on this machine rather than eg. on the other side of an ssh session
+    pub fn local_path(&self) -> Option<&PathBuf> {
+        if self.is_local_to(local_hostname().as_deref()) {
+            Some(&self.path)
+        } else {
+            None
+        }
+    }
+    pub fn is_local_to(&self, local_hostname: Option<&str>) -> bool {
+        match self.hostname.as_deref() {
+            None => true,
+            Some(hostname) => {
+                hostname.eq_ignore_ascii_case("localhost")
+                    || local_hostname
+                        .map(|local_hostname| hostname.eq_ignore_ascii_case(local_hostname))
+                        .unwrap_or(false)
+            },
+        }
+    }
+}
+
+fn local_hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    gethostname(&mut buffer)
+        .ok()
+        .and_then(|hostname| hostname.to_str().ok())
+        .map(|hostname| hostname.to_owned())
+}
@@ -0,0 +1,622 @@
+use crate::panes::grapheme::Grapheme;
+use crate::panes::grid::Row;
+use crate::panes::semantic_prompt::{is_prompt_line, SemanticPromptMarks};
+use crate::panes::terminal_character::{CharacterStyles, RcCharacterStyles, TerminalCharacter};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::rc::{Rc, Weak};
+
+/// How many of the newest lines of the scrollback are kept as they are, these are the ones that
+/// are about to be scrolled back into view or joined with a wrapped line of the viewport, so
+/// there is no point in packing and inflating them over and over again
+const UNPACKED_LINES: usize = 100;
+
+/// How many styles of a style table may be unused by its lines before it is compacted (as long as
+/// they are also more than half of its styles)
+const UNUSED_STYLES_BEFORE_COMPACTING: usize = 256;
+
+thread_local! {
+    // all the panes of a session live on the screen thread
+    static SCROLLBACK_MEMORY: RefCell<ScrollbackMemory> = RefCell::new(ScrollbackMemory::default());
+}
+
+/// Limits the memory taken up by the packed lines of all the scrollbacks on this thread together
+/// (in bytes), once it is exceeded the oldest lines are dropped first (whichever pane they belong
+/// to). None means there is no limit
+pub fn set_scrollback_memory_budget(budget: Option<usize>) {
+    SCROLLBACK_MEMORY.with(|memory| {
+        let mut memory = memory.borrow_mut();
+        memory.budget = budget;
+        memory.evict_oldest_lines();
+    });
+}
+
+/// Whether lines were dropped from any scrollback to keep the session under its memory budget
+/// since the last time this was called, if so the panes should be told with
+/// `drop_lines_evicted_from_scrollback`
+pub fn take_scrollback_evictions() -> bool {
+    SCROLLBACK_MEMORY.with(|memory| std::mem::take(&mut memory.borrow_mut().lines_evicted))
+}
+
+// never called while the memory is borrowed: eviction accounts for the lines it drops itself
+fn release_scrollback_memory(amount: usize) {
+    let _ = SCROLLBACK_MEMORY.try_with(|memory| {
+        let mut memory = memory.borrow_mut();
+        memory.used = memory.used.saturating_sub(amount);
+    });
+}
+
+#[derive(Default)]
+struct ScrollbackMemory {
+    budget: Option<usize>,
+    used: usize,
+    next_sequence: u64,
+    scrollbacks: Vec<Weak<RefCell<PackedLines>>>,
+    lines_evicted: bool,
+}
+
+impl ScrollbackMemory {
+    fn register(&mut self, packed_lines: &Rc<RefCell<PackedLines>>) {
+        self.scrollbacks
+            .retain(|packed_lines| packed_lines.strong_count() > 0);
+        self.scrollbacks.push(Rc::downgrade(packed_lines));
+    }
+    fn next_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+    fn evict_oldest_lines(&mut self) {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return,
+        };
+        while self.used > budget {
+            let oldest = self
+                .scrollbacks
+                .iter()
+                .filter_map(|packed_lines| packed_lines.upgrade())
+                .filter_map(|packed_lines| {
+                    let oldest_sequence = packed_lines.borrow().oldest_sequence()?;
+                    Some((oldest_sequence, packed_lines))
+                })
+                .min_by_key(|(oldest_sequence, _)| *oldest_sequence);
+            match oldest {
+                Some((_, packed_lines)) => {
+                    let released = packed_lines.borrow_mut().evict_oldest_line();
+                    self.used = self.used.saturating_sub(released);
+                    self.lines_evicted = true;
+                },
+                None => break,
+            }
+        }
+    }
+}
+
+/// The lines above the viewport of a pane, oldest first.
+///
+/// Apart from the newest few, lines are kept packed: their text in one string, their styles as
+/// runs pointing into a style table shared by all the lines of the scrollback. They are only
+/// inflated back into a `Row` when they are scrolled back into view, or temporarily when they are
+/// read (eg. when searching or selecting).
+pub struct Scrollback {
+    packed_lines: Rc<RefCell<PackedLines>>,
+    unpacked_lines: VecDeque<Row>,
+    // the line numbers (counting the lines dropped from the top) of the lines a prompt starts on,
+    // so that they can be found without going through the whole scrollback
+    prompt_lines: VecDeque<u64>,
+}
+
+impl Scrollback {
+    pub fn new() -> Self {
+        Scrollback::from_packed_lines(PackedLines::default())
+    }
+    fn from_packed_lines(packed_lines: PackedLines) -> Self {
+        let memory_used = packed_lines.memory_used;
+        let packed_lines = Rc::new(RefCell::new(packed_lines));
+        SCROLLBACK_MEMORY.with(|memory| {
+            let mut memory = memory.borrow_mut();
+            memory.register(&packed_lines);
+            memory.used += memory_used;
+            memory.evict_oldest_lines();
+        });
+        Scrollback {
+            packed_lines,
+            unpacked_lines: VecDeque::new(),
+            prompt_lines: VecDeque::new(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.packed_lines.borrow().lines.len() + self.unpacked_lines.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.unpacked_lines.is_empty() && self.packed_lines.borrow().lines.is_empty()
+    }
+    pub fn push_back(&mut self, row: Row) {
+        let first_line_number = self.line_number(0);
+        while self
+            .prompt_lines
+            .front()
+            .map(|line_number| *line_number < first_line_number)
+            .unwrap_or(false)
+        {
+            // the line was dropped from the top
+            self.prompt_lines.pop_front();
+        }
+        if is_prompt_line(&row) {
+            let line_number = self.line_number(self.len());
+            self.prompt_lines.push_back(line_number);
+        }
+        self.unpacked_lines.push_back(row);
+        if self.unpacked_lines.len() > UNPACKED_LINES {
+            if let Some(row) = self.unpacked_lines.pop_front() {
+                self.pack(row);
+            }
+        }
+    }
+    pub fn pop_back(&mut self) -> Option<Row> {
+        let row = match self.unpacked_lines.pop_back() {
+            Some(row) => Some(row),
+            None => self.unpack_newest_line(),
+        };
+        let line_number = self.line_number(self.len());
+        if self.prompt_lines.back() == Some(&line_number) {
+            self.prompt_lines.pop_back();
+        }
+        row
+    }
+    pub fn back_mut(&mut self) -> Option<&mut Row> {
+        if self.unpacked_lines.is_empty() {
+            if let Some(row) = self.unpack_newest_line() {
+                self.unpacked_lines.push_back(row);
+            }
+        }
+        self.unpacked_lines.back_mut()
+    }
+    /// Drops the oldest line, returning its width
+    pub fn drop_oldest_line(&mut self) -> Option<usize> {
+        let packed_line = self.packed_lines.borrow_mut().pop_front();
+        match packed_line {
+            Some((width, released)) => {
+                release_scrollback_memory(released);
+                Some(width)
+            },
+            None => {
+                let row = self.unpacked_lines.pop_front()?;
+                self.packed_lines.borrow_mut().lines_dropped += 1;
+                Some(row.width())
+            },
+        }
+    }
+    pub fn get(&self, index: usize) -> Option<Cow<'_, Row>> {
+        let packed_lines = self.packed_lines.borrow();
+        let packed_line_count = packed_lines.lines.len();
+        if index < packed_line_count {
+            packed_lines.inflate(index).map(Cow::Owned)
+        } else {
+            self.unpacked_lines
+                .get(index - packed_line_count)
+                .map(Cow::Borrowed)
+        }
+    }
+    /// Unpacks the line (along with the newer ones packed after it) so that it can be changed
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Row> {
+        let packed_line_count = self.packed_lines.borrow().lines.len();
+        for _ in index..packed_line_count {
+            match self.unpack_newest_line() {
+                Some(row) => self.unpacked_lines.push_front(row),
+                None => break,
+            }
+        }
+        let packed_line_count = self.packed_lines.borrow().lines.len();
+        self.unpacked_lines
+            .get_mut(index.checked_sub(packed_line_count)?)
+    }
+    /// Notes that a prompt now starts on this line, for when its semantic prompt marks were
+    /// changed with `get_mut`
+    pub fn mark_prompt_line(&mut self, index: usize) {
+        if index >= self.len() {
+            return;
+        }
+        let line_number = self.line_number(index);
+        if let Err(position) = self.prompt_lines.binary_search(&line_number) {
+            self.prompt_lines.insert(position, line_number);
+        }
+    }
+    /// The indices of the lines a prompt starts on, oldest first
+    pub fn prompt_lines(&self) -> Vec<usize> {
+        let first_line_number = self.line_number(0);
+        let len = self.len();
+        self.prompt_lines
+            .iter()
+            .filter_map(|line_number| line_number.checked_sub(first_line_number))
+            .map(|index| index as usize)
+            .filter(|index| *index < len)
+            .collect()
+    }
+    /// The width of the line, without inflating it if it's packed
+    pub fn line_width(&self, index: usize) -> Option<usize> {
+        let packed_lines = self.packed_lines.borrow();
+        let packed_line_count = packed_lines.lines.len();
+        if index < packed_line_count {
+            packed_lines
+                .lines
+                .get(index)
+                .map(|packed_row| packed_row.width)
+        } else {
+            self.unpacked_lines
+                .get(index - packed_line_count)
+                .map(|row| row.width())
+        }
+    }
+    /// The width of each line, without inflating the packed ones
+    pub fn line_widths(&self) -> Vec<usize> {
+        self.packed_lines
+            .borrow()
+            .lines
+            .iter()
+            .map(|packed_row| packed_row.width)
+            .chain(self.unpacked_lines.iter().map(|row| row.width()))
+            .collect()
+    }
+    /// How many styles the style table of the packed lines holds
+    pub fn style_count(&self) -> usize {
+        self.packed_lines.borrow().styles.styles.len()
+    }
+    pub fn iter(&self) -> ScrollbackIter<'_> {
+        ScrollbackIter {
+            scrollback: self,
+            range: 0..self.len(),
+        }
+    }
+    pub fn clear(&mut self) {
+        self.unpacked_lines.clear();
+        self.prompt_lines.clear();
+        let released = self.packed_lines.borrow_mut().clear();
+        release_scrollback_memory(released);
+    }
+    /// The widths of the lines that were dropped to keep the session under its memory budget
+    /// since the last time this was called, oldest first
+    pub fn take_evicted_line_widths(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.packed_lines.borrow_mut().evicted_line_widths)
+    }
+    fn line_number(&self, index: usize) -> u64 {
+        self.packed_lines.borrow().lines_dropped + index as u64
+    }
+    fn pack(&mut self, row: Row) {
+        SCROLLBACK_MEMORY.with(|memory| {
+            let mut memory = memory.borrow_mut();
+            let sequence = memory.next_sequence();
+            let memory_used = self.packed_lines.borrow_mut().push_back(row, sequence);
+            memory.used += memory_used;
+            memory.evict_oldest_lines();
+        });
+    }
+    fn unpack_newest_line(&mut self) -> Option<Row> {
+        let (row, released) = self.packed_lines.borrow_mut().pop_back()?;
+        release_scrollback_memory(released);
+        Some(row)
+    }
+}
+
+impl Default for Scrollback {
+    fn default() -> Self {
+        Scrollback::new()
+    }
+}
+
+impl Clone for Scrollback {
+    fn clone(&self) -> Self {
+        let packed_lines = self.packed_lines.borrow().clone();
+        let mut scrollback = Scrollback::from_packed_lines(packed_lines);
+        scrollback.unpacked_lines = self.unpacked_lines.clone();
+        scrollback.prompt_lines = self.prompt_lines.clone();
+        scrollback
+    }
+}
+
+impl Drop for Scrollback {
+    fn drop(&mut self) {
+        if let Ok(packed_lines) = self.packed_lines.try_borrow() {
+            release_scrollback_memory(packed_lines.memory_used);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Scrollback {
+    type Item = Cow<'a, Row>;
+    type IntoIter = ScrollbackIter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct ScrollbackIter<'a> {
+    scrollback: &'a Scrollback,
+    range: Range<usize>,
+}
+
+impl<'a> Iterator for ScrollbackIter<'a> {
+    type Item = Cow<'a, Row>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        self.scrollback.get(index)
+    }
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // so that skipping lines does not inflate them
+        let index = self.range.nth(n)?;
+        self.scrollback.get(index)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for ScrollbackIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        self.scrollback.get(index)
+    }
+}
+
+impl ExactSizeIterator for ScrollbackIter<'_> {}
+
+#[derive(Clone, Default)]
+struct PackedLines {
+    lines: VecDeque<PackedRow>,
+    styles: StyleTable,
+    memory_used: usize,
+    evicted_line_widths: Vec<usize>,
+    // how many lines were dropped from the top of the scrollback, evicted ones included
+    lines_dropped: u64,
+}
+
+impl PackedLines {
+    fn push_back(&mut self, row: Row, sequence: u64) -> usize {
+        let styles_memory_used = self.styles.memory_used();
+        let packed_row = PackedRow::pack(row, &mut self.styles, sequence);
+        let memory_used = packed_row.memory_used() + self.styles.memory_used() - styles_memory_used;
+        self.memory_used += memory_used;
+        self.lines.push_back(packed_row);
+        memory_used
+    }
+    fn pop_back(&mut self) -> Option<(Row, usize)> {
+        let packed_row = self.lines.pop_back()?;
+        let row = packed_row.inflate(&self.styles);
+        let released = self.release(packed_row);
+        Some((row, released))
+    }
+    fn pop_front(&mut self) -> Option<(usize, usize)> {
+        let packed_row = self.lines.pop_front()?;
+        self.lines_dropped += 1;
+        let width = packed_row.width;
+        let released = self.release(packed_row);
+        Some((width, released))
+    }
+    // returns the memory released by the line and the styles no longer used by any line
+    fn release(&mut self, packed_row: PackedRow) -> usize {
+        let styles_memory_used = self.styles.memory_used();
+        for (_, style_index) in packed_row.style_runs.iter() {
+            self.styles.release(*style_index);
+        }
+        if self.styles.should_compact() {
+            let new_indices = self.styles.compact();
+            for packed_row in self.lines.iter_mut() {
+                for (_, style_index) in packed_row.style_runs.iter_mut() {
+                    *style_index = new_indices[*style_index as usize];
+                }
+            }
+        }
+        let released =
+            packed_row.memory_used() + styles_memory_used.saturating_sub(self.styles.memory_used());
+        self.memory_used = self.memory_used.saturating_sub(released);
+        released
+    }
+    fn inflate(&self, index: usize) -> Option<Row> {
+        self.lines
+            .get(index)
+            .map(|packed_row| packed_row.inflate(&self.styles))
+    }
+    fn oldest_sequence(&self) -> Option<u64> {
+        self.lines.front().map(|packed_row| packed_row.sequence)
+    }
+    fn evict_oldest_line(&mut self) -> usize {
+        match self.pop_front() {
+            Some((width, released)) => {
+                self.evicted_line_widths.push(width);
+                released
+            },
+            None => 0,
+        }
+    }
+    fn clear(&mut self) -> usize {
+        let released = self.memory_used;
+        self.lines.clear();
+        self.styles = StyleTable::default();
+        self.memory_used = 0;
+        released
+    }
+}
+
+/// The distinct styles used in a scrollback, each one stored once
+#[derive(Clone, Default)]
+struct StyleTable {
+    styles: Vec<RcCharacterStyles>,
+    // how many style runs of the packed lines point to each of the styles
+    uses: Vec<u32>,
+    indices: HashMap<CharacterStyles, u32>,
+    unused: usize,
+}
+
+impl StyleTable {
+    /// The index of these styles in the table (adding them if they're not there yet), counting
+    /// one more use of them
+    fn index_of(&mut self, styles: &RcCharacterStyles) -> u32 {
+        if let Some(index) = self.indices.get(&**styles) {
+            let uses = &mut self.uses[*index as usize];
+            if *uses == 0 {
+                self.unused -= 1;
+            }
+            *uses += 1;
+            return *index;
+        }
+        let index = self.styles.len() as u32;
+        self.styles.push(styles.clone());
+        self.uses.push(1);
+        self.indices.insert(**styles, index);
+        index
+    }
+    fn get(&self, index: u32) -> RcCharacterStyles {
+        self.styles.get(index as usize).cloned().unwrap_or_default()
+    }
+    fn release(&mut self, index: u32) {
+        if let Some(uses) = self.uses.get_mut(index as usize) {
+            if *uses == 1 {
+                self.unused += 1;
+            }
+            *uses = uses.saturating_sub(1);
+        }
+    }
+    fn should_compact(&self) -> bool {
+        self.unused > UNUSED_STYLES_BEFORE_COMPACTING && self.unused * 2 > self.styles.len()
+    }
+    /// Drops the styles no longer used by any line, returning the new index of each of the
+    /// previous ones (the dropped ones are mapped to 0, but nothing points to them anyway)
+    fn compact(&mut self) -> Vec<u32> {
+        let mut new_indices = Vec::with_capacity(self.styles.len());
+        let mut compacted = StyleTable::default();
+        for (styles, uses) in self.styles.drain(..).zip(self.uses.drain(..)) {
+            if uses == 0 {
+                new_indices.push(0);
+            } else {
+                let index = compacted.styles.len() as u32;
+                compacted.indices.insert(*styles, index);
+                compacted.styles.push(styles);
+                compacted.uses.push(uses);
+                new_indices.push(index);
+            }
+        }
+        *self = compacted;
+        new_indices
+    }
+    fn memory_used(&self) -> usize {
+        self.styles.len()
+            * (std::mem::size_of::<RcCharacterStyles>()
+                + std::mem::size_of::<CharacterStyles>()
+                + std::mem::size_of::<u32>()
+                + std::mem::size_of::<(CharacterStyles, u32)>())
+    }
+}
+
+#[derive(Clone)]
+struct PackedRow {
+    // the first character of every cell
+    text: Box<str>,
+    // (number of characters, index in the style table)
+    style_runs: Box<[(u32, u32)]>,
+    // (character index, grapheme) of the cells holding more than a single character
+    clusters: Box<[(u32, Grapheme)]>,
+    // (character index, width) of the characters that are not as wide as their unicode width
+    unusual_widths: Box<[(u32, u8)]>,
+    width: usize,
+    is_canonical: bool,
+    semantic_prompt_marks: Option<Box<SemanticPromptMarks>>,
+    // the order in which lines were packed across all scrollbacks, so the oldest can be evicted
+    sequence: u64,
+}
+
+impl PackedRow {
+    fn pack(mut row: Row, styles: &mut StyleTable, sequence: u64) -> Self {
+        let mut text = String::with_capacity(row.columns.len());
+        let mut style_runs: Vec<(u32, u32)> = vec![];
+        let mut clusters = vec![];
+        let mut unusual_widths = vec![];
+        let mut width = 0;
+        let mut previous_styles: Option<&RcCharacterStyles> = None;
+        for (index, terminal_character) in row.columns.iter().enumerate() {
+            text.push(terminal_character.character.base_char());
+            if terminal_character.character.as_char().is_none() {
+                clusters.push((index as u32, terminal_character.character));
+            }
+            width += terminal_character.width();
+            if terminal_character.width() != terminal_character.character.width() {
+                unusual_widths.push((index as u32, terminal_character.width() as u8));
+            }
+            if previous_styles == Some(&terminal_character.styles) {
+                if let Some((count, _)) = style_runs.last_mut() {
+                    *count += 1;
+                }
+            } else {
+                style_runs.push((1, styles.index_of(&terminal_character.styles)));
+            }
+            previous_styles = Some(&terminal_character.styles);
+        }
+        PackedRow {
+            text: text.into_boxed_str(),
+            style_runs: style_runs.into_boxed_slice(),
+            clusters: clusters.into_boxed_slice(),
+            unusual_widths: unusual_widths.into_boxed_slice(),
+            width,
+            is_canonical: row.is_canonical,
+            semantic_prompt_marks: row.take_semantic_prompt_marks(),
+            sequence,
+        }
+    }
+    fn inflate(&self, styles: &StyleTable) -> Row {
+        let mut columns = VecDeque::with_capacity(self.text.chars().count());
+        let mut characters = self.text.chars();
+        let mut clusters = self.clusters.iter().peekable();
+        let mut unusual_widths = self.unusual_widths.iter().peekable();
+        let mut index = 0;
+        for (count, style_index) in self.style_runs.iter() {
+            let character_styles = styles.get(*style_index);
+            for _ in 0..*count {
+                let character = match characters.next() {
+                    Some(character) => character,
+                    None => break,
+                };
+                let character = match clusters.peek() {
+                    Some((cluster_index, cluster)) if *cluster_index == index => {
+                        clusters.next();
+                        *cluster
+                    },
+                    _ => Grapheme::from(character),
+                };
+                let width = match unusual_widths.peek() {
+                    Some((unusual_width_index, width)) if *unusual_width_index == index => {
+                        unusual_widths.next();
+                        *width
+                    },
+                    _ => character.width() as u8,
+                };
+                columns.push_back(TerminalCharacter::new_styled_with_width(
+                    character,
+                    character_styles.clone(),
+                    width,
+                ));
+                index += 1;
+            }
+        }
+        let row = Row::from_columns(columns)
+            .with_semantic_prompt_marks(self.semantic_prompt_marks.clone());
+        if self.is_canonical {
+            row.canonical()
+        } else {
+            row
+        }
+    }
+    fn memory_used(&self) -> usize {
+        std::mem::size_of::<PackedRow>()
+            + self.text.len()
+            + self.style_runs.len() * std::mem::size_of::<(u32, u32)>()
+            + self.clusters.len() * std::mem::size_of::<(u32, Grapheme)>()
+            + self.unusual_widths.len() * std::mem::size_of::<(u32, u8)>()
+            + self
+                .semantic_prompt_marks
+                .as_ref()
+                .map(|_| std::mem::size_of::<SemanticPromptMarks>())
+                .unwrap_or(0)
+    }
+}
@@ -1,14 +1,19 @@
 use crate::panes::selection::Selection;
 use crate::panes::terminal_character::TerminalCharacter;
-use crate::panes::{Grid, Row};
+use crate::panes::{Grapheme, Grid, Row};
 use std::borrow::Cow;
 use std::fmt::Debug;
+use unicode_segmentation::UnicodeSegmentation;
 use zellij_utils::input::actions::SearchDirection;
 use zellij_utils::position::Position;
+use zellij_utils::regex::{Regex, RegexBuilder};
 
 // If char is neither alphanumeric nor an underscore do we consider it a word-boundary
-fn is_word_boundary(x: &Option<char>) -> bool {
-    x.map_or(true, |c| !c.is_ascii_alphanumeric() && c != '_')
+fn is_word_boundary(x: &Option<Grapheme>) -> bool {
+    x.map_or(true, |c| {
+        let c = c.base_char();
+        !c.is_ascii_alphanumeric() && c != '_'
+    })
 }
 
 #[derive(Debug)]
@@ -63,7 +68,11 @@ impl<'a> SearchSource<'a> {
     }
 
     // Get the char at hidx and, if existing, the following char as well
-    fn get_next_two_chars(&self, hidx: usize, whole_word_search: bool) -> (char, Option<char>) {
+    fn get_next_two_chars(
+        &self,
+        hidx: usize,
+        whole_word_search: bool,
+    ) -> (Grapheme, Option<Grapheme>) {
         // Get the current haystack character
         let haystack_char = match self {
             SearchSource::Main(row) => row.columns[hidx].character,
@@ -98,6 +107,10 @@ pub struct SearchResult {
     pub whole_word_only: bool, // TODO
     // Jump from the bottom to the top (or vice versa), if we run out of lines to search
     pub wrap_search: bool,
+    // Interpret the needle as a regular expression
+    pub regex_search: bool,
+    // The needle compiled with the current options (None if it is not a valid regex)
+    compiled_regex: Option<Regex>,
 }
 
 impl SearchResult {
@@ -141,15 +154,28 @@ impl SearchResult {
     }
 
     pub fn has_modifiers_set(&self) -> bool {
-        self.wrap_search || self.whole_word_only || self.case_insensitive
+        self.wrap_search || self.whole_word_only || self.case_insensitive || self.regex_search
+    }
+
+    /// Compile the needle into a regex, if we are doing regex search.
+    /// Has to be called every time the needle or the case sensitivity changes.
+    pub(crate) fn update_regex(&mut self) {
+        self.compiled_regex = if self.regex_search && !self.needle.is_empty() {
+            RegexBuilder::new(&self.needle)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .ok()
+        } else {
+            None
+        };
     }
 
     fn check_if_haystack_char_matches_needle(
         &self,
         nidx: usize,
-        needle_char: char,
-        haystack_char: char,
-        prev_haystack_char: Option<char>,
+        needle_char: Grapheme,
+        haystack_char: Grapheme,
+        prev_haystack_char: Option<Grapheme>,
     ) -> bool {
         let mut chars_match = if self.case_insensitive {
             // Case insensitive search
@@ -181,21 +207,26 @@ impl SearchResult {
         if self.needle.is_empty() || row.columns.is_empty() {
             return res;
         }
+        if self.regex_search {
+            return self.search_row_with_regex(ridx, row, tail);
+        }
 
+        // Every cell holds a grapheme cluster, so that's what we match the needle by
+        let needle: Vec<Grapheme> = self.needle.graphemes(true).map(Grapheme::new).collect();
         let mut tailit = tail.iter();
         let mut source = SearchSource::Main(row); // Where we currently get the haystack-characters from
         let orig_ridx = ridx;
         let mut start = None; // If we find a hit, this is where it starts
         let mut nidx = 0; // Needle index
         let mut hidx = 0; // Haystack index
-        let mut prev_haystack_char: Option<char> = None;
+        let mut prev_haystack_char: Option<Grapheme> = None;
         loop {
             // Get the current and next haystack character
             let (mut haystack_char, next_haystack_char) =
                 source.get_next_two_chars(hidx, self.whole_word_only);
 
             // Get current needle character
-            let needle_char = self.needle.chars().nth(nidx).unwrap(); // Unwrapping is safe here
+            let needle_char = needle[nidx]; // Indexing is safe here
 
             // Check if needle and haystack match (with search-options)
             let chars_match = self.check_if_haystack_char_matches_needle(
@@ -210,7 +241,7 @@ impl SearchResult {
                 if nidx == 0 {
                     start = Some(Position::new(ridx as i32, hidx as u16));
                 }
-                if nidx == self.needle.len() - 1 {
+                if nidx == needle.len() - 1 {
                     let mut end_found = true;
                     // If we search whole-word-only, the next non-needle char needs to be a word-boundary,
                     // otherwise its not a hit (e.g. some occurrence inside a longer word).
@@ -267,18 +298,82 @@ impl SearchResult {
             }
         }
 
-        // The tail may have not been wrapped yet (when coming from lines_below),
-        // so it could be that the end extends across more characters than the row is wide.
-        // Therefore we need to reflow the end:
-        for s in res.iter_mut() {
-            while s.end.column() > row.width() {
-                s.end.column.0 -= row.width();
-                s.end.line.0 += 1;
+        Self::reflow_selection_ends(&mut res, row.width());
+        res
+    }
+
+    /// Search a row and its tail with the compiled regex.
+    /// The row and its tail are joined into one haystack, so that matches can span wrapped lines.
+    /// Only matches starting in `row` are returned, the others are found when searching their own row.
+    fn search_row_with_regex(&self, ridx: usize, row: &Row, tail: &[&Row]) -> Vec<Selection> {
+        let mut res = Vec::new();
+        let regex = match &self.compiled_regex {
+            Some(regex) => regex,
+            None => return res, // Not a valid regex, so nothing can match
+        };
+
+        // For every character in the haystack: its byte offset, the line it is on
+        // (relative to `row`) and its column
+        let mut haystack = String::new();
+        let mut positions: Vec<(usize, usize, usize)> = Vec::new();
+        for (line_offset, line) in std::iter::once(row).chain(tail.iter().copied()).enumerate() {
+            for (cidx, c) in line.columns.iter().enumerate() {
+                positions.push((haystack.len(), line_offset, cidx));
+                c.character.push_to(&mut haystack);
+            }
+        }
+
+        for hit in regex.find_iter(&haystack) {
+            if hit.start() == hit.end() {
+                // Empty matches can't be highlighted
+                continue;
             }
+            let start_idx = positions.partition_point(|(offset, _, _)| *offset < hit.start());
+            let end_idx = positions.partition_point(|(offset, _, _)| *offset < hit.end());
+            let (_, start_line, start_column) = positions[start_idx];
+            if start_line > 0 {
+                // Everything from here on starts in the tail
+                break;
+            }
+            if self.whole_word_only {
+                let prev_char = haystack[..hit.start()]
+                    .chars()
+                    .next_back()
+                    .map(Grapheme::from);
+                let next_char = haystack[hit.end()..].chars().next().map(Grapheme::from);
+                if !is_word_boundary(&prev_char) || !is_word_boundary(&next_char) {
+                    continue;
+                }
+            }
+            let (_, end_line, end_column) = positions[end_idx - 1];
+            let mut selection = Selection::default();
+            selection.start(Position::new(
+                (ridx + start_line) as i32,
+                start_column as u16,
+            ));
+            selection.end(Position::new(
+                (ridx + end_line) as i32,
+                (end_column + 1) as u16,
+            ));
+            res.push(selection);
         }
+
+        Self::reflow_selection_ends(&mut res, row.width());
         res
     }
 
+    // The tail may have not been wrapped yet (when coming from lines_below),
+    // so it could be that the end extends across more characters than the row is wide.
+    // Therefore we need to reflow the end:
+    fn reflow_selection_ends(selections: &mut [Selection], width: usize) {
+        for s in selections.iter_mut() {
+            while s.end.column() > width {
+                s.end.column.0 -= width;
+                s.end.line.0 += 1;
+            }
+        }
+    }
+
     pub(crate) fn move_active_selection_to_next(&mut self) {
         if let Some(active_idx) = self.active {
             self.active = self
@@ -412,6 +507,7 @@ impl Grid {
 
     pub fn set_search_string(&mut self, needle: &str) {
         self.search_results.needle = needle.to_string();
+        self.search_results.update_regex();
         self.search_viewport();
         // If the current viewport does not contain any hits,
         // we jump around until we find something. Starting
@@ -452,8 +548,30 @@ impl Grid {
         }
     }
 
+    /// Scrolling a line at a time only searches the line that comes into view, after jumping
+    /// further than that the whole viewport is searched again (keeping the active result if it
+    /// is still in view)
+    pub(crate) fn search_viewport_after_jump(&mut self, lines_moved_down: isize) {
+        if self.search_results.needle.is_empty() {
+            self.search_results.selections.clear();
+            self.search_results.active = None;
+            return;
+        }
+        if let Some(active) = self.search_results.active.as_mut() {
+            if lines_moved_down >= 0 {
+                active.move_down(lines_moved_down as usize);
+            } else {
+                active.move_up(lines_moved_down.unsigned_abs());
+            }
+        }
+        self.search_results.selections.clear();
+        self.search_viewport();
+        self.search_results.unset_active_selection_if_nonexistent();
+    }
+
     pub fn toggle_search_case_sensitivity(&mut self) {
         self.search_results.case_insensitive = !self.search_results.case_insensitive;
+        self.search_results.update_regex();
         for line in self.search_results.selections.drain(..) {
             self.output_buffer
                 .update_lines(line.start.line() as usize, line.end.line() as usize);
@@ -467,6 +585,17 @@ impl Grid {
         self.search_results.wrap_search = !self.search_results.wrap_search;
     }
 
+    pub fn toggle_search_regex(&mut self) {
+        self.search_results.regex_search = !self.search_results.regex_search;
+        self.search_results.update_regex();
+        for line in self.search_results.selections.drain(..) {
+            self.output_buffer
+                .update_lines(line.start.line() as usize, line.end.line() as usize);
+        }
+        self.search_results.active = None;
+        self.search_viewport();
+    }
+
     pub fn toggle_search_whole_words(&mut self) {
         self.search_results.whole_word_only = !self.search_results.whole_word_only;
         for line in self.search_results.selections.drain(..) {
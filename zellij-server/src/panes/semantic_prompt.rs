@@ -0,0 +1,327 @@
+use crate::panes::grid::calculate_row_display_height;
+use crate::panes::{Grid, Row};
+use std::borrow::Cow;
+use std::str;
+use zellij_utils::position::Position;
+
+/// A shell integration mark, as sent by the shell with OSC 133 (the FinalTerm semantic prompt
+/// sequences)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticPromptMark {
+    PromptStart,                  // OSC 133 ; A
+    CommandStart,                 // OSC 133 ; B
+    OutputStart,                  // OSC 133 ; C
+    CommandFinished(Option<i32>), // OSC 133 ; D [; <exit status>]
+}
+
+impl SemanticPromptMark {
+    pub fn from_osc_params(params: &[&[u8]]) -> Option<Self> {
+        match params.get(1)?.first()? {
+            b'A' => Some(SemanticPromptMark::PromptStart),
+            b'B' => Some(SemanticPromptMark::CommandStart),
+            b'C' => Some(SemanticPromptMark::OutputStart),
+            b'D' => {
+                let exit_status = params
+                    .get(2)
+                    .and_then(|exit_status| str::from_utf8(exit_status).ok())
+                    .and_then(|exit_status| exit_status.parse().ok());
+                Some(SemanticPromptMark::CommandFinished(exit_status))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The semantic prompt marks placed on a canonical line, each one as the position inside the
+/// line (the width of the characters before it) at which it was received
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SemanticPromptMarks {
+    pub prompt_start: Option<usize>,
+    pub command_start: Option<usize>,
+    pub output_start: Option<usize>,
+    pub command_finished: Option<usize>,
+    pub exit_status: Option<i32>,
+}
+
+impl SemanticPromptMarks {
+    fn set(&mut self, mark: SemanticPromptMark, position: usize) {
+        match mark {
+            SemanticPromptMark::PromptStart => self.prompt_start = Some(position),
+            SemanticPromptMark::CommandStart => self.command_start = Some(position),
+            SemanticPromptMark::OutputStart => self.output_start = Some(position),
+            SemanticPromptMark::CommandFinished(exit_status) => {
+                self.command_finished = Some(position);
+                self.exit_status = exit_status;
+            },
+        }
+    }
+}
+
+pub(crate) fn is_prompt_line(row: &Row) -> bool {
+    row.is_canonical
+        && row
+            .semantic_prompt_marks()
+            .map(|marks| marks.prompt_start.is_some())
+            .unwrap_or(false)
+}
+
+/// Translates a position inside the canonical line starting at `lines[line_start]` to the index
+/// of the line it falls on and the column inside that line
+fn position_in_canonical_line(
+    lines: &[(isize, Cow<Row>)],
+    line_start: usize,
+    mut position: usize,
+) -> (usize, usize) {
+    let mut index = line_start;
+    loop {
+        let width = lines[index].1.width();
+        let next_line_is_wrapped = lines
+            .get(index + 1)
+            .map(|(_, row)| !row.is_canonical)
+            .unwrap_or(false);
+        if position < width || !next_line_is_wrapped {
+            return (index, position);
+        }
+        position -= width;
+        index += 1;
+    }
+}
+
+/// The start and end (exclusive) of a command output, as (line index, column) pairs
+type OutputBounds = ((usize, usize), (usize, usize));
+
+/// The bounds of the output of the last command that printed anything
+fn last_command_output_bounds(lines: &[(isize, Cow<Row>)]) -> Option<OutputBounds> {
+    lines.iter().enumerate().rev().find_map(|(i, (_, row))| {
+        let output_start = row.semantic_prompt_marks()?.output_start?;
+        command_output_bounds(lines, i, output_start)
+    })
+}
+
+fn command_output_bounds(
+    lines: &[(isize, Cow<Row>)],
+    output_line: usize,
+    output_start: usize,
+) -> Option<((usize, usize), (usize, usize))> {
+    // the output ends where the command finished, or if the shell did not tell us that, where
+    // the next prompt starts
+    let output_end = lines
+        .iter()
+        .enumerate()
+        .skip(output_line)
+        .find_map(|(i, (_, row))| {
+            let marks = row.semantic_prompt_marks()?;
+            [marks.command_finished, marks.prompt_start]
+                .iter()
+                .flatten()
+                .filter(|position| i != output_line || **position >= output_start)
+                .min()
+                .map(|position| (i, *position))
+        });
+    let start = position_in_canonical_line(lines, output_line, output_start);
+    let end = match output_end {
+        Some((line, position)) => position_in_canonical_line(lines, line, position),
+        None => {
+            // the command is still running
+            let last_line = lines.len().saturating_sub(1);
+            (last_line, lines.get(last_line)?.1.width())
+        },
+    };
+    if start < end {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+impl Grid {
+    pub fn mark_semantic_prompt(&mut self, mark: SemanticPromptMark) {
+        if let SemanticPromptMark::CommandFinished(exit_status) = mark {
+            self.last_command_exit_status = exit_status;
+        }
+        while self.viewport.len() <= self.cursor.y {
+            self.viewport.push(Row::new().canonical());
+        }
+        let mut canonical_line_y = self.cursor.y;
+        while canonical_line_y > 0 && !self.viewport[canonical_line_y].is_canonical {
+            canonical_line_y -= 1;
+        }
+        let mut position = (self.cursor.y - canonical_line_y) * self.width + self.cursor.x;
+        let mut canonical_line_above = None;
+        let canonical_line = if self.viewport[canonical_line_y].is_canonical {
+            &mut self.viewport[canonical_line_y]
+        } else {
+            // the beginning of the line has already scrolled off the top of the viewport, possibly
+            // along with more of its wrapped parts
+            let mut width_above = 0;
+            for index in (0..self.lines_above.len()).rev() {
+                let line_above = match self.lines_above.get(index) {
+                    Some(line_above) => line_above,
+                    None => break,
+                };
+                width_above += line_above.width();
+                if line_above.is_canonical {
+                    canonical_line_above = Some(index);
+                    break;
+                }
+            }
+            match canonical_line_above.and_then(|index| self.lines_above.get_mut(index)) {
+                Some(line_above) => {
+                    position += width_above;
+                    line_above
+                },
+                None => &mut self.viewport[canonical_line_y],
+            }
+        };
+        canonical_line
+            .semantic_prompt_marks_mut()
+            .set(mark, position);
+        if let (SemanticPromptMark::PromptStart, Some(index)) = (mark, canonical_line_above) {
+            self.lines_above.mark_prompt_line(index);
+        }
+    }
+
+    /// Scroll up until the closest prompt above the top of the viewport becomes its first line.
+    /// Stays in place if there is no such prompt.
+    pub fn scroll_to_previous_prompt(&mut self) {
+        if self.viewport.len() != self.height {
+            return;
+        }
+        let prompt_line = match self.lines_above.prompt_lines().last() {
+            Some(prompt_line) => *prompt_line,
+            None => return,
+        };
+        let rows_above_viewport: usize = (prompt_line..self.lines_above.len())
+            .filter_map(|index| self.lines_above.line_width(index))
+            .map(|width| calculate_row_display_height(width, self.width))
+            .sum();
+        self.scroll_up_by(rows_above_viewport);
+    }
+
+    /// Scroll down until the closest prompt below the top of the viewport becomes its first line,
+    /// or until the bottom if there is no such prompt.
+    pub fn scroll_to_next_prompt(&mut self) {
+        if self.viewport.len() != self.height {
+            return;
+        }
+        let rows_to_prompt_in_viewport = self
+            .viewport
+            .iter()
+            .skip(1)
+            .position(is_prompt_line)
+            .map(|position| position + 1);
+        let rows_to_prompt = rows_to_prompt_in_viewport.or_else(|| {
+            let mut rows = self.viewport.len();
+            for row in &self.lines_below {
+                if is_prompt_line(row) {
+                    return Some(rows);
+                }
+                rows += calculate_row_display_height(row.width(), self.width);
+            }
+            None
+        });
+        match rows_to_prompt {
+            Some(rows) => self.scroll_down_by(rows),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    pub fn select_last_command_output(&mut self) {
+        let selection = self
+            .last_command_output_lines()
+            .map(|(lines, (start, end))| {
+                (
+                    Position::new(lines[start.0].0 as i32, start.1 as u16),
+                    Position::new(lines[end.0].0 as i32, end.1 as u16),
+                )
+            });
+        if let Some((start, end)) = selection {
+            self.selection.start(start);
+            self.selection.end(end);
+            self.output_buffer.update_all_lines();
+            self.mark_for_rerender();
+        }
+    }
+
+    pub fn last_command_output(&self) -> Option<String> {
+        let (lines, (start, end)) = self.last_command_output_lines()?;
+        let mut canonical_lines: Vec<String> = vec![];
+        for index in start.0..=end.0 {
+            let row = &lines[index].1;
+            let start_column = if index == start.0 { start.1 } else { 0 };
+            let end_column = if index == end.0 { end.1 } else { usize::MAX };
+            let mut line_text = String::new();
+            let mut column = 0;
+            for terminal_character in &row.columns {
+                if (start_column..end_column).contains(&column) {
+                    terminal_character.character.push_to(&mut line_text);
+                }
+                column += terminal_character.width();
+            }
+            match canonical_lines.last_mut() {
+                Some(previous_line) if !row.is_canonical => previous_line.push_str(&line_text),
+                _ => canonical_lines.push(line_text),
+            }
+        }
+        let output = canonical_lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let output = output.trim_end_matches('\n');
+        if output.is_empty() {
+            None
+        } else {
+            Some(output.to_owned())
+        }
+    }
+
+    /// The lines of the grid from `semantic_prompt_lines` holding the output of the last command,
+    /// along with its bounds. Only goes as far back into the scrollback as it needs to, starting
+    /// from the last prompt above the viewport and doubling the number of prompts looked through
+    /// each time the output is not found.
+    fn last_command_output_lines(&self) -> Option<(Vec<(isize, Cow<'_, Row>)>, OutputBounds)> {
+        let prompt_lines = self.lines_above.prompt_lines();
+        let mut prompts_looked_through = 1;
+        loop {
+            let first_line_above = prompt_lines
+                .len()
+                .checked_sub(prompts_looked_through)
+                .map(|index| prompt_lines[index])
+                .unwrap_or(0);
+            let lines = self.semantic_prompt_lines(first_line_above);
+            if let Some(bounds) = last_command_output_bounds(&lines) {
+                return Some((lines, bounds));
+            }
+            if first_line_above == 0 {
+                return None;
+            }
+            prompts_looked_through *= 2;
+        }
+    }
+
+    /// The lines of the grid from top to bottom, starting from this line above the viewport, along
+    /// with their line index as used by `Selection` (negative for lines above the viewport)
+    fn semantic_prompt_lines(&self, first_line_above: usize) -> Vec<(isize, Cow<'_, Row>)> {
+        let lines_above_count = self.lines_above.len() as isize;
+        let viewport_count = self.viewport.len() as isize;
+        self.lines_above
+            .iter()
+            .enumerate()
+            .skip(first_line_above)
+            .map(|(i, row)| (i as isize - lines_above_count, row))
+            .chain(
+                self.viewport
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| (i as isize, Cow::Borrowed(row))),
+            )
+            .chain(
+                self.lines_below
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| (viewport_count + i as isize, Cow::Borrowed(row))),
+            )
+            .collect()
+    }
+}
@@ -1,4 +1,6 @@
 use crate::output::SixelImageChunk;
+use crate::panes::kitty_graphics::{KittyGraphicsClient, KittyPlacement};
+use crate::ClientId;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -191,6 +193,30 @@ impl SixelGrid {
             None
         }
     }
+    pub fn new_kitty_placement(
+        &mut self,
+        new_image_id: usize,
+        x_pixel_coordinates: usize,
+        y_pixel_coordinates: usize,
+        kitty_placement: KittyPlacement,
+    ) {
+        // unlike sixel images, kitty placements are stacked by their z-index rather than replace
+        // the ones below them
+        let (width, height) = kitty_placement.size();
+        self.sixel_image_locations.insert(
+            new_image_id,
+            PixelRect::new(x_pixel_coordinates, y_pixel_coordinates, height, width),
+        );
+        self.sixel_image_store
+            .borrow_mut()
+            .kitty_placements
+            .insert(new_image_id, kitty_placement);
+    }
+    pub fn remove_image(&mut self, image_id: usize) {
+        if self.sixel_image_locations.remove(&image_id).is_some() {
+            self.image_ids_to_reap.push(image_id);
+        }
+    }
     pub fn image_coordinates(&self) -> impl Iterator<Item = (usize, &PixelRect)> {
         self.sixel_image_locations
             .iter()
@@ -262,7 +288,10 @@ impl SixelGrid {
         }
     }
     pub fn next_image_id(&self) -> usize {
-        self.sixel_image_store.borrow().sixel_images.keys().len()
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
+        let next_image_id = sixel_image_store.next_image_id;
+        sixel_image_store.next_image_id += 1;
+        next_image_id
     }
     pub fn new_sixel_image(&mut self, sixel_image_id: usize, sixel_image: SixelImage) {
         self.sixel_image_store
@@ -271,11 +300,13 @@ impl SixelGrid {
             .insert(sixel_image_id, (sixel_image, HashMap::new()));
     }
     pub fn remove_pixels_from_image(&mut self, image_id: usize, pixel_rect: PixelRect) {
-        if let Some((sixel_image, sixel_image_cache)) = self
-            .sixel_image_store
-            .borrow_mut()
-            .sixel_images
-            .get_mut(&image_id)
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
+        if let Some(kitty_placement) = sixel_image_store.kitty_placements.get_mut(&image_id) {
+            if let Some(character_cell_size) = *self.character_cell_size.borrow() {
+                kitty_placement.cut_out(&pixel_rect, character_cell_size);
+            }
+        } else if let Some((sixel_image, sixel_image_cache)) =
+            sixel_image_store.sixel_images.get_mut(&image_id)
         {
             sixel_image.cut_out(
                 pixel_rect.x,
@@ -287,8 +318,10 @@ impl SixelGrid {
         }
     }
     pub fn reap_images(&mut self, ids_to_reap: Vec<usize>) {
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
         for id in ids_to_reap {
-            drop(self.sixel_image_store.borrow_mut().sixel_images.remove(&id));
+            drop(sixel_image_store.sixel_images.remove(&id));
+            sixel_image_store.remove_kitty_placement(id);
         }
     }
     pub fn image_cell_coordinates_in_viewport(
@@ -428,6 +461,10 @@ type SixelImageCache = HashMap<PixelRect, String>;
 #[derive(Debug, Clone, Default)]
 pub struct SixelImageStore {
     sixel_images: HashMap<usize, (SixelImage, SixelImageCache)>,
+    kitty_placements: HashMap<usize, KittyPlacement>,
+    kitty_graphics_clients: HashMap<ClientId, KittyGraphicsClient>,
+    next_image_id: usize,
+    next_kitty_image_id: u32,
 }
 
 impl SixelImageStore {
@@ -461,6 +498,84 @@ impl SixelImageStore {
             })
     }
     pub fn image_count(&self) -> usize {
-        self.sixel_images.len()
+        self.sixel_images.len() + self.kitty_placements.len()
+    }
+    pub fn is_kitty_placement(&self, image_id: usize) -> bool {
+        self.kitty_placements.contains_key(&image_id)
+    }
+    pub fn kitty_placement(&self, image_id: usize) -> Option<&KittyPlacement> {
+        self.kitty_placements.get(&image_id)
+    }
+    pub fn next_kitty_image_id(&mut self) -> u32 {
+        self.next_kitty_image_id = self.next_kitty_image_id.wrapping_add(1).max(1);
+        self.next_kitty_image_id
+    }
+    pub fn serialize_kitty_placement(
+        &mut self,
+        client_id: ClientId,
+        chunk: &SixelImageChunk,
+        character_cell_size: SizeInPixels,
+    ) -> Option<String> {
+        let kitty_placement = self.kitty_placements.get(&chunk.sixel_image_id)?;
+        Some(
+            self.kitty_graphics_clients
+                .entry(client_id)
+                .or_default()
+                .serialize_chunk(
+                    chunk.sixel_image_id,
+                    kitty_placement,
+                    chunk,
+                    character_cell_size,
+                ),
+        )
+    }
+    pub fn serialize_kitty_placement_fallback(
+        &mut self,
+        chunk: &SixelImageChunk,
+        character_cell_size: SizeInPixels,
+    ) -> Option<String> {
+        self.kitty_placements
+            .get_mut(&chunk.sixel_image_id)
+            .map(|kitty_placement| kitty_placement.serialize_fallback(chunk, character_cell_size))
+    }
+    /// Deletes the kitty placements a client displays where text is drawn, as well as those
+    /// that are gone since the last render
+    pub fn uncover_kitty_placements(
+        &mut self,
+        client_id: ClientId,
+        covered_cells: &[(usize, usize, usize)], // (x, y, width)
+        character_cell_size: SizeInPixels,
+    ) -> String {
+        match self.kitty_graphics_clients.get_mut(&client_id) {
+            Some(kitty_graphics_client) => {
+                let mut deletions = kitty_graphics_client.drain_pending_deletions();
+                for (x, y, width) in covered_cells {
+                    deletions.push_str(&kitty_graphics_client.uncover(
+                        &self.kitty_placements,
+                        *x,
+                        *y,
+                        *width,
+                        character_cell_size,
+                    ));
+                }
+                deletions
+            },
+            None => String::new(),
+        }
+    }
+    pub fn remove_client(&mut self, client_id: ClientId) {
+        self.kitty_graphics_clients.remove(&client_id);
+    }
+    fn remove_kitty_placement(&mut self, image_id: usize) {
+        if let Some(kitty_placement) = self.kitty_placements.remove(&image_id) {
+            let kitty_image_id = kitty_placement.image_id();
+            let image_is_placed = self
+                .kitty_placements
+                .values()
+                .any(|p| p.image_id() == kitty_image_id);
+            for kitty_graphics_client in self.kitty_graphics_clients.values_mut() {
+                kitty_graphics_client.forget_placement(image_id, kitty_image_id, image_is_placed);
+            }
+        }
     }
 }
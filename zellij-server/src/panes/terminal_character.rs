@@ -1,5 +1,6 @@
 use std::convert::From;
 use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 use std::rc::Rc;
 use unicode_width::UnicodeWidthChar;
@@ -12,9 +13,10 @@ use zellij_utils::{
 };
 
 use crate::panes::alacritty_functions::parse_sgr_color;
+use crate::panes::grapheme::Grapheme;
 
 pub const EMPTY_TERMINAL_CHARACTER: TerminalCharacter = TerminalCharacter {
-    character: ' ',
+    character: Grapheme::from_char(' '),
     width: 1,
     styles: RcCharacterStyles::Reset,
 };
@@ -60,7 +62,7 @@ thread_local! {
         RcCharacterStyles::Rc(Rc::new(DEFAULT_STYLES));
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AnsiCode {
     On,
     Reset,
@@ -70,7 +72,7 @@ pub enum AnsiCode {
     Underline(Option<AnsiStyledUnderline>),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum AnsiStyledUnderline {
     Double,
     Undercurl,
@@ -87,7 +89,7 @@ impl From<PaletteColor> for AnsiCode {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
 pub enum NamedColor {
     Black,
     Red,
@@ -245,6 +247,27 @@ impl PartialEq for CharacterStyles {
     }
 }
 
+impl Eq for CharacterStyles {}
+
+// must agree with PartialEq above, so styled_underlines_enabled is left out here as well
+impl Hash for CharacterStyles {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.foreground.hash(state);
+        self.background.hash(state);
+        self.underline_color.hash(state);
+        self.strike.hash(state);
+        self.hidden.hash(state);
+        self.reverse.hash(state);
+        self.slow_blink.hash(state);
+        self.fast_blink.hash(state);
+        self.underline.hash(state);
+        self.bold.hash(state);
+        self.dim.hash(state);
+        self.italic.hash(state);
+        self.link_anchor.hash(state);
+    }
+}
+
 impl CharacterStyles {
     pub fn foreground(mut self, foreground_code: Option<AnsiCode>) -> Self {
         self.foreground = foreground_code;
@@ -391,6 +414,76 @@ impl CharacterStyles {
         }
         Some(diff)
     }
+    /// The SGR parameters that set these styles from scratch, as reported to DECRQSS
+    pub fn sgr_params(&self) -> String {
+        let mut params = vec!["0".to_owned()];
+        let is_on = |ansi_code: Option<AnsiCode>| ansi_code == Some(AnsiCode::On);
+        if is_on(self.bold) {
+            params.push("1".to_owned());
+        }
+        if is_on(self.dim) {
+            params.push("2".to_owned());
+        }
+        if is_on(self.italic) {
+            params.push("3".to_owned());
+        }
+        match self.underline {
+            Some(AnsiCode::Underline(Some(styled))) if self.styled_underlines_enabled => {
+                let style = match styled {
+                    AnsiStyledUnderline::Double => "4:2",
+                    AnsiStyledUnderline::Undercurl => "4:3",
+                    AnsiStyledUnderline::Underdotted => "4:4",
+                    AnsiStyledUnderline::Underdashed => "4:5",
+                };
+                params.push(style.to_owned());
+            },
+            Some(AnsiCode::Underline(_)) => params.push("4".to_owned()),
+            _ => {},
+        }
+        if is_on(self.slow_blink) {
+            params.push("5".to_owned());
+        }
+        if is_on(self.fast_blink) {
+            params.push("6".to_owned());
+        }
+        if is_on(self.reverse) {
+            params.push("7".to_owned());
+        }
+        if is_on(self.hidden) {
+            params.push("8".to_owned());
+        }
+        if is_on(self.strike) {
+            params.push("9".to_owned());
+        }
+        match self.foreground {
+            Some(AnsiCode::RgbCode((r, g, b))) => params.push(format!("38;2;{};{};{}", r, g, b)),
+            Some(AnsiCode::ColorIndex(color_index)) => params.push(format!("38;5;{}", color_index)),
+            Some(AnsiCode::NamedColor(named_color)) => {
+                params.push(named_color.to_foreground_ansi_code())
+            },
+            _ => {},
+        }
+        match self.background {
+            Some(AnsiCode::RgbCode((r, g, b))) => params.push(format!("48;2;{};{};{}", r, g, b)),
+            Some(AnsiCode::ColorIndex(color_index)) => params.push(format!("48;5;{}", color_index)),
+            Some(AnsiCode::NamedColor(named_color)) => {
+                params.push(named_color.to_background_ansi_code())
+            },
+            _ => {},
+        }
+        if self.styled_underlines_enabled {
+            match self.underline_color {
+                Some(AnsiCode::RgbCode((r, g, b))) => {
+                    params.push(format!("58;2;{};{};{}", r, g, b))
+                },
+                Some(AnsiCode::ColorIndex(color_index)) => {
+                    params.push(format!("58;5;{}", color_index))
+                },
+                _ => {},
+            }
+        }
+        params.join(";")
+    }
     fn reset_ansi(&mut self) {
         self.foreground = Some(AnsiCode::Reset);
         self.background = Some(AnsiCode::Reset);
@@ -762,7 +855,7 @@ impl Display for CharacterStyles {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum LinkAnchor {
     Start(u16),
     End,
@@ -884,6 +977,18 @@ impl CursorShape {
             CursorShape::BlinkingBeam => "\u{1b}[5 q",
         }
     }
+    /// The parameter of the DECSCUSR sequence that sets this shape
+    pub fn decscusr_param(&self) -> usize {
+        match self {
+            CursorShape::Initial => 0,
+            CursorShape::BlinkingBlock => 1,
+            CursorShape::Block => 2,
+            CursorShape::BlinkingUnderline => 3,
+            CursorShape::Underline => 4,
+            CursorShape::BlinkingBeam => 5,
+            CursorShape::Beam => 6,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -917,7 +1022,7 @@ impl Cursor {
 
 #[derive(Clone, PartialEq)]
 pub struct TerminalCharacter {
-    pub character: char,
+    pub character: Grapheme,
     pub styles: RcCharacterStyles,
     width: u8,
 }
@@ -934,7 +1039,7 @@ impl TerminalCharacter {
     #[inline]
     pub fn new_styled(character: char, styles: RcCharacterStyles) -> Self {
         TerminalCharacter {
-            character,
+            character: character.into(),
             styles,
             width: character.width().unwrap_or(0) as u8,
         }
@@ -948,12 +1053,25 @@ impl TerminalCharacter {
     #[inline]
     pub fn new_singlewidth_styled(character: char, styles: RcCharacterStyles) -> Self {
         TerminalCharacter {
-            character,
+            character: character.into(),
             styles,
             width: 1,
         }
     }
 
+    #[inline]
+    pub fn new_styled_with_width(
+        character: Grapheme,
+        styles: RcCharacterStyles,
+        width: u8,
+    ) -> Self {
+        TerminalCharacter {
+            character,
+            styles,
+            width,
+        }
+    }
+
     pub fn width(&self) -> usize {
         self.width as usize
     }
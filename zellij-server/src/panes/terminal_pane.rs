@@ -3,6 +3,10 @@ use crate::panes::sixel::SixelImageStore;
 use crate::panes::LinkHandler;
 use crate::panes::{
     grid::Grid,
+    kitty_graphics::{KittyGraphicsFileRead, KittyGraphicsInterceptor},
+    pane_monitor::{PaneAlerts, PaneMonitor},
+    pane_output_pipe::PaneOutputPipe,
+    reported_cwd::ReportedCwd,
     terminal_character::{render_first_run_banner, TerminalCharacter, EMPTY_TERMINAL_CHARACTER},
 };
 use crate::pty::VteBytes;
@@ -13,12 +17,13 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::rc::Rc;
 use std::time::{self, Instant};
+use zellij_utils::input::actions::{CopyModeMotion, CopyModeSelection, PipePaneTarget};
 use zellij_utils::input::command::RunCommand;
 use zellij_utils::pane_size::Offset;
 use zellij_utils::{
-    data::{InputMode, Palette, PaletteColor, PaneId as ZellijUtilsPaneId, Style},
+    data::{InputMode, KeyWithModifier, Palette, PaletteColor, PaneId as ZellijUtilsPaneId, Style},
     errors::prelude::*,
-    input::layout::Run,
+    input::layout::{PaneMonitors, Run},
     pane_size::PaneGeom,
     pane_size::SizeInPixels,
     position::Position,
@@ -95,6 +100,15 @@ impl From<ZellijUtilsPaneId> for PaneId {
     }
 }
 
+impl From<PaneId> for ZellijUtilsPaneId {
+    fn from(pane_id: PaneId) -> Self {
+        match pane_id {
+            PaneId::Terminal(id) => ZellijUtilsPaneId::Terminal(id),
+            PaneId::Plugin(id) => ZellijUtilsPaneId::Plugin(id),
+        }
+    }
+}
+
 type IsFirstRun = bool;
 
 // FIXME: This should hold an os_api handle so that terminal panes can set their own size via FD in
@@ -109,6 +123,7 @@ pub struct TerminalPane {
     pub active_at: Instant,
     pub style: Style,
     vte_parser: vte::Parser,
+    kitty_graphics_interceptor: KittyGraphicsInterceptor, // vte does not parse APC sequences
     selection_scrolled_at: time::Instant,
     content_offset: Offset,
     pane_title: String,
@@ -125,6 +140,9 @@ pub struct TerminalPane {
     // held on startup and can possibly be used to display some errors
     pane_frame_color_override: Option<(PaletteColor, Option<String>)>,
     invoked_with: Option<Run>,
+    reported_cwd: Option<ReportedCwd>, // the last working directory reported by the shell (OSC 7)
+    output_pipe: Option<PaneOutputPipe>, // where the bytes from the pty are also piped to
+    monitor: PaneMonitor,
     #[allow(dead_code)]
     arrow_fonts: bool,
 }
@@ -174,10 +192,21 @@ impl Pane for TerminalPane {
         self.reflow_lines();
     }
     fn handle_pty_bytes(&mut self, bytes: VteBytes) {
+        if let Some(output_pipe) = self.output_pipe.as_mut() {
+            if !output_pipe.write(&bytes) {
+                self.output_pipe = None;
+            }
+        }
+        self.replay_pty_bytes(bytes);
+    }
+    fn replay_pty_bytes(&mut self, bytes: VteBytes) {
         self.set_should_render(true);
         for &byte in &bytes {
-            self.vte_parser.advance(&mut self.grid, byte);
+            self.kitty_graphics_interceptor
+                .advance(&mut self.vte_parser, &mut self.grid, byte);
         }
+        let rang_bell = std::mem::take(&mut self.grid.rang_bell_since_last_output);
+        self.monitor.output(rang_bell);
     }
     fn cursor_coordinates(&self) -> Option<(usize, usize)> {
         // (x, y)
@@ -190,6 +219,25 @@ impl Pane for TerminalPane {
             .cursor_coordinates()
             .map(|(x, y)| (x + left, y + top))
     }
+    fn encode_key(
+        &self,
+        key_with_modifier: &KeyWithModifier,
+        raw_input_bytes: Vec<u8>,
+        raw_input_bytes_are_kitty: bool,
+    ) -> Vec<u8> {
+        match (
+            self.grid.supports_kitty_keyboard_protocol(),
+            raw_input_bytes_are_kitty,
+        ) {
+            (true, false) => key_with_modifier
+                .serialize_kitty()
+                .unwrap_or(raw_input_bytes),
+            (false, true) => key_with_modifier
+                .serialize_non_kitty()
+                .unwrap_or(raw_input_bytes),
+            _ => raw_input_bytes,
+        }
+    }
     fn adjust_input_to_terminal(&mut self, input_bytes: Vec<u8>) -> Option<AdjustedInput> {
         // there are some cases in which the terminal state means that input sent to it
         // needs to be adjusted.
@@ -368,6 +416,9 @@ impl Pane for TerminalPane {
                 if self.grid.search_results.wrap_search {
                     modifiers.push("w")
                 }
+                if self.grid.search_results.regex_search {
+                    modifiers.push("r")
+                }
                 modifier_text.push_str(&modifiers.join(", "));
                 modifier_text.push(']');
             }
@@ -394,7 +445,21 @@ impl Pane for TerminalPane {
             } else {
                 frame.add_exit_status(exit_status.as_ref().copied());
             }
+        } else if let Some(last_command_exit_status) = self.grid.last_command_exit_status {
+            frame.add_last_command_exit_status(last_command_exit_status);
+        }
+        if self
+            .output_pipe
+            .as_ref()
+            .map(|output_pipe| !output_pipe.is_open())
+            .unwrap_or(false)
+        {
+            self.output_pipe = None;
+        }
+        if self.output_pipe.is_some() {
+            frame.indicate_piping_output();
         }
+        frame.indicate_alerts(self.monitor.alerts());
         if let Some((frame_color_override, _text)) = self.pane_frame_color_override.as_ref() {
             frame.override_color(*frame_color_override);
         }
@@ -449,7 +514,7 @@ impl Pane for TerminalPane {
                 self.get_content_x() + cursor_x + 1,
                 &character_under_cursor.styles,
             );
-            fake_cursor.push(character_under_cursor.character);
+            character_under_cursor.character.push_to(&mut fake_cursor);
             vte_output = Some(fake_cursor);
         }
         vte_output
@@ -524,6 +589,37 @@ impl Pane for TerminalPane {
     fn dump_screen(&mut self, _client_id: ClientId, full: bool) -> String {
         self.grid.dump_screen(full)
     }
+    fn pane_contents(&self, include_scrollback: bool, with_ansi: bool) -> Vec<String> {
+        self.grid.pane_contents(include_scrollback, with_ansi)
+    }
+    fn pipe_output(&mut self, target: Option<PipePaneTarget>, strip_ansi: bool) -> Result<()> {
+        self.output_pipe = match target {
+            Some(target) => Some(PaneOutputPipe::new(target, strip_ansi)?),
+            None => None,
+        };
+        self.set_should_render(true);
+        Ok(())
+    }
+    fn watch_output(&mut self) {
+        if self.grid.committed_lines.is_none() {
+            self.grid.committed_lines = Some(vec![]);
+        }
+    }
+    fn unwatch_output(&mut self) {
+        self.grid.committed_lines = None;
+    }
+    fn drop_lines_evicted_from_scrollback(&mut self) {
+        if self.grid.drop_lines_evicted_from_scrollback() {
+            self.set_should_render(true);
+        }
+    }
+    fn drain_committed_lines(&mut self) -> Vec<String> {
+        self.grid
+            .committed_lines
+            .as_mut()
+            .map(|committed_lines| committed_lines.drain(..).collect())
+            .unwrap_or_default()
+    }
     fn clear_screen(&mut self) {
         self.grid.clear_screen()
     }
@@ -561,6 +657,32 @@ impl Pane for TerminalPane {
         self.grid.pending_clipboard_update.take()
     }
 
+    fn drain_kitty_graphics_file_reads(&mut self) -> Vec<KittyGraphicsFileRead> {
+        self.grid
+            .pending_kitty_graphics_file_reads
+            .drain(..)
+            .collect()
+    }
+
+    fn finish_kitty_graphics_file_read(
+        &mut self,
+        file_read_id: u32,
+        data: Result<Vec<u8>, String>,
+    ) {
+        self.grid
+            .finish_kitty_graphics_file_read(file_read_id, data);
+    }
+
+    fn drain_cwd_update(&mut self) -> Option<ReportedCwd> {
+        let reported_cwd = self.grid.pending_cwd_update.take()?;
+        if self.reported_cwd.as_ref() == Some(&reported_cwd) {
+            // shells report their cwd on every prompt, we only care about changes
+            return None;
+        }
+        self.reported_cwd = Some(reported_cwd.clone());
+        Some(reported_cwd)
+    }
+
     fn start_selection(&mut self, start: &Position, _client_id: ClientId) {
         self.grid.start_selection(start);
         self.set_should_render(true);
@@ -627,6 +749,38 @@ impl Pane for TerminalPane {
         self.borderless
     }
 
+    fn update_style(&mut self, style: Style) {
+        self.style = style;
+        self.grid.update_style(style);
+        self.set_should_render(true);
+    }
+
+    fn set_monitors(&mut self, monitors: PaneMonitors) {
+        self.monitor.set_monitors(monitors);
+    }
+    fn monitors(&self) -> PaneMonitors {
+        self.monitor.monitors()
+    }
+    fn is_monitoring_silence(&self, defaults: PaneMonitors) -> bool {
+        self.monitor.is_monitoring_silence(defaults)
+    }
+    fn alerts(&self) -> PaneAlerts {
+        self.monitor.alerts()
+    }
+    fn new_alerts(&mut self, defaults: PaneMonitors, now: Instant) -> PaneAlerts {
+        let new_alerts = self.monitor.new_alerts(defaults, now);
+        if new_alerts.any() {
+            self.set_should_render(true);
+        }
+        new_alerts
+    }
+    fn clear_alerts(&mut self) -> bool {
+        let had_alerts = self.monitor.clear_alerts();
+        if had_alerts {
+            self.set_should_render(true);
+        }
+        had_alerts
+    }
     fn set_exclude_from_sync(&mut self, exclude_from_sync: bool) {
         self.exclude_from_sync = exclude_from_sync;
     }
@@ -713,10 +867,49 @@ impl Pane for TerminalPane {
     fn toggle_search_wrap(&mut self) {
         self.grid.toggle_search_wrap();
     }
+    fn toggle_search_regex(&mut self) {
+        self.grid.toggle_search_regex();
+        self.set_should_render(true);
+    }
     fn clear_search(&mut self) {
         self.grid.clear_search();
         self.search_term.clear();
     }
+    fn start_copy_mode(&mut self) {
+        self.grid.start_copy_mode();
+        self.set_should_render(true);
+    }
+    fn exit_copy_mode(&mut self) {
+        self.grid.exit_copy_mode();
+        self.set_should_render(true);
+    }
+    fn copy_mode_motion(&mut self, motion: CopyModeMotion) {
+        self.grid.copy_mode_motion(motion);
+        self.set_should_render(true);
+    }
+    fn copy_mode_toggle_selection(&mut self, selection: CopyModeSelection) {
+        self.grid.copy_mode_toggle_selection(selection);
+        self.set_should_render(true);
+    }
+    fn copy_mode_clear_selection(&mut self) {
+        self.grid.copy_mode_clear_selection();
+        self.set_should_render(true);
+    }
+    fn scroll_to_previous_prompt(&mut self) {
+        self.grid.scroll_to_previous_prompt();
+        self.set_should_render(true);
+    }
+    fn scroll_to_next_prompt(&mut self) {
+        self.grid.scroll_to_next_prompt();
+        self.set_should_render(true);
+    }
+    fn select_last_command_output(&mut self) {
+        self.grid.select_last_command_output();
+        self.set_should_render(true);
+    }
+    fn last_command_output(&self) -> Option<String> {
+        self.grid.last_command_output()
+    }
     fn is_alternate_mode_active(&self) -> bool {
         self.grid.is_alternate_mode_active()
     }
@@ -784,6 +977,10 @@ impl Pane for TerminalPane {
     fn serialize(&self, scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         self.grid.serialize(scrollback_lines_to_serialize)
     }
+    fn serialize_scrollback(&self, scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
+        self.grid
+            .serialize_scrollback(scrollback_lines_to_serialize)
+    }
 }
 
 impl TerminalPane {
@@ -829,6 +1026,7 @@ impl TerminalPane {
             geom: position_and_size,
             geom_override: None,
             vte_parser: vte::Parser::new(),
+            kitty_graphics_interceptor: KittyGraphicsInterceptor::default(),
             active_at: Instant::now(),
             style,
             selection_scrolled_at: time::Instant::now(),
@@ -843,6 +1041,9 @@ impl TerminalPane {
             banner: None,
             pane_frame_color_override: None,
             invoked_with,
+            reported_cwd: None,
+            output_pipe: None,
+            monitor: PaneMonitor::default(),
             arrow_fonts,
         }
     }
@@ -880,6 +1081,9 @@ impl TerminalPane {
         }
         self.set_should_render(true);
     }
+    pub fn reported_cwd(&self) -> Option<&ReportedCwd> {
+        self.reported_cwd.as_ref()
+    }
     pub fn read_buffer_as_lines(&self) -> Vec<Vec<TerminalCharacter>> {
         self.grid.as_character_lines()
     }
@@ -901,7 +1105,7 @@ impl TerminalPane {
             None => render_first_run_banner(columns, rows, &self.style, None),
         };
         self.banner = Some(banner.clone());
-        self.handle_pty_bytes(banner.as_bytes().to_vec());
+        self.replay_pty_bytes(banner.as_bytes().to_vec());
     }
     fn remove_banner(&mut self) {
         if self.banner.is_some() {
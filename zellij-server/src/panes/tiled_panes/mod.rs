@@ -68,6 +68,7 @@ pub struct TiledPanes {
     draw_pane_frames: bool,
     panes_to_hide: HashSet<PaneId>,
     fullscreen_is_active: bool,
+    focus_before_fullscreen: Option<HashMap<ClientId, PaneId>>,
     senders: ThreadSenders,
     window_title: Option<String>,
     client_id_to_boundaries: HashMap<ClientId, Boundaries>,
@@ -104,6 +105,7 @@ impl TiledPanes {
             draw_pane_frames,
             panes_to_hide: HashSet::new(),
             fullscreen_is_active: false,
+            focus_before_fullscreen: None,
             senders,
             window_title: None,
             client_id_to_boundaries: HashMap::new(),
@@ -621,6 +623,13 @@ impl TiledPanes {
     pub fn panes_contain(&self, pane_id: &PaneId) -> bool {
         self.panes.contains_key(pane_id)
     }
+    pub fn reconfigure(&mut self, style: Style, default_mode_info: ModeInfo) {
+        self.style = style;
+        self.default_mode_info = default_mode_info;
+        for pane in self.panes.values_mut() {
+            pane.update_style(style);
+        }
+    }
     pub fn set_force_render(&mut self) {
         for pane in self.panes.values_mut() {
             pane.set_should_render(true);
@@ -677,12 +686,12 @@ impl TiledPanes {
                     should_draw_pane_frames,
                 );
                 for client_id in &connected_clients {
-                    let client_mode = self
-                        .mode_info
-                        .borrow()
-                        .get(client_id)
-                        .unwrap_or(&self.default_mode_info)
-                        .mode;
+                    let (client_mode, client_style) = {
+                        let mode_infos = self.mode_info.borrow();
+                        let mode_info =
+                            mode_infos.get(client_id).unwrap_or(&self.default_mode_info);
+                        (mode_info.mode, mode_info.style)
+                    };
                     let err_context =
                         || format!("failed to render tiled panes for client {client_id}");
                     if let PaneId::Plugin(..) = kind {
@@ -692,13 +701,23 @@ impl TiledPanes {
                     }
                     if self.draw_pane_frames {
                         pane_contents_and_ui
-                            .render_pane_frame(*client_id, client_mode, self.session_is_mirrored)
+                            .render_pane_frame(
+                                *client_id,
+                                client_mode,
+                                client_style,
+                                self.session_is_mirrored,
+                            )
                             .with_context(err_context)?;
                     } else if pane_is_stacked {
                         // if we have no pane frames but the pane is stacked, we need to render its
                         // frame which will amount to only rendering the title line
                         pane_contents_and_ui
-                            .render_pane_frame(*client_id, client_mode, self.session_is_mirrored)
+                            .render_pane_frame(
+                                *client_id,
+                                client_mode,
+                                client_style,
+                                self.session_is_mirrored,
+                            )
                             .with_context(err_context)?;
                         // we also need to render its boundaries as normal
                         let boundaries = client_id_to_boundaries
@@ -707,6 +726,7 @@ impl TiledPanes {
                         pane_contents_and_ui.render_pane_boundaries(
                             *client_id,
                             client_mode,
+                            client_style,
                             boundaries,
                             self.session_is_mirrored,
                         );
@@ -717,6 +737,7 @@ impl TiledPanes {
                         pane_contents_and_ui.render_pane_boundaries(
                             *client_id,
                             client_mode,
+                            client_style,
                             boundaries,
                             self.session_is_mirrored,
                         );
@@ -751,6 +772,9 @@ impl TiledPanes {
         }
         Ok(())
     }
+    pub fn get_panes_mut(&mut self) -> impl Iterator<Item = (&PaneId, &mut Box<dyn Pane>)> {
+        self.panes.iter_mut()
+    }
     pub fn get_panes(&self) -> impl Iterator<Item = (&PaneId, &Box<dyn Pane>)> {
         self.panes.iter()
     }
@@ -833,10 +857,20 @@ impl TiledPanes {
         client_id: ClientId,
         strategy: &ResizeStrategy,
     ) -> Result<()> {
-        let err_context =
-            || format!("failed to {strategy} for active tiled pane for client {client_id}");
+        match self.get_active_pane_id(client_id) {
+            Some(active_pane_id) => self.resize_pane_with_id(active_pane_id, strategy),
+            None => Ok(()),
+        }
+    }
 
-        if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
+    pub fn resize_pane_with_id(
+        &mut self,
+        pane_id: PaneId,
+        strategy: &ResizeStrategy,
+    ) -> Result<()> {
+        let err_context = || format!("failed to {strategy} for tiled pane {pane_id:?}");
+
+        if self.panes.contains_key(&pane_id) {
             let mut pane_grid = TiledPaneGrid::new(
                 &mut self.panes,
                 &self.panes_to_hide,
@@ -845,7 +879,7 @@ impl TiledPanes {
             );
 
             match pane_grid
-                .change_pane_size(&active_pane_id, strategy, (RESIZE_PERCENT, RESIZE_PERCENT))
+                .change_pane_size(&pane_id, strategy, (RESIZE_PERCENT, RESIZE_PERCENT))
                 .with_context(err_context)
             {
                 Ok(_) => {},
@@ -854,7 +888,7 @@ impl TiledPanes {
                         // try once more with double the resize percent, but let's keep it at that
                         match pane_grid
                             .change_pane_size(
-                                &active_pane_id,
+                                &pane_id,
                                 strategy,
                                 (RESIZE_PERCENT * 2.0, RESIZE_PERCENT * 2.0),
                             )
@@ -1600,15 +1634,43 @@ impl TiledPanes {
             let display_area = *self.display_area.borrow();
             self.resize(display_area);
             self.fullscreen_is_active = false;
+            self.focus_before_fullscreen = None;
         }
     }
     pub fn toggle_active_pane_fullscreen(&mut self, client_id: ClientId) {
         if let Some(active_pane_id) = self.get_active_pane_id(client_id) {
+            self.toggle_pane_fullscreen(active_pane_id);
+        }
+    }
+
+    /// Like `toggle_pane_fullscreen`, but once fullscreen is toggled off each client is focused
+    /// back on the pane it was on before
+    pub fn toggle_pane_fullscreen_and_restore_focus(&mut self, pane_id: PaneId) {
+        if self.fullscreen_is_active || !self.panes.contains_key(&pane_id) {
+            self.toggle_pane_fullscreen(pane_id);
+        } else {
+            let focus_before_fullscreen = self.active_panes.clone_active_panes();
+            self.toggle_pane_fullscreen(pane_id);
             if self.fullscreen_is_active {
+                self.focus_before_fullscreen = Some(focus_before_fullscreen);
+            }
+        }
+    }
+
+    /// Note that the pane becomes focused for all clients while it is fullscreen
+    pub fn toggle_pane_fullscreen(&mut self, pane_id: PaneId) {
+        if self.panes.contains_key(&pane_id) {
+            if self.fullscreen_is_active {
+                let focus_before_fullscreen = self.focus_before_fullscreen.take();
                 self.unset_fullscreen();
+                for (client_id, pane_id) in focus_before_fullscreen.into_iter().flatten() {
+                    if self.panes.contains_key(&pane_id) {
+                        self.focus_pane(pane_id, client_id);
+                    }
+                }
             } else {
                 let pane_ids_to_hide = self.panes.iter().filter_map(|(&id, _pane)| {
-                    if id != active_pane_id
+                    if id != pane_id
                         && is_inside_viewport(&*self.viewport.borrow(), self.get_pane(id).unwrap())
                     {
                         Some(id)
@@ -1641,18 +1703,18 @@ impl TiledPanes {
                         viewport_pane.set_geom_override(viewport_pane.position_and_size());
                     }
                     let viewport = { *self.viewport.borrow() };
-                    let active_pane = self.get_pane_mut(active_pane_id).unwrap();
+                    let pane = self.get_pane_mut(pane_id).unwrap();
                     let full_screen_geom = PaneGeom {
                         x: viewport.x,
                         y: viewport.y,
                         ..Default::default()
                     };
-                    active_pane.set_geom_override(full_screen_geom);
+                    pane.set_geom_override(full_screen_geom);
                 }
                 let connected_client_list: Vec<ClientId> =
                     { self.connected_clients.borrow().iter().copied().collect() };
                 for client_id in connected_client_list {
-                    self.focus_pane(active_pane_id, client_id);
+                    self.focus_pane(pane_id, client_id);
                 }
                 self.set_force_render();
                 let display_area = *self.display_area.borrow();
@@ -1,12 +1,16 @@
 use super::super::Grid;
 use crate::panes::grid::SixelImageStore;
+use crate::panes::kitty_graphics::KittyGraphicsInterceptor;
 use crate::panes::link_handler::LinkHandler;
+use crate::panes::ReportedCwd;
+use crate::panes::{set_scrollback_memory_budget, take_scrollback_evictions, Grapheme};
 use ::insta::assert_snapshot;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
+    input::actions::{CopyModeMotion, CopyModeSelection},
     pane_size::SizeInPixels,
     position::Position,
     vte,
@@ -1568,6 +1572,903 @@ fn copy_selected_text_from_lines_below() {
     );
 }
 
+#[test]
+fn copy_mode_word_motions() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "hello world.rs foo\r\nsecond line";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+
+    grid.start_copy_mode();
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((11, 1)),
+        "cursor starts at terminal cursor"
+    );
+    grid.copy_mode_motion(CopyModeMotion::Up);
+    grid.copy_mode_motion(CopyModeMotion::LineStart);
+    assert_eq!(grid.cursor_coordinates(), Some((0, 0)));
+    grid.copy_mode_motion(CopyModeMotion::WordForward);
+    assert_eq!(grid.cursor_coordinates(), Some((6, 0)), "start of word");
+    grid.copy_mode_motion(CopyModeMotion::WordForward);
+    assert_eq!(grid.cursor_coordinates(), Some((11, 0)), "punctuation");
+    grid.copy_mode_motion(CopyModeMotion::WordForward);
+    grid.copy_mode_motion(CopyModeMotion::WordForward);
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((15, 0)),
+        "last word in line"
+    );
+    grid.copy_mode_motion(CopyModeMotion::WordForward);
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((0, 1)),
+        "first word in next line"
+    );
+    grid.copy_mode_motion(CopyModeMotion::WordEnd);
+    assert_eq!(grid.cursor_coordinates(), Some((5, 1)), "end of word");
+    grid.copy_mode_motion(CopyModeMotion::WordBackward);
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((0, 1)),
+        "back to start of word"
+    );
+    grid.copy_mode_motion(CopyModeMotion::WordBackward);
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((15, 0)),
+        "last word in previous line"
+    );
+    grid.copy_mode_motion(CopyModeMotion::LineEnd);
+    assert_eq!(grid.cursor_coordinates(), Some((17, 0)), "end of line");
+    grid.exit_copy_mode();
+    assert_eq!(
+        grid.cursor_coordinates(),
+        Some((11, 1)),
+        "terminal cursor is restored"
+    );
+}
+
+#[test]
+fn copy_mode_char_selection() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "hello world.rs foo\r\nsecond line";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+
+    grid.start_copy_mode();
+    grid.copy_mode_motion(CopyModeMotion::Top);
+    grid.copy_mode_motion(CopyModeMotion::WordForward);
+    grid.copy_mode_toggle_selection(CopyModeSelection::Char);
+    grid.copy_mode_motion(CopyModeMotion::WordEnd);
+    assert_eq!(grid.get_selected_text().unwrap(), "world");
+    grid.copy_mode_motion(CopyModeMotion::Down);
+    assert_eq!(
+        grid.get_selected_text().unwrap(),
+        "world.rs foo\nsecond line"
+    );
+    grid.copy_mode_clear_selection();
+    assert_eq!(grid.get_selected_text(), None);
+}
+
+#[test]
+fn copy_mode_line_selection() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "hello world.rs foo\r\nsecond line";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+
+    grid.start_copy_mode();
+    grid.copy_mode_motion(CopyModeMotion::Up);
+    grid.copy_mode_toggle_selection(CopyModeSelection::Line);
+    assert_eq!(grid.get_selected_text().unwrap(), "hello world.rs foo");
+    grid.copy_mode_motion(CopyModeMotion::Down);
+    assert_eq!(
+        grid.get_selected_text().unwrap(),
+        "hello world.rs foo\nsecond line"
+    );
+}
+
+#[test]
+fn copy_mode_block_selection() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "hello world.rs foo\r\nsecond line";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+
+    grid.start_copy_mode();
+    grid.copy_mode_motion(CopyModeMotion::Top);
+    grid.copy_mode_motion(CopyModeMotion::Right);
+    grid.copy_mode_toggle_selection(CopyModeSelection::Block);
+    grid.copy_mode_motion(CopyModeMotion::Down);
+    grid.copy_mode_motion(CopyModeMotion::Right);
+    grid.copy_mode_motion(CopyModeMotion::Right);
+    assert_eq!(grid.get_selected_text().unwrap(), "ell\neco");
+}
+
+#[test]
+fn copy_mode_selection_across_scrollback() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        3,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "line 1\r\nline 2\r\nline 3\r\nline 4\r\nline 5\r\nline 6";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    grid.start_copy_mode();
+    grid.copy_mode_motion(CopyModeMotion::Top);
+    assert_eq!(grid.cursor_coordinates(), Some((0, 0)));
+    assert_eq!(
+        grid.lines_above.len(),
+        0,
+        "scrolled to the top of the scrollback"
+    );
+    grid.copy_mode_motion(CopyModeMotion::Down);
+    grid.copy_mode_toggle_selection(CopyModeSelection::Line);
+    grid.copy_mode_motion(CopyModeMotion::Bottom);
+    assert_eq!(grid.cursor_coordinates(), Some((0, 2)));
+    assert!(grid.is_scrolled, "pane stays frozen while in copy mode");
+    assert_eq!(
+        grid.get_selected_text().unwrap(),
+        "line 2\nline 3\nline 4\nline 5\nline 6"
+    );
+    grid.exit_copy_mode();
+    assert!(!grid.is_scrolled);
+}
+
+#[test]
+fn copy_mode_top_and_bottom_match_scrolling_a_line_at_a_time() {
+    let new_grid = || {
+        let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+        let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+        let debug = false;
+        let arrow_fonts = true;
+        let styled_underlines = true;
+        let mut grid = Grid::new(
+            4,
+            10,
+            Rc::new(RefCell::new(Palette::default())),
+            terminal_emulator_color_codes,
+            Rc::new(RefCell::new(LinkHandler::new())),
+            Rc::new(RefCell::new(None)),
+            sixel_image_store,
+            Style::default(),
+            debug,
+            arrow_fonts,
+            styled_underlines,
+        );
+        let mut vte_parser = vte::Parser::new();
+        for i in 0..30 {
+            // every third line wraps, some of them over several rows
+            let line = if i % 3 == 0 {
+                format!("line {} {}\r\n", i, "wrapped ".repeat(i % 4 + 1))
+            } else {
+                format!("line {}\r\n", i)
+            };
+            for byte in line.as_bytes() {
+                vte_parser.advance(&mut grid, *byte);
+            }
+        }
+        grid.start_copy_mode();
+        grid
+    };
+    let mut jumping_grid = new_grid();
+    let mut scrolling_grid = new_grid();
+
+    jumping_grid.copy_mode_motion(CopyModeMotion::Top);
+    scrolling_grid.move_viewport_up(1000);
+    assert_eq!(
+        format!("{:?}", jumping_grid),
+        format!("{:?}", scrolling_grid)
+    );
+    assert_eq!(jumping_grid.lines_above.len(), 0);
+    assert_eq!(
+        jumping_grid.lines_below.len(),
+        scrolling_grid.lines_below.len()
+    );
+
+    jumping_grid.copy_mode_motion(CopyModeMotion::Bottom);
+    scrolling_grid.move_viewport_down(1000);
+    assert_eq!(
+        format!("{:?}", jumping_grid),
+        format!("{:?}", scrolling_grid)
+    );
+    assert_eq!(jumping_grid.lines_below.len(), 0);
+    assert_eq!(
+        jumping_grid.lines_above.len(),
+        scrolling_grid.lines_above.len()
+    );
+    assert_eq!(
+        jumping_grid.scrollback_position_and_length(),
+        scrolling_grid.scrollback_position_and_length()
+    );
+}
+
+#[test]
+fn semantic_prompt_last_command_output() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        10,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "\u{1b}]133;A\u{7}$ \u{1b}]133;B\u{7}echo hi\r\n\u{1b}]133;C\u{7}hi\r\nthere\r\n\
+        \u{1b}]133;D;0\u{7}\u{1b}]133;A\u{7}$ \u{1b}]133;B\u{7}false\r\n\
+        \u{1b}]133;C\u{7}\u{1b}]133;D;1\u{7}\u{1b}]133;A\u{7}$ ";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.last_command_exit_status, Some(1));
+    assert_eq!(
+        grid.last_command_output(),
+        Some(String::from("hi\nthere")),
+        "commands without output are skipped"
+    );
+    grid.select_last_command_output();
+    assert_eq!(grid.get_selected_text(), Some(String::from("hi\nthere")));
+
+    // the marks stay with their lines when they are re-wrapped
+    grid.change_size(10, 4);
+    assert_eq!(grid.last_command_output(), Some(String::from("hi\nthere")));
+}
+
+#[test]
+fn semantic_prompt_marks_prompts_wrapped_above_the_viewport() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        2,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    // a prompt wrapped over four lines, the first two of which scroll off the viewport
+    let content = format!("\u{1b}]133;A\u{7}$ {}\u{1b}]133;B\u{7}", "x".repeat(30));
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.lines_above.len(), 2);
+    let prompt_line = grid.lines_above.get(0).unwrap();
+    assert!(prompt_line.is_canonical);
+    let marks = prompt_line.semantic_prompt_marks().unwrap();
+    assert_eq!(marks.prompt_start, Some(0));
+    assert_eq!(
+        marks.command_start,
+        Some(32),
+        "the command starts after the whole prompt, on its first line"
+    );
+    let wrapped_line = grid.lines_above.get(1).unwrap();
+    assert!(wrapped_line.semantic_prompt_marks().is_none());
+}
+
+#[test]
+fn semantic_prompt_scroll_to_prompt() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        5,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let mut content = String::new();
+    for command in 1..=3 {
+        write!(
+            &mut content,
+            "\u{1b}]133;A\u{7}$ \u{1b}]133;B\u{7}cmd{}\r\n\u{1b}]133;C\u{7}",
+            command
+        )
+        .unwrap();
+        for line in 1..=4 {
+            write!(&mut content, "out{}-{}\r\n", command, line).unwrap();
+        }
+        content.push_str("\u{1b}]133;D;0\u{7}");
+    }
+    content.push_str("\u{1b}]133;A\u{7}$ ");
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let top_line = |grid: &Grid| -> String {
+        grid.viewport[0]
+            .columns
+            .iter()
+            .map(|character| character.character)
+            .collect()
+    };
+    assert_eq!(top_line(&grid), "out3-1");
+    grid.scroll_to_previous_prompt();
+    assert_eq!(top_line(&grid), "$ cmd3");
+    grid.scroll_to_previous_prompt();
+    assert_eq!(top_line(&grid), "$ cmd2");
+    grid.scroll_to_next_prompt();
+    assert_eq!(top_line(&grid), "$ cmd3");
+    grid.scroll_to_next_prompt();
+    assert_eq!(
+        top_line(&grid),
+        "out3-1",
+        "no more prompts, back at the bottom"
+    );
+    assert!(!grid.is_scrolled);
+}
+
+#[test]
+fn semantic_prompt_scroll_to_prompts_far_up_the_scrollback() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        5,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    // enough output for most of the prompts to be packed in the scrollback
+    let mut content = String::new();
+    for command in 1..=3 {
+        write!(
+            &mut content,
+            "\u{1b}]133;A\u{7}$ \u{1b}]133;B\u{7}cmd{}\r\n\u{1b}]133;C\u{7}",
+            command
+        )
+        .unwrap();
+        for line in 1..=150 {
+            write!(&mut content, "out{}-{}\r\n", command, line).unwrap();
+        }
+        content.push_str("\u{1b}]133;D;0\u{7}");
+    }
+    content.push_str("\u{1b}]133;A\u{7}$ ");
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let top_line = |grid: &Grid| -> String {
+        grid.viewport[0]
+            .columns
+            .iter()
+            .map(|character| character.character)
+            .collect()
+    };
+    assert_eq!(grid.lines_above.prompt_lines().len(), 3);
+    let expected_output = (1..=150)
+        .map(|line| format!("out3-{}", line))
+        .collect::<Vec<String>>()
+        .join("\n");
+    assert_eq!(grid.last_command_output(), Some(expected_output));
+    grid.scroll_to_previous_prompt();
+    assert_eq!(top_line(&grid), "$ cmd3");
+    grid.scroll_to_previous_prompt();
+    assert_eq!(top_line(&grid), "$ cmd2");
+    grid.scroll_to_previous_prompt();
+    assert_eq!(top_line(&grid), "$ cmd1");
+    grid.scroll_to_previous_prompt();
+    assert_eq!(top_line(&grid), "$ cmd1", "no more prompts, stays in place");
+    grid.scroll_to_next_prompt();
+    assert_eq!(top_line(&grid), "$ cmd2");
+    assert_eq!(grid.lines_above.prompt_lines().len(), 1);
+    grid.scroll_to_bottom();
+    assert_eq!(grid.lines_above.prompt_lines().len(), 3);
+    assert!(!grid.is_scrolled);
+}
+
+#[test]
+fn osc_7_reports_cwd() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        5,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "\u{1b}]7;file://remote-host/home/user/my%20project\u{7}";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.pending_cwd_update.take(),
+        Some(ReportedCwd {
+            path: std::path::PathBuf::from("/home/user/my project"),
+            hostname: Some(String::from("remote-host")),
+        })
+    );
+    let content = "\u{1b}]7;file:///tmp\u{1b}\\";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.pending_cwd_update.take(),
+        Some(ReportedCwd {
+            path: std::path::PathBuf::from("/tmp"),
+            hostname: None,
+        })
+    );
+    let content = "\u{1b}]7;https://example.com/tmp\u{7}";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.pending_cwd_update, None, "only file urls are accepted");
+}
+
+#[test]
+fn reported_cwd_from_another_host_is_not_local() {
+    let reported_cwd = |uri: &str| {
+        ReportedCwd::from_osc_params(&[&b"7"[..], uri.as_bytes()]).expect("failed to parse cwd")
+    };
+    let local_hostname = Some("my-machine");
+    assert!(reported_cwd("file:///tmp").is_local_to(local_hostname));
+    assert!(reported_cwd("file://localhost/tmp").is_local_to(local_hostname));
+    assert!(reported_cwd("file://My-Machine/tmp").is_local_to(local_hostname));
+    assert!(
+        !reported_cwd("file://remote-host/tmp").is_local_to(local_hostname),
+        "a cwd reported over ssh is not a path on this machine"
+    );
+    assert!(!reported_cwd("file://remote-host/tmp").is_local_to(None));
+    assert_eq!(reported_cwd("file://remote-host/tmp").local_path(), None);
+}
+
+#[test]
+fn kitty_keyboard_protocol_flags() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        5,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    // push "disambiguate escape codes" and "report event types" and query the flags
+    let content = "\u{1b}[>3u\u{1b}[?u";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(grid.supports_kitty_keyboard_protocol());
+    assert_eq!(
+        grid.pending_messages_to_pty.drain(..).collect::<Vec<_>>(),
+        vec!["\u{1b}[?1u".as_bytes().to_vec()],
+        "only the flags we support are reported"
+    );
+
+    // the alternate screen has its own stack
+    let content = "\u{1b}[?1049h";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.supports_kitty_keyboard_protocol());
+    let content = "\u{1b}[?1049l\u{1b}[<u";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.supports_kitty_keyboard_protocol(), "flags popped");
+
+    // CSI u without intermediates still restores the cursor position
+    let content = "ab\u{1b}[scd\u{1b}[u";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.cursor_coordinates(), Some((2, 0)));
+}
+
+#[test]
+fn terminal_capability_queries() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        20,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let mut query = |grid: &mut Grid, content: &str| {
+        for byte in content.as_bytes() {
+            vte_parser.advance(grid, *byte);
+        }
+        grid.pending_messages_to_pty
+            .drain(..)
+            .map(|message| String::from_utf8(message).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    // XTVERSION
+    assert_eq!(
+        query(&mut grid, "\u{1b}[>q"),
+        vec![format!(
+            "\u{1b}P>|Zellij({})\u{1b}\\",
+            zellij_utils::consts::VERSION
+        )]
+    );
+
+    // XTGETTCAP for Tc, colors, Smulx and an unknown capability
+    assert_eq!(
+        query(
+            &mut grid,
+            "\u{1b}P+q5463;636F6C6F7273;536D756C78;666F6F\u{1b}\\"
+        ),
+        vec![concat!(
+            "\u{1b}P1+r5463\u{1b}\\",
+            "\u{1b}P1+r636F6C6F7273=323536\u{1b}\\",
+            "\u{1b}P1+r536D756C78=1B5B343A25703125646D\u{1b}\\",
+            "\u{1b}P0+r666F6F\u{1b}\\",
+        )
+        .to_owned()]
+    );
+
+    // DECRQSS for the current SGR, cursor style and scroll region
+    assert_eq!(
+        query(&mut grid, "\u{1b}[1;3;38;2;10;20;30m\u{1b}P$qm\u{1b}\\"),
+        vec!["\u{1b}P1$r0;1;3;38;2;10;20;30m\u{1b}\\".to_owned()]
+    );
+    assert_eq!(
+        query(&mut grid, "\u{1b}[5 q\u{1b}P$q q\u{1b}\\"),
+        vec!["\u{1b}P1$r5 q\u{1b}\\".to_owned()]
+    );
+    assert_eq!(
+        query(&mut grid, "\u{1b}P$qr\u{1b}\\"),
+        vec!["\u{1b}P1$r1;20r\u{1b}\\".to_owned()],
+        "without a scroll region, the whole screen scrolls"
+    );
+    assert_eq!(
+        query(&mut grid, "\u{1b}[2;10r\u{1b}P$qr\u{1b}\\"),
+        vec!["\u{1b}P1$r2;10r\u{1b}\\".to_owned()]
+    );
+    assert_eq!(
+        query(&mut grid, "\u{1b}P$qx\u{1b}\\"),
+        vec!["\u{1b}P0$r\u{1b}\\".to_owned()],
+        "unknown settings are reported as invalid"
+    );
+
+    // DECRQM for synchronized output
+    assert_eq!(
+        query(&mut grid, "\u{1b}[?2026h\u{1b}[?2026$p"),
+        vec!["\u{1b}[?2026;1$y".to_owned()]
+    );
+    assert_eq!(
+        query(&mut grid, "\u{1b}[?2026l\u{1b}[?2026$p"),
+        vec!["\u{1b}[?2026;2$y".to_owned()]
+    );
+}
+
+#[test]
+fn pane_contents_joins_wrapped_lines() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content = "line 1\n\rline 2 is a long line\n\r\u{1b}[31mred\u{1b}[m\n\rlast";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.pane_contents(false, false),
+        vec!["e", "red", "last"],
+        "viewport only"
+    );
+    assert_eq!(
+        grid.pane_contents(true, false),
+        vec!["line 1", "line 2 is a long line", "red", "last"],
+        "including scrollback"
+    );
+    // every line starts from no styles at all, so the default ones are reset and the styles of
+    // the red line are set one by one
+    assert_eq!(
+        grid.pane_contents(true, true),
+        vec![
+            "\u{1b}[mline 1\u{1b}[m",
+            "\u{1b}[mline 2 is a long line\u{1b}[m",
+            "\u{1b}[31m\u{1b}[49m\u{1b}[59m\u{1b}[29m\u{1b}[28m\u{1b}[27m\u{1b}[25m\u{1b}[25m\
+             \u{1b}[22m\u{1b}[24m\u{1b}[24m\u{1b}[22m\u{1b}[23mred\u{1b}[m",
+            "\u{1b}[mlast\u{1b}[m",
+        ],
+        "styles are serialized"
+    );
+}
+
+#[test]
+fn serialized_scrollback_is_restored_when_replayed_into_a_new_grid() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes.clone(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store.clone(),
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let content =
+        "line 1\n\rline 2 is a long line\n\r\u{1b}[31mred\u{1b}[m\n\r\u{1b}[1;44mlast\u{1b}[m\n\r";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let serialized_scrollback = grid.serialize_scrollback(None).unwrap();
+    assert_eq!(
+        serialized_scrollback.split("\r\n").count(),
+        4,
+        "one line per canonical line, without the empty line at the end"
+    );
+
+    let mut restored_grid = Grid::new(
+        5,
+        30,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    for byte in serialized_scrollback.as_bytes() {
+        vte_parser.advance(&mut restored_grid, *byte);
+    }
+    assert_eq!(
+        restored_grid.pane_contents(true, true),
+        grid.pane_contents(true, true)[..4].to_vec(),
+        "text and styles are restored"
+    );
+    assert_eq!(
+        grid.serialize_scrollback(Some(2)).unwrap(),
+        serialized_scrollback
+            .split("\r\n")
+            .skip(2)
+            .collect::<Vec<_>>()
+            .join("\r\n"),
+        "only the newest lines are kept when asked to"
+    );
+    assert_eq!(
+        Grid::new(
+            3,
+            10,
+            Rc::new(RefCell::new(Palette::default())),
+            Rc::new(RefCell::new(HashMap::new())),
+            Rc::new(RefCell::new(LinkHandler::new())),
+            Rc::new(RefCell::new(None)),
+            Rc::new(RefCell::new(SixelImageStore::default())),
+            Style::default(),
+            debug,
+            arrow_fonts,
+            styled_underlines,
+        )
+        .serialize_scrollback(None),
+        None,
+        "nothing to restore in an empty pane"
+    );
+}
+
+#[test]
+fn committed_lines_are_only_collected_when_watched() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        3,
+        10,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    for byte in "not watched\n\r".as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.committed_lines, None);
+    grid.committed_lines = Some(vec![]);
+    let content = "running 2 tests\n\rtest tests::it_works ... FAILED\n\rstill printing";
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.committed_lines,
+        Some(vec![
+            "running 2 tests".to_owned(),
+            "test tests::it_works ... FAILED".to_owned()
+        ]),
+        "wrapped lines are committed whole, the line being printed is not committed"
+    );
+}
+
 /*
  * These tests below are general compatibility tests for non-trivial scenarios running in the terminal.
  * They use fake TTY input replicated from these scenarios.
@@ -3636,3 +4537,472 @@ fn text_ui_component_with_coordinates() {
     }
     assert_snapshot!(format!("{:?}", grid));
 }
+
+#[test]
+fn scrollback_is_unchanged_by_being_packed_and_scrolled_back_into_view() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        3,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let mut content = String::new();
+    for i in 0..500 {
+        write!(
+            content,
+            "\u{1b}[3{}mline {} 中\u{1b}[1m!\u{1b}[m\n\r",
+            i % 8,
+            i
+        )
+        .unwrap();
+    }
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let contents = grid.pane_contents(true, true);
+    assert_eq!(grid.pane_contents(true, false)[0], "line 0 中!");
+    while !grid.lines_above.is_empty() {
+        grid.scroll_up_one_line();
+    }
+    assert_eq!(
+        grid.pane_contents(true, true),
+        contents,
+        "text and styles are the same after scrolling to the top"
+    );
+    while !grid.lines_below.is_empty() {
+        grid.scroll_down_one_line();
+    }
+    assert_eq!(
+        grid.pane_contents(true, true),
+        contents,
+        "text and styles are the same after scrolling back to the bottom"
+    );
+}
+
+#[test]
+fn oldest_scrollback_lines_of_the_session_are_evicted_when_over_the_memory_budget() {
+    set_scrollback_memory_budget(Some(32 * 1024));
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grids: Vec<Grid> = (0..2)
+        .map(|_| {
+            Grid::new(
+                3,
+                20,
+                Rc::new(RefCell::new(Palette::default())),
+                terminal_emulator_color_codes.clone(),
+                Rc::new(RefCell::new(LinkHandler::new())),
+                Rc::new(RefCell::new(None)),
+                sixel_image_store.clone(),
+                Style::default(),
+                debug,
+                arrow_fonts,
+                styled_underlines,
+            )
+        })
+        .collect();
+    for grid in grids.iter_mut() {
+        let mut content = String::new();
+        for i in 0..1000 {
+            write!(content, "\u{1b}[32mline {}\u{1b}[m\n\r", i).unwrap();
+        }
+        for byte in content.as_bytes() {
+            vte_parser.advance(grid, *byte);
+        }
+    }
+    assert!(take_scrollback_evictions(), "evictions are reported");
+    for grid in grids.iter_mut() {
+        grid.drop_lines_evicted_from_scrollback();
+    }
+    set_scrollback_memory_budget(None);
+
+    let (first_grid, second_grid) = (&grids[0], &grids[1]);
+    assert!(
+        first_grid.lines_above.len() < second_grid.lines_above.len(),
+        "lines are evicted from the pane that was written to first"
+    );
+    assert!(
+        second_grid.lines_above.len() < 1000,
+        "lines are evicted once over the budget"
+    );
+    assert_ne!(first_grid.pane_contents(true, false)[0], "line 0");
+    assert!(
+        second_grid
+            .pane_contents(true, false)
+            .contains(&"line 999".to_owned()),
+        "the newest lines are kept"
+    );
+    for grid in &grids {
+        assert_eq!(
+            grid.scrollback_position_and_length(),
+            (0, grid.lines_above.len()),
+            "evicted lines are no longer counted in the scrollback"
+        );
+    }
+}
+
+#[test]
+fn styles_no_longer_used_by_the_scrollback_are_dropped() {
+    let mut vte_parser = vte::Parser::new();
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        3,
+        20,
+        Rc::new(RefCell::new(Palette::default())),
+        Rc::new(RefCell::new(HashMap::new())),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        Rc::new(RefCell::new(SixelImageStore::default())),
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let mut content = String::new();
+    for i in 0..3000 {
+        write!(
+            content,
+            "\u{1b}[38;2;{};{};0mline {}\u{1b}[m\n\r",
+            i % 256,
+            i / 256,
+            i
+        )
+        .unwrap();
+    }
+    for byte in content.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let lines_before = grid.pane_contents(true, true);
+    let style_count_before = grid.lines_above.style_count();
+    assert!(
+        style_count_before > 2000,
+        "every packed line has its own style, found {} styles",
+        style_count_before
+    );
+    while grid.lines_above.len() > 10 {
+        grid.lines_above.drop_oldest_line();
+    }
+    let lines_after = grid.pane_contents(true, true);
+    let style_count_after = grid.lines_above.style_count();
+    // no packed lines are left, so at most the unused styles that don't warrant compacting yet
+    assert!(
+        style_count_after <= 256,
+        "unused styles are dropped, found {} styles",
+        style_count_after
+    );
+    assert_eq!(
+        lines_after,
+        lines_before[lines_before.len() - lines_after.len()..].to_vec(),
+        "the remaining lines keep their styles"
+    );
+}
+
+#[test]
+fn grapheme_clusters_take_up_a_single_cell() {
+    let mut vte_parser = vte::Parser::new();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut grid = Grid::new(
+        5,
+        40,
+        Rc::new(RefCell::new(Palette::default())),
+        terminal_emulator_color_codes,
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        Style::default(),
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    );
+    let line = "e\u{301} 👍🏽 🇺🇸 ❤\u{fe0f} 👨\u{200d}👩\u{200d}👧 x";
+    for byte in line.as_bytes() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    let cells: Vec<(String, usize)> = grid.viewport[0]
+        .columns
+        .iter()
+        .map(|character| (character.character.to_string(), character.width()))
+        .collect();
+    let expected_cells: Vec<(String, usize)> = [
+        ("e\u{301}", 1),
+        (" ", 1),
+        ("👍🏽", 2),
+        (" ", 1),
+        ("🇺🇸", 2),
+        (" ", 1),
+        ("❤\u{fe0f}", 2),
+        (" ", 1),
+        ("👨\u{200d}👩\u{200d}👧", 2),
+        (" ", 1),
+        ("x", 1),
+    ]
+    .iter()
+    .map(|(cluster, width)| (cluster.to_string(), *width))
+    .collect();
+    assert_eq!(cells, expected_cells);
+    assert_eq!(
+        grid.cursor.x, 15,
+        "the cursor is moved by the width of the clusters"
+    );
+
+    grid.start_selection(&Position::new(0, 0));
+    grid.end_selection(&Position::new(0, 15));
+    assert_eq!(grid.get_selected_text(), Some(line.to_owned()));
+    assert!(grid.serialize(None).unwrap().contains(line));
+
+    grid.set_search_string("👍🏽");
+    assert_eq!(grid.search_results.selections.len(), 1);
+    grid.set_search_string("👍");
+    assert!(
+        grid.search_results.selections.is_empty(),
+        "only whole clusters are matched"
+    );
+    for cluster in [
+        "e\u{301}",
+        "👍🏽",
+        "🇺🇸",
+        "👨\u{200d}👩\u{200d}👧",
+        "\u{915}\u{93e}",   // a letter followed by a spacing mark
+        "\u{1100}\u{1161}", // hangul jamo making up a syllable
+    ] {
+        let mut characters = cluster.chars();
+        let first_character = characters.next().unwrap();
+        let joined = characters.fold(Some(Grapheme::from(first_character)), |grapheme, c| {
+            grapheme.and_then(|grapheme| grapheme.extended_with(c))
+        });
+        assert_eq!(
+            joined,
+            Some(Grapheme::new(cluster)),
+            "a cluster is the same however it was put together"
+        );
+    }
+    for (first_character, character) in [('ä', '€'), ('\u{915}', '\u{916}'), ('👍', '👍')] {
+        assert_eq!(
+            Grapheme::from(first_character).extended_with(character),
+            None,
+            "characters that don't join {:?} are not appended to it",
+            first_character
+        );
+    }
+}
+
+#[test]
+pub fn kitty_graphics_images_are_transmitted_and_placed() {
+    let mut vte_parser = vte::Parser::new();
+    let mut kitty_graphics_interceptor = KittyGraphicsInterceptor::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let character_cell_size = Rc::new(RefCell::new(Some(SizeInPixels {
+        width: 8,
+        height: 21,
+    })));
+    let mut grid = Grid::new(
+        10,
+        50,
+        Rc::new(RefCell::new(Palette::default())),
+        Rc::new(RefCell::new(HashMap::new())),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        character_cell_size,
+        sixel_image_store.clone(),
+        Style::default(),
+        false,
+        true,
+        true,
+    );
+    // a 2x2 white rgb image between two words
+    let content = "foo \u{1b}_Ga=T,i=1,f=24,s=2,v=2;////////////////\u{1b}\\ bar";
+    for byte in content.as_bytes() {
+        kitty_graphics_interceptor.advance(&mut vte_parser, &mut grid, *byte);
+    }
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        1,
+        "image was placed"
+    );
+    assert_eq!(
+        grid.pending_messages_to_pty.drain(..).collect::<Vec<_>>(),
+        vec![b"\x1b_Gi=1;OK\x1b\\".to_vec()],
+        "transmission was acknowledged"
+    );
+    assert_eq!(
+        grid.viewport[0]
+            .columns
+            .iter()
+            .map(|c| c.character.to_string())
+            .collect::<String>(),
+        "foo   bar",
+        "text around the command is printed and the cursor is moved past the image"
+    );
+
+    let content = "\u{1b}_Ga=p,i=2\u{1b}\\\u{1b}_Ga=d,d=i,i=1\u{1b}\\";
+    for byte in content.as_bytes() {
+        kitty_graphics_interceptor.advance(&mut vte_parser, &mut grid, *byte);
+    }
+    assert_eq!(
+        grid.pending_messages_to_pty,
+        vec![b"\x1b_Gi=2;ENOENT:no such image\x1b\\".to_vec()],
+        "placing an unknown image is an error"
+    );
+    let _ = grid.read_changes(0, 0); // we do this because this is where the images are reaped
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        0,
+        "placement was deleted"
+    );
+}
+
+#[test]
+pub fn kitty_graphics_placements_are_no_larger_than_the_pane() {
+    let mut vte_parser = vte::Parser::new();
+    let mut kitty_graphics_interceptor = KittyGraphicsInterceptor::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut grid = Grid::new(
+        10,
+        50,
+        Rc::new(RefCell::new(Palette::default())),
+        Rc::new(RefCell::new(HashMap::new())),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(Some(SizeInPixels {
+            width: 8,
+            height: 21,
+        }))),
+        sixel_image_store.clone(),
+        Style::default(),
+        false,
+        true,
+        true,
+    );
+    let mut place_image = |size: &str| {
+        let content = format!(
+            "\u{1b}[H\u{1b}_Ga=T,f=24,s=2,v=2,{};////////////////\u{1b}\\",
+            size
+        );
+        for byte in content.as_bytes() {
+            kitty_graphics_interceptor.advance(&mut vte_parser, &mut grid, *byte);
+        }
+        let placement_size = grid
+            .sixel_grid
+            .image_coordinates()
+            .map(|(image_id, _)| image_id)
+            .max()
+            .and_then(|image_id| {
+                sixel_image_store
+                    .borrow()
+                    .kitty_placement(image_id)
+                    .map(|placement| placement.size())
+            });
+        (placement_size, grid.cursor.y)
+    };
+    assert_eq!(
+        place_image("c=18446744073709551615,r=18446744073709551615"),
+        (Some((50 * 8, 10 * 21)), 9),
+        "the columns and rows are cut to the size of the pane"
+    );
+    assert_eq!(
+        place_image("c=18446744073709551615"),
+        (Some((10 * 21, 10 * 21)), 9),
+        "an image keeping its aspect ratio is made to fit the pane"
+    );
+    assert_eq!(
+        place_image("r=18446744073709551615"),
+        (Some((10 * 21, 10 * 21)), 9),
+        "whichever side is given"
+    );
+}
+
+#[test]
+pub fn kitty_graphics_images_are_only_read_from_regular_files() {
+    let mut vte_parser = vte::Parser::new();
+    let mut kitty_graphics_interceptor = KittyGraphicsInterceptor::default();
+    let mut grid = Grid::new(
+        10,
+        50,
+        Rc::new(RefCell::new(Palette::default())),
+        Rc::new(RefCell::new(HashMap::new())),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(Some(SizeInPixels {
+            width: 8,
+            height: 21,
+        }))),
+        Rc::new(RefCell::new(SixelImageStore::default())),
+        Style::default(),
+        false,
+        true,
+        true,
+    );
+    let temp_dir = tempfile::tempdir().unwrap();
+    let image_file = temp_dir.path().join("tty-graphics-protocol-image");
+    std::fs::write(&image_file, [255u8; 12]).unwrap();
+    let link_to_proc = temp_dir.path().join("link");
+    std::os::unix::fs::symlink("/proc/self/status", &link_to_proc).unwrap();
+    let mut transmit_file = |medium: &str, path: &std::path::Path| {
+        let content = format!(
+            "\u{1b}_Ga=t,i=1,f=24,s=2,v=2,t={};{}\u{1b}\\",
+            medium,
+            base64::encode(path.to_string_lossy().as_bytes())
+        );
+        for byte in content.as_bytes() {
+            kitty_graphics_interceptor.advance(&mut vte_parser, &mut grid, *byte);
+        }
+        assert!(
+            grid.pending_messages_to_pty.is_empty(),
+            "the file is read in the background"
+        );
+        for file_read in std::mem::take(&mut grid.pending_kitty_graphics_file_reads) {
+            grid.finish_kitty_graphics_file_read(file_read.id, file_read.read());
+        }
+        String::from_utf8(
+            grid.pending_messages_to_pty
+                .drain(..)
+                .collect::<Vec<_>>()
+                .concat(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        transmit_file("f", &image_file),
+        "\u{1b}_Gi=1;OK\u{1b}\\",
+        "a regular file is read"
+    );
+    assert!(image_file.exists(), "and only deleted when asked to");
+    assert!(
+        transmit_file("f", std::path::Path::new("/proc/self/status")).contains("EPERM"),
+        "files under /proc are not read"
+    );
+    assert!(
+        transmit_file("f", &link_to_proc).contains("EPERM"),
+        "not even through a symlink"
+    );
+    assert!(
+        transmit_file("f", temp_dir.path()).contains("not a regular file"),
+        "directories are not read"
+    );
+    assert_eq!(
+        transmit_file("t", &image_file),
+        "\u{1b}_Gi=1;OK\u{1b}\\",
+        "a temporary file is read"
+    );
+    assert!(!image_file.exists(), "and then deleted");
+}
@@ -419,3 +419,61 @@ pub fn searching_whole_word_case_insensitive() {
         format!("{:?}", terminal_pane.grid)
     );
 }
+
+#[test]
+pub fn searching_with_regex() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("qua[m]");
+    terminal_pane.toggle_search_regex();
+    assert_snapshot!(
+        "grid_copy_quam_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("tortor|quam");
+    terminal_pane.toggle_search_regex();
+    assert_snapshot!(
+        "grid_copy_tortor_or_quam_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+}
+
+#[test]
+pub fn searching_with_regex_whole_word_case_insensitive() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("q.am");
+    terminal_pane.toggle_search_regex();
+    terminal_pane.toggle_search_whole_words();
+    assert_snapshot!(
+        "grid_copy_quam_whole_word_only",
+        format!("{:?}", terminal_pane.grid)
+    );
+
+    terminal_pane.toggle_search_case_sensitivity();
+    assert_snapshot!(
+        "grid_copy_quam_whole_word_case_insensitive",
+        format!("{:?}", terminal_pane.grid)
+    );
+}
+
+#[test]
+pub fn searching_with_regex_across_line_wrap() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("aliquam sem fringil+a");
+    terminal_pane.toggle_search_regex();
+    // Spread across two lines
+    terminal_pane.grid.change_size(30, 60);
+    assert_snapshot!(
+        "grid_copy_multiline_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+}
+
+#[test]
+pub fn searching_with_invalid_regex() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("quam(");
+    terminal_pane.toggle_search_regex();
+    assert!(terminal_pane.grid.search_results.selections.is_empty());
+}
@@ -8,6 +8,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
+    input::actions::PipePaneTarget,
     pane_size::{Offset, PaneGeom, SizeInPixels},
     position::Position,
 };
@@ -740,3 +741,112 @@ pub fn frameless_pane_position_is_on_frame() {
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 130)));
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 131)));
 }
+
+#[test]
+pub fn pane_output_is_piped_to_a_file_until_piping_is_stopped() {
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(121);
+    fake_win_size.rows.set_inner(20);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+        None,
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    ); // 0 is the pane index
+    let log_dir = tempfile::tempdir().unwrap();
+    let log_file = log_dir.path().join("pane.log");
+    terminal_pane.handle_pty_bytes("before piping\n\r".as_bytes().to_vec());
+    terminal_pane
+        .pipe_output(Some(PipePaneTarget::File(log_file.clone())), true)
+        .unwrap();
+    terminal_pane.replay_pty_bytes("replayed contents\n\r".as_bytes().to_vec());
+    terminal_pane.handle_pty_bytes(
+        "\u{1b}[31mred\u{1b}[m and \u{1b}[1mbold\n\r"
+            .as_bytes()
+            .to_vec(),
+    );
+    terminal_pane.pipe_output(None, false).unwrap();
+    terminal_pane.handle_pty_bytes("after piping\n\r".as_bytes().to_vec());
+
+    // the bytes are written on a thread of their own
+    let expected_log = "red and bold\n\r";
+    let mut log = String::new();
+    for _ in 0..100 {
+        log = std::fs::read_to_string(&log_file).unwrap_or_default();
+        if log == expected_log {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(log, expected_log);
+}
+
+#[test]
+pub fn pane_output_stops_being_piped_when_the_command_exits() {
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(121);
+    fake_win_size.rows.set_inner(20);
+
+    let pid = 1;
+    let style = Style::default();
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let debug = false;
+    let arrow_fonts = true;
+    let styled_underlines = true;
+    let mut terminal_pane = TerminalPane::new(
+        pid,
+        fake_win_size,
+        style,
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        Rc::new(RefCell::new(None)),
+        sixel_image_store,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+        None,
+        debug,
+        arrow_fonts,
+        styled_underlines,
+    ); // 0 is the pane index
+    terminal_pane
+        .pipe_output(Some(PipePaneTarget::Command("true".to_owned())), false)
+        .unwrap();
+    assert!(terminal_pane.output_pipe.is_some());
+
+    // the writer thread checks on the command every second
+    for _ in 0..300 {
+        if !terminal_pane.output_pipe.as_ref().unwrap().is_open() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    terminal_pane.handle_pty_bytes("output\n\r".as_bytes().to_vec());
+    assert!(
+        terminal_pane.output_pipe.is_none(),
+        "the pipe is dropped once its command exited"
+    );
+}
@@ -130,6 +130,7 @@ pub enum PluginInstruction {
         message: MessageToPlugin,
     },
     UnblockCliPipes(Vec<PluginRenderAsset>),
+    RefreshTerminalProcessInfo,
     Exit,
 }
 
@@ -165,6 +166,9 @@ impl From<&PluginInstruction> for PluginContext {
             PluginInstruction::CachePluginEvents { .. } => PluginContext::CachePluginEvents,
             PluginInstruction::MessageFromPlugin { .. } => PluginContext::MessageFromPlugin,
             PluginInstruction::UnblockCliPipes { .. } => PluginContext::UnblockCliPipes,
+            PluginInstruction::RefreshTerminalProcessInfo => {
+                PluginContext::RefreshTerminalProcessInfo
+            },
         }
     }
 }
@@ -251,6 +255,10 @@ pub(crate) fn plugin_thread_main(
             },
             PluginInstruction::Unload(pid) => {
                 wasm_bridge.unload_plugin(pid)?;
+                drop(
+                    bus.senders
+                        .send_to_screen(ScreenInstruction::UnwatchPanesOfPlugin(pid)),
+                );
             },
             PluginInstruction::Reload(should_float, pane_title, run, tab_index, size) => {
                 match wasm_bridge.reload_plugin(&run) {
@@ -399,6 +407,13 @@ pub(crate) fn plugin_thread_main(
                 wasm_bridge.update_plugins(updates, shutdown_send.clone())?;
             },
             PluginInstruction::PluginSubscribedToEvents(_plugin_id, _client_id, events) => {
+                if events.contains(&EventType::PaneUpdate) {
+                    // so that the plugin doesn't have to wait for the next refresh
+                    drop(
+                        bus.senders
+                            .send_to_pty(PtyInstruction::ReportTerminalProcessInfo),
+                    );
+                }
                 for event in events {
                     if let EventType::FileSystemCreate
                     | EventType::FileSystemRead
@@ -575,6 +590,16 @@ pub(crate) fn plugin_thread_main(
                         .context("failed to unblock input pipe");
                 }
             },
+            PluginInstruction::RefreshTerminalProcessInfo => {
+                // collecting the process info means querying the system, so we only do it if a
+                // plugin is there to be told about it
+                if wasm_bridge.has_subscribers_to(EventType::PaneUpdate) {
+                    drop(
+                        bus.senders
+                            .send_to_pty(PtyInstruction::ReportTerminalProcessInfo),
+                    );
+                }
+            },
             PluginInstruction::Exit => {
                 break;
             },
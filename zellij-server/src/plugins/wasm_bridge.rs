@@ -1016,6 +1016,16 @@ impl WasmBridge {
         }
         Ok(())
     }
+    pub fn has_subscribers_to(&self, event_type: EventType) -> bool {
+        self.plugin_map
+            .lock()
+            .unwrap()
+            .running_plugins_and_subscriptions()
+            .iter()
+            .any(|(_plugin_id, _client_id, _running_plugin, subscriptions)| {
+                subscriptions.lock().unwrap().contains(&event_type)
+            })
+    }
     pub fn start_fs_watcher_if_not_started(&mut self) {
         if self.watcher.is_none() {
             self.watcher = match watch_filesystem(self.senders.clone(), &self.zellij_cwd) {
@@ -1218,6 +1228,12 @@ fn check_event_permission(
         Event::ModeUpdate(..)
         | Event::TabUpdate(..)
         | Event::PaneUpdate(..)
+        | Event::PaneOpened(..)
+        | Event::PaneClosed(..)
+        | Event::CommandPaneExited(..)
+        | Event::PaneBell(..)
+        | Event::PaneActivity(..)
+        | Event::PaneSilence(..)
         | Event::SessionUpdate(..)
         | Event::CopyToClipboard(..)
         | Event::SystemClipboardFailure
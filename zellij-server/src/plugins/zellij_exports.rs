@@ -18,8 +18,8 @@ use std::{
 use wasmer::{imports, AsStoreMut, Function, FunctionEnv, FunctionEnvMut, Imports};
 use wasmer_wasi::WasiEnv;
 use zellij_utils::data::{
-    CommandType, ConnectToSession, FloatingPaneCoordinates, HttpVerb, LayoutInfo, MessageToPlugin,
-    PermissionStatus, PermissionType, PluginPermission,
+    ClientId, CommandType, ConnectToSession, FloatingPaneCoordinates, HttpVerb, LayoutInfo,
+    MessageToPlugin, PermissionStatus, PermissionType, PluginPermission,
 };
 use zellij_utils::input::permission::PermissionCache;
 use zellij_utils::{
@@ -40,7 +40,7 @@ use zellij_utils::{
     errors::prelude::*,
     input::{
         actions::Action,
-        command::{RunCommand, RunCommandAction, TerminalAction},
+        command::{OriginatingPlugin, RunCommand, RunCommandAction, TerminalAction},
         layout::{Layout, PluginUserConfiguration, RunPlugin, RunPluginLocation},
         plugins::PluginType,
     },
@@ -124,13 +124,19 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
                     PluginCommand::OpenTerminalFloating(cwd, floating_pane_coordinates) => {
                         open_terminal_floating(env, cwd.path.try_into()?, floating_pane_coordinates)
                     },
-                    PluginCommand::OpenCommandPane(command_to_run) => {
-                        open_command_pane(env, command_to_run)
+                    PluginCommand::OpenCommandPane(command_to_run, context) => {
+                        open_command_pane(env, command_to_run, context)
                     },
                     PluginCommand::OpenCommandPaneFloating(
                         command_to_run,
                         floating_pane_coordinates,
-                    ) => open_command_pane_floating(env, command_to_run, floating_pane_coordinates),
+                        context,
+                    ) => open_command_pane_floating(
+                        env,
+                        command_to_run,
+                        floating_pane_coordinates,
+                        context,
+                    ),
                     PluginCommand::SwitchTabTo(tab_index) => switch_tab_to(env, tab_index),
                     PluginCommand::SetTimeout(seconds) => set_timeout(env, seconds),
                     PluginCommand::ExecCmd(command_line) => exec_cmd(env, command_line),
@@ -242,8 +248,8 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
                     PluginCommand::OpenTerminalInPlace(cwd) => {
                         open_terminal_in_place(env, cwd.path.try_into()?)
                     },
-                    PluginCommand::OpenCommandPaneInPlace(command_to_run) => {
-                        open_command_pane_in_place(env, command_to_run)
+                    PluginCommand::OpenCommandPaneInPlace(command_to_run, context) => {
+                        open_command_pane_in_place(env, command_to_run, context)
                     },
                     PluginCommand::RenameSession(new_session_name) => {
                         rename_session(env, new_session_name)
@@ -260,6 +266,16 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
                     PluginCommand::MessageToPlugin(message) => message_to_plugin(env, message)?,
                     PluginCommand::DisconnectOtherClients => disconnect_other_clients(env),
                     PluginCommand::KillSessions(session_list) => kill_sessions(session_list),
+                    PluginCommand::GetPaneContents {
+                        pane_id,
+                        include_scrollback,
+                        with_ansi,
+                    } => get_pane_contents(env, pane_id.into(), include_scrollback, with_ansi),
+                    PluginCommand::WatchPane(pane_id) => watch_pane(env, pane_id.into()),
+                    PluginCommand::UnwatchPane(pane_id) => unwatch_pane(env, pane_id.into()),
+                    PluginCommand::SetTheme(theme_name, client_id) => {
+                        set_theme(env, theme_name, client_id)
+                    },
                 },
                 (PermissionStatus::Denied, permission) => {
                     log::error!(
@@ -555,7 +571,11 @@ fn open_terminal_in_place(env: &ForeignFunctionEnv, cwd: PathBuf) {
     apply_action!(action, error_msg, env);
 }
 
-fn open_command_pane(env: &ForeignFunctionEnv, command_to_run: CommandToRun) {
+fn open_command_pane(
+    env: &ForeignFunctionEnv,
+    command_to_run: CommandToRun,
+    context: BTreeMap<String, String>,
+) {
     let error_msg = || format!("failed to open command in plugin {}", env.plugin_env.name());
     let command = command_to_run.path;
     let cwd = command_to_run
@@ -566,6 +586,11 @@ fn open_command_pane(env: &ForeignFunctionEnv, command_to_run: CommandToRun) {
     let hold_on_close = true;
     let hold_on_start = false;
     let name = None;
+    let originating_plugin = Some(OriginatingPlugin {
+        plugin_id: env.plugin_env.plugin_id,
+        client_id: env.plugin_env.client_id,
+        context,
+    });
     let run_command_action = RunCommandAction {
         command,
         args,
@@ -573,6 +598,7 @@ fn open_command_pane(env: &ForeignFunctionEnv, command_to_run: CommandToRun) {
         direction,
         hold_on_close,
         hold_on_start,
+        originating_plugin,
     };
     let action = Action::NewTiledPane(direction, Some(run_command_action), name);
     apply_action!(action, error_msg, env);
@@ -582,6 +608,7 @@ fn open_command_pane_floating(
     env: &ForeignFunctionEnv,
     command_to_run: CommandToRun,
     floating_pane_coordinates: Option<FloatingPaneCoordinates>,
+    context: BTreeMap<String, String>,
 ) {
     let error_msg = || format!("failed to open command in plugin {}", env.plugin_env.name());
     let command = command_to_run.path;
@@ -593,6 +620,11 @@ fn open_command_pane_floating(
     let hold_on_close = true;
     let hold_on_start = false;
     let name = None;
+    let originating_plugin = Some(OriginatingPlugin {
+        plugin_id: env.plugin_env.plugin_id,
+        client_id: env.plugin_env.client_id,
+        context,
+    });
     let run_command_action = RunCommandAction {
         command,
         args,
@@ -600,12 +632,17 @@ fn open_command_pane_floating(
         direction,
         hold_on_close,
         hold_on_start,
+        originating_plugin,
     };
     let action = Action::NewFloatingPane(Some(run_command_action), name, floating_pane_coordinates);
     apply_action!(action, error_msg, env);
 }
 
-fn open_command_pane_in_place(env: &ForeignFunctionEnv, command_to_run: CommandToRun) {
+fn open_command_pane_in_place(
+    env: &ForeignFunctionEnv,
+    command_to_run: CommandToRun,
+    context: BTreeMap<String, String>,
+) {
     let error_msg = || format!("failed to open command in plugin {}", env.plugin_env.name());
     let command = command_to_run.path;
     let cwd = command_to_run
@@ -616,6 +653,11 @@ fn open_command_pane_in_place(env: &ForeignFunctionEnv, command_to_run: CommandT
     let hold_on_close = true;
     let hold_on_start = false;
     let name = None;
+    let originating_plugin = Some(OriginatingPlugin {
+        plugin_id: env.plugin_env.plugin_id,
+        client_id: env.plugin_env.client_id,
+        context,
+    });
     let run_command_action = RunCommandAction {
         command,
         args,
@@ -623,6 +665,7 @@ fn open_command_pane_in_place(env: &ForeignFunctionEnv, command_to_run: CommandT
         direction,
         hold_on_close,
         hold_on_start,
+        originating_plugin,
     };
     let action = Action::NewInPlacePane(Some(run_command_action), name);
     apply_action!(action, error_msg, env);
@@ -1307,6 +1350,12 @@ fn rename_session(env: &ForeignFunctionEnv, new_session_name: String) {
     apply_action!(action, error_msg, env);
 }
 
+fn set_theme(env: &ForeignFunctionEnv, theme_name: String, client_id: Option<ClientId>) {
+    let error_msg = || format!("failed to set theme in plugin {}", env.plugin_env.name());
+    let action = Action::SwitchTheme(theme_name, client_id);
+    apply_action!(action, error_msg, env);
+}
+
 fn disconnect_other_clients(env: &ForeignFunctionEnv) {
     let _ = env
         .plugin_env
@@ -1331,6 +1380,65 @@ fn kill_sessions(session_names: Vec<String>) {
     }
 }
 
+fn get_pane_contents(
+    env: &ForeignFunctionEnv,
+    pane_id: PaneId,
+    include_scrollback: bool,
+    with_ansi: bool,
+) {
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::GetPaneContents(
+            pane_id,
+            include_scrollback,
+            with_ansi,
+            env.plugin_env.plugin_id,
+            env.plugin_env.client_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to get contents of pane {:?} from plugin {}",
+                pane_id,
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn watch_pane(env: &ForeignFunctionEnv, pane_id: PaneId) {
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::WatchPane(
+            pane_id,
+            env.plugin_env.plugin_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to watch pane {:?} from plugin {}",
+                pane_id,
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
+fn unwatch_pane(env: &ForeignFunctionEnv, pane_id: PaneId) {
+    env.plugin_env
+        .senders
+        .send_to_screen(ScreenInstruction::UnwatchPane(
+            pane_id,
+            env.plugin_env.plugin_id,
+        ))
+        .with_context(|| {
+            format!(
+                "failed to unwatch pane {:?} from plugin {}",
+                pane_id,
+                env.plugin_env.name()
+            )
+        })
+        .non_fatal();
+}
+
 // Custom panic handler for plugins.
 //
 // This is called when a panic occurs in a plugin. Since most panics will likely originate in the
@@ -1459,6 +1567,7 @@ fn check_command_permission(
         | PluginCommand::DeleteDeadSession(..)
         | PluginCommand::DeleteAllDeadSessions
         | PluginCommand::RenameSession(..)
+        | PluginCommand::SetTheme(..)
         | PluginCommand::RenameTab(..)
         | PluginCommand::DisconnectOtherClients
         | PluginCommand::KillSessions(..) => PermissionType::ChangeApplicationState,
@@ -1466,6 +1575,9 @@ fn check_command_permission(
         | PluginCommand::BlockCliPipeInput(..)
         | PluginCommand::CliPipeOutput(..) => PermissionType::ReadCliPipes,
         PluginCommand::MessageToPlugin(..) => PermissionType::MessageAndLaunchOtherPlugins,
+        PluginCommand::GetPaneContents { .. }
+        | PluginCommand::WatchPane(..)
+        | PluginCommand::UnwatchPane(..) => PermissionType::ReadPaneContents,
         _ => return (PermissionStatus::Granted, None),
     };
 
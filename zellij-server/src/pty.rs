@@ -1,7 +1,7 @@
 use crate::background_jobs::BackgroundJob;
 use crate::terminal_bytes::TerminalBytes;
 use crate::{
-    panes::PaneId,
+    panes::{PaneId, ReportedCwd},
     plugins::PluginInstruction,
     screen::ScreenInstruction,
     session_layout_metadata::SessionLayoutMetadata,
@@ -94,6 +94,9 @@ pub enum PtyInstruction {
         Option<PathBuf>, // if Some, will not fill cwd but just forward the message
         Option<FloatingPaneCoordinates>,
     ),
+    UpdateReportedCwd(u32, ReportedCwd), // u32 is the terminal id
+    ReportTerminalProcessInfo,
+    ListPanes(bool, ClientId), // bool is json
     Exit,
 }
 
@@ -115,15 +118,27 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::DumpLayout(..) => PtyContext::DumpLayout,
             PtyInstruction::LogLayoutToHd(..) => PtyContext::LogLayoutToHd,
             PtyInstruction::FillPluginCwd(..) => PtyContext::FillPluginCwd,
+            PtyInstruction::UpdateReportedCwd(..) => PtyContext::UpdateReportedCwd,
+            PtyInstruction::ReportTerminalProcessInfo => PtyContext::ReportTerminalProcessInfo,
+            PtyInstruction::ListPanes(..) => PtyContext::ListPanes,
             PtyInstruction::Exit => PtyContext::Exit,
         }
     }
 }
 
+/// Information about the process running in a terminal pane, as known by the pty thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalProcessInfo {
+    pub pid: u32,
+    pub cwd: Option<PathBuf>,
+    pub foreground_process: Option<Vec<String>>, // the command and args of the child of the pane's process, if any
+}
+
 pub(crate) struct Pty {
     pub active_panes: HashMap<ClientId, PaneId>,
     pub bus: Bus<PtyInstruction>,
     pub id_to_child_pid: HashMap<u32, RawFd>, // terminal_id => child raw fd
+    id_to_reported_cwd: HashMap<u32, ReportedCwd>, // terminal_id => cwd reported by the shell (OSC 7)
     debug_to_file: bool,
     task_handles: HashMap<u32, JoinHandle<()>>, // terminal_id to join-handle
     default_editor: Option<PathBuf>,
@@ -679,6 +694,25 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     floating_pane_coordinates,
                 )?;
             },
+            PtyInstruction::UpdateReportedCwd(terminal_id, reported_cwd) => {
+                pty.id_to_reported_cwd.insert(terminal_id, reported_cwd);
+            },
+            PtyInstruction::ReportTerminalProcessInfo => {
+                pty.report_terminal_process_info()
+                    .context("failed to report terminal process info")
+                    .non_fatal();
+            },
+            PtyInstruction::ListPanes(json, client_id) => {
+                // the process info is only collected on demand, so we bring it up to date before
+                // the screen lists the panes
+                pty.report_terminal_process_info()
+                    .context("failed to report terminal process info")
+                    .non_fatal();
+                pty.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::ListPanes(json, client_id))
+                    .context("failed to list panes")?;
+            },
             PtyInstruction::Exit => break,
         }
     }
@@ -695,6 +729,7 @@ impl Pty {
             active_panes: HashMap::new(),
             bus,
             id_to_child_pid: HashMap::new(),
+            id_to_reported_cwd: HashMap::new(),
             debug_to_file,
             task_handles: HashMap::new(),
             default_editor,
@@ -734,38 +769,49 @@ impl Pty {
                     cwd, // note: this might also be filled by the calling function, eg. spawn_terminal
                     hold_on_close: false,
                     hold_on_start: false,
+                    originating_plugin: None,
                 })
             },
         }
     }
+    fn get_local_reported_cwd(&self, terminal_id: &u32) -> Option<&PathBuf> {
+        // a cwd reported from another machine (eg. through ssh) does not exist here
+        self.id_to_reported_cwd
+            .get(terminal_id)
+            .and_then(|reported_cwd| reported_cwd.local_path())
+    }
+    fn get_terminal_cwd(&self, terminal_id: &u32) -> Option<PathBuf> {
+        // prefer the cwd reported by the shell, since the cwd of the child process is wrong when
+        // it runs eg. a nested shell
+        match self.get_local_reported_cwd(terminal_id) {
+            Some(reported_cwd) => Some(reported_cwd.clone()),
+            None => self.id_to_child_pid.get(terminal_id).and_then(|&id| {
+                self.bus
+                    .os_input
+                    .as_ref()
+                    .and_then(|input| input.get_cwd(Pid::from_raw(id)))
+            }),
+        }
+    }
+    fn get_active_pane_cwd(&self, client_id: ClientId) -> Option<PathBuf> {
+        self.active_panes
+            .get(&client_id)
+            .and_then(|pane| match pane {
+                PaneId::Plugin(..) => None,
+                PaneId::Terminal(id) => self.get_terminal_cwd(id),
+            })
+    }
     fn fill_cwd(&self, terminal_action: &mut TerminalAction, client_id: ClientId) {
         if let TerminalAction::RunCommand(run_command) = terminal_action {
             if run_command.cwd.is_none() {
-                run_command.cwd = self
-                    .active_panes
-                    .get(&client_id)
-                    .and_then(|pane| match pane {
-                        PaneId::Plugin(..) => None,
-                        PaneId::Terminal(id) => self.id_to_child_pid.get(id),
-                    })
-                    .and_then(|&id| {
-                        self.bus
-                            .os_input
-                            .as_ref()
-                            .and_then(|input| input.get_cwd(Pid::from_raw(id)))
-                    });
+                run_command.cwd = self.get_active_pane_cwd(client_id);
             };
         };
     }
     fn fill_cwd_from_pane_id(&self, terminal_action: &mut TerminalAction, pane_id: &u32) {
         if let TerminalAction::RunCommand(run_command) = terminal_action {
             if run_command.cwd.is_none() {
-                run_command.cwd = self.id_to_child_pid.get(pane_id).and_then(|&id| {
-                    self.bus
-                        .os_input
-                        .as_ref()
-                        .and_then(|input| input.get_cwd(Pid::from_raw(id)))
-                });
+                run_command.cwd = self.get_terminal_cwd(pane_id);
             };
         };
     }
@@ -776,6 +822,8 @@ impl Pty {
     ) -> Result<(u32, bool)> {
         // bool is starts_held
         let err_context = || format!("failed to spawn terminal for {:?}", client_or_tab_index);
+        // as opposed to the default shell or an editor
+        let is_command_pane = matches!(terminal_action, Some(TerminalAction::RunCommand(..)));
 
         // returns the terminal id
         let terminal_action = match client_or_tab_index {
@@ -829,6 +877,13 @@ impl Pty {
                         None,
                     ));
                 } else {
+                    if is_command_pane {
+                        let _ = senders.send_to_screen(ScreenInstruction::CommandPaneExited(
+                            pane_id,
+                            exit_status,
+                            command,
+                        ));
+                    }
                     let _ = senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
                 }
             }
@@ -1035,6 +1090,11 @@ impl Pty {
                                 None,
                             ));
                         } else {
+                            let _ = senders.send_to_screen(ScreenInstruction::CommandPaneExited(
+                                pane_id,
+                                exit_status,
+                                command,
+                            ));
                             let _ =
                                 senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
                         }
@@ -1180,6 +1240,7 @@ impl Pty {
         match id {
             PaneId::Terminal(id) => {
                 self.task_handles.remove(&id);
+                self.id_to_reported_cwd.remove(&id);
                 if let Some(child_fd) = self.id_to_child_pid.remove(&id) {
                     task::block_on(async {
                         let err_context = || format!("failed to run async task for pane {id}");
@@ -1245,6 +1306,11 @@ impl Pty {
                                 None,
                             ));
                         } else {
+                            let _ = senders.send_to_screen(ScreenInstruction::CommandPaneExited(
+                                pane_id,
+                                exit_status,
+                                command,
+                            ));
                             let _ =
                                 senders.send_to_screen(ScreenInstruction::ClosePane(pane_id, None));
                         }
@@ -1293,19 +1359,7 @@ impl Pty {
     ) {
         let terminal_ids = session_layout_metadata.all_terminal_ids();
         let mut terminal_ids_to_commands: HashMap<u32, Vec<String>> = HashMap::new();
-        let mut terminal_ids_to_cwds: HashMap<u32, PathBuf> = HashMap::new();
-
-        let pids: Vec<_> = terminal_ids
-            .iter()
-            .filter_map(|id| self.id_to_child_pid.get(&id))
-            .map(|pid| Pid::from_raw(*pid))
-            .collect();
-        let pids_to_cwds = self
-            .bus
-            .os_input
-            .as_ref()
-            .map(|os_input| os_input.get_cwds(pids))
-            .unwrap_or_default();
+        let terminal_ids_to_cwds = self.get_terminal_cwds(&terminal_ids);
         let ppids_to_cmds = self
             .bus
             .os_input
@@ -1315,23 +1369,75 @@ impl Pty {
 
         for terminal_id in terminal_ids {
             let process_id = self.id_to_child_pid.get(&terminal_id);
-            let cwd = process_id
-                .as_ref()
-                .and_then(|pid| pids_to_cwds.get(&Pid::from_raw(**pid)));
             let cmd = process_id
                 .as_ref()
                 .and_then(|pid| ppids_to_cmds.get(&format!("{}", pid)));
             if let Some(cmd) = cmd {
                 terminal_ids_to_commands.insert(terminal_id, cmd.clone());
             }
-            if let Some(cwd) = cwd {
-                terminal_ids_to_cwds.insert(terminal_id, cwd.clone());
-            }
         }
         session_layout_metadata.update_default_shell(get_default_shell());
         session_layout_metadata.update_terminal_commands(terminal_ids_to_commands);
         session_layout_metadata.update_terminal_cwds(terminal_ids_to_cwds);
     }
+    fn get_terminal_cwds(&self, terminal_ids: &[u32]) -> HashMap<u32, PathBuf> {
+        // like get_terminal_cwd, but queries the system only once for all terminals that did not
+        // report their cwd
+        let pids: Vec<_> = terminal_ids
+            .iter()
+            .filter(|id| self.get_local_reported_cwd(id).is_none())
+            .filter_map(|id| self.id_to_child_pid.get(&id))
+            .map(|pid| Pid::from_raw(*pid))
+            .collect();
+        let pids_to_cwds = self
+            .bus
+            .os_input
+            .as_ref()
+            .map(|os_input| os_input.get_cwds(pids))
+            .unwrap_or_default();
+        let mut terminal_ids_to_cwds = HashMap::new();
+        for terminal_id in terminal_ids {
+            let cwd = self.get_local_reported_cwd(terminal_id).or_else(|| {
+                self.id_to_child_pid
+                    .get(terminal_id)
+                    .and_then(|pid| pids_to_cwds.get(&Pid::from_raw(*pid)))
+            });
+            if let Some(cwd) = cwd {
+                terminal_ids_to_cwds.insert(*terminal_id, cwd.clone());
+            }
+        }
+        terminal_ids_to_cwds
+    }
+    pub fn report_terminal_process_info(&self) -> Result<()> {
+        let terminal_ids: Vec<u32> = self.id_to_child_pid.keys().copied().collect();
+        let mut terminal_ids_to_cwds = self.get_terminal_cwds(&terminal_ids);
+        let ppids_to_cmds = self
+            .bus
+            .os_input
+            .as_ref()
+            .map(|os_input| os_input.get_all_cmds_by_ppid())
+            .unwrap_or_default();
+        let terminal_process_info = self
+            .id_to_child_pid
+            .iter()
+            .map(|(terminal_id, pid)| {
+                (
+                    *terminal_id,
+                    TerminalProcessInfo {
+                        pid: *pid as u32,
+                        cwd: terminal_ids_to_cwds.remove(terminal_id),
+                        foreground_process: ppids_to_cmds.get(&format!("{}", pid)).cloned(),
+                    },
+                )
+            })
+            .collect();
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::UpdateTerminalProcessInfo(
+                terminal_process_info,
+            ))
+            .context("failed to send terminal process info to screen")
+    }
     pub fn fill_plugin_cwd(
         &self,
         should_float: Option<bool>,
@@ -1346,20 +1452,7 @@ impl Pty {
         cwd: Option<PathBuf>,
         floating_pane_coordinates: Option<FloatingPaneCoordinates>,
     ) -> Result<()> {
-        let cwd = cwd.or_else(|| {
-            self.active_panes
-                .get(&client_id)
-                .and_then(|pane| match pane {
-                    PaneId::Plugin(..) => None,
-                    PaneId::Terminal(id) => self.id_to_child_pid.get(id),
-                })
-                .and_then(|&id| {
-                    self.bus
-                        .os_input
-                        .as_ref()
-                        .and_then(|input| input.get_cwd(Pid::from_raw(id)))
-                })
-        });
+        let cwd = cwd.or_else(|| self.get_active_pane_cwd(client_id));
 
         self.bus.senders.send_to_plugin(PluginInstruction::Load(
             should_float,
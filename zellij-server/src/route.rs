@@ -8,11 +8,12 @@ use crate::{
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction},
     screen::ScreenInstruction,
+    tab::PaneAction,
     ServerInstruction, SessionMetaData, SessionState,
 };
 use zellij_utils::{
     channels::SenderWithContext,
-    data::{Direction, Event, PluginCapabilities, ResizeStrategy},
+    data::{Direction, Event, InputMode, PluginCapabilities, ResizeStrategy},
     errors::prelude::*,
     input::{
         actions::{Action, SearchDirection, SearchOption},
@@ -71,6 +72,19 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::WriteCharacter(val, client_id))
                 .with_context(err_context)?;
         },
+        Action::WriteKey(key_with_modifier, raw_bytes, is_kitty_keyboard_protocol) => {
+            senders
+                .send_to_screen(ScreenInstruction::ClearScroll(client_id))
+                .with_context(err_context)?;
+            senders
+                .send_to_screen(ScreenInstruction::WriteKey(
+                    key_with_modifier,
+                    raw_bytes,
+                    is_kitty_keyboard_protocol,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
         Action::WriteChars(val) => {
             senders
                 .send_to_screen(ScreenInstruction::ClearScroll(client_id))
@@ -103,6 +117,17 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::Render)
                 .with_context(err_context)?;
         },
+        Action::PendingKeySequence(mode, keys) => {
+            let mut mode_info = get_mode_info(mode, &client_attributes, capabilities);
+            mode_info.pending_key_sequence = keys.iter().map(|key| key.to_string()).collect();
+            senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    Some(client_id),
+                    Event::ModeUpdate(mode_info),
+                )]))
+                .with_context(err_context)?;
+        },
         Action::Resize(resize, direction) => {
             let screen_instr =
                 ScreenInstruction::Resize(client_id, ResizeStrategy::new(resize, direction));
@@ -174,6 +199,13 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::DumpScreen(val, client_id, full))
                 .with_context(err_context)?;
         },
+        Action::PipePaneOutput(target, strip_ansi) => {
+            senders
+                .send_to_screen(ScreenInstruction::PipePaneOutput(
+                    target, strip_ansi, client_id,
+                ))
+                .with_context(err_context)?;
+        },
         Action::DumpLayout => {
             let default_shell = match default_shell {
                 Some(TerminalAction::RunCommand(run_command)) => Some(run_command.command),
@@ -218,6 +250,16 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::ScrollToTop(client_id))
                 .with_context(err_context)?;
         },
+        Action::ScrollToPreviousPrompt => {
+            senders
+                .send_to_screen(ScreenInstruction::ScrollToPreviousPrompt(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ScrollToNextPrompt => {
+            senders
+                .send_to_screen(ScreenInstruction::ScrollToNextPrompt(client_id))
+                .with_context(err_context)?;
+        },
         Action::PageScrollUp => {
             senders
                 .send_to_screen(ScreenInstruction::PageScrollUp(client_id))
@@ -632,6 +674,51 @@ pub(crate) fn route_action(
             },
             _ => {},
         },
+        Action::ActOnTarget(target, action) => {
+            let pane_action = match *action {
+                Action::Write(bytes) => Some(PaneAction::Write(bytes)),
+                Action::WriteChars(chars) => Some(PaneAction::Write(chars.into_bytes())),
+                Action::Resize(resize, direction) => {
+                    Some(PaneAction::Resize(ResizeStrategy::new(resize, direction)))
+                },
+                Action::ClearScreen => Some(PaneAction::Clear),
+                Action::DumpScreen(file, full) => Some(PaneAction::DumpScreen(file, full)),
+                Action::PipePaneOutput(target, strip_ansi) => {
+                    Some(PaneAction::PipeOutput(target, strip_ansi))
+                },
+                Action::ScrollUp => Some(PaneAction::ScrollUp),
+                Action::ScrollDown => Some(PaneAction::ScrollDown),
+                Action::ScrollToBottom => Some(PaneAction::ScrollToBottom),
+                Action::ScrollToTop => Some(PaneAction::ScrollToTop),
+                Action::ScrollToPreviousPrompt => Some(PaneAction::ScrollToPreviousPrompt),
+                Action::ScrollToNextPrompt => Some(PaneAction::ScrollToNextPrompt),
+                Action::PageScrollUp => Some(PaneAction::PageScrollUp),
+                Action::PageScrollDown => Some(PaneAction::PageScrollDown),
+                Action::HalfPageScrollUp => Some(PaneAction::HalfPageScrollUp),
+                Action::HalfPageScrollDown => Some(PaneAction::HalfPageScrollDown),
+                Action::ToggleFocusFullscreen => Some(PaneAction::ToggleFullscreen),
+                Action::PaneNameInput(name) => Some(PaneAction::Rename(name)),
+                Action::CloseFocus => Some(PaneAction::Close),
+                _ => None,
+            };
+            match pane_action {
+                Some(pane_action) => {
+                    senders
+                        .send_to_screen(ScreenInstruction::ActOnTarget(
+                            target,
+                            pane_action,
+                            client_id,
+                        ))
+                        .with_context(err_context)?;
+                },
+                None => {
+                    log::error!("Action cannot be performed on {:?}", target);
+                    senders
+                        .send_to_server(ServerInstruction::UnblockInputThread)
+                        .with_context(err_context)?;
+                },
+            }
+        },
         Action::NoOp => {},
         Action::SearchInput(c) => {
             senders
@@ -654,11 +741,39 @@ pub(crate) fn route_action(
                 },
                 SearchOption::WholeWord => ScreenInstruction::SearchToggleWholeWord(client_id),
                 SearchOption::Wrap => ScreenInstruction::SearchToggleWrap(client_id),
+                SearchOption::Regex => ScreenInstruction::SearchToggleRegex(client_id),
             };
             senders
                 .send_to_screen(instruction)
                 .with_context(err_context)?;
         },
+        Action::CopyModeMotion(motion) => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyModeMotion(motion, client_id))
+                .with_context(err_context)?;
+        },
+        Action::CopyModeToggleSelection(selection) => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyModeToggleSelection(
+                    selection, client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::CopyModeYank => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyModeYank(client_id))
+                .with_context(err_context)?;
+        },
+        Action::SelectLastCommandOutput => {
+            senders
+                .send_to_screen(ScreenInstruction::SelectLastCommandOutput(client_id))
+                .with_context(err_context)?;
+        },
+        Action::CopyLastCommandOutput => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyLastCommandOutput(client_id))
+                .with_context(err_context)?;
+        },
         Action::ToggleMouseMode => {}, // Handled client side
         Action::PreviousSwapLayout => {
             senders
@@ -670,6 +785,37 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::NextSwapLayout(client_id))
                 .with_context(err_context)?;
         },
+        Action::ListTabs(json) => {
+            senders
+                .send_to_screen(ScreenInstruction::ListTabs(json, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ListPanes(json) => {
+            senders
+                .send_to_pty(PtyInstruction::ListPanes(json, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ListClients(json) => {
+            // the client sizes are only known to the server, which forwards them to the screen
+            senders
+                .send_to_server(ServerInstruction::ListClients(json, client_id))
+                .with_context(err_context)?;
+        },
+        Action::ReloadConfig => {
+            senders
+                .send_to_server(ServerInstruction::ReloadConfig)
+                .with_context(err_context)?;
+        },
+        Action::SwitchTheme(theme_name, target_client_id) => {
+            // the themes are kept by the server, which sends the chosen palette to the screen
+            senders
+                .send_to_server(ServerInstruction::SwitchTheme(
+                    theme_name,
+                    target_client_id,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
         Action::QueryTabNames => {
             senders
                 .send_to_screen(ScreenInstruction::QueryTabNames(client_id))
@@ -914,6 +1060,7 @@ pub(crate) fn route_thread_main(
     let mut retry_queue = VecDeque::new();
     let err_context = || format!("failed to handle instruction for client {client_id}");
     let mut seen_cli_pipes = HashSet::new();
+    let mut client_is_read_only = false;
     'route_loop: loop {
         match receiver.recv() {
             Some((instruction, err_ctx)) => {
@@ -927,6 +1074,26 @@ pub(crate) fn route_thread_main(
                     let mut should_break = false;
                     match instruction {
                         ClientToServerMsg::Action(action, maybe_pane_id, maybe_client_id) => {
+                            if client_is_read_only && !action.is_allowed_for_read_only_client() {
+                                log::debug!(
+                                    "Ignoring action of read-only client {}: {:?}",
+                                    client_id,
+                                    action
+                                );
+                                if let Action::SwitchToMode(_) = action {
+                                    // the client already switched on its own, so switch it back
+                                    let _ = os_input.send_to_client(
+                                        client_id,
+                                        ServerToClientMsg::SwitchToMode(InputMode::Normal),
+                                    );
+                                }
+                                return Ok(false);
+                            }
+                            // a read-only client leaving the session does not end it for the others
+                            let action = match action {
+                                Action::Quit if client_is_read_only => Action::Detach,
+                                action => action,
+                            };
                             let client_id = maybe_client_id.unwrap_or(client_id);
                             if let Some(rlocked_sessions) = rlocked_sessions.as_ref() {
                                 if let Action::SwitchToMode(input_mode) = action {
@@ -997,6 +1164,10 @@ pub(crate) fn route_thread_main(
                                 retry_queue
                             )
                             .with_context(err_context)?;
+                            let _ = to_server.send(ServerInstruction::TerminalBackgroundColor(
+                                background_color_instruction.clone(),
+                                client_id,
+                            ));
                         },
                         ClientToServerMsg::ForegroundColor(ref foreground_color_instruction) => {
                             send_to_screen_or_retry_queue!(
@@ -1018,6 +1189,15 @@ pub(crate) fn route_thread_main(
                             )
                             .with_context(err_context)?;
                         },
+                        ClientToServerMsg::TerminalSupportsKittyGraphics => {
+                            send_to_screen_or_retry_queue!(
+                                rlocked_sessions,
+                                ScreenInstruction::TerminalSupportsKittyGraphics(client_id),
+                                instruction,
+                                retry_queue
+                            )
+                            .with_context(err_context)?;
+                        },
                         ClientToServerMsg::NewClient(
                             client_attributes,
                             cli_args,
@@ -1042,12 +1222,17 @@ pub(crate) fn route_thread_main(
                             opts,
                             tab_position_to_focus,
                             pane_id_to_focus,
+                            is_read_only,
+                            client_to_follow,
                         ) => {
+                            client_is_read_only = is_read_only;
                             let attach_client_instruction = ServerInstruction::AttachClient(
                                 client_attributes,
                                 opts,
                                 tab_position_to_focus,
                                 pane_id_to_focus,
+                                is_read_only,
+                                client_to_follow,
                                 client_id,
                             );
                             to_server
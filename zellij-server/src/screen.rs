@@ -5,11 +5,11 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use log::{debug, warn};
 use zellij_utils::data::{
-    Direction, PaneManifest, PluginPermission, Resize, ResizeStrategy, SessionInfo,
+    Direction, KeyWithModifier, PaneManifest, PluginPermission, Resize, ResizeStrategy, SessionInfo,
 };
 use zellij_utils::errors::prelude::*;
 use zellij_utils::input::command::RunCommand;
@@ -18,10 +18,11 @@ use zellij_utils::pane_size::{Size, SizeInPixels};
 use zellij_utils::{
     consts::{session_info_folder_for_session, ZELLIJ_SOCK_DIR},
     envs::set_session_name,
+    input::actions::{ActionTarget, CopyModeMotion, CopyModeSelection, PipePaneTarget},
     input::command::TerminalAction,
     input::layout::{
-        FloatingPaneLayout, Layout, PluginUserConfiguration, Run, RunPlugin, RunPluginLocation,
-        SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
+        FloatingPaneLayout, Layout, PaneMonitors, PluginUserConfiguration, Run, RunPlugin,
+        RunPluginLocation, SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
     },
     position::Position,
 };
@@ -30,19 +31,21 @@ use crate::background_jobs::BackgroundJob;
 use crate::os_input_output::ResizeCache;
 use crate::panes::alacritty_functions::xparse_color;
 use crate::panes::terminal_character::AnsiCode;
+use crate::panes::{set_scrollback_memory_budget, take_scrollback_evictions};
 use crate::session_layout_metadata::{PaneLayoutMetadata, SessionLayoutMetadata};
+use crate::session_listing::{self, ListedClient, ListedPane, ListedTab};
 
 use crate::{
     output::Output,
     panes::sixel::SixelImageStore,
     panes::PaneId,
     plugins::{PluginInstruction, PluginRenderAsset},
-    pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
-    tab::Tab,
+    pty::{ClientTabIndexOrPaneId, PtyInstruction, TerminalProcessInfo, VteBytes},
+    tab::{Pane, PaneAction, Tab},
     thread_bus::Bus,
     ui::{
         loading_indication::LoadingIndication,
-        overlay::{Overlay, OverlayWindow},
+        overlay::{notification::Notification, Overlay, OverlayType, OverlayWindow, Overlayable},
     },
     ClientId, ServerInstruction,
 };
@@ -52,8 +55,8 @@ use zellij_utils::{
         PluginCapabilities, Style, TabInfo,
     },
     errors::{ContextType, ScreenContext},
-    input::{get_mode_info, options::Options},
-    ipc::{ClientAttributes, PixelDimensions, ServerToClientMsg},
+    input::{get_mode_info, keybinds::Keybinds, options::Options},
+    ipc::{ClientAttributes, ColorDepth, PixelDimensions, ServerToClientMsg},
 };
 
 /// Get the active tab and call a closure on it
@@ -159,6 +162,7 @@ pub enum ScreenInstruction {
     HorizontalSplit(PaneId, Option<InitialTitle>, HoldForCommand, ClientId),
     VerticalSplit(PaneId, Option<InitialTitle>, HoldForCommand, ClientId),
     WriteCharacter(Vec<u8>, ClientId),
+    WriteKey(KeyWithModifier, Vec<u8>, bool, ClientId), // bool -> is_kitty_keyboard_protocol
     Resize(ClientId, ResizeStrategy),
     SwitchFocus(ClientId),
     FocusNextPane(ClientId),
@@ -180,6 +184,7 @@ pub enum ScreenInstruction {
     DumpScreen(String, ClientId, bool),
     DumpLayout(Option<PathBuf>, ClientId), // PathBuf is the default configured
     // shell
+    PipePaneOutput(Option<PipePaneTarget>, bool, ClientId), // bool is strip_ansi
     EditScrollback(ClientId),
     ScrollUp(ClientId),
     ScrollUpAt(Position, ClientId),
@@ -187,6 +192,8 @@ pub enum ScreenInstruction {
     ScrollDownAt(Position, ClientId),
     ScrollToBottom(ClientId),
     ScrollToTop(ClientId),
+    ScrollToPreviousPrompt(ClientId),
+    ScrollToNextPrompt(ClientId),
     PageScrollUp(ClientId),
     PageScrollDown(ClientId),
     HalfPageScrollUp(ClientId),
@@ -204,6 +211,7 @@ pub enum ScreenInstruction {
         Option<usize>,
         Option<ClientId>,
     ), // Option<i32> is the exit status, Option<usize> is the tab_index
+    CommandPaneExited(PaneId, Option<i32>, RunCommand), // the pane is closed rather than held
     UpdatePaneName(Vec<u8>, ClientId),
     UndoRenamePane(ClientId),
     NewTab(
@@ -246,6 +254,9 @@ pub enum ScreenInstruction {
     TerminalBackgroundColor(String),
     TerminalForegroundColor(String),
     TerminalColorRegisters(Vec<(usize, String)>),
+    TerminalSupportsKittyGraphics(ClientId),
+    ClientColorDepth(ClientId, ColorDepth),
+    CheckPaneMonitors,
     ChangeMode(ModeInfo, ClientId),
     ChangeModeForAllClients(ModeInfo),
     LeftClick(Position, ClientId),
@@ -264,6 +275,7 @@ pub enum ScreenInstruction {
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane_id to focus
     ),
     RemoveClient(ClientId),
+    FollowClient(ClientId, ClientId), // the first client follows the focus of the second
     AddOverlay(Overlay, ClientId),
     RemoveOverlay(ClientId),
     ConfirmPrompt(ClientId),
@@ -274,6 +286,12 @@ pub enum ScreenInstruction {
     SearchToggleCaseSensitivity(ClientId),
     SearchToggleWholeWord(ClientId),
     SearchToggleWrap(ClientId),
+    SearchToggleRegex(ClientId),
+    CopyModeMotion(CopyModeMotion, ClientId),
+    CopyModeToggleSelection(CopyModeSelection, ClientId),
+    CopyModeYank(ClientId),
+    SelectLastCommandOutput(ClientId),
+    CopyLastCommandOutput(ClientId),
     AddRedPaneFrameColorOverride(Vec<PaneId>, Option<String>), // Option<String> => optional error text
     ClearPaneFrameColorOverride(Vec<PaneId>),
     PreviousSwapLayout(ClientId),
@@ -334,6 +352,7 @@ pub enum ScreenInstruction {
         BTreeMap<String, SessionInfo>, // String is the session name
         BTreeMap<String, Duration>,    // resurrectable sessions - <name, created>
     ),
+    UpdateTerminalProcessInfo(HashMap<u32, TerminalProcessInfo>), // u32 is the terminal id
     ReplacePane(
         PaneId,
         HoldForCommand,
@@ -343,6 +362,28 @@ pub enum ScreenInstruction {
     ),
     DumpLayoutToHd,
     RenameSession(String, ClientId), // String -> new name
+    GetPaneContents(
+        PaneId,
+        bool, // include scrollback
+        bool, // with ansi
+        u32,  // plugin id
+        ClientId,
+    ),
+    WatchPane(PaneId, u32),   // u32 is the plugin id
+    UnwatchPane(PaneId, u32), // u32 is the plugin id
+    UnwatchPanesOfPlugin(u32),
+    ActOnTarget(ActionTarget, PaneAction, ClientId),
+    ListTabs(bool, ClientId),  // bool is json
+    ListPanes(bool, ClientId), // bool is json
+    ListClients(
+        bool,                            // json
+        HashMap<ClientId, Option<Size>>, // the sizes of the clients, if they reported them
+        ClientId,
+    ),
+    Reconfigure(Keybinds, Style, Box<Options>),
+    ShowConfigError(String),
+    SwitchTheme(Palette, Option<ClientId>), // None => all clients
+    KittyGraphicsFileRead(u32, u32, Result<Vec<u8>, String>), // pid, file read id, data
 }
 
 impl From<&ScreenInstruction> for ScreenContext {
@@ -360,6 +401,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::HorizontalSplit(..) => ScreenContext::HorizontalSplit,
             ScreenInstruction::VerticalSplit(..) => ScreenContext::VerticalSplit,
             ScreenInstruction::WriteCharacter(..) => ScreenContext::WriteCharacter,
+            ScreenInstruction::WriteKey(..) => ScreenContext::WriteKey,
             ScreenInstruction::Resize(.., strategy) => match strategy {
                 ResizeStrategy {
                     resize: Resize::Increase,
@@ -406,12 +448,15 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::Exit => ScreenContext::Exit,
             ScreenInstruction::ClearScreen(..) => ScreenContext::ClearScreen,
             ScreenInstruction::DumpScreen(..) => ScreenContext::DumpScreen,
+            ScreenInstruction::PipePaneOutput(..) => ScreenContext::PipePaneOutput,
             ScreenInstruction::DumpLayout(..) => ScreenContext::DumpLayout,
             ScreenInstruction::EditScrollback(..) => ScreenContext::EditScrollback,
             ScreenInstruction::ScrollUp(..) => ScreenContext::ScrollUp,
             ScreenInstruction::ScrollDown(..) => ScreenContext::ScrollDown,
             ScreenInstruction::ScrollToBottom(..) => ScreenContext::ScrollToBottom,
             ScreenInstruction::ScrollToTop(..) => ScreenContext::ScrollToTop,
+            ScreenInstruction::ScrollToPreviousPrompt(..) => ScreenContext::ScrollToPreviousPrompt,
+            ScreenInstruction::ScrollToNextPrompt(..) => ScreenContext::ScrollToNextPrompt,
             ScreenInstruction::PageScrollUp(..) => ScreenContext::PageScrollUp,
             ScreenInstruction::PageScrollDown(..) => ScreenContext::PageScrollDown,
             ScreenInstruction::HalfPageScrollUp(..) => ScreenContext::HalfPageScrollUp,
@@ -425,6 +470,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::SetSelectable(..) => ScreenContext::SetSelectable,
             ScreenInstruction::ClosePane(..) => ScreenContext::ClosePane,
             ScreenInstruction::HoldPane(..) => ScreenContext::HoldPane,
+            ScreenInstruction::CommandPaneExited(..) => ScreenContext::CommandPaneExited,
             ScreenInstruction::UpdatePaneName(..) => ScreenContext::UpdatePaneName,
             ScreenInstruction::UndoRenamePane(..) => ScreenContext::UndoRenamePane,
             ScreenInstruction::NewTab(..) => ScreenContext::NewTab,
@@ -449,6 +495,11 @@ impl From<&ScreenInstruction> for ScreenContext {
                 ScreenContext::TerminalForegroundColor
             },
             ScreenInstruction::TerminalColorRegisters(..) => ScreenContext::TerminalColorRegisters,
+            ScreenInstruction::TerminalSupportsKittyGraphics(..) => {
+                ScreenContext::TerminalSupportsKittyGraphics
+            },
+            ScreenInstruction::ClientColorDepth(..) => ScreenContext::ClientColorDepth,
+            ScreenInstruction::CheckPaneMonitors => ScreenContext::CheckPaneMonitors,
             ScreenInstruction::ChangeMode(..) => ScreenContext::ChangeMode,
             ScreenInstruction::ChangeModeForAllClients(..) => {
                 ScreenContext::ChangeModeForAllClients
@@ -468,6 +519,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::Copy(..) => ScreenContext::Copy,
             ScreenInstruction::ToggleTab(..) => ScreenContext::ToggleTab,
             ScreenInstruction::AddClient(..) => ScreenContext::AddClient,
+            ScreenInstruction::FollowClient(..) => ScreenContext::FollowClient,
             ScreenInstruction::RemoveClient(..) => ScreenContext::RemoveClient,
             ScreenInstruction::AddOverlay(..) => ScreenContext::AddOverlay,
             ScreenInstruction::RemoveOverlay(..) => ScreenContext::RemoveOverlay,
@@ -481,6 +533,16 @@ impl From<&ScreenInstruction> for ScreenContext {
             },
             ScreenInstruction::SearchToggleWholeWord(..) => ScreenContext::SearchToggleWholeWord,
             ScreenInstruction::SearchToggleWrap(..) => ScreenContext::SearchToggleWrap,
+            ScreenInstruction::SearchToggleRegex(..) => ScreenContext::SearchToggleRegex,
+            ScreenInstruction::CopyModeMotion(..) => ScreenContext::CopyModeMotion,
+            ScreenInstruction::CopyModeToggleSelection(..) => {
+                ScreenContext::CopyModeToggleSelection
+            },
+            ScreenInstruction::CopyModeYank(..) => ScreenContext::CopyModeYank,
+            ScreenInstruction::SelectLastCommandOutput(..) => {
+                ScreenContext::SelectLastCommandOutput
+            },
+            ScreenInstruction::CopyLastCommandOutput(..) => ScreenContext::CopyLastCommandOutput,
             ScreenInstruction::AddRedPaneFrameColorOverride(..) => {
                 ScreenContext::AddRedPaneFrameColorOverride
             },
@@ -521,10 +583,25 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::BreakPaneRight(..) => ScreenContext::BreakPaneRight,
             ScreenInstruction::BreakPaneLeft(..) => ScreenContext::BreakPaneLeft,
             ScreenInstruction::UpdateSessionInfos(..) => ScreenContext::UpdateSessionInfos,
+            ScreenInstruction::UpdateTerminalProcessInfo(..) => {
+                ScreenContext::UpdateTerminalProcessInfo
+            },
             ScreenInstruction::ReplacePane(..) => ScreenContext::ReplacePane,
             ScreenInstruction::NewInPlacePluginPane(..) => ScreenContext::NewInPlacePluginPane,
             ScreenInstruction::DumpLayoutToHd => ScreenContext::DumpLayoutToHd,
             ScreenInstruction::RenameSession(..) => ScreenContext::RenameSession,
+            ScreenInstruction::GetPaneContents(..) => ScreenContext::GetPaneContents,
+            ScreenInstruction::WatchPane(..) => ScreenContext::WatchPane,
+            ScreenInstruction::UnwatchPane(..) => ScreenContext::UnwatchPane,
+            ScreenInstruction::UnwatchPanesOfPlugin(..) => ScreenContext::UnwatchPanesOfPlugin,
+            ScreenInstruction::ActOnTarget(..) => ScreenContext::ActOnTarget,
+            ScreenInstruction::ListTabs(..) => ScreenContext::ListTabs,
+            ScreenInstruction::ListPanes(..) => ScreenContext::ListPanes,
+            ScreenInstruction::ListClients(..) => ScreenContext::ListClients,
+            ScreenInstruction::Reconfigure(..) => ScreenContext::Reconfigure,
+            ScreenInstruction::ShowConfigError(..) => ScreenContext::ShowConfigError,
+            ScreenInstruction::SwitchTheme(..) => ScreenContext::SwitchTheme,
+            ScreenInstruction::KittyGraphicsFileRead(..) => ScreenContext::KittyGraphicsFileRead,
         }
     }
 }
@@ -573,6 +650,12 @@ pub(crate) struct Screen {
     pixel_dimensions: PixelDimensions,
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     sixel_image_store: Rc<RefCell<SixelImageStore>>,
+    /// The clients whose terminal supports the kitty graphics protocol, which are sent kitty
+    /// images as they are rather than in sixel form
+    kitty_graphics_clients: HashSet<ClientId>,
+    /// How many colours the terminal of each client can display, clients that are not here are
+    /// rendered to in truecolor
+    client_color_depths: HashMap<ClientId, ColorDepth>,
     /// The overlay that is drawn on top of [`Pane`]'s', [`Tab`]'s and the [`Screen`]
     overlay: OverlayWindow,
     terminal_emulator_colors: Rc<RefCell<Palette>>,
@@ -589,6 +672,7 @@ pub(crate) struct Screen {
     session_serialization: bool,
     serialize_pane_viewport: bool,
     scrollback_lines_to_serialize: Option<usize>,
+    serialize_pane_scrollback: bool,
     session_is_mirrored: bool,
     copy_options: CopyOptions,
     debug: bool,
@@ -602,7 +686,13 @@ pub(crate) struct Screen {
     styled_underlines: bool,
     arrow_fonts: bool,
     layout_dir: Option<PathBuf>,
+    default_pane_monitors: PaneMonitors, // the monitors of panes the layout did not set any for
+    panes_monitored_for_silence: bool,   // only then are the monitors checked when nothing happens
     default_layout_name: Option<String>,
+    terminal_process_info: HashMap<u32, TerminalProcessInfo>, // u32 is the terminal id
+    pane_output_watchers: HashMap<PaneId, HashSet<u32>>,      // u32 is the plugin id
+    reported_pane_ids: HashSet<PaneId>, // the panes plugins were last told about
+    following_clients: BTreeMap<ClientId, ClientId>, // read-only client => the client it follows
 }
 
 impl Screen {
@@ -623,9 +713,11 @@ impl Screen {
         session_serialization: bool,
         serialize_pane_viewport: bool,
         scrollback_lines_to_serialize: Option<usize>,
+        serialize_pane_scrollback: bool,
         styled_underlines: bool,
         arrow_fonts: bool,
         layout_dir: Option<PathBuf>,
+        default_pane_monitors: PaneMonitors,
     ) -> Self {
         let session_name = mode_info.session_name.clone().unwrap_or_default();
         let session_info = SessionInfo::new(session_name.clone());
@@ -639,6 +731,8 @@ impl Screen {
             pixel_dimensions: Default::default(),
             character_cell_size: Rc::new(RefCell::new(None)),
             sixel_image_store: Rc::new(RefCell::new(SixelImageStore::default())),
+            kitty_graphics_clients: HashSet::new(),
+            client_color_depths: HashMap::new(),
             style: client_attributes.style,
             connected_clients: Rc::new(RefCell::new(HashSet::new())),
             active_tab_indices: BTreeMap::new(),
@@ -662,10 +756,17 @@ impl Screen {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_pane_scrollback,
             styled_underlines,
             arrow_fonts,
             resurrectable_sessions,
             layout_dir,
+            default_pane_monitors,
+            panes_monitored_for_silence: false,
+            terminal_process_info: HashMap::new(),
+            pane_output_watchers: HashMap::new(),
+            reported_pane_ids: HashSet::new(),
+            following_clients: BTreeMap::new(),
         }
     }
 
@@ -1071,10 +1172,18 @@ impl Screen {
     pub fn render(&mut self, plugin_render_assets: Option<Vec<PluginRenderAsset>>) -> Result<()> {
         let err_context = "failed to render screen";
 
+        if !self.following_clients.is_empty() {
+            self.update_following_clients().context(err_context)?;
+        }
+        // before rendering, so that the frames show the alerts as they are now
+        self.update_pane_alerts().context(err_context)?;
+
         let mut output = Output::new(
             self.sixel_image_store.clone(),
             self.character_cell_size.clone(),
             self.styled_underlines,
+            self.kitty_graphics_clients.clone(),
+            self.client_color_depths.clone(),
         );
         let mut tabs_to_close = vec![];
         for (tab_index, tab) in &mut self.tabs {
@@ -1087,6 +1196,17 @@ impl Screen {
         for tab_index in tabs_to_close {
             self.close_tab_at_index(tab_index).context(err_context)?;
         }
+        if output.is_dirty() && !self.overlay.overlay_stack.is_empty() {
+            // overlays are drawn over whatever the tabs rendered, so that they are not overwritten
+            let overlays = self
+                .overlay
+                .generate_overlay(self.size)
+                .context(err_context)?;
+            output.add_post_vte_instruction_to_multiple_clients(
+                self.connected_clients.borrow().iter().copied(),
+                &overlays,
+            );
+        }
         if output.is_dirty() {
             let serialized_output = output.serialize().context(err_context)?;
             let _ = self
@@ -1353,12 +1473,41 @@ impl Screen {
             self.tab_history.remove(&client_id);
         }
         self.connected_clients.borrow_mut().remove(&client_id);
+        self.following_clients.remove(&client_id);
+        self.following_clients
+            .retain(|_, followed_client_id| *followed_client_id != client_id);
+        self.kitty_graphics_clients.remove(&client_id);
+        self.client_color_depths.remove(&client_id);
+        self.sixel_image_store.borrow_mut().remove_client(client_id);
         self.log_and_report_session_state()
             .with_context(err_context)
     }
+    /// Moves the clients following another client's focus to the tab and pane it is focused on
+    fn update_following_clients(&mut self) -> Result<()> {
+        let err_context = || format!("failed to update the focus of following clients");
+        for (client_id, followed_client_id) in self.following_clients.clone() {
+            let followed_tab_index = match self.active_tab_indices.get(&followed_client_id) {
+                Some(tab_index) => *tab_index,
+                None => continue, // the followed client is not (or no longer) attached
+            };
+            if self.active_tab_indices.get(&client_id) != Some(&followed_tab_index) {
+                if let Some(tab_position) =
+                    self.tabs.get(&followed_tab_index).map(|tab| tab.position)
+                {
+                    self.switch_active_tab(tab_position, None, true, client_id)
+                        .with_context(err_context)?;
+                }
+            }
+            if let Some(tab) = self.tabs.get_mut(&followed_tab_index) {
+                if tab.follow_client_focus(followed_client_id, client_id) {
+                    tab.set_force_render();
+                }
+            }
+        }
+        Ok(())
+    }
 
-    pub fn generate_and_report_tab_state(&mut self) -> Result<Vec<TabInfo>> {
-        let mut plugin_updates = vec![];
+    fn tab_infos(&self) -> Vec<TabInfo> {
         let mut tab_infos_for_screen_state = BTreeMap::new();
         for tab in self.tabs.values() {
             let all_focused_clients: Vec<ClientId> = self
@@ -1369,6 +1518,7 @@ impl Screen {
                 .copied()
                 .collect();
             let (active_swap_layout_name, is_swap_layout_dirty) = tab.swap_layout_info();
+            let alerts = tab.alerts();
             let tab_info_for_screen = TabInfo {
                 position: tab.position,
                 name: tab.name.clone(),
@@ -1380,9 +1530,16 @@ impl Screen {
                 other_focused_clients: all_focused_clients,
                 active_swap_layout_name,
                 is_swap_layout_dirty,
+                bell_alert: alerts.bell,
+                activity_alert: alerts.activity,
+                silence_alert: alerts.silence,
             };
             tab_infos_for_screen_state.insert(tab.position, tab_info_for_screen);
         }
+        tab_infos_for_screen_state.values().cloned().collect()
+    }
+    pub fn generate_and_report_tab_state(&mut self) -> Result<Vec<TabInfo>> {
+        let mut plugin_updates = vec![];
         for (client_id, active_tab_index) in self.active_tab_indices.iter() {
             let mut plugin_tab_updates = vec![];
             for tab in self.tabs.values() {
@@ -1399,6 +1556,7 @@ impl Screen {
                         .collect()
                 };
                 let (active_swap_layout_name, is_swap_layout_dirty) = tab.swap_layout_info();
+                let alerts = tab.alerts();
                 let tab_info_for_plugins = TabInfo {
                     position: tab.position,
                     name: tab.name.clone(),
@@ -1410,6 +1568,9 @@ impl Screen {
                     other_focused_clients,
                     active_swap_layout_name,
                     is_swap_layout_dirty,
+                    bell_alert: alerts.bell,
+                    activity_alert: alerts.activity,
+                    silence_alert: alerts.silence,
                 };
                 plugin_tab_updates.push(tab_info_for_plugins);
             }
@@ -1419,24 +1580,446 @@ impl Screen {
             .senders
             .send_to_plugin(PluginInstruction::Update(plugin_updates))
             .context("failed to update tabs")?;
-        Ok(tab_infos_for_screen_state.values().cloned().collect())
+        Ok(self.tab_infos())
     }
-    fn generate_and_report_pane_state(&mut self) -> Result<PaneManifest> {
+    fn pane_manifest(&self) -> PaneManifest {
         let mut pane_manifest = PaneManifest::default();
         for tab in self.tabs.values() {
-            pane_manifest.panes.insert(tab.position, tab.pane_infos());
+            let mut pane_infos = tab.pane_infos();
+            for pane_info in pane_infos.iter_mut().filter(|p| !p.is_plugin) {
+                if let Some(terminal_process_info) = self.terminal_process_info.get(&pane_info.id) {
+                    pane_info.pid = Some(terminal_process_info.pid);
+                    pane_info.cwd = terminal_process_info.cwd.clone();
+                    pane_info.foreground_process = terminal_process_info.foreground_process.clone();
+                }
+            }
+            pane_manifest.panes.insert(tab.position, pane_infos);
+        }
+        pane_manifest
+    }
+    fn generate_and_report_pane_state(&mut self) -> Result<PaneManifest> {
+        let pane_manifest = self.pane_manifest();
+        let mut updates = vec![(None, None, Event::PaneUpdate(pane_manifest.clone()))];
+        // we diff the panes here rather than where they are opened or closed so that plugins
+        // are told about every pane regardless of how it came and went
+        let pane_ids: HashSet<PaneId> = pane_manifest
+            .panes
+            .values()
+            .flatten()
+            .map(|pane_info| {
+                if pane_info.is_plugin {
+                    PaneId::Plugin(pane_info.id)
+                } else {
+                    PaneId::Terminal(pane_info.id)
+                }
+            })
+            .collect();
+        for opened_pane_id in pane_ids.difference(&self.reported_pane_ids) {
+            updates.push((None, None, Event::PaneOpened((*opened_pane_id).into())));
+        }
+        for closed_pane_id in self.reported_pane_ids.difference(&pane_ids) {
+            updates.push((None, None, Event::PaneClosed((*closed_pane_id).into())));
+            self.pane_output_watchers.remove(closed_pane_id);
         }
+        self.reported_pane_ids = pane_ids;
         self.bus
             .senders
-            .send_to_plugin(PluginInstruction::Update(vec![(
-                None,
-                None,
-                Event::PaneUpdate(pane_manifest.clone()),
-            )]))
+            .send_to_plugin(PluginInstruction::Update(updates))
             .context("failed to update tabs")?;
 
         Ok(pane_manifest)
     }
+    fn report_command_pane_exited(
+        &self,
+        pane_id: PaneId,
+        exit_status: Option<i32>,
+        run_command: &RunCommand,
+    ) -> Result<()> {
+        // the context is only sent back to the plugin that provided it
+        let update = match &run_command.originating_plugin {
+            Some(originating_plugin) => (
+                Some(originating_plugin.plugin_id),
+                Some(originating_plugin.client_id),
+                Event::CommandPaneExited(
+                    pane_id.into(),
+                    exit_status,
+                    originating_plugin.context.clone(),
+                ),
+            ),
+            None => (
+                None,
+                None,
+                Event::CommandPaneExited(pane_id.into(), exit_status, BTreeMap::new()),
+            ),
+        };
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::Update(vec![update]))
+            .with_context(|| format!("failed to report exit of command pane {:?}", pane_id))
+    }
+    pub fn watch_pane(&mut self, pane_id: PaneId, plugin_id: u32) {
+        match self
+            .tabs
+            .values_mut()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+        {
+            Some(tab) => {
+                tab.watch_pane_output(pane_id);
+                self.pane_output_watchers
+                    .entry(pane_id)
+                    .or_default()
+                    .insert(plugin_id);
+            },
+            None => {
+                log::error!("Could not find pane {:?} to watch", pane_id);
+            },
+        }
+    }
+    pub fn unwatch_pane(&mut self, pane_id: PaneId, plugin_id: u32) {
+        let is_still_watched = match self.pane_output_watchers.get_mut(&pane_id) {
+            Some(plugin_ids) => {
+                plugin_ids.remove(&plugin_id);
+                !plugin_ids.is_empty()
+            },
+            None => return,
+        };
+        if !is_still_watched {
+            self.pane_output_watchers.remove(&pane_id);
+            if let Some(tab) = self
+                .tabs
+                .values_mut()
+                .find(|tab| tab.has_pane_with_pid(&pane_id))
+            {
+                tab.unwatch_pane_output(pane_id);
+            }
+        }
+    }
+    /// So that a plugin that is no longer running is not sent the output of the panes it watched
+    pub fn unwatch_panes_of_plugin(&mut self, plugin_id: u32) {
+        let watched_pane_ids: Vec<PaneId> = self.pane_output_watchers.keys().copied().collect();
+        for pane_id in watched_pane_ids {
+            self.unwatch_pane(pane_id, plugin_id);
+        }
+    }
+    /// Perform the action on the target pane (or on the focused pane of the target tab) rather
+    /// than on the focused pane of the client, without changing focus
+    pub fn act_on_target(
+        &mut self,
+        target: ActionTarget,
+        pane_action: PaneAction,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to act on {target:?}");
+
+        let (tab, pane_id) = match &target {
+            ActionTarget::Pane(pane_id) => {
+                let pane_id = PaneId::from(*pane_id);
+                let tab = self
+                    .tabs
+                    .values_mut()
+                    .find(|tab| tab.has_pane_with_pid(&pane_id));
+                (tab, Some(pane_id))
+            },
+            ActionTarget::TabIndex(tab_index) => {
+                let tab = self
+                    .tabs
+                    .values_mut()
+                    .find(|tab| tab.position + 1 == *tab_index);
+                let pane_id = tab.as_ref().and_then(|tab| tab.target_pane_id());
+                (tab, pane_id)
+            },
+            ActionTarget::TabName(tab_name) => {
+                let tab = self.tabs.values_mut().find(|tab| tab.name == *tab_name);
+                let pane_id = tab.as_ref().and_then(|tab| tab.target_pane_id());
+                (tab, pane_id)
+            },
+        };
+        match (tab, pane_id) {
+            (Some(tab), Some(pane_id)) => tab
+                .act_on_pane(pane_id, pane_action, client_id)
+                .with_context(err_context),
+            _ => Err(anyhow!("could not find target")).with_context(err_context),
+        }
+    }
+    pub fn list_tabs(&self, json: bool) -> Result<Vec<String>> {
+        let tabs: Vec<ListedTab> = self
+            .tab_infos()
+            .into_iter()
+            .map(|info| ListedTab {
+                index: info.position + 1,
+                info,
+            })
+            .collect();
+        session_listing::list_tabs(&tabs, json)
+    }
+    pub fn list_panes(&self, json: bool) -> Result<Vec<String>> {
+        let mut pane_manifest = self.pane_manifest();
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        let mut panes = vec![];
+        for tab in tabs {
+            for info in pane_manifest
+                .panes
+                .remove(&tab.position)
+                .unwrap_or_default()
+            {
+                let pane_id = if info.is_plugin {
+                    zellij_utils::data::PaneId::Plugin(info.id)
+                } else {
+                    zellij_utils::data::PaneId::Terminal(info.id)
+                };
+                panes.push(ListedPane {
+                    pane_id: pane_id.to_string(),
+                    tab_index: tab.position + 1,
+                    tab_name: tab.name.clone(),
+                    info,
+                });
+            }
+        }
+        session_listing::list_panes(&panes, json)
+    }
+    pub fn list_clients(
+        &self,
+        json: bool,
+        client_sizes: &HashMap<ClientId, Option<Size>>,
+    ) -> Result<Vec<String>> {
+        let clients: Vec<ListedClient> = self
+            .active_tab_indices
+            .iter()
+            .map(|(client_id, tab_index)| {
+                let tab = self.tabs.get(tab_index);
+                ListedClient {
+                    client_id: *client_id,
+                    tab_index: tab.map(|tab| tab.position + 1),
+                    tab_name: tab.map(|tab| tab.name.clone()),
+                    focused_pane_id: tab
+                        .and_then(|tab| tab.get_active_pane_id(*client_id))
+                        .map(|pane_id| zellij_utils::data::PaneId::from(pane_id).to_string()),
+                    size: client_sizes.get(client_id).copied().flatten(),
+                    mode: self
+                        .mode_info
+                        .get(client_id)
+                        .unwrap_or(&self.default_mode_info)
+                        .mode,
+                    is_read_only: self
+                        .mode_info
+                        .get(client_id)
+                        .map(|mode_info| mode_info.is_read_only)
+                        .unwrap_or(false),
+                }
+            })
+            .collect();
+        session_listing::list_clients(&clients, json)
+    }
+    fn log_listing(&self, listing: Result<Vec<String>>, client_id: ClientId) -> Result<()> {
+        let instruction = match listing {
+            Ok(lines) => ServerInstruction::Log(lines, client_id),
+            Err(e) => ServerInstruction::LogError(vec![format!("{:?}", e)], client_id),
+        };
+        self.bus
+            .senders
+            .send_to_server(instruction)
+            .context("failed to send listing to client")
+    }
+    pub fn reconfigure(
+        &mut self,
+        keybinds: Keybinds,
+        style: Style,
+        config_options: Options,
+    ) -> Result<()> {
+        let err_context = || format!("failed to reconfigure screen");
+        let keybinds = keybinds.to_keybinds_vec();
+        self.style = style;
+        self.draw_pane_frames = config_options.pane_frames.unwrap_or(true);
+        self.auto_layout = config_options.auto_layout.unwrap_or(true);
+        set_scrollback_memory_budget(
+            config_options
+                .scrollback_memory_budget
+                .map(|megabytes| megabytes.saturating_mul(1024 * 1024)),
+        );
+        self.drop_lines_evicted_from_scrollbacks();
+        self.default_pane_monitors = PaneMonitors {
+            bell: config_options.monitor_bell,
+            activity: config_options.monitor_activity,
+            silence: config_options.monitor_silence,
+        };
+        self.copy_options = CopyOptions::new(
+            config_options.copy_command,
+            config_options.copy_clipboard.unwrap_or_default(),
+            config_options.copy_on_select.unwrap_or(true),
+        );
+        self.default_mode_info.keybinds = keybinds.clone();
+        self.default_mode_info.style = style;
+        for mode_info in self.mode_info.values_mut() {
+            mode_info.keybinds = keybinds.clone();
+            mode_info.style = style;
+        }
+        for tab in self.tabs.values_mut() {
+            tab.reconfigure(
+                keybinds.clone(),
+                style,
+                self.draw_pane_frames,
+                self.auto_layout,
+                &self.copy_options,
+            )
+            .with_context(err_context)?;
+        }
+        // the config is valid now, so any error reported while loading it is stale
+        self.remove_notifications();
+        self.render(None).with_context(err_context)?;
+        self.log_and_report_session_state()
+            .with_context(err_context)
+    }
+    pub fn switch_theme(
+        &mut self,
+        palette: Palette,
+        target_client_id: Option<ClientId>,
+    ) -> Result<()> {
+        let err_context = || format!("failed to switch theme");
+        match target_client_id {
+            Some(client_id) => {
+                let mut mode_info = self
+                    .mode_info
+                    .get(&client_id)
+                    .unwrap_or(&self.default_mode_info)
+                    .clone();
+                mode_info.style.colors = palette;
+                self.mode_info.insert(client_id, mode_info.clone());
+                for tab in self.tabs.values_mut() {
+                    tab.change_mode_info(mode_info.clone(), client_id);
+                    tab.set_force_render();
+                    tab.update_input_modes().with_context(err_context)?;
+                }
+            },
+            None => {
+                let mut style = self.style;
+                style.colors = palette;
+                self.style = style;
+                self.default_mode_info.style = style;
+                for mode_info in self.mode_info.values_mut() {
+                    mode_info.style = style;
+                }
+                for tab in self.tabs.values_mut() {
+                    tab.update_style(style);
+                    tab.update_input_modes().with_context(err_context)?;
+                }
+            },
+        }
+        self.render(None).with_context(err_context)
+    }
+    pub fn show_config_error(&mut self, error: String) -> Result<()> {
+        self.remove_notifications();
+        self.overlay
+            .overlay_stack
+            .push(Overlay::new(OverlayType::Notification(Notification::new(
+                format!("{}\n(press any key to dismiss)", error),
+            ))));
+        for tab in self.tabs.values_mut() {
+            tab.set_force_render();
+        }
+        self.render(None)
+            .with_context(|| format!("failed to show config error"))
+    }
+    /// Removes the notifications shown to the user (eg. a config error), returns true if there
+    /// were any
+    pub fn dismiss_notifications(&mut self) -> Result<bool> {
+        let had_notifications = self.remove_notifications();
+        if had_notifications {
+            self.render(None)
+                .with_context(|| format!("failed to dismiss notifications"))?;
+        }
+        Ok(had_notifications)
+    }
+    fn remove_notifications(&mut self) -> bool {
+        let overlay_count = self.overlay.overlay_stack.len();
+        self.overlay
+            .overlay_stack
+            .retain(|overlay| !matches!(overlay.overlay_type, OverlayType::Notification(_)));
+        let removed_notifications = self.overlay.overlay_stack.len() != overlay_count;
+        if removed_notifications {
+            // make sure whatever the notification was drawn over is drawn again
+            for tab in self.tabs.values_mut() {
+                tab.set_force_render();
+            }
+        }
+        removed_notifications
+    }
+    fn report_pane_output(&self, pane_id: PaneId, lines: Vec<String>) -> Result<()> {
+        if let Some(plugin_ids) = self.pane_output_watchers.get(&pane_id) {
+            let updates = plugin_ids
+                .iter()
+                .map(|plugin_id| {
+                    (
+                        Some(*plugin_id),
+                        None,
+                        Event::PaneOutput(pane_id.into(), lines.clone()),
+                    )
+                })
+                .collect();
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(updates))
+                .with_context(|| format!("failed to report output of pane {:?}", pane_id))?;
+        }
+        Ok(())
+    }
+    /// Raises the alerts of the pane monitors that were triggered, telling plugins about them,
+    /// and clears the alerts of the panes focused by any client. Also lets the background jobs
+    /// know whether any pane is monitored for silence. Returns whether any alert changed.
+    fn update_pane_alerts(&mut self) -> Result<bool> {
+        let focused_pane_ids: HashSet<PaneId> = self
+            .active_tab_indices
+            .iter()
+            .filter_map(|(client_id, tab_index)| {
+                self.tabs
+                    .get(tab_index)
+                    .and_then(|tab| tab.get_active_pane_id(*client_id))
+            })
+            .collect();
+        let now = Instant::now();
+        let mut updates = vec![];
+        let mut alerts_changed = false;
+        for tab in self.tabs.values_mut() {
+            let (new_alerts, tab_alerts_changed) =
+                tab.update_pane_alerts(self.default_pane_monitors, &focused_pane_ids, now);
+            alerts_changed |= tab_alerts_changed;
+            for (pane_id, new_pane_alerts) in new_alerts {
+                if new_pane_alerts.bell {
+                    updates.push((None, None, Event::PaneBell(pane_id.into())));
+                }
+                if new_pane_alerts.activity {
+                    updates.push((None, None, Event::PaneActivity(pane_id.into())));
+                }
+                if new_pane_alerts.silence {
+                    updates.push((None, None, Event::PaneSilence(pane_id.into())));
+                }
+            }
+        }
+        if !updates.is_empty() {
+            self.bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(updates))
+                .context("failed to report pane alerts")?;
+        }
+        if alerts_changed {
+            self.log_and_report_session_state()
+                .context("failed to report pane alerts")?;
+        }
+        let panes_monitored_for_silence = self
+            .tabs
+            .values()
+            .any(|tab| tab.is_monitoring_silence(self.default_pane_monitors));
+        if panes_monitored_for_silence != self.panes_monitored_for_silence {
+            self.panes_monitored_for_silence = panes_monitored_for_silence;
+            self.bus
+                .senders
+                .send_to_background_jobs(BackgroundJob::ReportPanesMonitoredForSilence(
+                    panes_monitored_for_silence,
+                ))
+                .context("failed to report pane alerts")?;
+        }
+        Ok(alerts_changed)
+    }
     fn log_and_report_session_state(&mut self) -> Result<()> {
         let err_context = || format!("Failed to log and report session state");
         // generate own session info
@@ -1472,6 +2055,15 @@ impl Screen {
             .with_context(err_context)?;
         Ok(())
     }
+    /// The oldest lines of any pane's scrollback can be dropped when another pane's output takes
+    /// the session over its scrollback memory budget, so they're all told about it right away
+    fn drop_lines_evicted_from_scrollbacks(&mut self) {
+        if take_scrollback_evictions() {
+            for tab in self.tabs.values_mut() {
+                tab.drop_lines_evicted_from_scrollback();
+            }
+        }
+    }
     fn dump_layout_to_hd(&mut self) -> Result<()> {
         let err_context = || format!("Failed to log and report session state");
         let session_layout_metadata = self.get_layout_metadata(self.default_shell.clone());
@@ -1678,15 +2270,34 @@ impl Screen {
             )
         };
 
+        // Copy mode keeps its cursor and selection while searching from within it, so we only
+        // start it when first entering Copy mode and end it when leaving these modes altogether
+        let copy_related_modes = [InputMode::Copy, InputMode::EnterSearch, InputMode::Search];
+        if mode_info.mode == InputMode::Copy && previous_mode != InputMode::Copy {
+            active_tab!(self, client_id, |tab: &mut Tab| tab
+                .start_copy_mode(client_id));
+        } else if copy_related_modes.contains(&previous_mode)
+            && !copy_related_modes.contains(&mode_info.mode)
+        {
+            active_tab!(self, client_id, |tab: &mut Tab| tab
+                .exit_copy_mode(client_id)
+                .with_context(err_context), ?);
+        }
+
         // If we leave the Search-related modes, we need to clear all previous searches
-        let search_related_modes = [InputMode::EnterSearch, InputMode::Search, InputMode::Scroll];
+        let search_related_modes = [
+            InputMode::EnterSearch,
+            InputMode::Search,
+            InputMode::Scroll,
+            InputMode::Copy,
+        ];
         if search_related_modes.contains(&previous_mode)
             && !search_related_modes.contains(&mode_info.mode)
         {
             active_tab!(self, client_id, |tab: &mut Tab| tab.clear_search(client_id));
         }
 
-        if previous_mode == InputMode::Scroll
+        if (previous_mode == InputMode::Scroll || previous_mode == InputMode::Copy)
             && (mode_info.mode == InputMode::Normal || mode_info.mode == InputMode::Locked)
         {
             if let Ok(active_tab) = self.get_active_tab_mut(client_id) {
@@ -1712,7 +2323,11 @@ impl Screen {
             }
         }
 
-        self.style = mode_info.style;
+        // a client keeps the theme it switched to and whether it is read-only when changing modes
+        if let Some(previous_mode_info) = self.mode_info.get(&client_id) {
+            mode_info.style = previous_mode_info.style;
+            mode_info.is_read_only = previous_mode_info.is_read_only;
+        }
         self.mode_info.insert(client_id, mode_info.clone());
         for tab in self.tabs.values_mut() {
             tab.change_mode_info(mode_info.clone(), client_id);
@@ -2105,6 +2720,17 @@ impl Screen {
             .send_to_server(ServerInstruction::UnblockInputThread)
             .context("failed to unblock input")
     }
+    /// What we write to disk along with the session layout so that it can be shown in this pane
+    /// when the session is resurrected
+    fn serialize_pane_contents(&self, pane: &dyn Pane) -> Option<String> {
+        if self.serialize_pane_scrollback {
+            pane.serialize_scrollback(self.scrollback_lines_to_serialize)
+        } else if self.serialize_pane_viewport {
+            pane.serialize(self.scrollback_lines_to_serialize)
+        } else {
+            None
+        }
+    }
     fn get_layout_metadata(&self, default_shell: Option<PathBuf>) -> SessionLayoutMetadata {
         let mut session_layout_metadata = SessionLayoutMetadata::new(self.default_layout.clone());
         if let Some(default_shell) = default_shell {
@@ -2146,11 +2772,8 @@ impl Screen {
                         p.invoked_with().clone(),
                         p.custom_title(),
                         active_pane_id == Some(pane_id),
-                        if self.serialize_pane_viewport {
-                            p.serialize(self.scrollback_lines_to_serialize)
-                        } else {
-                            None
-                        },
+                        p.monitors(),
+                        self.serialize_pane_contents(p.as_ref()),
                     )
                 })
                 .collect();
@@ -2177,11 +2800,8 @@ impl Screen {
                         p.invoked_with().clone(),
                         p.custom_title(),
                         active_pane_id == Some(pane_id),
-                        if self.serialize_pane_viewport {
-                            p.serialize(self.scrollback_lines_to_serialize)
-                        } else {
-                            None
-                        },
+                        p.monitors(),
+                        self.serialize_pane_contents(p.as_ref()),
                     )
                 })
                 .collect();
@@ -2189,6 +2809,7 @@ impl Screen {
                 tab.name.clone(),
                 tab_is_focused,
                 hide_floating_panes,
+                tab.monitors(),
                 tiled_panes,
                 floating_panes,
             );
@@ -2230,6 +2851,13 @@ pub(crate) fn screen_thread_main(
     let session_serialization = config_options.session_serialization.unwrap_or(true);
     let serialize_pane_viewport = config_options.serialize_pane_viewport.unwrap_or(false);
     let scrollback_lines_to_serialize = config_options.scrollback_lines_to_serialize;
+    let serialize_pane_scrollback = config_options.serialize_pane_scrollback.unwrap_or(false);
+    // all the panes of the session live on this thread, so this applies to the whole session
+    set_scrollback_memory_budget(
+        config_options
+            .scrollback_memory_budget
+            .map(|megabytes| megabytes.saturating_mul(1024 * 1024)),
+    );
     let session_is_mirrored = config_options.mirror_session.unwrap_or(false);
     let layout_dir = config_options.layout_dir;
     let default_shell = config_options.default_shell;
@@ -2242,6 +2870,11 @@ pub(crate) fn screen_thread_main(
         config_options.copy_on_select.unwrap_or(true),
     );
     let styled_underlines = config_options.styled_underlines.unwrap_or(true);
+    let default_pane_monitors = PaneMonitors {
+        bell: config_options.monitor_bell,
+        activity: config_options.monitor_activity,
+        silence: config_options.monitor_silence,
+    };
 
     let thread_senders = bus.senders.clone();
     let mut screen = Screen::new(
@@ -2267,9 +2900,11 @@ pub(crate) fn screen_thread_main(
         session_serialization,
         serialize_pane_viewport,
         scrollback_lines_to_serialize,
+        serialize_pane_scrollback,
         styled_underlines,
         arrow_fonts,
         layout_dir,
+        default_pane_monitors,
     );
 
     let mut pending_tab_ids: HashSet<usize> = HashSet::new();
@@ -2289,14 +2924,20 @@ pub(crate) fn screen_thread_main(
 
         match event {
             ScreenInstruction::PtyBytes(pid, vte_bytes) => {
+                let mut committed_lines = vec![];
                 let all_tabs = screen.get_tabs_mut();
                 for tab in all_tabs.values_mut() {
                     if tab.has_terminal_pid(pid) {
                         tab.handle_pty_bytes(pid, vte_bytes)
                             .context("failed to process pty bytes")?;
+                        committed_lines = tab.drain_committed_lines(pid);
                         break;
                     }
                 }
+                if !committed_lines.is_empty() {
+                    screen.report_pane_output(PaneId::Terminal(pid), committed_lines)?;
+                }
+                screen.drop_lines_evicted_from_scrollbacks();
             },
             ScreenInstruction::PluginBytes(mut plugin_render_assets) => {
                 for plugin_render_asset in plugin_render_assets.iter_mut() {
@@ -2462,6 +3103,8 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
             },
             ScreenInstruction::WriteCharacter(bytes, client_id) => {
+                // these are not necessarily typed (eg. `zellij action write-chars` or a plugin
+                // writing to a pane), so they are never taken to dismiss a notification
                 let mut state_changed = false;
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2482,6 +3125,47 @@ pub(crate) fn screen_thread_main(
                     screen.log_and_report_session_state()?;
                 }
             },
+            ScreenInstruction::WriteKey(
+                key_with_modifier,
+                raw_bytes,
+                is_kitty_keyboard_protocol,
+                client_id,
+            ) => {
+                if screen.dismiss_notifications()? {
+                    // only a client's keypresses are written as keys, this one was typed to
+                    // dismiss the notification rather than to the pane
+                    continue;
+                }
+                let mut state_changed = false;
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| {
+                        let write_result = match tab.is_sync_panes_active() {
+                            true => tab.write_key_to_terminals_on_current_tab(
+                                &key_with_modifier,
+                                raw_bytes,
+                                is_kitty_keyboard_protocol,
+                                client_id,
+                            ),
+                            false => tab.write_key_to_active_terminal(
+                                &key_with_modifier,
+                                raw_bytes,
+                                is_kitty_keyboard_protocol,
+                                client_id,
+                            ),
+                        };
+                        if let Ok(true) = write_result {
+                            state_changed = true;
+                        }
+                        write_result
+                    },
+                    ?
+                );
+                if state_changed {
+                    screen.log_and_report_session_state()?;
+                }
+            },
             ScreenInstruction::Resize(client_id, strategy) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2604,6 +3288,20 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::PipePaneOutput(target, strip_ansi, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.pipe_active_pane_output(
+                        target,
+                        strip_ansi,
+                        client_id
+                    ),
+                    ?
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
             ScreenInstruction::DumpLayout(default_shell, client_id) => {
                 let err_context = || format!("Failed to dump layout");
                 let session_layout_metadata = screen.get_layout_metadata(default_shell);
@@ -2744,6 +3442,26 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::ScrollToPreviousPrompt(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .scroll_active_terminal_to_previous_prompt(client_id)
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ScrollToNextPrompt(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .scroll_active_terminal_to_next_prompt(client_id), ?
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
             ScreenInstruction::PageScrollUp(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2840,8 +3558,12 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
             },
+            ScreenInstruction::CommandPaneExited(id, exit_status, run_command) => {
+                screen.report_command_pane_exited(id, exit_status, &run_command)?;
+            },
             ScreenInstruction::HoldPane(id, exit_status, run_command, tab_index, client_id) => {
                 let is_first_run = false;
+                screen.report_command_pane_exited(id, exit_status, &run_command)?;
                 match (client_id, tab_index) {
                     (Some(client_id), _) => {
                         active_tab!(screen, client_id, |tab: &mut Tab| tab.hold_pane(
@@ -3092,6 +3814,19 @@ pub(crate) fn screen_thread_main(
             ScreenInstruction::TerminalColorRegisters(color_registers) => {
                 screen.update_terminal_color_registers(color_registers);
             },
+            ScreenInstruction::TerminalSupportsKittyGraphics(client_id) => {
+                screen.kitty_graphics_clients.insert(client_id);
+                screen.render(None)?;
+            },
+            ScreenInstruction::ClientColorDepth(client_id, color_depth) => {
+                screen.client_color_depths.insert(client_id, color_depth);
+            },
+            ScreenInstruction::CheckPaneMonitors => {
+                // panes going silent is noticed here, everything else is noticed when rendering
+                if screen.update_pane_alerts()? {
+                    screen.render(None)?;
+                }
+            },
             ScreenInstruction::ChangeMode(mode_info, client_id) => {
                 screen.change_mode(mode_info, client_id)?;
                 screen.render(None)?;
@@ -3199,6 +3934,12 @@ pub(crate) fn screen_thread_main(
                 screen.log_and_report_session_state()?;
                 screen.render(None)?;
             },
+            ScreenInstruction::FollowClient(client_id, followed_client_id) => {
+                screen
+                    .following_clients
+                    .insert(client_id, followed_client_id);
+                screen.render(None)?;
+            },
             ScreenInstruction::AddOverlay(overlay, _client_id) => {
                 screen.get_active_overlays_mut().pop();
                 screen.get_active_overlays_mut().push(overlay);
@@ -3270,6 +4011,15 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::SearchToggleRegex(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.toggle_search_regex(client_id)
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
             ScreenInstruction::SearchToggleWholeWord(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -3279,6 +4029,53 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::CopyModeMotion(motion, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.copy_mode_motion(motion, client_id)
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::CopyModeToggleSelection(selection, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .copy_mode_toggle_selection(selection, client_id)
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::CopyModeYank(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.copy_mode_yank(client_id), ?
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::SelectLastCommandOutput(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.select_last_command_output(client_id)
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::CopyLastCommandOutput(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .copy_last_command_output(client_id), ?
+                );
+                screen.render(None)?;
+                screen.unblock_input()?;
+            },
             ScreenInstruction::AddRedPaneFrameColorOverride(pane_ids, error_text) => {
                 let all_tabs = screen.get_tabs_mut();
                 for pane_id in pane_ids {
@@ -3785,6 +4582,12 @@ pub(crate) fn screen_thread_main(
             ScreenInstruction::UpdateSessionInfos(new_session_infos, resurrectable_sessions) => {
                 screen.update_session_infos(new_session_infos, resurrectable_sessions)?;
             },
+            ScreenInstruction::UpdateTerminalProcessInfo(terminal_process_info) => {
+                if screen.terminal_process_info != terminal_process_info {
+                    screen.terminal_process_info = terminal_process_info;
+                    screen.log_and_report_session_state()?;
+                }
+            },
             ScreenInstruction::ReplacePane(
                 new_pane_id,
                 hold_for_command,
@@ -3873,6 +4676,81 @@ pub(crate) fn screen_thread_main(
                 }
                 screen.unblock_input()?;
             },
+            ScreenInstruction::GetPaneContents(
+                pane_id,
+                include_scrollback,
+                with_ansi,
+                plugin_id,
+                client_id,
+            ) => {
+                let pane_contents = screen
+                    .tabs
+                    .values()
+                    .find_map(|tab| tab.pane_contents(pane_id, include_scrollback, with_ansi));
+                match pane_contents {
+                    Some(pane_contents) => {
+                        screen
+                            .bus
+                            .senders
+                            .send_to_plugin(PluginInstruction::Update(vec![(
+                                Some(plugin_id),
+                                Some(client_id),
+                                Event::PaneContents(pane_id.into(), pane_contents),
+                            )]))
+                            .with_context(|| {
+                                format!("failed to send contents of pane {:?}", pane_id)
+                            })
+                            .non_fatal();
+                    },
+                    None => {
+                        log::error!("Could not find pane {:?} to get its contents", pane_id);
+                    },
+                }
+            },
+            ScreenInstruction::WatchPane(pane_id, plugin_id) => {
+                screen.watch_pane(pane_id, plugin_id);
+            },
+            ScreenInstruction::UnwatchPane(pane_id, plugin_id) => {
+                screen.unwatch_pane(pane_id, plugin_id);
+            },
+            ScreenInstruction::UnwatchPanesOfPlugin(plugin_id) => {
+                screen.unwatch_panes_of_plugin(plugin_id);
+            },
+            ScreenInstruction::ActOnTarget(target, pane_action, client_id) => {
+                screen
+                    .act_on_target(target, pane_action, client_id)
+                    .non_fatal();
+                screen.render(None)?;
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::ListTabs(json, client_id) => {
+                screen.log_listing(screen.list_tabs(json), client_id)?;
+            },
+            ScreenInstruction::ListPanes(json, client_id) => {
+                screen.log_listing(screen.list_panes(json), client_id)?;
+            },
+            ScreenInstruction::ListClients(json, client_sizes, client_id) => {
+                screen.log_listing(screen.list_clients(json, &client_sizes), client_id)?;
+            },
+            ScreenInstruction::Reconfigure(keybinds, style, config_options) => {
+                screen.reconfigure(keybinds, style, *config_options)?;
+            },
+            ScreenInstruction::ShowConfigError(error) => {
+                screen.show_config_error(error)?;
+            },
+            ScreenInstruction::SwitchTheme(palette, target_client_id) => {
+                screen.switch_theme(palette, target_client_id)?;
+            },
+            ScreenInstruction::KittyGraphicsFileRead(pid, file_read_id, data) => {
+                for tab in screen.get_tabs_mut().values_mut() {
+                    if tab.has_terminal_pid(pid) {
+                        tab.finish_kitty_graphics_file_read(pid, file_read_id, data)?;
+                        break;
+                    }
+                }
+                screen.render(None)?;
+            },
         }
     }
     Ok(())
@@ -5,7 +5,7 @@ use zellij_utils::common_path::common_path_all;
 use zellij_utils::pane_size::PaneGeom;
 use zellij_utils::{
     input::command::RunCommand,
-    input::layout::{Layout, Run, RunPlugin},
+    input::layout::{Layout, PaneMonitors, Run, RunPlugin},
     session_serialization::{GlobalLayoutManifest, PaneLayoutManifest, TabLayoutManifest},
 };
 
@@ -73,6 +73,7 @@ impl SessionLayoutMetadata {
         name: String,
         is_focused: bool,
         hide_floating_panes: bool,
+        monitors: PaneMonitors,
         tiled_panes: Vec<PaneLayoutMetadata>,
         floating_panes: Vec<PaneLayoutMetadata>,
     ) {
@@ -80,6 +81,7 @@ impl SessionLayoutMetadata {
             name: Some(name),
             is_focused,
             hide_floating_panes,
+            monitors,
             tiled_panes,
             floating_panes,
         })
@@ -215,6 +217,7 @@ impl Into<TabLayoutManifest> for TabLayoutMetadata {
             floating_panes: self.floating_panes.into_iter().map(|t| t.into()).collect(),
             is_focused: self.is_focused,
             hide_floating_panes: self.hide_floating_panes,
+            monitors: self.monitors,
         }
     }
 }
@@ -228,6 +231,7 @@ impl Into<PaneLayoutManifest> for PaneLayoutMetadata {
             is_borderless: self.is_borderless,
             title: self.title,
             is_focused: self.is_focused,
+            monitors: self.monitors,
             pane_contents: self.pane_contents,
         }
     }
@@ -240,6 +244,7 @@ pub struct TabLayoutMetadata {
     floating_panes: Vec<PaneLayoutMetadata>,
     is_focused: bool,
     hide_floating_panes: bool,
+    monitors: PaneMonitors,
 }
 
 #[derive(Debug, Clone)]
@@ -251,6 +256,7 @@ pub struct PaneLayoutMetadata {
     is_borderless: bool,
     title: Option<String>,
     is_focused: bool,
+    monitors: PaneMonitors,
     pane_contents: Option<String>,
 }
 
@@ -262,6 +268,7 @@ impl PaneLayoutMetadata {
         run: Option<Run>,
         title: Option<String>,
         is_focused: bool,
+        monitors: PaneMonitors,
         pane_contents: Option<String>,
     ) -> Self {
         PaneLayoutMetadata {
@@ -272,6 +279,7 @@ impl PaneLayoutMetadata {
             is_borderless,
             title,
             is_focused,
+            monitors,
             pane_contents,
         }
     }
@@ -0,0 +1,118 @@
+//! The tabs, panes and clients of the session as listed to the cli by `zellij action list-tabs`,
+//! `list-panes` and `list-clients`
+
+use crate::ClientId;
+use zellij_utils::{
+    data::{InputMode, PaneInfo, TabInfo},
+    errors::prelude::*,
+    pane_size::Size,
+    serde,
+};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+pub struct ListedTab {
+    pub index: usize, // as in `go-to-tab` and `--tab-index`, starting at 1
+    #[serde(flatten)]
+    pub info: TabInfo,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+pub struct ListedPane {
+    pub pane_id: String, // as in `--pane-id` (eg. terminal_3 or plugin_2)
+    pub tab_index: usize,
+    pub tab_name: String,
+    #[serde(flatten)]
+    pub info: PaneInfo,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "self::serde")]
+pub struct ListedClient {
+    pub client_id: ClientId,
+    pub tab_index: Option<usize>,
+    pub tab_name: Option<String>,
+    pub focused_pane_id: Option<String>,
+    pub size: Option<Size>, // None if the client did not report its size
+    pub mode: InputMode,
+    pub is_read_only: bool,
+}
+
+pub fn list_tabs(tabs: &[ListedTab], json: bool) -> Result<Vec<String>> {
+    to_lines(tabs, json, "INDEX\tNAME\tACTIVE", |tab| {
+        format!("{}\t{}\t{}", tab.index, tab.info.name, tab.info.active)
+    })
+}
+
+pub fn list_panes(panes: &[ListedPane], json: bool) -> Result<Vec<String>> {
+    to_lines(
+        panes,
+        json,
+        "PANE_ID\tTAB_INDEX\tFLOATING\tFOCUSED\tTITLE\tCOMMAND\tCWD",
+        |pane| {
+            format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                pane.pane_id,
+                pane.tab_index,
+                pane.info.is_floating,
+                pane.info.is_focused,
+                pane.info.title,
+                pane.info
+                    .terminal_command
+                    .as_ref()
+                    .or(pane.info.plugin_url.as_ref())
+                    .map(|command| command.as_str())
+                    .unwrap_or("-"),
+                pane.info
+                    .cwd
+                    .as_ref()
+                    .map(|cwd| cwd.display().to_string())
+                    .unwrap_or_else(|| "-".to_owned()),
+            )
+        },
+    )
+}
+
+pub fn list_clients(clients: &[ListedClient], json: bool) -> Result<Vec<String>> {
+    to_lines(
+        clients,
+        json,
+        "CLIENT_ID\tTAB_INDEX\tFOCUSED_PANE_ID\tSIZE\tMODE\tREAD_ONLY",
+        |client| {
+            format!(
+                "{}\t{}\t{}\t{}\t{:?}\t{}",
+                client.client_id,
+                client
+                    .tab_index
+                    .map(|tab_index| tab_index.to_string())
+                    .unwrap_or_else(|| "-".to_owned()),
+                client.focused_pane_id.as_deref().unwrap_or("-"),
+                client
+                    .size
+                    .map(|size| format!("{}x{}", size.cols, size.rows))
+                    .unwrap_or_else(|| "-".to_owned()),
+                client.mode,
+                client.is_read_only,
+            )
+        },
+    )
+}
+
+fn to_lines<T: Serialize>(
+    items: &[T],
+    json: bool,
+    header: &str,
+    to_line: impl Fn(&T) -> String,
+) -> Result<Vec<String>> {
+    if json {
+        let json = serde_json::to_string(items).context("failed to serialize listing to json")?;
+        Ok(vec![json])
+    } else {
+        Ok(std::iter::once(header.to_owned())
+            .chain(items.iter().map(to_line))
+            .collect())
+    }
+}
@@ -291,8 +291,8 @@ impl<'a> LayoutApplier<'a> {
                             self.styled_underlines,
                         );
                         if let Some(pane_initial_contents) = &layout.pane_initial_contents {
-                            new_plugin.handle_pty_bytes(pane_initial_contents.as_bytes().into());
-                            new_plugin.handle_pty_bytes("\n\r".as_bytes().into());
+                            new_plugin.replay_pty_bytes(pane_initial_contents.as_bytes().into());
+                            new_plugin.replay_pty_bytes("\n\r".as_bytes().into());
                         }
 
                         new_plugin.set_borderless(layout.borderless);
@@ -329,13 +329,14 @@ impl<'a> LayoutApplier<'a> {
                                 self.styled_underlines,
                             );
                             if let Some(pane_initial_contents) = &layout.pane_initial_contents {
-                                new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
-                                new_pane.handle_pty_bytes("\n\r".as_bytes().into());
+                                new_pane.replay_pty_bytes(pane_initial_contents.as_bytes().into());
+                                new_pane.replay_pty_bytes("\n\r".as_bytes().into());
                             }
                             new_pane.set_borderless(layout.borderless);
                             if let Some(exclude_from_sync) = layout.exclude_from_sync {
                                 new_pane.set_exclude_from_sync(exclude_from_sync);
                             }
+                            new_pane.set_monitors(layout.monitors);
                             if let Some(held_command) = hold_for_command {
                                 new_pane.hold(None, true, held_command.clone());
                             }
@@ -420,8 +421,8 @@ impl<'a> LayoutApplier<'a> {
                     self.styled_underlines,
                 );
                 if let Some(pane_initial_contents) = &floating_pane_layout.pane_initial_contents {
-                    new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
-                    new_pane.handle_pty_bytes("\n\r".as_bytes().into());
+                    new_pane.replay_pty_bytes(pane_initial_contents.as_bytes().into());
+                    new_pane.replay_pty_bytes("\n\r".as_bytes().into());
                 }
                 new_pane.set_borderless(false);
                 new_pane.set_content_offset(Offset::frame(1));
@@ -461,11 +462,12 @@ impl<'a> LayoutApplier<'a> {
                     self.styled_underlines,
                 );
                 if let Some(pane_initial_contents) = &floating_pane_layout.pane_initial_contents {
-                    new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
-                    new_pane.handle_pty_bytes("\n\r".as_bytes().into());
+                    new_pane.replay_pty_bytes(pane_initial_contents.as_bytes().into());
+                    new_pane.replay_pty_bytes("\n\r".as_bytes().into());
                 }
                 new_pane.set_borderless(false);
                 new_pane.set_content_offset(Offset::frame(1));
+                new_pane.set_monitors(floating_pane_layout.monitors);
                 if let Some(held_command) = hold_for_command {
                     new_pane.hold(None, true, held_command.clone());
                 }
@@ -31,7 +31,10 @@ use crate::{
     output::{CharacterChunk, Output, SixelImageChunk},
     panes::sixel::SixelImageStore,
     panes::{FloatingPanes, TiledPanes},
-    panes::{LinkHandler, PaneId, PluginPane, TerminalPane},
+    panes::{
+        KittyGraphicsFileRead, LinkHandler, PaneAlerts, PaneId, PluginPane, ReportedCwd,
+        TerminalPane,
+    },
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
     thread_bus::ThreadSenders,
@@ -46,12 +49,16 @@ use std::{
     str,
 };
 use zellij_utils::{
-    data::{Event, FloatingPaneCoordinates, InputMode, ModeInfo, Palette, PaletteColor, Style},
+    data::{
+        Event, FloatingPaneCoordinates, InputMode, KeyWithModifier, KeybindsVec, ModeInfo, Palette,
+        PaletteColor, Style,
+    },
     input::{
+        actions::{CopyModeMotion, CopyModeSelection, PipePaneTarget},
         command::TerminalAction,
         layout::{
-            FloatingPaneLayout, PluginUserConfiguration, Run, RunPlugin, RunPluginLocation,
-            SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
+            FloatingPaneLayout, PaneMonitors, PluginUserConfiguration, Run, RunPlugin,
+            RunPluginLocation, SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
         },
         parse_keys,
     },
@@ -141,6 +148,30 @@ enum BufferedTabInstruction {
     HoldPane(PaneId, Option<i32>, bool, RunCommand), // Option<i32> is the exit status, bool is is_first_run
 }
 
+/// An action performed on a specific pane rather than on the focused one (eg. from the cli with
+/// `--pane-id`)
+#[derive(Debug, Clone)]
+pub enum PaneAction {
+    Write(Vec<u8>),
+    Resize(ResizeStrategy),
+    Clear,
+    DumpScreen(String, bool), // String is the file path, bool is full (with scrollback)
+    PipeOutput(Option<PipePaneTarget>, bool), // bool is strip_ansi
+    ScrollUp,
+    ScrollDown,
+    ScrollToBottom,
+    ScrollToTop,
+    ScrollToPreviousPrompt,
+    ScrollToNextPrompt,
+    PageScrollUp,
+    PageScrollDown,
+    HalfPageScrollUp,
+    HalfPageScrollDown,
+    ToggleFullscreen,
+    Rename(Vec<u8>),
+    Close,
+}
+
 pub(crate) struct Tab {
     pub index: usize,
     pub position: usize,
@@ -187,6 +218,7 @@ pub(crate) struct Tab {
     debug: bool,
     arrow_fonts: bool,
     styled_underlines: bool,
+    monitors: PaneMonitors, // the ones the layout set for this tab, for panes that set none
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -213,11 +245,32 @@ pub trait Pane {
     fn set_geom(&mut self, position_and_size: PaneGeom);
     fn set_geom_override(&mut self, pane_geom: PaneGeom);
     fn handle_pty_bytes(&mut self, _bytes: VteBytes) {}
+    /// Like handle_pty_bytes, for bytes that did not come from the pty (eg. the contents a pane
+    /// is restored with), so they are not piped anywhere
+    fn replay_pty_bytes(&mut self, bytes: VteBytes) {
+        self.handle_pty_bytes(bytes)
+    }
     fn handle_plugin_bytes(&mut self, _client_id: ClientId, _bytes: VteBytes) {}
     fn cursor_coordinates(&self) -> Option<(usize, usize)>;
     fn adjust_input_to_terminal(&mut self, _input_bytes: Vec<u8>) -> Option<AdjustedInput> {
         None
     }
+    /// The bytes to send this pane for a key press, given the raw bytes we read for it and
+    /// whether those are encoded with the kitty keyboard protocol
+    fn encode_key(
+        &self,
+        key_with_modifier: &KeyWithModifier,
+        raw_input_bytes: Vec<u8>,
+        raw_input_bytes_are_kitty: bool,
+    ) -> Vec<u8> {
+        if raw_input_bytes_are_kitty {
+            key_with_modifier
+                .serialize_non_kitty()
+                .unwrap_or(raw_input_bytes)
+        } else {
+            raw_input_bytes
+        }
+    }
     fn position_and_size(&self) -> PaneGeom;
     fn current_geom(&self) -> PaneGeom;
     fn geom_override(&self) -> Option<PaneGeom>;
@@ -257,6 +310,19 @@ pub trait Pane {
     fn dump_screen(&mut self, _client_id: ClientId, _full: bool) -> String {
         "".to_owned()
     }
+    fn pane_contents(&self, _include_scrollback: bool, _with_ansi: bool) -> Vec<String> {
+        vec![]
+    }
+    /// Keep piping the bytes this pane receives to the target, or stop doing so if there is none
+    fn pipe_output(&mut self, _target: Option<PipePaneTarget>, _strip_ansi: bool) -> Result<()> {
+        Ok(())
+    }
+    fn watch_output(&mut self) {}
+    fn unwatch_output(&mut self) {}
+    fn drain_committed_lines(&mut self) -> Vec<String> {
+        vec![]
+    }
+    fn drop_lines_evicted_from_scrollback(&mut self) {}
     fn scroll_up(&mut self, count: usize, client_id: ClientId);
     fn scroll_down(&mut self, count: usize, client_id: ClientId);
     fn clear_scroll(&mut self);
@@ -354,6 +420,18 @@ pub trait Pane {
     fn drain_clipboard_update(&mut self) -> Option<String> {
         None
     }
+    fn drain_kitty_graphics_file_reads(&mut self) -> Vec<KittyGraphicsFileRead> {
+        vec![]
+    }
+    fn finish_kitty_graphics_file_read(
+        &mut self,
+        _file_read_id: u32,
+        _data: Result<Vec<u8>, String>,
+    ) {
+    }
+    fn drain_cwd_update(&mut self) -> Option<ReportedCwd> {
+        None
+    }
     fn render_full_viewport(&mut self) {}
     fn relative_position(&self, position_on_screen: &Position) -> Position {
         position_on_screen.relative_to(self.get_content_y(), self.get_content_x())
@@ -389,8 +467,29 @@ pub trait Pane {
     fn load_pane_name(&mut self);
     fn set_borderless(&mut self, borderless: bool);
     fn borderless(&self) -> bool;
+    fn update_style(&mut self, style: Style);
     fn set_exclude_from_sync(&mut self, exclude_from_sync: bool);
     fn exclude_from_sync(&self) -> bool;
+    fn set_monitors(&mut self, _monitors: PaneMonitors) {
+        // No-op by default (only terminal-panes are monitored)
+    }
+    fn monitors(&self) -> PaneMonitors {
+        PaneMonitors::default()
+    }
+    fn is_monitoring_silence(&self, _defaults: PaneMonitors) -> bool {
+        false
+    }
+    fn alerts(&self) -> PaneAlerts {
+        PaneAlerts::default()
+    }
+    /// Raises the alerts of the monitors triggered since the last time, returning the new ones
+    fn new_alerts(&mut self, _defaults: PaneMonitors, _now: Instant) -> PaneAlerts {
+        PaneAlerts::default()
+    }
+    /// Returns whether there were any alerts to clear
+    fn clear_alerts(&mut self) -> bool {
+        false
+    }
 
     // TODO: this should probably be merged with the mouse_right_click
     fn handle_right_click(&mut self, _to: &Position, _client_id: ClientId) {}
@@ -445,9 +544,40 @@ pub trait Pane {
     fn toggle_search_wrap(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
+    fn toggle_search_regex(&mut self) {
+        // No-op by default (only terminal-panes currently have search capability)
+    }
     fn clear_search(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
+    fn start_copy_mode(&mut self) {
+        // No-op by default (only terminal-panes currently have copy mode capability)
+    }
+    fn exit_copy_mode(&mut self) {
+        // No-op by default (only terminal-panes currently have copy mode capability)
+    }
+    fn copy_mode_motion(&mut self, _motion: CopyModeMotion) {
+        // No-op by default (only terminal-panes currently have copy mode capability)
+    }
+    fn copy_mode_toggle_selection(&mut self, _selection: CopyModeSelection) {
+        // No-op by default (only terminal-panes currently have copy mode capability)
+    }
+    fn copy_mode_clear_selection(&mut self) {
+        // No-op by default (only terminal-panes currently have copy mode capability)
+    }
+    fn scroll_to_previous_prompt(&mut self) {
+        // No-op by default (only terminal-panes currently track shell prompts)
+    }
+    fn scroll_to_next_prompt(&mut self) {
+        // No-op by default (only terminal-panes currently track shell prompts)
+    }
+    fn select_last_command_output(&mut self) {
+        // No-op by default (only terminal-panes currently track shell prompts)
+    }
+    fn last_command_output(&self) -> Option<String> {
+        // None by default (only terminal-panes currently track shell prompts)
+        None
+    }
     fn is_alternate_mode_active(&self) -> bool {
         // False by default (only terminal-panes support alternate mode)
         false
@@ -478,6 +608,12 @@ pub trait Pane {
     fn serialize(&self, _scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         None
     }
+    fn serialize_scrollback(
+        &self,
+        _scrollback_lines_to_serialize: Option<usize>,
+    ) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -627,6 +763,7 @@ impl Tab {
             debug,
             arrow_fonts,
             styled_underlines,
+            monitors: PaneMonitors::default(),
         }
     }
 
@@ -641,6 +778,9 @@ impl Tab {
     ) -> Result<()> {
         self.swap_layouts
             .set_base_layout((layout.clone(), floating_panes_layout.clone()));
+        // the monitors of the tab end up on the root of its layout, panes opened later in this
+        // tab fall back to them
+        self.monitors = layout.monitors;
         let should_show_floating_panes = LayoutApplier::new(
             &self.viewport,
             &self.senders,
@@ -867,6 +1007,50 @@ impl Tab {
             .with_context(|| format!("failed to update plugins with mode info"))?;
         Ok(())
     }
+    pub fn reconfigure(
+        &mut self,
+        keybinds: KeybindsVec,
+        style: Style,
+        draw_pane_frames: bool,
+        auto_layout: bool,
+        copy_options: &CopyOptions,
+    ) -> Result<()> {
+        {
+            let mode_infos = &mut self.mode_info.borrow_mut();
+            for (_client_id, mode_info) in mode_infos.iter_mut() {
+                mode_info.keybinds = keybinds.clone();
+            }
+            self.default_mode_info.keybinds = keybinds;
+        }
+        self.update_style(style);
+        self.auto_layout = auto_layout;
+        self.copy_on_select = copy_options.copy_on_select;
+        self.clipboard_provider = match &copy_options.command {
+            Some(command) => ClipboardProvider::Command(CopyCommand::new(command.clone())),
+            None => ClipboardProvider::Osc52(copy_options.clipboard),
+        };
+        self.set_pane_frames(draw_pane_frames);
+        self.update_input_modes()
+    }
+    /// Applies a new style (eg. a new theme) to all clients of this tab and to all of its panes
+    pub fn update_style(&mut self, style: Style) {
+        {
+            let mode_infos = &mut self.mode_info.borrow_mut();
+            for (_client_id, mode_info) in mode_infos.iter_mut() {
+                mode_info.style = style;
+            }
+            self.default_mode_info.style = style;
+        }
+        self.style = style;
+        self.tiled_panes
+            .reconfigure(style, self.default_mode_info.clone());
+        self.floating_panes
+            .reconfigure(style, self.default_mode_info.clone());
+        for (_is_scrollback_editor, pane) in self.suppressed_panes.values_mut() {
+            pane.update_style(style);
+        }
+        self.set_force_render();
+    }
     pub fn add_client(&mut self, client_id: ClientId, mode_info: Option<ModeInfo>) -> Result<()> {
         let other_clients_exist_in_tab = { !self.connected_clients.borrow().is_empty() };
         if other_clients_exist_in_tab {
@@ -915,6 +1099,22 @@ impl Tab {
     pub fn change_mode_info(&mut self, mode_info: ModeInfo, client_id: ClientId) {
         self.mode_info.borrow_mut().insert(client_id, mode_info);
     }
+    /// Focuses the pane `followed_client_id` is focused on for `client_id`, returning true if the
+    /// focus changed
+    pub fn follow_client_focus(
+        &mut self,
+        followed_client_id: ClientId,
+        client_id: ClientId,
+    ) -> bool {
+        match self.get_active_pane_id(followed_client_id) {
+            Some(pane_id) if self.get_active_pane_id(client_id) != Some(pane_id) => self
+                .tiled_panes
+                .focus_pane_if_exists(pane_id, client_id)
+                .or_else(|_| self.floating_panes.focus_pane_if_exists(pane_id, client_id))
+                .is_ok(),
+            _ => false,
+        }
+    }
 
     pub fn add_multiple_clients(
         &mut self,
@@ -1499,6 +1699,28 @@ impl Tab {
     pub fn has_non_suppressed_pane_with_pid(&self, pid: &PaneId) -> bool {
         self.tiled_panes.panes_contain(pid) || self.floating_panes.panes_contain(pid)
     }
+    /// Goes on with an image transmission of a terminal pane once the file holding the image
+    /// was read in the background
+    pub fn finish_kitty_graphics_file_read(
+        &mut self,
+        pid: u32,
+        file_read_id: u32,
+        data: Result<Vec<u8>, String>,
+    ) -> Result<()> {
+        let err_context = || format!("failed to finish reading an image file for fd {pid}");
+        let messages_to_pty = match self.get_pane_with_id_mut(PaneId::Terminal(pid)) {
+            Some(terminal_output) => {
+                terminal_output.finish_kitty_graphics_file_read(file_read_id, data);
+                terminal_output.drain_messages_to_pty()
+            },
+            None => return Ok(()),
+        };
+        for message in messages_to_pty {
+            self.write_to_pane_id(message, PaneId::Terminal(pid), None)
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
     pub fn handle_pty_bytes(&mut self, pid: u32, bytes: VteBytes) -> Result<()> {
         if self.is_pending {
             self.pending_instructions
@@ -1590,14 +1812,26 @@ impl Tab {
             terminal_output.handle_pty_bytes(bytes);
             let messages_to_pty = terminal_output.drain_messages_to_pty();
             let clipboard_update = terminal_output.drain_clipboard_update();
+            let cwd_update = terminal_output.drain_cwd_update();
+            let kitty_graphics_file_reads = terminal_output.drain_kitty_graphics_file_reads();
             for message in messages_to_pty {
                 self.write_to_pane_id(message, PaneId::Terminal(pid), None)
                     .with_context(err_context)?;
             }
+            for file_read in kitty_graphics_file_reads {
+                self.senders
+                    .send_to_background_jobs(BackgroundJob::ReadKittyGraphicsFile(pid, file_read))
+                    .with_context(err_context)?;
+            }
             if let Some(string) = clipboard_update {
                 self.write_selection_to_clipboard(&string)
                     .with_context(err_context)?;
             }
+            if let Some(reported_cwd) = cwd_update {
+                self.senders
+                    .send_to_pty(PtyInstruction::UpdateReportedCwd(pid, reported_cwd))
+                    .with_context(err_context)?;
+            }
         }
         Ok(())
     }
@@ -1636,24 +1870,106 @@ impl Tab {
         };
 
         self.clear_search(client_id); // this is an inexpensive operation if empty, if we need more such cleanups we should consider moving this and the rest to some sort of cleanup method
-        let pane_id = if self.floating_panes.panes_are_visible() {
+        let pane_id = self
+            .get_active_pane_id_for_input(client_id)
+            .with_context(err_context)?;
+        // Can't use 'err_context' here since it borrows 'input_bytes'
+        self.write_to_pane_id(input_bytes, pane_id, Some(client_id))
+            .with_context(|| format!("failed to write to active terminal for client {client_id}"))
+    }
+
+    pub fn write_key_to_terminals_on_current_tab(
+        &mut self,
+        key_with_modifier: &KeyWithModifier,
+        raw_input_bytes: Vec<u8>,
+        raw_input_bytes_are_kitty: bool,
+        client_id: ClientId,
+    ) -> Result<bool> {
+        // returns true if a UI update should be triggered (eg. when closing a command pane with
+        // ctrl-c)
+        let mut should_trigger_ui_change = false;
+        let pane_ids = self.get_static_and_floating_pane_ids();
+        for pane_id in pane_ids {
+            let input_bytes = self.encode_key_for_pane(
+                pane_id,
+                key_with_modifier,
+                raw_input_bytes.clone(),
+                raw_input_bytes_are_kitty,
+            );
+            let ui_change_triggered = self
+                .write_to_pane_id(input_bytes, pane_id, Some(client_id))
+                .with_context(|| {
+                    format!("failed to write {key_with_modifier} to terminals on current tab")
+                })?;
+            if ui_change_triggered {
+                should_trigger_ui_change = true;
+            }
+        }
+        Ok(should_trigger_ui_change)
+    }
+
+    pub fn write_key_to_active_terminal(
+        &mut self,
+        key_with_modifier: &KeyWithModifier,
+        raw_input_bytes: Vec<u8>,
+        raw_input_bytes_are_kitty: bool,
+        client_id: ClientId,
+    ) -> Result<bool> {
+        // returns true if a UI update should be triggered (eg. if a command pane
+        // was closed with ctrl-c)
+        let err_context = || {
+            format!("failed to write {key_with_modifier} to active terminal for client {client_id}")
+        };
+
+        self.clear_search(client_id);
+        let pane_id = self
+            .get_active_pane_id_for_input(client_id)
+            .with_context(err_context)?;
+        let input_bytes = self.encode_key_for_pane(
+            pane_id,
+            key_with_modifier,
+            raw_input_bytes,
+            raw_input_bytes_are_kitty,
+        );
+        self.write_to_pane_id(input_bytes, pane_id, Some(client_id))
+            .with_context(err_context)
+    }
+
+    fn get_active_pane_id_for_input(&self, client_id: ClientId) -> Result<PaneId> {
+        let active_pane_id = if self.floating_panes.panes_are_visible() {
             self.floating_panes
                 .get_active_pane_id(client_id)
                 .or_else(|| self.tiled_panes.get_active_pane_id(client_id))
-                .ok_or_else(|| {
-                    anyhow!(format!(
-                        "failed to find active pane id for client {client_id}"
-                    ))
-                })
-                .with_context(err_context)?
         } else {
-            self.tiled_panes
-                .get_active_pane_id(client_id)
-                .with_context(err_context)?
+            self.tiled_panes.get_active_pane_id(client_id)
         };
-        // Can't use 'err_context' here since it borrows 'input_bytes'
-        self.write_to_pane_id(input_bytes, pane_id, Some(client_id))
-            .with_context(|| format!("failed to write to active terminal for client {client_id}"))
+        active_pane_id.ok_or_else(|| {
+            anyhow!(format!(
+                "failed to find active pane id for client {client_id}"
+            ))
+        })
+    }
+
+    fn encode_key_for_pane(
+        &self,
+        pane_id: PaneId,
+        key_with_modifier: &KeyWithModifier,
+        raw_input_bytes: Vec<u8>,
+        raw_input_bytes_are_kitty: bool,
+    ) -> Vec<u8> {
+        match self
+            .floating_panes
+            .get(&pane_id)
+            .or_else(|| self.tiled_panes.get_pane(pane_id))
+            .or_else(|| self.suppressed_panes.get(&pane_id).map(|p| &p.1))
+        {
+            Some(pane) => pane.encode_key(
+                key_with_modifier,
+                raw_input_bytes,
+                raw_input_bytes_are_kitty,
+            ),
+            None => raw_input_bytes,
+        }
     }
 
     pub fn write_to_terminal_at(
@@ -2074,19 +2390,32 @@ impl Tab {
         Ok(())
     }
     pub fn resize(&mut self, client_id: ClientId, strategy: ResizeStrategy) -> Result<()> {
+        self.swap_layouts.set_is_floating_damaged();
+        self.swap_layouts.set_is_tiled_damaged();
+        let active_pane_id = if self.floating_panes.panes_are_visible() {
+            self.floating_panes.active_pane_id(client_id)
+        } else {
+            self.tiled_panes.get_active_pane_id(client_id)
+        };
+        match active_pane_id {
+            Some(active_pane_id) => self.resize_pane_with_id(active_pane_id, strategy),
+            None => Ok(()),
+        }
+    }
+    pub fn resize_pane_with_id(&mut self, pane_id: PaneId, strategy: ResizeStrategy) -> Result<()> {
         let err_context = || format!("unable to resize pane");
         self.swap_layouts.set_is_floating_damaged();
         self.swap_layouts.set_is_tiled_damaged();
-        if self.floating_panes.panes_are_visible() {
+        if self.floating_panes.panes_contain(&pane_id) {
             let successfully_resized = self
                 .floating_panes
-                .resize_active_pane(client_id, &mut self.os_api, &strategy)
+                .resize_pane_with_id(pane_id, &mut self.os_api, &strategy)
                 .with_context(err_context)?;
             if successfully_resized {
                 self.set_force_render(); // we force render here to make sure the panes under the floating pane render and don't leave "garbage" in case of a decrease
             }
         } else {
-            match self.tiled_panes.resize_active_pane(client_id, &strategy) {
+            match self.tiled_panes.resize_pane_with_id(pane_id, &strategy) {
                 Ok(_) => {},
                 Err(err) => match err.downcast_ref::<ZellijError>() {
                     Some(ZellijError::CantResizeFixedPanes { pane_ids }) => {
@@ -2602,6 +2931,85 @@ impl Tab {
         }
         Ok(())
     }
+    pub fn pipe_active_pane_output(
+        &mut self,
+        target: Option<PipePaneTarget>,
+        strip_ansi: bool,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to pipe active pane output for client {client_id}");
+
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane
+                .pipe_output(target, strip_ansi)
+                .with_context(err_context)
+                .non_fatal();
+        }
+        Ok(())
+    }
+    pub fn pane_contents(
+        &self,
+        pane_id: PaneId,
+        include_scrollback: bool,
+        with_ansi: bool,
+    ) -> Option<Vec<String>> {
+        self.tiled_panes
+            .get_pane(pane_id)
+            .or_else(|| self.floating_panes.get_pane(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values()
+                    .find(|s_p| s_p.1.pid() == pane_id)
+                    .map(|s_p| &s_p.1)
+            })
+            .map(|pane| pane.pane_contents(include_scrollback, with_ansi))
+    }
+    pub fn watch_pane_output(&mut self, pane_id: PaneId) {
+        if let Some(pane) = self
+            .tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == pane_id)
+                    .map(|s_p| &mut s_p.1)
+            })
+        {
+            pane.watch_output();
+        }
+    }
+    pub fn unwatch_pane_output(&mut self, pane_id: PaneId) {
+        if let Some(pane) = self.get_pane_with_id_mut(pane_id) {
+            pane.unwatch_output();
+        }
+    }
+    /// Lets all the panes of this tab know about lines dropped from their scrollback to keep the
+    /// session under its memory budget
+    pub fn drop_lines_evicted_from_scrollback(&mut self) {
+        for (_, pane) in self.tiled_panes.get_panes_mut() {
+            pane.drop_lines_evicted_from_scrollback();
+        }
+        for (_, pane) in self.floating_panes.get_panes_mut() {
+            pane.drop_lines_evicted_from_scrollback();
+        }
+        for (_, pane) in self.suppressed_panes.values_mut() {
+            pane.drop_lines_evicted_from_scrollback();
+        }
+    }
+    pub fn drain_committed_lines(&mut self, pid: u32) -> Vec<String> {
+        self.tiled_panes
+            .get_pane_mut(PaneId::Terminal(pid))
+            .or_else(|| self.floating_panes.get_pane_mut(PaneId::Terminal(pid)))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == PaneId::Terminal(pid))
+                    .map(|s_p| &mut s_p.1)
+            })
+            .map(|pane| pane.drain_committed_lines())
+            .unwrap_or_default()
+    }
     pub fn edit_scrollback(&mut self, client_id: ClientId) -> Result<()> {
         let err_context = || format!("failed to edit scrollback for client {client_id}");
 
@@ -2721,6 +3129,28 @@ impl Tab {
         Ok(())
     }
 
+    pub fn scroll_active_terminal_to_previous_prompt(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.scroll_to_previous_prompt();
+        }
+    }
+
+    pub fn scroll_active_terminal_to_next_prompt(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context =
+            || format!("failed to scroll to next prompt in active pane for client {client_id}");
+
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.scroll_to_next_prompt();
+            if !active_pane.is_scrolled() {
+                if let PaneId::Terminal(raw_fd) = active_pane.pid() {
+                    self.process_pending_vte_events(raw_fd)
+                        .with_context(err_context)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn clear_active_terminal_scroll(&mut self, client_id: ClientId) -> Result<()> {
         // TODO: is this a thing?
         let err_context =
@@ -3268,24 +3698,38 @@ impl Tab {
             .get_active_pane(client_id)
             .and_then(|p| p.get_selected_text());
         if let Some(selected_text) = selected_text {
-            self.write_selection_to_clipboard(&selected_text)
-                .with_context(|| {
-                    format!("failed to write selection to clipboard for client {client_id}")
-                })?;
-            self.senders
-                .send_to_plugin(PluginInstruction::Update(vec![(
-                    None,
-                    None,
-                    Event::CopyToClipboard(self.clipboard_provider.as_copy_destination()),
-                )]))
-                .with_context(|| {
-                    format!("failed to inform plugins about copy selection for client {client_id}")
-                })
-                .non_fatal();
+            self.copy_text_to_clipboard(&selected_text, client_id)?;
+        }
+        Ok(())
+    }
+
+    pub fn copy_last_command_output(&self, client_id: ClientId) -> Result<()> {
+        let last_command_output = self
+            .get_active_pane(client_id)
+            .and_then(|p| p.last_command_output());
+        if let Some(last_command_output) = last_command_output {
+            self.copy_text_to_clipboard(&last_command_output, client_id)?;
         }
         Ok(())
     }
 
+    fn copy_text_to_clipboard(&self, text: &str, client_id: ClientId) -> Result<()> {
+        self.write_selection_to_clipboard(text).with_context(|| {
+            format!("failed to write selection to clipboard for client {client_id}")
+        })?;
+        self.senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::CopyToClipboard(self.clipboard_provider.as_copy_destination()),
+            )]))
+            .with_context(|| {
+                format!("failed to inform plugins about copy selection for client {client_id}")
+            })
+            .non_fatal();
+        Ok(())
+    }
+
     fn write_selection_to_clipboard(&self, selection: &str) -> Result<()> {
         let err_context = || format!("failed to write selection to clipboard: '{}'", selection);
 
@@ -3368,6 +3812,171 @@ impl Tab {
         Ok(())
     }
 
+    /// The pane an action targeting this tab is performed on: the pane focused by the first client
+    /// in this tab, or its first selectable pane if no client is looking at it
+    pub fn target_pane_id(&self) -> Option<PaneId> {
+        if self.floating_panes.panes_are_visible() {
+            if let Some(pane_id) = self.floating_panes.first_active_floating_pane_id() {
+                return Some(pane_id);
+            }
+        }
+        self.tiled_panes
+            .first_active_pane_id()
+            .or_else(|| self.tiled_panes.first_selectable_pane_id())
+    }
+
+    /// Perform the action on this pane without focusing it
+    pub fn act_on_pane(
+        &mut self,
+        pane_id: PaneId,
+        pane_action: PaneAction,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to act on pane {pane_id:?}");
+
+        match pane_action {
+            PaneAction::Write(bytes) => {
+                self.scroll_pane_with_id(pane_id, |pane| pane.clear_scroll())
+                    .with_context(err_context)?;
+                self.write_to_pane_id(bytes, pane_id, Some(client_id))
+                    .with_context(err_context)?;
+            },
+            PaneAction::Resize(strategy) => {
+                self.resize_pane_with_id(pane_id, strategy)
+                    .with_context(err_context)?;
+            },
+            PaneAction::Clear => {
+                self.get_pane_with_id_mut(pane_id)
+                    .with_context(err_context)?
+                    .clear_screen();
+            },
+            PaneAction::DumpScreen(file, full) => {
+                let dump = self
+                    .get_pane_with_id_mut(pane_id)
+                    .with_context(err_context)?
+                    .dump_screen(client_id, full);
+                self.os_api
+                    .write_to_file(dump, Some(file))
+                    .with_context(err_context)?;
+            },
+            PaneAction::PipeOutput(target, strip_ansi) => {
+                self.get_pane_with_id_mut(pane_id)
+                    .with_context(err_context)?
+                    .pipe_output(target, strip_ansi)
+                    .with_context(err_context)
+                    .non_fatal();
+            },
+            PaneAction::ScrollUp => {
+                self.scroll_pane_with_id(pane_id, |pane| pane.scroll_up(1, client_id))
+                    .with_context(err_context)?;
+            },
+            PaneAction::ScrollDown => {
+                self.scroll_pane_with_id(pane_id, |pane| pane.scroll_down(1, client_id))
+                    .with_context(err_context)?;
+            },
+            PaneAction::ScrollToBottom => {
+                self.scroll_pane_with_id(pane_id, |pane| pane.clear_scroll())
+                    .with_context(err_context)?;
+            },
+            PaneAction::ScrollToTop => {
+                self.scroll_pane_with_id(pane_id, |pane| {
+                    pane.clear_scroll();
+                    if let Some(size) = pane.get_line_number() {
+                        pane.scroll_up(size, client_id);
+                    }
+                })
+                .with_context(err_context)?;
+            },
+            PaneAction::ScrollToPreviousPrompt => {
+                self.scroll_pane_with_id(pane_id, |pane| pane.scroll_to_previous_prompt())
+                    .with_context(err_context)?;
+            },
+            PaneAction::ScrollToNextPrompt => {
+                self.scroll_pane_with_id(pane_id, |pane| pane.scroll_to_next_prompt())
+                    .with_context(err_context)?;
+            },
+            PaneAction::PageScrollUp => {
+                self.scroll_pane_with_id(pane_id, |pane| {
+                    // prevent overflow when row == 0
+                    let scroll_rows = pane.rows().max(1) - 1;
+                    pane.scroll_up(scroll_rows, client_id);
+                })
+                .with_context(err_context)?;
+            },
+            PaneAction::PageScrollDown => {
+                self.scroll_pane_with_id(pane_id, |pane| {
+                    let scroll_rows = pane.get_content_rows();
+                    pane.scroll_down(scroll_rows, client_id);
+                })
+                .with_context(err_context)?;
+            },
+            PaneAction::HalfPageScrollUp => {
+                self.scroll_pane_with_id(pane_id, |pane| {
+                    // prevent overflow when row == 0
+                    let scroll_rows = (pane.rows().max(1) - 1) / 2;
+                    pane.scroll_up(scroll_rows, client_id);
+                })
+                .with_context(err_context)?;
+            },
+            PaneAction::HalfPageScrollDown => {
+                self.scroll_pane_with_id(pane_id, |pane| {
+                    let scroll_rows = (pane.rows().max(1) - 1) / 2;
+                    pane.scroll_down(scroll_rows, client_id);
+                })
+                .with_context(err_context)?;
+            },
+            PaneAction::ToggleFullscreen => {
+                // only tiled panes can be made fullscreen
+                if !self.floating_panes.panes_contain(&pane_id) {
+                    self.tiled_panes
+                        .toggle_pane_fullscreen_and_restore_focus(pane_id);
+                }
+            },
+            PaneAction::Rename(name) => {
+                self.rename_pane(name, pane_id).with_context(err_context)?;
+            },
+            PaneAction::Close => {
+                self.close_pane(pane_id, false, None);
+                self.senders
+                    .send_to_pty(PtyInstruction::ClosePane(pane_id))
+                    .with_context(err_context)?;
+            },
+        }
+        Ok(())
+    }
+
+    fn get_pane_with_id_mut(&mut self, pane_id: PaneId) -> Option<&mut Box<dyn Pane>> {
+        self.tiled_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.floating_panes.get_pane_mut(pane_id))
+            .or_else(|| {
+                self.suppressed_panes
+                    .values_mut()
+                    .find(|s_p| s_p.1.pid() == pane_id)
+                    .map(|s_p| &mut s_p.1)
+            })
+    }
+
+    fn scroll_pane_with_id(
+        &mut self,
+        pane_id: PaneId,
+        scroll: impl FnOnce(&mut Box<dyn Pane>),
+    ) -> Result<()> {
+        let err_context = || format!("failed to scroll pane {pane_id:?}");
+
+        let pane = self
+            .get_pane_with_id_mut(pane_id)
+            .with_context(err_context)?;
+        scroll(pane);
+        if !pane.is_scrolled() {
+            if let PaneId::Terminal(raw_fd) = pane_id {
+                self.process_pending_vte_events(raw_fd)
+                    .with_context(err_context)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn rename_pane(&mut self, buf: Vec<u8>, pane_id: PaneId) -> Result<()> {
         let err_context = || {
             format!(
@@ -3473,6 +4082,12 @@ impl Tab {
         }
     }
 
+    pub fn toggle_search_regex(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.toggle_search_regex();
+        }
+    }
+
     pub fn toggle_search_whole_words(&mut self, client_id: ClientId) {
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.toggle_search_whole_words();
@@ -3485,6 +4100,59 @@ impl Tab {
         }
     }
 
+    pub fn start_copy_mode(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.start_copy_mode();
+        }
+    }
+
+    pub fn exit_copy_mode(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to exit copy mode for client {client_id}");
+
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.exit_copy_mode();
+            if !active_pane.is_scrolled() {
+                if let PaneId::Terminal(raw_fd) = active_pane.pid() {
+                    self.process_pending_vte_events(raw_fd)
+                        .with_context(err_context)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn copy_mode_motion(&mut self, motion: CopyModeMotion, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.copy_mode_motion(motion);
+        }
+    }
+
+    pub fn copy_mode_toggle_selection(
+        &mut self,
+        selection: CopyModeSelection,
+        client_id: ClientId,
+    ) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.copy_mode_toggle_selection(selection);
+        }
+    }
+
+    pub fn select_last_command_output(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.select_last_command_output();
+        }
+    }
+
+    pub fn copy_mode_yank(&mut self, client_id: ClientId) -> Result<()> {
+        self.copy_selection(client_id).with_context(|| {
+            format!("failed to yank copy mode selection for client {client_id}")
+        })?;
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.copy_mode_clear_selection();
+        }
+        Ok(())
+    }
+
     pub fn is_pending(&self) -> bool {
         self.is_pending
     }
@@ -3642,6 +4310,67 @@ impl Tab {
                 .insert(pane_id, (is_scrollback_editor, pane));
         }
     }
+    /// The alerts of all the panes in this tab
+    pub fn alerts(&self) -> PaneAlerts {
+        self.tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .map(|(_pane_id, pane)| pane.alerts())
+            .chain(
+                self.suppressed_panes
+                    .values()
+                    .map(|(_is_scrollback_editor, pane)| pane.alerts()),
+            )
+            .fold(PaneAlerts::default(), |alerts, pane_alerts| {
+                alerts.or(pane_alerts)
+            })
+    }
+    pub fn monitors(&self) -> PaneMonitors {
+        self.monitors
+    }
+    /// Whether any pane in this tab has the silence monitor on
+    pub fn is_monitoring_silence(&self, defaults: PaneMonitors) -> bool {
+        let defaults = self.monitors.or(defaults);
+        self.tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .any(|(_pane_id, pane)| pane.is_monitoring_silence(defaults))
+            || self
+                .suppressed_panes
+                .values()
+                .any(|(_is_scrollback_editor, pane)| pane.is_monitoring_silence(defaults))
+    }
+    /// Raises the alerts of the monitors that were triggered in the panes of this tab, and
+    /// clears those of the focused panes. Returns the new alerts of the other panes, and whether
+    /// the alerts of any pane changed.
+    pub fn update_pane_alerts(
+        &mut self,
+        defaults: PaneMonitors,
+        focused_pane_ids: &HashSet<PaneId>,
+        now: Instant,
+    ) -> (Vec<(PaneId, PaneAlerts)>, bool) {
+        let defaults = self.monitors.or(defaults);
+        let mut new_alerts = vec![];
+        let mut alerts_changed = false;
+        let mut pane_ids = self.get_all_pane_ids();
+        pane_ids.extend(
+            self.suppressed_panes
+                .values()
+                .map(|(_is_scrollback_editor, pane)| pane.pid()),
+        );
+        for pane_id in pane_ids {
+            if let Some(pane) = self.get_pane_with_id_mut(pane_id) {
+                let new_pane_alerts = pane.new_alerts(defaults, now);
+                if focused_pane_ids.contains(&pane_id) {
+                    alerts_changed |= pane.clear_alerts();
+                } else if new_pane_alerts.any() {
+                    alerts_changed = true;
+                    new_alerts.push((pane_id, new_pane_alerts));
+                }
+            }
+        }
+        (new_alerts, alerts_changed)
+    }
     pub fn pane_infos(&self) -> Vec<PaneInfo> {
         let mut pane_info = vec![];
         let mut tiled_pane_info = self.tiled_panes.pane_info();
@@ -3755,6 +4484,10 @@ pub fn pane_info_for_pane(pane_id: &PaneId, pane: &Box<dyn Pane>) -> PaneInfo {
     pane_info.exited = pane.exited();
     pane_info.exit_status = pane.exit_status();
     pane_info.is_held = pane.is_held();
+    let alerts = pane.alerts();
+    pane_info.bell_alert = alerts.bell;
+    pane_info.activity_alert = alerts.activity;
+    pane_info.silence_alert = alerts.silence;
 
     match pane_id {
         PaneId::Terminal(terminal_id) => {
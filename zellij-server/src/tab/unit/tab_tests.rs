@@ -1,4 +1,4 @@
-use super::Tab;
+use super::{PaneAction, Tab};
 use crate::panes::sixel::SixelImageStore;
 use crate::screen::CopyOptions;
 use crate::{
@@ -874,6 +874,38 @@ pub fn toggle_focused_pane_fullscreen() {
     // function and we already test that in the e2e tests
 }
 
+#[test]
+pub fn toggle_targeted_pane_fullscreen_restores_focus() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    for i in 2..5 {
+        let new_pane_id = PaneId::Terminal(i);
+        tab.new_pane(new_pane_id, None, None, None, None, Some(1))
+            .unwrap();
+    }
+    tab.act_on_pane(PaneId::Terminal(2), PaneAction::ToggleFullscreen, 1)
+        .unwrap();
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(2))
+            .unwrap()
+            .cols(),
+        121,
+        "Targeted pane is fullscreen"
+    );
+    tab.act_on_pane(PaneId::Terminal(2), PaneAction::ToggleFullscreen, 1)
+        .unwrap();
+    assert_eq!(
+        tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(4)),
+        "Focus is back on the pane that was focused before"
+    );
+}
+
 #[test]
 fn switch_to_next_pane_fullscreen() {
     let size = Size {
@@ -6,6 +6,7 @@
 //!
 //! notification's:
 
+pub mod notification;
 pub mod prompt;
 
 use crate::ServerInstruction;
@@ -26,6 +27,7 @@ pub trait Overlayable {
 #[derive(Clone, Debug)]
 pub enum OverlayType {
     Prompt(prompt::Prompt),
+    Notification(notification::Notification),
 }
 
 impl Overlayable for OverlayType {
@@ -34,6 +36,9 @@ impl Overlayable for OverlayType {
             OverlayType::Prompt(prompt) => prompt
                 .generate_overlay(size)
                 .context("failed to generate VTE output from overlay type"),
+            OverlayType::Notification(notification) => notification
+                .generate_overlay(size)
+                .context("failed to generate VTE output from overlay type"),
         }
     }
 }
@@ -65,11 +70,13 @@ impl Overlay {
     pub fn prompt_confirm(self) -> Option<Box<ServerInstruction>> {
         match self.overlay_type {
             OverlayType::Prompt(p) => p.confirm(),
+            OverlayType::Notification(_) => None,
         }
     }
     pub fn prompt_deny(self) -> Option<Box<ServerInstruction>> {
         match self.overlay_type {
             OverlayType::Prompt(p) => p.deny(),
+            OverlayType::Notification(_) => None,
         }
     }
 }
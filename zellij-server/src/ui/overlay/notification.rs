@@ -0,0 +1,50 @@
+use zellij_utils::pane_size::Size;
+
+use super::Overlayable;
+use zellij_utils::errors::prelude::*;
+
+use std::fmt::Write;
+use unicode_width::UnicodeWidthChar;
+
+/// A (possibly multi-line) message drawn over the bottom rows of the screen
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub message: String,
+}
+
+impl Notification {
+    pub fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+impl Overlayable for Notification {
+    fn generate_overlay(&self, size: Size) -> Result<String> {
+        let mut output = String::new();
+        let lines: Vec<&str> = self.message.lines().take(size.rows).collect();
+        let first_row = size.rows.saturating_sub(lines.len()) + 1;
+        for (index, line) in lines.iter().enumerate() {
+            let mut vte_output = String::new();
+            let mut width = 0;
+            for character in line.chars() {
+                let character_width = character.width().unwrap_or(0);
+                if width + character_width > size.cols {
+                    break;
+                }
+                width += character_width;
+                vte_output.push(character);
+            }
+            for _ in width..size.cols {
+                vte_output.push(' ');
+            }
+            write!(
+                &mut output,
+                "\u{1b}[{};1H\u{1b}[m\u{1b}[48;5;238m{}\u{1b}[m",
+                first_row + index,
+                vte_output,
+            )
+            .context("failed to generate VTE output from notification")?;
+        }
+        Ok(output)
+    }
+}
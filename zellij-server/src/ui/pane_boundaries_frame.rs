@@ -1,5 +1,7 @@
 use crate::output::CharacterChunk;
-use crate::panes::{AnsiCode, RcCharacterStyles, TerminalCharacter, EMPTY_TERMINAL_CHARACTER};
+use crate::panes::{
+    AnsiCode, PaneAlerts, RcCharacterStyles, TerminalCharacter, EMPTY_TERMINAL_CHARACTER,
+};
 use crate::ui::boundaries::boundary_type;
 use crate::ClientId;
 use zellij_utils::data::{client_id_to_colors, PaletteColor, Style};
@@ -74,6 +76,9 @@ pub struct PaneFrame {
     pub other_cursors_exist_in_session: bool,
     pub other_focused_clients: Vec<ClientId>,
     exit_status: Option<ExitStatus>,
+    last_command_exit_status: Option<i32>,
+    is_piping_output: bool,
+    alerts: PaneAlerts,
     is_first_run: bool,
     pane_is_stacked_over: bool,
     pane_is_stacked_under: bool,
@@ -98,6 +103,9 @@ impl PaneFrame {
             other_focused_clients: frame_params.other_focused_clients,
             other_cursors_exist_in_session: frame_params.other_cursors_exist_in_session,
             exit_status: None,
+            last_command_exit_status: None,
+            is_piping_output: false,
+            alerts: PaneAlerts::default(),
             is_first_run: false,
             pane_is_stacked_over: frame_params.pane_is_stacked_over,
             pane_is_stacked_under: frame_params.pane_is_stacked_under,
@@ -110,6 +118,15 @@ impl PaneFrame {
             None => Some(ExitStatus::Exited),
         };
     }
+    pub fn add_last_command_exit_status(&mut self, exit_status: i32) {
+        self.last_command_exit_status = Some(exit_status);
+    }
+    pub fn indicate_piping_output(&mut self) {
+        self.is_piping_output = true;
+    }
+    pub fn indicate_alerts(&mut self, alerts: PaneAlerts) {
+        self.alerts = alerts;
+    }
     pub fn indicate_first_run(&mut self) {
         self.is_first_run = true;
     }
@@ -147,6 +164,152 @@ impl PaneFrame {
     fn render_title_right_side(
         &self,
         max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
+        // string and length because of color
+        let scroll_indication = self.render_scroll_indication(max_length);
+        let scroll_indication_len = scroll_indication.as_ref().map(|(_, len)| *len).unwrap_or(0);
+        let last_command_exit_status_indication =
+            self.render_last_command_exit_status(max_length.saturating_sub(scroll_indication_len));
+        let last_command_exit_status_indication_len = last_command_exit_status_indication
+            .as_ref()
+            .map(|(_, len)| *len)
+            .unwrap_or(0);
+        let piping_output_indication = self.render_piping_output_indication(
+            max_length
+                .saturating_sub(scroll_indication_len)
+                .saturating_sub(last_command_exit_status_indication_len),
+        );
+        let piping_output_indication_len = piping_output_indication
+            .as_ref()
+            .map(|(_, len)| *len)
+            .unwrap_or(0);
+        let alerts_indication = self.render_alerts_indication(
+            max_length
+                .saturating_sub(scroll_indication_len)
+                .saturating_sub(last_command_exit_status_indication_len)
+                .saturating_sub(piping_output_indication_len),
+        );
+        let mut right_side = vec![];
+        let mut right_side_len = 0;
+        for (mut indication, indication_len) in [
+            alerts_indication,
+            piping_output_indication,
+            last_command_exit_status_indication,
+            scroll_indication,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            right_side.append(&mut indication);
+            right_side_len += indication_len;
+        }
+        if right_side_len > 0 {
+            Some((right_side, right_side_len))
+        } else {
+            None
+        }
+    }
+    fn render_piping_output_indication(
+        &self,
+        max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
+        // string and length because of color
+        if !self.is_piping_output {
+            return None;
+        }
+        let full_indication = " PIPING ";
+        let short_indication = " P ";
+        let full_indication_len = full_indication.chars().count();
+        let short_indication_len = short_indication.chars().count();
+        if full_indication_len <= max_length {
+            Some((
+                foreground_color(full_indication, Some(self.style.colors.orange)),
+                full_indication_len,
+            ))
+        } else if short_indication_len <= max_length {
+            Some((
+                foreground_color(short_indication, Some(self.style.colors.orange)),
+                short_indication_len,
+            ))
+        } else {
+            None
+        }
+    }
+    fn render_alerts_indication(
+        &self,
+        max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
+        // string and length because of color
+        let alert_names: Vec<&str> = [
+            (self.alerts.bell, "BELL"),
+            (self.alerts.activity, "ACTIVITY"),
+            (self.alerts.silence, "SILENCE"),
+        ]
+        .iter()
+        .filter(|(is_alerted, _)| *is_alerted)
+        .map(|(_, alert_name)| *alert_name)
+        .collect();
+        if alert_names.is_empty() {
+            return None;
+        }
+        let full_indication = format!(" {} ", alert_names.join(" "));
+        let short_indication: String = alert_names
+            .iter()
+            .filter_map(|alert_name| alert_name.chars().next())
+            .collect();
+        let short_indication = format!(" {} ", short_indication);
+        let full_indication_len = full_indication.chars().count();
+        let short_indication_len = short_indication.chars().count();
+        if full_indication_len <= max_length {
+            Some((
+                foreground_color(&full_indication, Some(self.style.colors.red)),
+                full_indication_len,
+            ))
+        } else if short_indication_len <= max_length {
+            Some((
+                foreground_color(&short_indication, Some(self.style.colors.red)),
+                short_indication_len,
+            ))
+        } else {
+            None
+        }
+    }
+    fn render_last_command_exit_status(
+        &self,
+        max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
+        // string and length because of color
+        let exit_status = self.last_command_exit_status?;
+        let exit_status_color = if exit_status == 0 {
+            self.style.colors.green
+        } else {
+            self.style.colors.red
+        };
+        let prefix = " EXIT: ";
+        let exit_status_text = format!("{} ", exit_status);
+        let full_indication_len = prefix.chars().count() + exit_status_text.chars().count();
+        let short_indication_len = exit_status_text.chars().count() + 1;
+        if full_indication_len <= max_length {
+            let mut full_indication = foreground_color(prefix, self.color);
+            full_indication.append(&mut foreground_color(
+                &exit_status_text,
+                Some(exit_status_color),
+            ));
+            Some((full_indication, full_indication_len))
+        } else if short_indication_len <= max_length {
+            let mut short_indication = foreground_color(" ", self.color);
+            short_indication.append(&mut foreground_color(
+                &exit_status_text,
+                Some(exit_status_color),
+            ));
+            Some((short_indication, short_indication_len))
+        } else {
+            None
+        }
+    }
+    fn render_scroll_indication(
+        &self,
+        max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
         // string and length because of color
         if self.scroll_position.0 > 0 || self.scroll_position.1 > 0 {
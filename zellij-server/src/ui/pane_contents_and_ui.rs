@@ -174,6 +174,7 @@ impl<'a> PaneContentsAndUi<'a> {
         &mut self,
         client_id: ClientId,
         client_mode: InputMode,
+        client_style: Style,
         session_is_mirrored: bool,
     ) -> Result<()> {
         let err_context = || format!("failed to render pane frame for client {client_id}");
@@ -187,7 +188,8 @@ impl<'a> PaneContentsAndUi<'a> {
             .collect();
         let pane_focused_for_differet_client = !other_focused_clients.is_empty();
 
-        let frame_color = self.frame_color(client_id, client_mode, session_is_mirrored);
+        let frame_color =
+            self.frame_color(client_id, client_mode, client_style, session_is_mirrored);
         let focused_client = if pane_focused_for_client_id {
             Some(client_id)
         } else if pane_focused_for_differet_client {
@@ -200,7 +202,7 @@ impl<'a> PaneContentsAndUi<'a> {
                 focused_client,
                 is_main_client: pane_focused_for_client_id,
                 other_focused_clients: vec![],
-                style: self.style,
+                style: client_style,
                 color: frame_color,
                 other_cursors_exist_in_session: false,
                 pane_is_stacked_over: self.pane_is_stacked_over,
@@ -212,7 +214,7 @@ impl<'a> PaneContentsAndUi<'a> {
                 focused_client,
                 is_main_client: pane_focused_for_client_id,
                 other_focused_clients,
-                style: self.style,
+                style: client_style,
                 color: frame_color,
                 other_cursors_exist_in_session: self.multiple_users_exist_in_session,
                 pane_is_stacked_over: self.pane_is_stacked_over,
@@ -240,16 +242,18 @@ impl<'a> PaneContentsAndUi<'a> {
         &self,
         client_id: ClientId,
         client_mode: InputMode,
+        client_style: Style,
         boundaries: &mut Boundaries,
         session_is_mirrored: bool,
     ) {
-        let color = self.frame_color(client_id, client_mode, session_is_mirrored);
+        let color = self.frame_color(client_id, client_mode, client_style, session_is_mirrored);
         boundaries.add_rect(self.pane.as_ref(), color);
     }
     fn frame_color(
         &self,
         client_id: ClientId,
         mode: InputMode,
+        style: Style,
         session_is_mirrored: bool,
     ) -> Option<PaletteColor> {
         let pane_focused_for_client_id = self.focused_clients.contains(&client_id);
@@ -259,14 +263,14 @@ impl<'a> PaneContentsAndUi<'a> {
             match mode {
                 InputMode::Normal | InputMode::Locked => {
                     if session_is_mirrored || !self.multiple_users_exist_in_session {
-                        let colors = single_client_color(self.style.colors); // mirrored sessions only have one focused color
+                        let colors = single_client_color(style.colors); // mirrored sessions only have one focused color
                         Some(colors.0)
                     } else {
-                        let colors = client_id_to_colors(client_id, self.style.colors);
+                        let colors = client_id_to_colors(client_id, style.colors);
                         colors.map(|colors| colors.0)
                     }
                 },
-                _ => Some(self.style.colors.orange),
+                _ => Some(style.colors.orange),
             }
         } else {
             None
@@ -1,5 +1,6 @@
 use super::{screen_thread_main, CopyOptions, Screen, ScreenInstruction};
 use crate::panes::PaneId;
+use crate::tab::PaneAction;
 use crate::{
     channels::SenderWithContext,
     os_input_output::{AsyncReader, Pid, ServerOsApi},
@@ -12,11 +13,12 @@ use std::path::PathBuf;
 use zellij_utils::cli::CliAction;
 use zellij_utils::data::{Event, Resize, Style};
 use zellij_utils::errors::{prelude::*, ErrorContext};
-use zellij_utils::input::actions::Action;
+use zellij_utils::input::actions::{Action, ActionTarget};
 use zellij_utils::input::command::{RunCommand, TerminalAction};
+use zellij_utils::input::config::Config;
 use zellij_utils::input::layout::{
-    FloatingPaneLayout, Layout, Run, RunPlugin, RunPluginLocation, SplitDirection, SplitSize,
-    TiledPaneLayout,
+    FloatingPaneLayout, Layout, PaneMonitors, Run, RunPlugin, RunPluginLocation, SplitDirection,
+    SplitSize, TiledPaneLayout,
 };
 use zellij_utils::input::options::Options;
 use zellij_utils::ipc::IpcReceiverWithContext;
@@ -28,12 +30,18 @@ use std::env::set_var;
 use std::os::unix::io::RawFd;
 use std::sync::{Arc, Mutex};
 
-use crate::{plugins::PluginInstruction, pty::PtyInstruction};
+use crate::{
+    plugins::PluginInstruction,
+    pty::{ClientTabIndexOrPaneId, PtyInstruction},
+};
 use zellij_utils::ipc::PixelDimensions;
 
 use zellij_utils::{
     channels::{self, ChannelWithContext, Receiver},
-    data::{Direction, FloatingPaneCoordinates, InputMode, ModeInfo, Palette, PluginCapabilities},
+    data::{
+        Direction, FloatingPaneCoordinates, InputMode, ModeInfo, Palette, PaletteColor,
+        PluginCapabilities,
+    },
     interprocess::local_socket::LocalSocketStream,
     ipc::{ClientAttributes, ClientToServerMsg, ServerToClientMsg},
 };
@@ -247,7 +255,9 @@ fn create_new_screen(size: Size) -> Screen {
     let session_serialization = true;
     let serialize_pane_viewport = false;
     let scrollback_lines_to_serialize = None;
+    let serialize_pane_scrollback = false;
     let layout_dir = None;
+    let default_pane_monitors = PaneMonitors::default();
 
     let debug = false;
     let styled_underlines = true;
@@ -268,9 +278,11 @@ fn create_new_screen(size: Size) -> Screen {
         session_serialization,
         serialize_pane_viewport,
         scrollback_lines_to_serialize,
+        serialize_pane_scrollback,
         styled_underlines,
         arrow_fonts,
         layout_dir,
+        default_pane_monitors,
     );
     screen
 }
@@ -431,6 +443,9 @@ impl MockScreen {
             pty_writer_thread: None,
             background_jobs_thread: None,
             config_options: Default::default(),
+            cli_args: Default::default(),
+            themes: Default::default(),
+            _config_file_watcher: None,
             layout,
         }
     }
@@ -488,6 +503,9 @@ impl MockScreen {
             pty_writer_thread: None,
             background_jobs_thread: None,
             config_options: Default::default(),
+            cli_args: Default::default(),
+            themes: Default::default(),
+            _config_file_watcher: None,
             layout,
         };
 
@@ -654,6 +672,400 @@ pub fn switch_to_tab_name() {
     );
 }
 
+#[test]
+pub fn pane_output_is_no_longer_collected_once_unwatched() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    new_tab(&mut screen, 1, 0);
+    let pane_id = PaneId::Terminal(1);
+    screen.watch_pane(pane_id, 10);
+    screen.watch_pane(pane_id, 11);
+    let tab = screen.tabs.get_mut(&0).unwrap();
+    tab.handle_pty_bytes(1, b"watched\r\n".to_vec()).unwrap();
+    assert_eq!(tab.drain_committed_lines(1), vec![String::from("watched")]);
+
+    screen.unwatch_pane(pane_id, 10);
+    let tab = screen.tabs.get_mut(&0).unwrap();
+    tab.handle_pty_bytes(1, b"still watched\r\n".to_vec())
+        .unwrap();
+    assert_eq!(
+        tab.drain_committed_lines(1),
+        vec![String::from("still watched")],
+        "the pane is still watched by the other plugin"
+    );
+
+    // eg. when the plugin is unloaded
+    screen.unwatch_panes_of_plugin(11);
+    assert!(screen.pane_output_watchers.is_empty());
+    let tab = screen.tabs.get_mut(&0).unwrap();
+    tab.handle_pty_bytes(1, b"not watched\r\n".to_vec())
+        .unwrap();
+    assert!(tab.drain_committed_lines(1).is_empty());
+}
+
+#[test]
+pub fn plugins_are_told_about_opened_closed_and_exited_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut mock_screen = MockScreen::new(size);
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let command_pane_id = PaneId::Terminal(2);
+    let _ = mock_screen.to_screen.send(ScreenInstruction::NewPane(
+        command_pane_id,
+        None,
+        None,
+        false,
+        None,
+        None,
+        ClientTabIndexOrPaneId::ClientId(client_id),
+    ));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    // this is what pty sends when a command pane that is not held on close exits
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::CommandPaneExited(
+            command_pane_id,
+            Some(0),
+            RunCommand::default(),
+        ));
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ClosePane(command_pane_id, None));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let received_events: Vec<Event> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => Some(updates.clone()),
+            _ => None,
+        })
+        .flatten()
+        .map(|(_, _, event)| event)
+        .collect();
+    let terminal_2 = zellij_utils::data::PaneId::Terminal(2);
+    assert!(received_events
+        .iter()
+        .any(|event| *event == Event::PaneOpened(terminal_2)));
+    assert!(received_events
+        .iter()
+        .any(|event| *event == Event::CommandPaneExited(terminal_2, Some(0), Default::default())));
+    assert!(received_events
+        .iter()
+        .any(|event| *event == Event::PaneClosed(terminal_2)));
+}
+
+#[test]
+pub fn act_on_pane_in_another_tab_without_focusing_it() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    screen
+        .act_on_target(
+            ActionTarget::Pane(zellij_utils::data::PaneId::Terminal(1)),
+            PaneAction::Rename("renamed".as_bytes().to_vec()),
+            1,
+        )
+        .expect("TEST");
+
+    let first_tab = screen.tabs.values().find(|tab| tab.position == 0).unwrap();
+    assert_eq!(
+        first_tab.pane_infos()[0].title,
+        "renamed",
+        "Pane in the first tab was renamed"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab did not change"
+    );
+}
+
+#[test]
+pub fn act_on_focused_pane_of_tab_by_name() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    screen
+        .act_on_target(
+            ActionTarget::TabName("Tab #1".to_string()),
+            PaneAction::Rename("renamed".as_bytes().to_vec()),
+            1,
+        )
+        .expect("TEST");
+
+    let first_tab = screen.tabs.values().find(|tab| tab.position == 0).unwrap();
+    let second_tab = screen.tabs.values().find(|tab| tab.position == 1).unwrap();
+    assert_eq!(
+        first_tab.pane_infos()[0].title,
+        "renamed",
+        "Focused pane of the named tab was renamed"
+    );
+    assert_ne!(
+        second_tab.pane_infos()[0].title,
+        "renamed",
+        "Pane in the active tab was not renamed"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        1,
+        "Active tab did not change"
+    );
+}
+
+#[test]
+pub fn act_on_missing_target_is_an_error() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    assert!(screen
+        .act_on_target(ActionTarget::TabIndex(5), PaneAction::Clear, 1)
+        .is_err());
+}
+
+#[test]
+pub fn list_tabs_as_table() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    let listing = screen.list_tabs(false).expect("TEST");
+
+    assert_eq!(
+        listing,
+        vec![
+            "INDEX\tNAME\tACTIVE".to_owned(),
+            "1\tTab #2\tfalse".to_owned(),
+            "2\tTab #3\ttrue".to_owned(),
+        ],
+        "Tabs listed by position with the active one marked"
+    );
+}
+
+#[test]
+pub fn list_panes_as_json() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    new_tab(&mut screen, 2, 2);
+    let listing = screen.list_panes(true).expect("TEST");
+
+    assert_eq!(listing.len(), 1, "Json listing is a single line");
+    let panes: Vec<serde_json::Value> = serde_json::from_str(&listing[0]).expect("TEST");
+    assert_eq!(panes.len(), 2, "Panes of all tabs are listed");
+    assert_eq!(panes[0]["pane_id"], "terminal_1");
+    assert_eq!(panes[0]["tab_index"], 1);
+    assert_eq!(panes[1]["pane_id"], "terminal_2");
+    assert_eq!(panes[1]["tab_name"], "Tab #3");
+}
+
+#[test]
+pub fn dismiss_config_error() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    screen
+        .show_config_error("Failed to reload config".to_owned())
+        .expect("TEST");
+    assert!(screen.dismiss_notifications().expect("TEST"));
+    assert!(
+        screen.get_active_overlays_mut().is_empty(),
+        "Config error dismissed"
+    );
+    assert!(
+        !screen.dismiss_notifications().expect("TEST"),
+        "Nothing left to dismiss"
+    );
+}
+
+#[test]
+pub fn send_cli_write_chars_action_to_screen_while_showing_config_error() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ShowConfigError(
+            "Failed to reload config".to_owned(),
+        ));
+    let cli_action = CliAction::WriteChars {
+        chars: "input from the cli".into(),
+        target: Default::default(),
+    };
+    send_cli_action_to_server(&session_metadata, cli_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let written_to_pty = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|instruction| match instruction {
+            PtyWriteInstruction::Write(bytes, _) => bytes == b"input from the cli",
+            _ => false,
+        });
+    assert!(
+        written_to_pty,
+        "scripted writes are not taken to dismiss the config error"
+    );
+}
+
+#[test]
+pub fn reconfigure_screen_after_reloading_config() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    screen
+        .show_config_error("Failed to reload config".to_owned())
+        .expect("TEST");
+    assert_eq!(
+        screen.get_active_overlays_mut().len(),
+        1,
+        "Config error shown in an overlay"
+    );
+
+    let keybinds = Config::from_default_assets().expect("TEST").keybinds;
+    let style = Style {
+        rounded_corners: true,
+        ..Default::default()
+    };
+    let config_options = Options {
+        pane_frames: Some(false),
+        ..Default::default()
+    };
+    screen
+        .reconfigure(keybinds, style, config_options)
+        .expect("TEST");
+
+    assert!(
+        screen.get_active_overlays_mut().is_empty(),
+        "Config error removed once the config is valid"
+    );
+    assert_eq!(screen.style, style, "Screen style updated");
+    assert_eq!(
+        screen.get_active_tab(1).expect("TEST").style,
+        style,
+        "Tab style updated"
+    );
+    assert!(!screen.draw_pane_frames, "Pane frames option applied");
+    assert!(
+        !screen
+            .default_mode_info
+            .get_keybinds_for_mode(InputMode::Normal)
+            .is_empty(),
+        "New keybinds sent along with the mode info"
+    );
+}
+
+#[test]
+pub fn switch_theme_for_one_client_and_for_all_clients() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 1);
+    let client_palette = Palette {
+        orange: PaletteColor::Rgb((255, 0, 0)),
+        ..Default::default()
+    };
+    screen.switch_theme(client_palette, Some(1)).expect("TEST");
+    assert_eq!(
+        screen.mode_info.get(&1).map(|m| m.style.colors),
+        Some(client_palette),
+        "Theme switched for the client"
+    );
+    assert_ne!(
+        screen.style.colors, client_palette,
+        "Session theme not changed when switching the theme of one client"
+    );
+
+    let mut mode_info = ModeInfo::default();
+    mode_info.mode = InputMode::Pane;
+    screen.change_mode(mode_info, 1).expect("TEST");
+    assert_eq!(
+        screen.mode_info.get(&1).map(|m| m.style.colors),
+        Some(client_palette),
+        "Client keeps its theme when changing modes"
+    );
+
+    let session_palette = Palette {
+        orange: PaletteColor::Rgb((0, 255, 0)),
+        ..Default::default()
+    };
+    screen.switch_theme(session_palette, None).expect("TEST");
+    assert_eq!(
+        screen.style.colors, session_palette,
+        "Session theme switched"
+    );
+    assert_eq!(
+        screen.get_active_tab(1).expect("TEST").style.colors,
+        session_palette,
+        "Tab theme switched"
+    );
+    assert_eq!(
+        screen.mode_info.get(&1).map(|m| m.style.colors),
+        Some(session_palette),
+        "Theme switched for all clients"
+    );
+}
+
 #[test]
 pub fn close_tab() {
     let size = Size {
@@ -1194,6 +1606,45 @@ fn attach_after_first_tab_closed() {
     screen.add_client(1).expect("TEST");
 }
 
+#[test]
+fn read_only_client_follows_focus_of_another_client() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    screen.add_client(2).expect("TEST");
+    screen.following_clients.insert(2, 1);
+
+    screen.go_to_tab(1, 1).expect("TEST");
+    assert_eq!(
+        screen.active_tab_indices.get(&2),
+        screen.active_tab_indices.get(&1),
+        "Following client moved to the tab of the followed client"
+    );
+
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(3), None, None, None, None, Some(1))
+        .unwrap();
+    screen.render(None).expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(2).unwrap().get_active_pane_id(2),
+        Some(PaneId::Terminal(3)),
+        "Following client focused on the pane of the followed client"
+    );
+
+    screen.remove_client(1).expect("TEST");
+    assert!(
+        screen.following_clients.is_empty(),
+        "Nobody follows a client that left"
+    );
+}
+
 #[test]
 fn open_new_floating_pane_with_custom_coordinates() {
     let size = Size {
@@ -1286,6 +1737,7 @@ pub fn send_cli_write_chars_action_to_screen() {
     );
     let cli_action = CliAction::WriteChars {
         chars: "input from the cli".into(),
+        target: Default::default(),
     };
     send_cli_action_to_server(&session_metadata, cli_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
@@ -1312,6 +1764,7 @@ pub fn send_cli_write_action_to_screen() {
     );
     let cli_action = CliAction::Write {
         bytes: vec![102, 111, 111],
+        target: Default::default(),
     };
     send_cli_action_to_server(&session_metadata, cli_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
@@ -1339,6 +1792,7 @@ pub fn send_cli_resize_action_to_screen() {
     let resize_cli_action = CliAction::Resize {
         resize: Resize::Increase,
         direction: Some(Direction::Left),
+        target: Default::default(),
     };
     send_cli_action_to_server(&session_metadata, resize_cli_action, client_id);
     mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
@@ -1543,6 +1997,7 @@ pub fn send_cli_dump_screen_action() {
     let cli_action = CliAction::DumpScreen {
         path: PathBuf::from("/tmp/foo"),
         full: true,
+        target: Default::default(),
     };
     let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
         0,
@@ -1622,7 +2077,9 @@ pub fn send_cli_scroll_up_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let cli_action = CliAction::ScrollUp;
+    let cli_action = CliAction::ScrollUp {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1666,8 +2123,12 @@ pub fn send_cli_scroll_down_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let scroll_up_cli_action = CliAction::ScrollUp;
-    let scroll_down_cli_action = CliAction::ScrollDown;
+    let scroll_up_cli_action = CliAction::ScrollUp {
+        target: Default::default(),
+    };
+    let scroll_down_cli_action = CliAction::ScrollDown {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1716,8 +2177,12 @@ pub fn send_cli_scroll_to_bottom_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let scroll_up_cli_action = CliAction::ScrollUp;
-    let scroll_to_bottom_action = CliAction::ScrollToBottom;
+    let scroll_up_cli_action = CliAction::ScrollUp {
+        target: Default::default(),
+    };
+    let scroll_to_bottom_action = CliAction::ScrollToBottom {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1769,7 +2234,9 @@ pub fn send_cli_scroll_to_top_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let scroll_to_top_action = CliAction::ScrollToTop;
+    let scroll_to_top_action = CliAction::ScrollToTop {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1811,7 +2278,9 @@ pub fn send_cli_page_scroll_up_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let page_scroll_up_action = CliAction::PageScrollUp;
+    let page_scroll_up_action = CliAction::PageScrollUp {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1852,8 +2321,12 @@ pub fn send_cli_page_scroll_down_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let page_scroll_up_action = CliAction::PageScrollUp;
-    let page_scroll_down_action = CliAction::PageScrollDown;
+    let page_scroll_up_action = CliAction::PageScrollUp {
+        target: Default::default(),
+    };
+    let page_scroll_down_action = CliAction::PageScrollDown {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1900,7 +2373,9 @@ pub fn send_cli_half_page_scroll_up_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let half_page_scroll_up_action = CliAction::HalfPageScrollUp;
+    let half_page_scroll_up_action = CliAction::HalfPageScrollUp {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1941,8 +2416,12 @@ pub fn send_cli_half_page_scroll_down_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let half_page_scroll_up_action = CliAction::HalfPageScrollUp;
-    let half_page_scroll_down_action = CliAction::HalfPageScrollDown;
+    let half_page_scroll_up_action = CliAction::HalfPageScrollUp {
+        target: Default::default(),
+    };
+    let half_page_scroll_down_action = CliAction::HalfPageScrollDown {
+        target: Default::default(),
+    };
     let mut pane_contents = String::new();
     for i in 0..20 {
         pane_contents.push_str(&format!("fill pane up with something {}\n\r", i));
@@ -1997,7 +2476,9 @@ pub fn send_cli_toggle_full_screen_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let toggle_full_screen_action = CliAction::ToggleFullscreen;
+    let toggle_full_screen_action = CliAction::ToggleFullscreen {
+        target: Default::default(),
+    };
     send_cli_action_to_server(&session_metadata, toggle_full_screen_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
     mock_screen.teardown(vec![server_instruction, screen_thread]);
@@ -2067,6 +2548,7 @@ pub fn send_cli_toggle_active_tab_sync_action() {
     let cli_toggle_active_tab_sync_action = CliAction::ToggleActiveSyncTab;
     let cli_write_action = CliAction::Write {
         bytes: vec![102, 111, 111],
+        target: Default::default(),
     };
     send_cli_action_to_server(
         &session_metadata,
@@ -2495,7 +2977,9 @@ pub fn send_cli_close_pane_action() {
         ServerInstruction::KillSession,
         server_receiver
     );
-    let close_pane_action = CliAction::ClosePane;
+    let close_pane_action = CliAction::ClosePane {
+        target: Default::default(),
+    };
     send_cli_action_to_server(&session_metadata, close_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
     mock_screen.teardown(vec![server_instruction, screen_thread]);
@@ -2884,7 +3368,7 @@ pub fn send_cli_launch_or_focus_plugin_action() {
         floating: true,
         in_place: false,
         move_to_focused_tab: true,
-        url: url::Url::parse("file:/path/to/fake/plugin").unwrap(),
+        url: "file:/path/to/fake/plugin".to_owned(),
         configuration: Default::default(),
         skip_plugin_cache: false,
     };
@@ -2944,7 +3428,7 @@ pub fn send_cli_launch_or_focus_plugin_action_when_plugin_is_already_loaded() {
         floating: true,
         in_place: false,
         move_to_focused_tab: true,
-        url: url::Url::parse("file:/path/to/fake/plugin").unwrap(),
+        url: "file:/path/to/fake/plugin".to_owned(),
         configuration: Default::default(),
         skip_plugin_cache: false,
     };
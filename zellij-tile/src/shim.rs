@@ -132,7 +132,15 @@ pub fn open_terminal_in_place<P: AsRef<Path>>(path: P) {
 
 /// Open a new command pane with the specified command and args (this sort of pane allows the user to control the command, re-run it and see its exit status through the Zellij UI).
 pub fn open_command_pane(command_to_run: CommandToRun) {
-    let plugin_command = PluginCommand::OpenCommandPane(command_to_run);
+    open_command_pane_with_context(command_to_run, BTreeMap::new())
+}
+
+/// Like `open_command_pane`, the context will be sent back with the `CommandPaneExited` event.
+pub fn open_command_pane_with_context(
+    command_to_run: CommandToRun,
+    context: BTreeMap<String, String>,
+) {
+    let plugin_command = PluginCommand::OpenCommandPane(command_to_run, context);
     let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
     object_to_stdout(&protobuf_plugin_command.encode_to_vec());
     unsafe { host_run_plugin_command() };
@@ -143,7 +151,18 @@ pub fn open_command_pane_floating(
     command_to_run: CommandToRun,
     coordinates: Option<FloatingPaneCoordinates>,
 ) {
-    let plugin_command = PluginCommand::OpenCommandPaneFloating(command_to_run, coordinates);
+    open_command_pane_floating_with_context(command_to_run, coordinates, BTreeMap::new())
+}
+
+/// Like `open_command_pane_floating`, the context will be sent back with the `CommandPaneExited`
+/// event.
+pub fn open_command_pane_floating_with_context(
+    command_to_run: CommandToRun,
+    coordinates: Option<FloatingPaneCoordinates>,
+    context: BTreeMap<String, String>,
+) {
+    let plugin_command =
+        PluginCommand::OpenCommandPaneFloating(command_to_run, coordinates, context);
     let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
     object_to_stdout(&protobuf_plugin_command.encode_to_vec());
     unsafe { host_run_plugin_command() };
@@ -151,7 +170,16 @@ pub fn open_command_pane_floating(
 
 /// Open a new in place command pane with the specified command and args (this sort of pane allows the user to control the command, re-run it and see its exit status through the Zellij UI).
 pub fn open_command_pane_in_place(command_to_run: CommandToRun) {
-    let plugin_command = PluginCommand::OpenCommandPaneInPlace(command_to_run);
+    open_command_pane_in_place_with_context(command_to_run, BTreeMap::new())
+}
+
+/// Like `open_command_pane_in_place`, the context will be sent back with the `CommandPaneExited`
+/// event.
+pub fn open_command_pane_in_place_with_context(
+    command_to_run: CommandToRun,
+    context: BTreeMap<String, String>,
+) {
+    let plugin_command = PluginCommand::OpenCommandPaneInPlace(command_to_run, context);
     let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
     object_to_stdout(&protobuf_plugin_command.encode_to_vec());
     unsafe { host_run_plugin_command() };
@@ -765,6 +793,45 @@ where
     unsafe { host_run_plugin_command() };
 }
 
+/// Request the contents of a pane (optionally including its scrollback and ANSI styling), the
+/// result will be sent to the plugin as an `Event::PaneContents`
+pub fn get_pane_contents(pane_id: PaneId, include_scrollback: bool, with_ansi: bool) {
+    let plugin_command = PluginCommand::GetPaneContents {
+        pane_id,
+        include_scrollback,
+        with_ansi,
+    };
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Start receiving the lines printed in a pane as `Event::PaneOutput` (the plugin also needs to
+/// subscribe to `EventType::PaneOutput`)
+pub fn watch_pane(pane_id: PaneId) {
+    let plugin_command = PluginCommand::WatchPane(pane_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Stop receiving the lines printed in a pane, as started with `watch_pane`
+pub fn unwatch_pane(pane_id: PaneId) {
+    let plugin_command = PluginCommand::UnwatchPane(pane_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Switch to one of the themes defined in the user's config, either for a single client or (if
+/// `client_id` is `None`) for all clients of the session
+pub fn set_theme(theme_name: impl Into<String>, client_id: Option<ClientId>) {
+    let plugin_command = PluginCommand::SetTheme(theme_name.into(), client_id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 // Utility Functions
 
 #[allow(unused)]
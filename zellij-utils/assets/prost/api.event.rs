@@ -11,7 +11,7 @@ pub struct Event {
     pub name: i32,
     #[prost(
         oneof = "event::Payload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23"
     )]
     pub payload: ::core::option::Option<event::Payload>,
 }
@@ -48,10 +48,56 @@ pub mod event {
         RunCommandResultPayload(super::RunCommandResultPayload),
         #[prost(message, tag = "15")]
         WebRequestResultPayload(super::WebRequestResultPayload),
+        #[prost(message, tag = "16")]
+        PaneContentsPayload(super::PaneContentsPayload),
+        #[prost(message, tag = "17")]
+        PaneOutputPayload(super::PaneContentsPayload),
+        #[prost(message, tag = "18")]
+        PaneOpenedPayload(super::PaneIdPayload),
+        #[prost(message, tag = "19")]
+        PaneClosedPayload(super::PaneIdPayload),
+        #[prost(message, tag = "20")]
+        CommandPaneExitedPayload(super::CommandPaneExitedPayload),
+        #[prost(message, tag = "21")]
+        PaneBellPayload(super::PaneIdPayload),
+        #[prost(message, tag = "22")]
+        PaneActivityPayload(super::PaneIdPayload),
+        #[prost(message, tag = "23")]
+        PaneSilencePayload(super::PaneIdPayload),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaneIdPayload {
+    #[prost(uint32, tag = "1")]
+    pub pane_id: u32,
+    #[prost(bool, tag = "2")]
+    pub is_plugin: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CommandPaneExitedPayload {
+    #[prost(uint32, tag = "1")]
+    pub pane_id: u32,
+    #[prost(bool, tag = "2")]
+    pub is_plugin: bool,
+    #[prost(int32, optional, tag = "3")]
+    pub exit_code: ::core::option::Option<i32>,
+    #[prost(message, repeated, tag = "4")]
+    pub context: ::prost::alloc::vec::Vec<ContextItem>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PaneContentsPayload {
+    #[prost(uint32, tag = "1")]
+    pub pane_id: u32,
+    #[prost(bool, tag = "2")]
+    pub is_plugin: bool,
+    #[prost(string, repeated, tag = "3")]
+    pub lines: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SessionUpdatePayload {
     #[prost(message, repeated, tag = "1")]
     pub session_manifests: ::prost::alloc::vec::Vec<SessionManifest>,
@@ -238,6 +284,18 @@ pub struct PaneInfo {
     pub plugin_url: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(bool, tag = "22")]
     pub is_selectable: bool,
+    #[prost(string, optional, tag = "23")]
+    pub cwd: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "24")]
+    pub pid: ::core::option::Option<u32>,
+    #[prost(string, repeated, tag = "25")]
+    pub foreground_process: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "26")]
+    pub bell_alert: bool,
+    #[prost(bool, tag = "27")]
+    pub activity_alert: bool,
+    #[prost(bool, tag = "28")]
+    pub silence_alert: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -262,6 +320,12 @@ pub struct TabInfo {
     pub active_swap_layout_name: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(bool, tag = "10")]
     pub is_swap_layout_dirty: bool,
+    #[prost(bool, tag = "11")]
+    pub bell_alert: bool,
+    #[prost(bool, tag = "12")]
+    pub activity_alert: bool,
+    #[prost(bool, tag = "13")]
+    pub silence_alert: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -276,6 +340,10 @@ pub struct ModeUpdatePayload {
     pub arrow_fonts_support: bool,
     #[prost(string, optional, tag = "5")]
     pub session_name: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "6")]
+    pub pending_key_sequence: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "7")]
+    pub is_read_only: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -330,6 +398,20 @@ pub enum EventType {
     SessionUpdate = 16,
     RunCommandResult = 17,
     WebRequestResult = 18,
+    /// / The contents of a pane, as requested by the plugin
+    PaneContents = 19,
+    /// / New lines printed in a pane watched by the plugin
+    PaneOutput = 20,
+    PaneOpened = 21,
+    PaneClosed = 22,
+    /// / The command of a command pane exited
+    CommandPaneExited = 23,
+    /// / A pane that isn't focused rang the bell
+    PaneBell = 24,
+    /// / A pane monitored for activity had output
+    PaneActivity = 25,
+    /// / A pane monitored for silence went quiet
+    PaneSilence = 26,
 }
 impl EventType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -357,6 +439,14 @@ impl EventType {
             EventType::SessionUpdate => "SessionUpdate",
             EventType::RunCommandResult => "RunCommandResult",
             EventType::WebRequestResult => "WebRequestResult",
+            EventType::PaneContents => "PaneContents",
+            EventType::PaneOutput => "PaneOutput",
+            EventType::PaneOpened => "PaneOpened",
+            EventType::PaneClosed => "PaneClosed",
+            EventType::CommandPaneExited => "CommandPaneExited",
+            EventType::PaneBell => "PaneBell",
+            EventType::PaneActivity => "PaneActivity",
+            EventType::PaneSilence => "PaneSilence",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -381,6 +471,14 @@ impl EventType {
             "SessionUpdate" => Some(Self::SessionUpdate),
             "RunCommandResult" => Some(Self::RunCommandResult),
             "WebRequestResult" => Some(Self::WebRequestResult),
+            "PaneContents" => Some(Self::PaneContents),
+            "PaneOutput" => Some(Self::PaneOutput),
+            "PaneOpened" => Some(Self::PaneOpened),
+            "PaneClosed" => Some(Self::PaneClosed),
+            "CommandPaneExited" => Some(Self::CommandPaneExited),
+            "PaneBell" => Some(Self::PaneBell),
+            "PaneActivity" => Some(Self::PaneActivity),
+            "PaneSilence" => Some(Self::PaneSilence),
             _ => None,
         }
     }
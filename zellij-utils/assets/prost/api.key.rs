@@ -3,6 +3,11 @@
 pub struct Key {
     #[prost(enumeration = "key::KeyModifier", optional, tag = "1")]
     pub modifier: ::core::option::Option<i32>,
+    /// set for combinations of modifiers that `modifier` cannot express (eg. Ctrl Shift t), as the
+    /// bits of the kitty keyboard protocol (Shift 1, Alt 2, Ctrl 4, Super 8), main_key is then the
+    /// bare key and chars are unicode code points
+    #[prost(uint32, optional, tag = "4")]
+    pub key_modifiers: ::core::option::Option<u32>,
     #[prost(oneof = "key::MainKey", tags = "2, 3")]
     pub main_key: ::core::option::Option<key::MainKey>,
 }
@@ -82,6 +87,7 @@ pub mod key {
         F12 = 22,
         Tab = 23,
         Esc = 24,
+        Enter = 25,
     }
     impl NamedKey {
         /// String value of the enum field names used in the ProtoBuf definition.
@@ -115,6 +121,7 @@ pub mod key {
                 NamedKey::F12 => "F12",
                 NamedKey::Tab => "Tab",
                 NamedKey::Esc => "Esc",
+                NamedKey::Enter => "Enter",
             }
         }
         /// Creates an enum from field names used in the ProtoBuf definition.
@@ -145,6 +152,7 @@ pub mod key {
                 "F12" => Some(Self::F12),
                 "Tab" => Some(Self::Tab),
                 "Esc" => Some(Self::Esc),
+                "Enter" => Some(Self::Enter),
                 _ => None,
             }
         }
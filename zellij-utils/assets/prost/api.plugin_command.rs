@@ -5,7 +5,7 @@ pub struct PluginCommand {
     pub name: i32,
     #[prost(
         oneof = "plugin_command::Payload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 60"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 60, 61, 62, 63, 64"
     )]
     pub payload: ::core::option::Option<plugin_command::Payload>,
 }
@@ -114,10 +114,36 @@ pub mod plugin_command {
         MessageToPluginPayload(super::MessageToPluginPayload),
         #[prost(message, tag = "60")]
         KillSessionsPayload(super::KillSessionsPayload),
+        #[prost(message, tag = "61")]
+        GetPaneContentsPayload(super::GetPaneContentsPayload),
+        #[prost(message, tag = "62")]
+        WatchPanePayload(super::PaneId),
+        #[prost(message, tag = "63")]
+        SetThemePayload(super::SetThemePayload),
+        #[prost(message, tag = "64")]
+        UnwatchPanePayload(super::PaneId),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetThemePayload {
+    #[prost(string, tag = "1")]
+    pub theme_name: ::prost::alloc::string::String,
+    #[prost(uint32, optional, tag = "2")]
+    pub client_id: ::core::option::Option<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPaneContentsPayload {
+    #[prost(message, optional, tag = "1")]
+    pub pane_id: ::core::option::Option<PaneId>,
+    #[prost(bool, tag = "2")]
+    pub include_scrollback: bool,
+    #[prost(bool, tag = "3")]
+    pub with_ansi: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct KillSessionsPayload {
     #[prost(string, repeated, tag = "1")]
     pub session_names: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
@@ -219,6 +245,8 @@ pub struct OpenCommandPanePayload {
     pub command_to_run: ::core::option::Option<super::command::Command>,
     #[prost(message, optional, tag = "2")]
     pub floating_pane_coordinates: ::core::option::Option<FloatingPaneCoordinates>,
+    #[prost(message, repeated, tag = "3")]
+    pub context: ::prost::alloc::vec::Vec<ContextItem>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -412,6 +440,10 @@ pub enum CommandName {
     MessageToPlugin = 79,
     DisconnectOtherClients = 80,
     KillSessions = 81,
+    GetPaneContents = 82,
+    WatchPane = 83,
+    SetTheme = 84,
+    UnwatchPane = 85,
 }
 impl CommandName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -502,6 +534,10 @@ impl CommandName {
             CommandName::MessageToPlugin => "MessageToPlugin",
             CommandName::DisconnectOtherClients => "DisconnectOtherClients",
             CommandName::KillSessions => "KillSessions",
+            CommandName::GetPaneContents => "GetPaneContents",
+            CommandName::WatchPane => "WatchPane",
+            CommandName::SetTheme => "SetTheme",
+            CommandName::UnwatchPane => "UnwatchPane",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -589,6 +625,10 @@ impl CommandName {
             "MessageToPlugin" => Some(Self::MessageToPlugin),
             "DisconnectOtherClients" => Some(Self::DisconnectOtherClients),
             "KillSessions" => Some(Self::KillSessions),
+            "GetPaneContents" => Some(Self::GetPaneContents),
+            "WatchPane" => Some(Self::WatchPane),
+            "SetTheme" => Some(Self::SetTheme),
+            "UnwatchPane" => Some(Self::UnwatchPane),
             _ => None,
         }
     }
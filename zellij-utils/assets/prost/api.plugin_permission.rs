@@ -10,6 +10,7 @@ pub enum PermissionType {
     WebAccess = 6,
     ReadCliPipes = 7,
     MessageAndLaunchOtherPlugins = 8,
+    ReadPaneContents = 9,
 }
 impl PermissionType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -29,6 +30,7 @@ impl PermissionType {
             PermissionType::MessageAndLaunchOtherPlugins => {
                 "MessageAndLaunchOtherPlugins"
             }
+            PermissionType::ReadPaneContents => "ReadPaneContents",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -43,6 +45,7 @@ impl PermissionType {
             "WebAccess" => Some(Self::WebAccess),
             "ReadCliPipes" => Some(Self::ReadCliPipes),
             "MessageAndLaunchOtherPlugins" => Some(Self::MessageAndLaunchOtherPlugins),
+            "ReadPaneContents" => Some(Self::ReadPaneContents),
             _ => None,
         }
     }
@@ -1,13 +1,16 @@
-use crate::data::{Direction, InputMode, Resize};
+use crate::data::{Direction, InputMode, PaneId, Resize};
 use crate::setup::Setup;
 use crate::{
     consts::{ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV},
-    input::{layout::PluginUserConfiguration, options::CliOptions},
+    input::{
+        actions::{Action, ActionTarget, CopyModeMotion, CopyModeSelection},
+        layout::PluginUserConfiguration,
+        options::CliOptions,
+    },
 };
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use url::Url;
 
 fn validate_session(name: &str) -> Result<String, String> {
     #[cfg(unix)]
@@ -130,6 +133,14 @@ pub enum Sessions {
         /// If resurrecting a dead session, immediately run all its commands on startup
         #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
         force_run_commands: bool,
+
+        /// Attach as a spectator: follow the session without being able to change it
+        #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
+        read_only: bool,
+
+        /// When attaching read-only, follow the focus of this client (see list-clients for the ids)
+        #[clap(long, value_parser, requires("read_only"))]
+        follow: Option<u16>,
     },
 
     /// Kill a specific session
@@ -233,7 +244,8 @@ pub enum Sessions {
     /// Load a plugin
     #[clap(visible_alias = "r")]
     Plugin {
-        /// Plugin URL, can either start with http(s), file: or zellij:
+        /// Plugin URL, can either start with http(s), file: or zellij: or be the name of a plugin
+        /// alias from the config
         #[clap(last(true), required(true))]
         url: String,
 
@@ -365,20 +377,73 @@ tail -f /tmp/my-live-logfile | zellij pipe --name logs --plugin https://example.
     },
 }
 
+/// The pane a cli action should be performed on instead of the focused one. The pane is not
+/// focused by the action, except for toggle-fullscreen which focuses the pane until fullscreen is
+/// toggled off again.
+#[derive(Args, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliActionTarget {
+    /// The id of the pane to act on (eg. terminal_3 or plugin_2, a bare number is a terminal)
+    #[clap(long, value_parser, conflicts_with_all(&["tab_index", "tab_name"]))]
+    pub pane_id: Option<PaneId>,
+
+    /// Act on the focused pane of the tab with this index (starting at 1, as in go-to-tab)
+    #[clap(long, value_parser, conflicts_with("tab_name"))]
+    pub tab_index: Option<usize>,
+
+    /// Act on the focused pane of the tab with this name
+    #[clap(long, value_parser)]
+    pub tab_name: Option<String>,
+}
+
+impl CliActionTarget {
+    pub fn is_empty(&self) -> bool {
+        self.action_target().is_none()
+    }
+    pub fn action_target(&self) -> Option<ActionTarget> {
+        if let Some(pane_id) = self.pane_id {
+            Some(ActionTarget::Pane(pane_id))
+        } else if let Some(tab_index) = self.tab_index {
+            Some(ActionTarget::TabIndex(tab_index))
+        } else {
+            self.tab_name.clone().map(ActionTarget::TabName)
+        }
+    }
+    /// Wrap the actions so that they are performed on the target (if there is one) rather than
+    /// on the focused pane
+    pub fn apply_to(&self, actions: Vec<Action>) -> Vec<Action> {
+        match self.action_target() {
+            Some(target) => actions
+                .into_iter()
+                .map(|action| Action::ActOnTarget(target.clone(), Box::new(action)))
+                .collect(),
+            None => actions,
+        }
+    }
+}
+
 #[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
 pub enum CliAction {
     /// Write bytes to the terminal.
     Write {
         bytes: Vec<u8>,
+
+        #[clap(flatten)]
+        target: CliActionTarget,
     },
     /// Write characters to the terminal.
     WriteChars {
         chars: String,
+
+        #[clap(flatten)]
+        target: CliActionTarget,
     },
     /// [increase|decrease] the focused panes area at the [left|down|up|right] border.
     Resize {
         resize: Resize,
         direction: Option<Direction>,
+
+        #[clap(flatten)]
+        target: CliActionTarget,
     },
     /// Change focus to the next pane
     FocusNextPane,
@@ -401,7 +466,10 @@ pub enum CliAction {
     /// Rotate the location of the previous pane backwards
     MovePaneBackwards,
     /// Clear all buffers for a focused pane
-    Clear,
+    Clear {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Dump the focused pane to a file
     DumpScreen {
         path: PathBuf,
@@ -409,29 +477,103 @@ pub enum CliAction {
         /// Dump the pane with full scrollback
         #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
         full: bool,
+
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
+    /// Keep piping everything the focused pane prints to a file or to the stdin of a command,
+    /// run it with neither of them to stop piping the pane
+    PipePane {
+        /// Append the output of the pane to this file
+        #[clap(short, long, value_parser, conflicts_with("command"))]
+        file: Option<PathBuf>,
+
+        /// Write the output of the pane to the stdin of this command (run with `sh -c`)
+        #[clap(short, long, value_parser)]
+        command: Option<String>,
+
+        /// Strip ANSI escape sequences (colors, cursor movements, etc.) from the output
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        strip_ansi: bool,
+
+        #[clap(flatten)]
+        target: CliActionTarget,
     },
     /// Dump current layout to stdout
     DumpLayout,
     /// Open the pane scrollback in your default editor
     EditScrollback,
     /// Scroll up in the focused pane
-    ScrollUp,
+    ScrollUp {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll down in focus pane.
-    ScrollDown,
+    ScrollDown {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll down to bottom in focus pane.
-    ScrollToBottom,
+    ScrollToBottom {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll up to top in focus pane.
-    ScrollToTop,
+    ScrollToTop {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
+    /// Scroll up to the previous shell prompt in focus pane (needs shell integration).
+    ScrollToPreviousPrompt {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
+    /// Scroll down to the next shell prompt in focus pane (needs shell integration).
+    ScrollToNextPrompt {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll up one page in focus pane.
-    PageScrollUp,
+    PageScrollUp {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll down one page in focus pane.
-    PageScrollDown,
+    PageScrollDown {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll up half page in focus pane.
-    HalfPageScrollUp,
+    HalfPageScrollUp {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Scroll down half page in focus pane.
-    HalfPageScrollDown,
+    HalfPageScrollDown {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
+    /// Move the copy mode cursor in the focused pane
+    /// [left|down|up|right|word-forward|word-backward|word-end|line-start|line-end|top|bottom|search-next|search-previous]
+    CopyModeMotion {
+        motion: CopyModeMotion,
+    },
+    /// Start, change or stop the copy mode selection [char|line|block]
+    CopyModeToggleSelection {
+        selection: CopyModeSelection,
+    },
+    /// Copy the copy mode selection of the focused pane to the clipboard
+    CopyModeYank,
+    /// Select the output of the last command in the focused pane (needs shell integration)
+    SelectLastCommandOutput,
+    /// Copy the output of the last command in the focused pane to the clipboard (needs shell
+    /// integration)
+    CopyLastCommandOutput,
     /// Toggle between fullscreen focus pane and normal layout.
-    ToggleFullscreen,
+    ToggleFullscreen {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Toggle frames around panes in the UI
     TogglePaneFrames,
     /// Toggle between sending text commands to all panes on the current tab and normal mode.
@@ -563,10 +705,16 @@ pub enum CliAction {
     /// Toggle the visibility of all floating panes in the current Tab, open one if none exist
     ToggleFloatingPanes,
     /// Close the focused pane.
-    ClosePane,
+    ClosePane {
+        #[clap(flatten)]
+        target: CliActionTarget,
+    },
     /// Renames the focused pane
     RenamePane {
         name: String,
+
+        #[clap(flatten)]
+        target: CliActionTarget,
     },
     /// Remove a previously set pane name
     UndoRenamePane,
@@ -615,6 +763,35 @@ pub enum CliAction {
     NextSwapLayout,
     /// Query all tab names
     QueryTabNames,
+    /// List the tabs of the session
+    ListTabs {
+        /// Print the tabs as JSON
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        json: bool,
+    },
+    /// List the panes of the session (including their ids, titles, geometry, command, cwd and
+    /// exit status)
+    ListPanes {
+        /// Print the panes as JSON
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        json: bool,
+    },
+    /// List the clients attached to the session
+    ListClients {
+        /// Print the clients as JSON
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        json: bool,
+    },
+    /// Re-read the config file and apply it to the running session
+    ReloadConfig,
+    /// Switch to one of the themes defined in the config, for all clients or a single one
+    SetTheme {
+        /// The name of the theme
+        name: String,
+        /// Only switch the theme of this client (see list-clients for the ids)
+        #[clap(short, long, value_parser)]
+        client: Option<u16>,
+    },
     StartOrReloadPlugin {
         url: String,
         #[clap(short, long, value_parser)]
@@ -627,7 +804,7 @@ pub enum CliAction {
         in_place: bool,
         #[clap(short, long, value_parser)]
         move_to_focused_tab: bool,
-        url: Url,
+        url: String,
         #[clap(short, long, value_parser)]
         configuration: Option<PluginUserConfiguration>,
         #[clap(short, long, value_parser)]
@@ -638,7 +815,7 @@ pub enum CliAction {
         floating: bool,
         #[clap(short, long, value_parser)]
         in_place: bool,
-        url: Url,
+        url: String,
         #[clap(short, long, value_parser)]
         configuration: Option<PluginUserConfiguration>,
         #[clap(short, long, value_parser)]
@@ -41,7 +41,9 @@ pub fn single_client_color(colors: Palette) -> (PaletteColor, PaletteColor) {
 // representations instead.
 // NOTE: Do not reorder the key variants since that influences what the `status_bar` plugin
 // displays!
+// non_exhaustive so that plugins matching on keys keep compiling as more keys become expressible
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+#[non_exhaustive]
 pub enum Key {
     PageDown,
     PageUp,
@@ -61,94 +63,13 @@ pub enum Key {
     BackTab,
     Null,
     Esc,
+    WithModifiers(KeyWithModifier), // combinations that cannot be expressed by the variants above
 }
 
 impl FromStr for Key {
     type Err = Box<dyn std::error::Error>;
     fn from_str(key_str: &str) -> Result<Self, Self::Err> {
-        let mut modifier: Option<&str> = None;
-        let mut main_key: Option<&str> = None;
-        for (index, part) in key_str.split_ascii_whitespace().enumerate() {
-            if index == 0 && (part == "Ctrl" || part == "Alt") {
-                modifier = Some(part);
-            } else if main_key.is_none() {
-                main_key = Some(part)
-            }
-        }
-        match (modifier, main_key) {
-            (Some("Ctrl"), Some(main_key)) => {
-                let mut key_chars = main_key.chars();
-                let key_count = main_key.chars().count();
-                if key_count == 1 {
-                    let key_char = key_chars.next().unwrap();
-                    Ok(Key::Ctrl(key_char))
-                } else {
-                    Err(format!("Failed to parse key: {}", key_str).into())
-                }
-            },
-            (Some("Alt"), Some(main_key)) => {
-                match main_key {
-                    // why crate::data::Direction and not just Direction?
-                    // Because it's a different type that we export in this wasm mandated soup - we
-                    // don't like it either! This will be solved as we chip away at our tech-debt
-                    "Left" => Ok(Key::Alt(CharOrArrow::Direction(Direction::Left))),
-                    "Right" => Ok(Key::Alt(CharOrArrow::Direction(Direction::Right))),
-                    "Up" => Ok(Key::Alt(CharOrArrow::Direction(Direction::Up))),
-                    "Down" => Ok(Key::Alt(CharOrArrow::Direction(Direction::Down))),
-                    _ => {
-                        let mut key_chars = main_key.chars();
-                        let key_count = main_key.chars().count();
-                        if key_count == 1 {
-                            let key_char = key_chars.next().unwrap();
-                            Ok(Key::Alt(CharOrArrow::Char(key_char)))
-                        } else {
-                            Err(format!("Failed to parse key: {}", key_str).into())
-                        }
-                    },
-                }
-            },
-            (None, Some(main_key)) => match main_key {
-                "Backspace" => Ok(Key::Backspace),
-                "Left" => Ok(Key::Left),
-                "Right" => Ok(Key::Right),
-                "Up" => Ok(Key::Up),
-                "Down" => Ok(Key::Down),
-                "Home" => Ok(Key::Home),
-                "End" => Ok(Key::End),
-                "PageUp" => Ok(Key::PageUp),
-                "PageDown" => Ok(Key::PageDown),
-                "Tab" => Ok(Key::BackTab),
-                "Delete" => Ok(Key::Delete),
-                "Insert" => Ok(Key::Insert),
-                "Space" => Ok(Key::Char(' ')),
-                "Enter" => Ok(Key::Char('\n')),
-                "Esc" => Ok(Key::Esc),
-                _ => {
-                    let mut key_chars = main_key.chars();
-                    let key_count = main_key.chars().count();
-                    if key_count == 1 {
-                        let key_char = key_chars.next().unwrap();
-                        Ok(Key::Char(key_char))
-                    } else if key_count > 1 {
-                        if let Some(first_char) = key_chars.next() {
-                            if first_char == 'F' {
-                                let f_index: String = key_chars.collect();
-                                let f_index: u8 = f_index
-                                    .parse()
-                                    .map_err(|e| format!("Failed to parse F index: {}", e))?;
-                                if f_index >= 1 && f_index <= 12 {
-                                    return Ok(Key::F(f_index));
-                                }
-                            }
-                        }
-                        Err(format!("Failed to parse key: {}", key_str).into())
-                    } else {
-                        Err(format!("Failed to parse key: {}", key_str).into())
-                    }
-                },
-            },
-            _ => Err(format!("Failed to parse key: {}", key_str).into()),
-        }
+        KeyWithModifier::from_str(key_str).map(Key::from)
     }
 }
 
@@ -178,6 +99,7 @@ impl fmt::Display for Key {
             Key::Ctrl(c) => write!(f, "Ctrl+{}", Key::Char(*c)),
             Key::Null => write!(f, "NULL"),
             Key::Esc => write!(f, "ESC"),
+            Key::WithModifiers(key_with_modifier) => write!(f, "{}", key_with_modifier),
         }
     }
 }
@@ -198,6 +120,363 @@ impl fmt::Display for CharOrArrow {
     }
 }
 
+/// A key without its modifiers
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+pub enum BareKey {
+    PageDown,
+    PageUp,
+    Left,
+    Down,
+    Up,
+    Right,
+    Home,
+    End,
+    Backspace,
+    Delete,
+    Insert,
+    F(u8),
+    Char(char),
+    Tab,
+    Esc,
+    Enter,
+}
+
+impl FromStr for BareKey {
+    type Err = Box<dyn std::error::Error>;
+    fn from_str(key_str: &str) -> Result<Self, Self::Err> {
+        match key_str {
+            "Backspace" => Ok(BareKey::Backspace),
+            "Left" => Ok(BareKey::Left),
+            "Right" => Ok(BareKey::Right),
+            "Up" => Ok(BareKey::Up),
+            "Down" => Ok(BareKey::Down),
+            "Home" => Ok(BareKey::Home),
+            "End" => Ok(BareKey::End),
+            "PageUp" => Ok(BareKey::PageUp),
+            "PageDown" => Ok(BareKey::PageDown),
+            "Tab" => Ok(BareKey::Tab),
+            "Delete" => Ok(BareKey::Delete),
+            "Insert" => Ok(BareKey::Insert),
+            "Space" => Ok(BareKey::Char(' ')),
+            "Enter" => Ok(BareKey::Enter),
+            "Esc" => Ok(BareKey::Esc),
+            _ => {
+                let mut key_chars = key_str.chars();
+                match (key_chars.next(), key_str.chars().count()) {
+                    (Some(key_char), 1) => Ok(BareKey::Char(key_char)),
+                    (Some('F'), _) => {
+                        let f_index: String = key_chars.collect();
+                        let f_index: u8 = f_index
+                            .parse()
+                            .map_err(|e| format!("Failed to parse F index: {}", e))?;
+                        if f_index >= 1 && f_index <= 12 {
+                            Ok(BareKey::F(f_index))
+                        } else {
+                            Err(format!("Failed to parse key: {}", key_str).into())
+                        }
+                    },
+                    _ => Err(format!("Failed to parse key: {}", key_str).into()),
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for BareKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BareKey::PageDown => write!(f, "{}", Key::PageDown),
+            BareKey::PageUp => write!(f, "{}", Key::PageUp),
+            BareKey::Left => write!(f, "{}", Key::Left),
+            BareKey::Down => write!(f, "{}", Key::Down),
+            BareKey::Up => write!(f, "{}", Key::Up),
+            BareKey::Right => write!(f, "{}", Key::Right),
+            BareKey::Home => write!(f, "{}", Key::Home),
+            BareKey::End => write!(f, "{}", Key::End),
+            BareKey::Backspace => write!(f, "{}", Key::Backspace),
+            BareKey::Delete => write!(f, "{}", Key::Delete),
+            BareKey::Insert => write!(f, "{}", Key::Insert),
+            BareKey::F(n) => write!(f, "{}", Key::F(*n)),
+            BareKey::Char(c) => write!(f, "{}", Key::Char(*c)),
+            BareKey::Tab => write!(f, "TAB"),
+            BareKey::Esc => write!(f, "{}", Key::Esc),
+            BareKey::Enter => write!(f, "ENTER"),
+        }
+    }
+}
+
+/// Key modifiers as bitflags, using the same bits as the kitty keyboard protocol (which encodes
+/// them on the wire as these bits plus one)
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord,
+)]
+pub struct KeyModifiers(u8);
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers(0);
+    pub const SHIFT: KeyModifiers = KeyModifiers(0b0001);
+    pub const ALT: KeyModifiers = KeyModifiers(0b0010);
+    pub const CTRL: KeyModifiers = KeyModifiers(0b0100);
+    pub const SUPER: KeyModifiers = KeyModifiers(0b1000);
+
+    /// Unknown bits (eg. the kitty protocol's Caps Lock and Num Lock states) are dropped
+    pub fn from_bits_truncate(bits: u8) -> Self {
+        KeyModifiers(bits & 0b1111)
+    }
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    pub fn contains(&self, other: KeyModifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn insert(&mut self, other: KeyModifiers) {
+        self.0 |= other.0;
+    }
+    pub fn remove(&mut self, other: KeyModifiers) {
+        self.0 &= !other.0;
+    }
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Ctrl" => Some(KeyModifiers::CTRL),
+            "Alt" => Some(KeyModifiers::ALT),
+            "Shift" => Some(KeyModifiers::SHIFT),
+            "Super" => Some(KeyModifiers::SUPER),
+            _ => None,
+        }
+    }
+}
+
+impl std::ops::BitOr for KeyModifiers {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        KeyModifiers(self.0 | other.0)
+    }
+}
+
+impl fmt::Display for KeyModifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CTRL, "Ctrl"),
+            (KeyModifiers::ALT, "Alt"),
+            (KeyModifiers::SHIFT, "Shift"),
+            (KeyModifiers::SUPER, "Super"),
+        ] {
+            if self.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A key along with any combination of modifiers.
+///
+/// Text keys are kept the way the kitty keyboard protocol reports them: Shift on its own is
+/// folded into the character (Shift a is `A`), but alongside other modifiers it is kept and the
+/// character is lowercase (Ctrl Shift t).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+pub struct KeyWithModifier {
+    pub bare_key: BareKey,
+    pub key_modifiers: KeyModifiers,
+}
+
+impl KeyWithModifier {
+    pub fn new(bare_key: BareKey, key_modifiers: KeyModifiers) -> Self {
+        KeyWithModifier {
+            bare_key,
+            key_modifiers,
+        }
+        .normalized()
+    }
+    fn normalized(mut self) -> Self {
+        if let BareKey::Char(c) = self.bare_key {
+            if self.key_modifiers == KeyModifiers::SHIFT {
+                self.bare_key = BareKey::Char(c.to_uppercase().next().unwrap_or(c));
+                self.key_modifiers = KeyModifiers::NONE;
+            } else if self.key_modifiers.contains(KeyModifiers::SHIFT) {
+                self.bare_key = BareKey::Char(c.to_lowercase().next().unwrap_or(c));
+            } else if self.key_modifiers.contains(KeyModifiers::CTRL) {
+                // terminals without the kitty keyboard protocol cannot tell Ctrl a and Ctrl A apart
+                self.bare_key = BareKey::Char(c.to_lowercase().next().unwrap_or(c));
+            }
+        }
+        self
+    }
+    /// The bytes a terminal without the kitty keyboard protocol would send for this key, or None
+    /// if it cannot send it at all
+    pub fn serialize_non_kitty(&self) -> Option<Vec<u8>> {
+        self.serialize(false)
+    }
+    /// The bytes a terminal with the "disambiguate escape codes" kitty keyboard enhancement
+    /// would send for this key
+    pub fn serialize_kitty(&self) -> Option<Vec<u8>> {
+        self.serialize(true)
+    }
+    fn serialize(&self, kitty: bool) -> Option<Vec<u8>> {
+        let modifiers = self.key_modifiers;
+        // as used on the wire by both xterm (for the keys it supports) and kitty
+        let encoded_modifiers = modifiers.bits() + 1;
+        let csi_with_modifiers = |number: u32, terminator: char| {
+            if modifiers.is_empty() {
+                format!("\u{1b}[{}{}", number, terminator).into_bytes()
+            } else {
+                format!("\u{1b}[{};{}{}", number, encoded_modifiers, terminator).into_bytes()
+            }
+        };
+        let cursor_key = |terminator: char| {
+            if modifiers.is_empty() {
+                format!("\u{1b}[{}", terminator).into_bytes()
+            } else {
+                format!("\u{1b}[1;{}{}", encoded_modifiers, terminator).into_bytes()
+            }
+        };
+        let kitty_key = |codepoint: u32| csi_with_modifiers(codepoint, 'u');
+        let legacy_key = |bytes: &[u8]| {
+            let mut encoded = vec![];
+            if modifiers.contains(KeyModifiers::ALT) {
+                encoded.push(0x1b);
+            }
+            encoded.extend_from_slice(bytes);
+            encoded
+        };
+        let bytes = match self.bare_key {
+            BareKey::Up => cursor_key('A'),
+            BareKey::Down => cursor_key('B'),
+            BareKey::Right => cursor_key('C'),
+            BareKey::Left => cursor_key('D'),
+            BareKey::End => cursor_key('F'),
+            BareKey::Home => cursor_key('H'),
+            BareKey::Insert => csi_with_modifiers(2, '~'),
+            BareKey::Delete => csi_with_modifiers(3, '~'),
+            BareKey::PageUp => csi_with_modifiers(5, '~'),
+            BareKey::PageDown => csi_with_modifiers(6, '~'),
+            BareKey::F(n @ 1..=4) => {
+                let terminator = (b'P' + n - 1) as char;
+                if modifiers.is_empty() {
+                    format!("\u{1b}O{}", terminator).into_bytes()
+                } else {
+                    cursor_key(terminator)
+                }
+            },
+            BareKey::F(n @ 5..=12) => {
+                let number = match n {
+                    5 => 15,
+                    6 => 17,
+                    7 => 18,
+                    8 => 19,
+                    9 => 20,
+                    10 => 21,
+                    11 => 23,
+                    _ => 24,
+                };
+                csi_with_modifiers(number, '~')
+            },
+            BareKey::F(_) => return None,
+            BareKey::Esc if kitty => kitty_key(27),
+            BareKey::Esc => legacy_key(&[0x1b]),
+            BareKey::Enter if kitty && !modifiers.is_empty() => kitty_key(13),
+            BareKey::Enter => legacy_key(&[b'\r']),
+            BareKey::Tab if kitty && !modifiers.is_empty() => kitty_key(9),
+            BareKey::Tab if modifiers.contains(KeyModifiers::SHIFT) => b"\x1b[Z".to_vec(),
+            BareKey::Tab => legacy_key(&[b'\t']),
+            BareKey::Backspace if kitty && !modifiers.is_empty() => kitty_key(127),
+            BareKey::Backspace if modifiers.contains(KeyModifiers::CTRL) => legacy_key(&[0x08]),
+            BareKey::Backspace => legacy_key(&[0x7f]),
+            BareKey::Char(c) if kitty && !modifiers.is_empty() => kitty_key(c as u32),
+            BareKey::Char(c) => {
+                let c = if modifiers.contains(KeyModifiers::SHIFT) {
+                    c.to_uppercase().next().unwrap_or(c)
+                } else {
+                    c
+                };
+                if modifiers.contains(KeyModifiers::CTRL) {
+                    match c {
+                        'a'..='z' | 'A'..='Z' => legacy_key(&[c.to_ascii_lowercase() as u8 & 0x1f]),
+                        ' ' | '@' | '2' => legacy_key(&[0]),
+                        '[' | '3' => legacy_key(&[0x1b]),
+                        '\\' | '4' => legacy_key(&[0x1c]),
+                        ']' | '5' => legacy_key(&[0x1d]),
+                        '^' | '6' => legacy_key(&[0x1e]),
+                        '_' | '/' | '7' => legacy_key(&[0x1f]),
+                        '?' | '8' => legacy_key(&[0x7f]),
+                        _ => legacy_key(c.to_string().as_bytes()),
+                    }
+                } else {
+                    legacy_key(c.to_string().as_bytes())
+                }
+            },
+        };
+        Some(bytes)
+    }
+}
+
+impl FromStr for KeyWithModifier {
+    type Err = Box<dyn std::error::Error>;
+    fn from_str(key_str: &str) -> Result<Self, Self::Err> {
+        // eg. "Ctrl Shift t", "Alt Ctrl h" or "Super Enter"
+        let mut key_modifiers = KeyModifiers::NONE;
+        let mut bare_key = None;
+        for part in key_str.split_ascii_whitespace() {
+            if bare_key.is_some() {
+                break;
+            }
+            match KeyModifiers::from_name(part) {
+                Some(modifier) => key_modifiers.insert(modifier),
+                None => bare_key = Some(BareKey::from_str(part)?),
+            }
+        }
+        match bare_key {
+            Some(bare_key) => Ok(KeyWithModifier::new(bare_key, key_modifiers)),
+            None => Err(format!("Failed to parse key: {}", key_str).into()),
+        }
+    }
+}
+
+impl fmt::Display for KeyWithModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.key_modifiers, self.bare_key)
+    }
+}
+
+impl From<KeyWithModifier> for Key {
+    fn from(key_with_modifier: KeyWithModifier) -> Self {
+        let KeyWithModifier {
+            bare_key,
+            key_modifiers,
+        } = key_with_modifier;
+        match (key_modifiers, bare_key) {
+            (KeyModifiers::NONE, BareKey::PageDown) => Key::PageDown,
+            (KeyModifiers::NONE, BareKey::PageUp) => Key::PageUp,
+            (KeyModifiers::NONE, BareKey::Left) => Key::Left,
+            (KeyModifiers::NONE, BareKey::Down) => Key::Down,
+            (KeyModifiers::NONE, BareKey::Up) => Key::Up,
+            (KeyModifiers::NONE, BareKey::Right) => Key::Right,
+            (KeyModifiers::NONE, BareKey::Home) => Key::Home,
+            (KeyModifiers::NONE, BareKey::End) => Key::End,
+            (KeyModifiers::NONE, BareKey::Backspace) => Key::Backspace,
+            (KeyModifiers::NONE, BareKey::Delete) => Key::Delete,
+            (KeyModifiers::NONE, BareKey::Insert) => Key::Insert,
+            (KeyModifiers::NONE, BareKey::F(n)) => Key::F(n),
+            (KeyModifiers::NONE, BareKey::Char(c)) => Key::Char(c),
+            (KeyModifiers::NONE, BareKey::Tab) => Key::BackTab,
+            (KeyModifiers::NONE, BareKey::Esc) => Key::Esc,
+            (KeyModifiers::NONE, BareKey::Enter) => Key::Char('\n'),
+            (KeyModifiers::CTRL, BareKey::Char(c)) => Key::Ctrl(c),
+            (KeyModifiers::ALT, BareKey::Char(c)) => Key::Alt(CharOrArrow::Char(c)),
+            (KeyModifiers::ALT, BareKey::Left) => Key::Alt(CharOrArrow::Direction(Direction::Left)),
+            (KeyModifiers::ALT, BareKey::Right) => {
+                Key::Alt(CharOrArrow::Direction(Direction::Right))
+            },
+            (KeyModifiers::ALT, BareKey::Up) => Key::Alt(CharOrArrow::Direction(Direction::Up)),
+            (KeyModifiers::ALT, BareKey::Down) => Key::Alt(CharOrArrow::Direction(Direction::Down)),
+            _ => Key::WithModifiers(key_with_modifier),
+        }
+    }
+}
+
 #[derive(Eq, Clone, Copy, Debug, PartialEq, Hash, Deserialize, Serialize, PartialOrd, Ord)]
 pub enum Direction {
     Left,
@@ -509,9 +788,26 @@ pub enum Event {
         Vec<u8>,
         BTreeMap<String, String>,
     ), // status,
-       // headers,
-       // body,
-       // context
+    // headers,
+    // body,
+    // context
+    /// The contents of a pane, as requested with `get_pane_contents`
+    PaneContents(PaneId, Vec<String>), // one String per line
+    /// New lines printed in a pane, as watched with `watch_pane`
+    PaneOutput(PaneId, Vec<String>), // one String per line
+    /// A pane was opened
+    PaneOpened(PaneId),
+    /// A pane was closed
+    PaneClosed(PaneId),
+    /// The command of a command pane exited, along with the context given to `open_command_pane`
+    /// (command panes opened by a plugin are only reported to that plugin)
+    CommandPaneExited(PaneId, Option<i32>, BTreeMap<String, String>), // exit status, context
+    /// A pane that isn't focused rang the bell
+    PaneBell(PaneId),
+    /// A pane that isn't focused and is monitored for activity had output
+    PaneActivity(PaneId),
+    /// A pane that is monitored for silence had no output for the configured number of seconds
+    PaneSilence(PaneId),
 }
 
 #[derive(
@@ -541,6 +837,7 @@ pub enum Permission {
     WebAccess,
     ReadCliPipes,
     MessageAndLaunchOtherPlugins,
+    ReadPaneContents,
 }
 
 impl PermissionType {
@@ -561,6 +858,9 @@ impl PermissionType {
             PermissionType::MessageAndLaunchOtherPlugins => {
                 "Send messages to and launch other plugins".to_owned()
             },
+            PermissionType::ReadPaneContents => {
+                "Read the contents of panes (including their scrollback)".to_owned()
+            },
         }
     }
 }
@@ -637,6 +937,10 @@ pub enum InputMode {
     /// `Tmux` mode allows for basic tmux keybindings functionality
     #[serde(alias = "tmux")]
     Tmux,
+    /// `Copy` mode allows moving a cursor over the scroll buffer of a pane and selecting text with
+    /// the keyboard.
+    #[serde(alias = "copy")]
+    Copy,
 }
 
 impl Default for InputMode {
@@ -685,6 +989,7 @@ impl FromStr for InputMode {
             "move" | "Move" => Ok(InputMode::Move),
             "prompt" | "Prompt" => Ok(InputMode::Prompt),
             "tmux" | "Tmux" => Ok(InputMode::Tmux),
+            "copy" | "Copy" => Ok(InputMode::Copy),
             "entersearch" | "Entersearch" | "EnterSearch" => Ok(InputMode::EnterSearch),
             e => Err(ConversionError::UnknownInputMode(e.into())),
         }
@@ -742,6 +1047,8 @@ pub struct ModeInfo {
     pub style: Style,
     pub capabilities: PluginCapabilities,
     pub session_name: Option<String>,
+    pub pending_key_sequence: Vec<String>, // the keys typed so far of a multi-key keybinding
+    pub is_read_only: bool,                // the client is attached as a spectator
 }
 
 impl ModeInfo {
@@ -837,6 +1144,12 @@ pub struct TabInfo {
     pub active_swap_layout_name: Option<String>,
     /// Whether the user manually changed the layout, moving out of the swap layout scheme
     pub is_swap_layout_dirty: bool,
+    /// Whether a pane in this tab rang the bell since it was last focused
+    pub bell_alert: bool,
+    /// Whether a pane in this tab monitored for activity had output since it was last focused
+    pub activity_alert: bool,
+    /// Whether a pane in this tab monitored for silence went quiet since it was last focused
+    pub silence_alert: bool,
 }
 
 /// The `PaneManifest` contains a dictionary of panes, indexed by the tab position (0 indexed).
@@ -902,6 +1215,20 @@ pub struct PaneInfo {
     /// Unselectable panes are often used for UI elements that do not have direct user interaction
     /// (eg. the default `status-bar` or `tab-bar`).
     pub is_selectable: bool,
+    /// The working directory of a terminal pane, as reported by its shell (if the shell supports
+    /// it) or otherwise as read from the system
+    pub cwd: Option<PathBuf>,
+    /// The pid of the process a terminal pane was started with (usually its shell)
+    pub pid: Option<u32>,
+    /// The command and arguments of the process currently running in the foreground of a terminal
+    /// pane (eg. `["vim", "Cargo.toml"]`), if it is not the pane's shell itself
+    pub foreground_process: Option<Vec<String>>,
+    /// Whether this pane rang the bell since it was last focused
+    pub bell_alert: bool,
+    /// Whether this pane had output since it was last focused, if it is monitored for activity
+    pub activity_alert: bool,
+    /// Whether this pane went quiet since it was last focused, if it is monitored for silence
+    pub silence_alert: bool,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -1025,12 +1352,39 @@ pub struct NewPluginArgs {
     pub skip_cache: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PaneId {
     Terminal(u32),
     Plugin(u32),
 }
 
+impl fmt::Display for PaneId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaneId::Terminal(id) => write!(f, "terminal_{}", id),
+            PaneId::Plugin(id) => write!(f, "plugin_{}", id),
+        }
+    }
+}
+
+impl FromStr for PaneId {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_id = |id: &str| {
+            id.parse::<u32>()
+                .map_err(|_| format!("failed to parse pane id. Unknown id '{}'", s))
+        };
+        if let Some(id) = s.strip_prefix("terminal_") {
+            Ok(PaneId::Terminal(parse_id(id)?))
+        } else if let Some(id) = s.strip_prefix("plugin_") {
+            Ok(PaneId::Plugin(parse_id(id)?))
+        } else {
+            // a bare number is a terminal pane id, as in the ZELLIJ_PANE_ID environment variable
+            Ok(PaneId::Terminal(parse_id(s)?))
+        }
+    }
+}
+
 impl MessageToPlugin {
     pub fn new(message_name: impl Into<String>) -> Self {
         MessageToPlugin {
@@ -1260,8 +1614,12 @@ pub enum PluginCommand {
     OpenFileFloating(FileToOpen, Option<FloatingPaneCoordinates>),
     OpenTerminal(FileToOpen), // only used for the path as cwd
     OpenTerminalFloating(FileToOpen, Option<FloatingPaneCoordinates>), // only used for the path as cwd
-    OpenCommandPane(CommandToRun),
-    OpenCommandPaneFloating(CommandToRun, Option<FloatingPaneCoordinates>),
+    OpenCommandPane(CommandToRun, BTreeMap<String, String>),           // BTreeMap is the context
+    OpenCommandPaneFloating(
+        CommandToRun,
+        Option<FloatingPaneCoordinates>,
+        BTreeMap<String, String>, // context
+    ),
     SwitchTabTo(u32), // tab index
     SetTimeout(f64),  // seconds
     ExecCmd(Vec<String>),
@@ -1323,7 +1681,7 @@ pub enum PluginCommand {
     DeleteAllDeadSessions,           // String -> session name
     OpenTerminalInPlace(FileToOpen), // only used for the path as cwd
     OpenFileInPlace(FileToOpen),
-    OpenCommandPaneInPlace(CommandToRun),
+    OpenCommandPaneInPlace(CommandToRun, BTreeMap<String, String>), // BTreeMap is the context
     RunCommand(
         Vec<String>,              // command
         BTreeMap<String, String>, // env_variables
@@ -1344,4 +1702,12 @@ pub enum PluginCommand {
     MessageToPlugin(MessageToPlugin),
     DisconnectOtherClients,
     KillSessions(Vec<String>), // one or more session names
+    GetPaneContents {
+        pane_id: PaneId,
+        include_scrollback: bool,
+        with_ansi: bool,
+    },
+    WatchPane(PaneId),
+    UnwatchPane(PaneId),
+    SetTheme(String, Option<ClientId>), // String -> theme name, None -> all clients
 }
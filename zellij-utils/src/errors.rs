@@ -222,6 +222,7 @@ pub enum ScreenContext {
     HorizontalSplit,
     VerticalSplit,
     WriteCharacter,
+    WriteKey,
     ResizeIncreaseAll,
     ResizeIncreaseLeft,
     ResizeIncreaseDown,
@@ -257,6 +258,7 @@ pub enum ScreenContext {
     Exit,
     ClearScreen,
     DumpScreen,
+    PipePaneOutput,
     DumpLayout,
     EditScrollback,
     ScrollUp,
@@ -265,6 +267,8 @@ pub enum ScreenContext {
     ScrollDownAt,
     ScrollToBottom,
     ScrollToTop,
+    ScrollToPreviousPrompt,
+    ScrollToNextPrompt,
     PageScrollUp,
     PageScrollDown,
     HalfPageScrollUp,
@@ -280,6 +284,7 @@ pub enum ScreenContext {
     SetFixedWidth,
     ClosePane,
     HoldPane,
+    CommandPaneExited,
     UpdatePaneName,
     UndoRenamePane,
     NewTab,
@@ -298,6 +303,9 @@ pub enum ScreenContext {
     TerminalBackgroundColor,
     TerminalForegroundColor,
     TerminalColorRegisters,
+    TerminalSupportsKittyGraphics,
+    ClientColorDepth,
+    CheckPaneMonitors,
     ChangeMode,
     ChangeModeForAllClients,
     LeftClick,
@@ -312,6 +320,7 @@ pub enum ScreenContext {
     Copy,
     ToggleTab,
     AddClient,
+    FollowClient,
     RemoveClient,
     AddOverlay,
     RemoveOverlay,
@@ -323,6 +332,12 @@ pub enum ScreenContext {
     SearchToggleCaseSensitivity,
     SearchToggleWholeWord,
     SearchToggleWrap,
+    SearchToggleRegex,
+    CopyModeMotion,
+    CopyModeToggleSelection,
+    CopyModeYank,
+    SelectLastCommandOutput,
+    CopyLastCommandOutput,
     AddRedPaneFrameColorOverride,
     ClearPaneFrameColorOverride,
     PreviousSwapLayout,
@@ -347,10 +362,23 @@ pub enum ScreenContext {
     BreakPaneRight,
     BreakPaneLeft,
     UpdateSessionInfos,
+    UpdateTerminalProcessInfo,
     ReplacePane,
     NewInPlacePluginPane,
     DumpLayoutToHd,
     RenameSession,
+    GetPaneContents,
+    WatchPane,
+    UnwatchPane,
+    UnwatchPanesOfPlugin,
+    ActOnTarget,
+    ListTabs,
+    ListPanes,
+    ListClients,
+    Reconfigure,
+    ShowConfigError,
+    SwitchTheme,
+    KittyGraphicsFileRead,
 }
 
 /// Stack call representations corresponding to the different types of [`PtyInstruction`]s.
@@ -371,6 +399,9 @@ pub enum PtyContext {
     DumpLayout,
     LogLayoutToHd,
     FillPluginCwd,
+    UpdateReportedCwd,
+    ReportTerminalProcessInfo,
+    ListPanes,
     Exit,
 }
 
@@ -400,6 +431,7 @@ pub enum PluginContext {
     CachePluginEvents,
     MessageFromPlugin,
     UnblockCliPipes,
+    RefreshTerminalProcessInfo,
 }
 
 /// Stack call representations corresponding to the different types of [`ClientInstruction`]s.
@@ -422,6 +454,7 @@ pub enum ClientContext {
     SetSynchronisedOutput,
     UnblockCliPipeInput,
     CliPipeOutput,
+    Reconfigure,
 }
 
 /// Stack call representations corresponding to the different types of [`ServerInstruction`]s.
@@ -445,6 +478,10 @@ pub enum ServerContext {
     CliPipeOutput,
     AssociatePipeWithClient,
     DisconnectAllClientsExcept,
+    ListClients,
+    ReloadConfig,
+    SwitchTheme,
+    TerminalBackgroundColor,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -464,8 +501,10 @@ pub enum BackgroundJobContext {
     ReadAllSessionInfosOnMachine,
     ReportSessionInfo,
     ReportLayoutInfo,
+    ReportPanesMonitoredForSilence,
     RunCommand,
     WebRequest,
+    ReadKittyGraphicsFile,
     Exit,
 }
 
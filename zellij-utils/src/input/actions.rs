@@ -6,7 +6,7 @@ use super::layout::{
     TiledPaneLayout,
 };
 use crate::cli::CliAction;
-use crate::data::{Direction, Resize};
+use crate::data::{ClientId, Direction, Key, KeyWithModifier, PaneId, Resize};
 use crate::data::{FloatingPaneCoordinates, InputMode};
 use crate::home::{find_default_config_dir, get_layout_dir};
 use crate::input::config::{Config, ConfigError, KdlError};
@@ -21,6 +21,23 @@ use std::str::FromStr;
 
 use crate::position::Position;
 
+/// The pane an action is performed on when it should not be performed on the focused pane
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ActionTarget {
+    Pane(PaneId),
+    TabIndex(usize), // the focused pane of this tab, starting at 1
+    TabName(String), // the focused pane of this tab
+}
+
+/// Where the output of a pane is piped to
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PipePaneTarget {
+    /// Appended to this file
+    File(PathBuf),
+    /// Written to the stdin of this command (run with `sh -c`)
+    Command(String),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum ResizeDirection {
     Left,
@@ -73,6 +90,7 @@ pub enum SearchOption {
     CaseSensitivity,
     WholeWord,
     Wrap,
+    Regex,
 }
 
 impl FromStr for SearchOption {
@@ -84,6 +102,7 @@ impl FromStr for SearchOption {
             },
             "WholeWord" | "wholeword" | "Wholeword" => Ok(SearchOption::WholeWord),
             "Wrap" | "wrap" => Ok(SearchOption::Wrap),
+            "Regex" | "regex" => Ok(SearchOption::Regex),
             _ => Err(format!(
                 "Failed to parse SearchOption. Unknown SearchOption: {}",
                 s
@@ -92,6 +111,72 @@ impl FromStr for SearchOption {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CopyModeMotion {
+    Left,
+    Down,
+    Up,
+    Right,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    LineStart,
+    LineEnd,
+    Top,
+    Bottom,
+    SearchNext,
+    SearchPrevious,
+}
+
+impl FromStr for CopyModeMotion {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Left" | "left" => Ok(CopyModeMotion::Left),
+            "Down" | "down" => Ok(CopyModeMotion::Down),
+            "Up" | "up" => Ok(CopyModeMotion::Up),
+            "Right" | "right" => Ok(CopyModeMotion::Right),
+            "WordForward" | "wordforward" | "word-forward" => Ok(CopyModeMotion::WordForward),
+            "WordBackward" | "wordbackward" | "word-backward" => Ok(CopyModeMotion::WordBackward),
+            "WordEnd" | "wordend" | "word-end" => Ok(CopyModeMotion::WordEnd),
+            "LineStart" | "linestart" | "line-start" => Ok(CopyModeMotion::LineStart),
+            "LineEnd" | "lineend" | "line-end" => Ok(CopyModeMotion::LineEnd),
+            "Top" | "top" => Ok(CopyModeMotion::Top),
+            "Bottom" | "bottom" => Ok(CopyModeMotion::Bottom),
+            "SearchNext" | "searchnext" | "search-next" => Ok(CopyModeMotion::SearchNext),
+            "SearchPrevious" | "searchprevious" | "search-previous" => {
+                Ok(CopyModeMotion::SearchPrevious)
+            },
+            _ => Err(format!(
+                "Failed to parse CopyModeMotion. Unknown CopyModeMotion: {}",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CopyModeSelection {
+    Char,
+    Line,
+    Block,
+}
+
+impl FromStr for CopyModeSelection {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Char" | "char" => Ok(CopyModeSelection::Char),
+            "Line" | "line" => Ok(CopyModeSelection::Line),
+            "Block" | "block" => Ok(CopyModeSelection::Block),
+            _ => Err(format!(
+                "Failed to parse CopyModeSelection. Unknown CopyModeSelection: {}",
+                s
+            )),
+        }
+    }
+}
+
 // As these actions are bound to the default config, please
 // do take care when refactoring - or renaming.
 // They might need to be adjusted in the default config
@@ -105,8 +190,15 @@ pub enum Action {
     Write(Vec<u8>),
     /// Write Characters to the terminal.
     WriteChars(String),
+    /// Write a key press to the terminal, encoded the way the application in the pane asked for
+    /// (the raw bytes as they were read, and whether they are encoded with the kitty keyboard
+    /// protocol).
+    WriteKey(KeyWithModifier, Vec<u8>, bool),
     /// Switch to the specified input mode.
     SwitchToMode(InputMode),
+    /// Show the keys typed so far of a multi-key keybinding in the given input mode (none once
+    /// the sequence completes or is abandoned).
+    PendingKeySequence(InputMode, Vec<Key>),
     /// Switch all connected clients to the specified input mode.
     SwitchModeForAllClients(InputMode),
     /// Shrink/enlarge focused pane at specified border
@@ -126,6 +218,9 @@ pub enum Action {
     ClearScreen,
     /// Dumps the screen to a file
     DumpScreen(String, bool),
+    /// Keep piping the bytes printed by the focused pane to the target (or stop doing so if
+    /// there is none), bool is whether ANSI escape sequences are stripped from them
+    PipePaneOutput(Option<PipePaneTarget>, bool),
     /// Dumps
     DumpLayout,
     /// Scroll up in focus pane.
@@ -141,6 +236,10 @@ pub enum Action {
     ScrollToBottom,
     /// Scroll up to top in focus pane.
     ScrollToTop,
+    /// Scroll up to the previous shell prompt in focus pane.
+    ScrollToPreviousPrompt,
+    /// Scroll down to the next shell prompt in focus pane.
+    ScrollToNextPrompt,
     /// Scroll up one page in focus pane.
     PageScrollUp,
     /// Scroll down one page in focus pane.
@@ -234,17 +333,37 @@ pub enum Action {
     Deny,
     /// Confirm an action that invokes a prompt automatically
     SkipConfirm(Box<Action>),
+    /// Perform a pane action on the target pane without focusing it
+    ActOnTarget(ActionTarget, Box<Action>),
     /// Search for String
     SearchInput(Vec<u8>),
     /// Search for something
     Search(SearchDirection),
     /// Toggle case sensitivity of search
     SearchToggleOption(SearchOption),
+    /// Move the copy mode cursor in the focused pane
+    CopyModeMotion(CopyModeMotion),
+    /// Start, change or stop the copy mode selection at the copy mode cursor
+    CopyModeToggleSelection(CopyModeSelection),
+    /// Copy the copy mode selection to the clipboard
+    CopyModeYank,
+    /// Select the output of the last command in the focused pane
+    SelectLastCommandOutput,
+    /// Copy the output of the last command in the focused pane to the clipboard
+    CopyLastCommandOutput,
     ToggleMouseMode,
     PreviousSwapLayout,
     NextSwapLayout,
     /// Query all tab names
     QueryTabNames,
+    /// List the tabs, panes or clients of the session (bool is whether to list them as JSON)
+    ListTabs(bool),
+    ListPanes(bool),
+    ListClients(bool),
+    /// Re-read the config file and apply it to the running session
+    ReloadConfig,
+    /// Switch to one of the loaded themes, for a single client or (if None) for all of them
+    SwitchTheme(String, Option<ClientId>),
     /// Open a new tiled (embedded, non-floating) plugin pane
     NewTiledPluginPane(RunPlugin, Option<String>, bool, Option<PathBuf>), // String is an optional name, bool is
     // skip_cache, Option<PathBuf> is cwd
@@ -287,6 +406,28 @@ pub enum Action {
 }
 
 impl Action {
+    /// Whether a read-only (spectator) client may perform this action: it can change its own input
+    /// mode, query the session and detach, but not change the session (which includes scrolling,
+    /// since panes are scrolled for every client, and for the same reason copy mode and searching)
+    pub fn is_allowed_for_read_only_client(&self) -> bool {
+        match self {
+            Action::SwitchToMode(input_mode) => !matches!(
+                input_mode,
+                InputMode::Copy | InputMode::EnterSearch | InputMode::Search
+            ),
+            action => matches!(
+                action,
+                Action::Quit
+                    | Action::Detach
+                    | Action::NoOp
+                    | Action::PendingKeySequence(..)
+                    | Action::QueryTabNames
+                    | Action::ListTabs(..)
+                    | Action::ListPanes(..)
+                    | Action::ListClients(..)
+            ),
+        }
+    }
     /// Checks that two Action are match except their mutable attributes.
     pub fn shallow_eq(&self, other_action: &Action) -> bool {
         match (self, other_action) {
@@ -303,31 +444,75 @@ impl Action {
         config: Option<Config>,
     ) -> Result<Vec<Action>, String> {
         match cli_action {
-            CliAction::Write { bytes } => Ok(vec![Action::Write(bytes)]),
-            CliAction::WriteChars { chars } => Ok(vec![Action::WriteChars(chars)]),
-            CliAction::Resize { resize, direction } => Ok(vec![Action::Resize(resize, direction)]),
+            CliAction::Write { bytes, target } => Ok(target.apply_to(vec![Action::Write(bytes)])),
+            CliAction::WriteChars { chars, target } => {
+                Ok(target.apply_to(vec![Action::WriteChars(chars)]))
+            },
+            CliAction::Resize {
+                resize,
+                direction,
+                target,
+            } => Ok(target.apply_to(vec![Action::Resize(resize, direction)])),
             CliAction::FocusNextPane => Ok(vec![Action::FocusNextPane]),
             CliAction::FocusPreviousPane => Ok(vec![Action::FocusPreviousPane]),
             CliAction::MoveFocus { direction } => Ok(vec![Action::MoveFocus(direction)]),
             CliAction::MoveFocusOrTab { direction } => Ok(vec![Action::MoveFocusOrTab(direction)]),
             CliAction::MovePane { direction } => Ok(vec![Action::MovePane(direction)]),
             CliAction::MovePaneBackwards => Ok(vec![Action::MovePaneBackwards]),
-            CliAction::Clear => Ok(vec![Action::ClearScreen]),
-            CliAction::DumpScreen { path, full } => Ok(vec![Action::DumpScreen(
-                path.as_os_str().to_string_lossy().into(),
-                full,
-            )]),
+            CliAction::Clear { target } => Ok(target.apply_to(vec![Action::ClearScreen])),
+            CliAction::DumpScreen { path, full, target } => {
+                Ok(target.apply_to(vec![Action::DumpScreen(
+                    path.as_os_str().to_string_lossy().into(),
+                    full,
+                )]))
+            },
+            CliAction::PipePane {
+                file,
+                command,
+                strip_ansi,
+                target,
+            } => {
+                let pipe_pane_target = match (file, command) {
+                    (Some(file), _) => Some(PipePaneTarget::File(get_current_dir().join(file))),
+                    (None, Some(command)) => Some(PipePaneTarget::Command(command)),
+                    (None, None) => None,
+                };
+                Ok(target.apply_to(vec![Action::PipePaneOutput(pipe_pane_target, strip_ansi)]))
+            },
             CliAction::DumpLayout => Ok(vec![Action::DumpLayout]),
             CliAction::EditScrollback => Ok(vec![Action::EditScrollback]),
-            CliAction::ScrollUp => Ok(vec![Action::ScrollUp]),
-            CliAction::ScrollDown => Ok(vec![Action::ScrollDown]),
-            CliAction::ScrollToBottom => Ok(vec![Action::ScrollToBottom]),
-            CliAction::ScrollToTop => Ok(vec![Action::ScrollToTop]),
-            CliAction::PageScrollUp => Ok(vec![Action::PageScrollUp]),
-            CliAction::PageScrollDown => Ok(vec![Action::PageScrollDown]),
-            CliAction::HalfPageScrollUp => Ok(vec![Action::HalfPageScrollUp]),
-            CliAction::HalfPageScrollDown => Ok(vec![Action::HalfPageScrollDown]),
-            CliAction::ToggleFullscreen => Ok(vec![Action::ToggleFocusFullscreen]),
+            CliAction::ScrollUp { target } => Ok(target.apply_to(vec![Action::ScrollUp])),
+            CliAction::ScrollDown { target } => Ok(target.apply_to(vec![Action::ScrollDown])),
+            CliAction::ScrollToBottom { target } => {
+                Ok(target.apply_to(vec![Action::ScrollToBottom]))
+            },
+            CliAction::ScrollToTop { target } => Ok(target.apply_to(vec![Action::ScrollToTop])),
+            CliAction::ScrollToPreviousPrompt { target } => {
+                Ok(target.apply_to(vec![Action::ScrollToPreviousPrompt]))
+            },
+            CliAction::ScrollToNextPrompt { target } => {
+                Ok(target.apply_to(vec![Action::ScrollToNextPrompt]))
+            },
+            CliAction::PageScrollUp { target } => Ok(target.apply_to(vec![Action::PageScrollUp])),
+            CliAction::PageScrollDown { target } => {
+                Ok(target.apply_to(vec![Action::PageScrollDown]))
+            },
+            CliAction::HalfPageScrollUp { target } => {
+                Ok(target.apply_to(vec![Action::HalfPageScrollUp]))
+            },
+            CliAction::HalfPageScrollDown { target } => {
+                Ok(target.apply_to(vec![Action::HalfPageScrollDown]))
+            },
+            CliAction::CopyModeMotion { motion } => Ok(vec![Action::CopyModeMotion(motion)]),
+            CliAction::CopyModeToggleSelection { selection } => {
+                Ok(vec![Action::CopyModeToggleSelection(selection)])
+            },
+            CliAction::CopyModeYank => Ok(vec![Action::CopyModeYank]),
+            CliAction::SelectLastCommandOutput => Ok(vec![Action::SelectLastCommandOutput]),
+            CliAction::CopyLastCommandOutput => Ok(vec![Action::CopyLastCommandOutput]),
+            CliAction::ToggleFullscreen { target } => {
+                Ok(target.apply_to(vec![Action::ToggleFocusFullscreen]))
+            },
             CliAction::TogglePaneFrames => Ok(vec![Action::TogglePaneFrames]),
             CliAction::ToggleActiveSyncTab => Ok(vec![Action::ToggleActiveSyncTab]),
             CliAction::NewPane {
@@ -402,6 +587,7 @@ impl Action {
                         direction,
                         hold_on_close,
                         hold_on_start,
+                        originating_plugin: None,
                     };
                     if floating {
                         Ok(vec![Action::NewFloatingPane(
@@ -469,11 +655,18 @@ impl Action {
             },
             CliAction::TogglePaneEmbedOrFloating => Ok(vec![Action::TogglePaneEmbedOrFloating]),
             CliAction::ToggleFloatingPanes => Ok(vec![Action::ToggleFloatingPanes]),
-            CliAction::ClosePane => Ok(vec![Action::CloseFocus]),
-            CliAction::RenamePane { name } => Ok(vec![
-                Action::UndoRenamePane,
-                Action::PaneNameInput(name.as_bytes().to_vec()),
-            ]),
+            CliAction::ClosePane { target } => Ok(target.apply_to(vec![Action::CloseFocus])),
+            CliAction::RenamePane { name, target } => {
+                if target.is_empty() {
+                    Ok(vec![
+                        Action::UndoRenamePane,
+                        Action::PaneNameInput(name.as_bytes().to_vec()),
+                    ])
+                } else {
+                    // renaming a pane that is not focused replaces its name outright
+                    Ok(target.apply_to(vec![Action::PaneNameInput(name.as_bytes().to_vec())]))
+                }
+            },
             CliAction::UndoRenamePane => Ok(vec![Action::UndoRenamePane]),
             CliAction::GoToNextTab => Ok(vec![Action::GoToNextTab]),
             CliAction::GoToPreviousTab => Ok(vec![Action::GoToPreviousTab]),
@@ -569,6 +762,11 @@ impl Action {
             CliAction::PreviousSwapLayout => Ok(vec![Action::PreviousSwapLayout]),
             CliAction::NextSwapLayout => Ok(vec![Action::NextSwapLayout]),
             CliAction::QueryTabNames => Ok(vec![Action::QueryTabNames]),
+            CliAction::ListTabs { json } => Ok(vec![Action::ListTabs(json)]),
+            CliAction::ListPanes { json } => Ok(vec![Action::ListPanes(json)]),
+            CliAction::ListClients { json } => Ok(vec![Action::ListClients(json)]),
+            CliAction::ReloadConfig => Ok(vec![Action::ReloadConfig]),
+            CliAction::SetTheme { name, client } => Ok(vec![Action::SwitchTheme(name, client)]),
             CliAction::StartOrReloadPlugin { url, configuration } => {
                 let current_dir = get_current_dir();
                 let run_plugin_location = RunPluginLocation::parse(&url, Some(current_dir))
@@ -589,7 +787,7 @@ impl Action {
                 skip_plugin_cache,
             } => {
                 let current_dir = get_current_dir();
-                let run_plugin_location = RunPluginLocation::parse(url.as_str(), Some(current_dir))
+                let run_plugin_location = RunPluginLocation::parse(&url, Some(current_dir))
                     .map_err(|e| format!("Failed to parse plugin location: {}", e))?;
                 let run_plugin = RunPlugin {
                     location: run_plugin_location,
@@ -612,9 +810,8 @@ impl Action {
                 skip_plugin_cache,
             } => {
                 let current_dir = get_current_dir();
-                let run_plugin_location =
-                    RunPluginLocation::parse(url.as_str(), Some(current_dir.clone()))
-                        .map_err(|e| format!("Failed to parse plugin location: {}", e))?;
+                let run_plugin_location = RunPluginLocation::parse(&url, Some(current_dir.clone()))
+                    .map_err(|e| format!("Failed to parse plugin location: {}", e))?;
                 let run_plugin = RunPlugin {
                     location: run_plugin_location,
                     _allow_exec_host_cmd: false,
@@ -1,6 +1,7 @@
 //! Trigger a command
-use crate::data::Direction;
+use crate::data::{ClientId, Direction};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
@@ -35,6 +36,17 @@ pub struct RunCommand {
     pub hold_on_close: bool,
     #[serde(default)]
     pub hold_on_start: bool,
+    #[serde(default)]
+    pub originating_plugin: Option<OriginatingPlugin>,
+}
+
+/// The plugin that opened a command pane, along with the context it provided, to be echoed back
+/// to it when the command exits
+#[derive(Clone, Debug, Deserialize, Default, Serialize, PartialEq, Eq)]
+pub struct OriginatingPlugin {
+    pub plugin_id: u32,
+    pub client_id: ClientId,
+    pub context: BTreeMap<String, String>,
 }
 
 impl std::fmt::Display for RunCommand {
@@ -68,6 +80,8 @@ pub struct RunCommandAction {
     pub hold_on_close: bool,
     #[serde(default)]
     pub hold_on_start: bool,
+    #[serde(default)]
+    pub originating_plugin: Option<OriginatingPlugin>,
 }
 
 impl From<RunCommandAction> for RunCommand {
@@ -78,6 +92,7 @@ impl From<RunCommandAction> for RunCommand {
             cwd: action.cwd,
             hold_on_close: action.hold_on_close,
             hold_on_start: action.hold_on_start,
+            originating_plugin: action.originating_plugin,
         }
     }
 }
@@ -91,6 +106,7 @@ impl From<RunCommand> for RunCommandAction {
             direction: None,
             hold_on_close: run_command.hold_on_close,
             hold_on_start: run_command.hold_on_start,
+            originating_plugin: run_command.originating_plugin,
         }
     }
 }
@@ -1,8 +1,10 @@
 use crate::data::Palette;
-use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode};
-use std::fs::File;
+use miette::{
+    Diagnostic, GraphicalReportHandler, GraphicalTheme, LabeledSpan, NamedSource, SourceCode,
+};
+use std::fs::{self, File};
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 use std::convert::TryFrom;
@@ -40,8 +42,11 @@ pub struct KdlError {
 }
 
 impl KdlError {
+    /// Does nothing if the error already has a source, eg. because it comes from an included file
     pub fn add_src(mut self, src_name: String, src_input: String) -> Self {
-        self.src = Some(NamedSource::new(src_name, src_input));
+        if self.src.is_none() {
+            self.src = Some(NamedSource::new(src_name, src_input));
+        }
         self
     }
 }
@@ -117,6 +122,21 @@ impl ConfigError {
             help_message: Some(format!("For more information, please see our layout guide: https://zellij.dev/documentation/creating-a-layout.html")),
         })
     }
+    /// Renders the error as plain text, pointing at the offending part of the file for KDL errors
+    pub fn to_report(&self) -> String {
+        match self {
+            ConfigError::KdlError(kdl_error) => {
+                let mut report = String::new();
+                match GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+                    .render_report(&mut report, kdl_error)
+                {
+                    Ok(()) => report,
+                    Err(_) => format!("{}: {}", kdl_error, kdl_error.error_message),
+                }
+            },
+            e => e.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -129,37 +149,35 @@ impl TryFrom<&CliArgs> for Config {
     type Error = ConfigError;
 
     fn try_from(opts: &CliArgs) -> ConfigResult {
+        match Config::config_file_path(opts) {
+            Some(path) => {
+                let default_config = Config::from_default_assets()?;
+                Config::from_path(&path, Some(default_config))
+            },
+            None => Config::from_default_assets(),
+        }
+    }
+}
+
+impl Config {
+    /// The config file that would be loaded for these cli args, if there is one
+    pub fn config_file_path(opts: &CliArgs) -> Option<PathBuf> {
         if let Some(ref path) = opts.config {
-            let default_config = Config::from_default_assets()?;
-            return Config::from_path(path, Some(default_config));
+            return Some(path.clone());
         }
 
         if let Some(Command::Setup(ref setup)) = opts.command {
             if setup.clean {
-                return Config::from_default_assets();
+                return None;
             }
         }
 
-        let config_dir = opts
-            .config_dir
+        opts.config_dir
             .clone()
-            .or_else(home::find_default_config_dir);
-
-        if let Some(ref config) = config_dir {
-            let path = config.join(DEFAULT_CONFIG_FILE_NAME);
-            if path.exists() {
-                let default_config = Config::from_default_assets()?;
-                Config::from_path(&path, Some(default_config))
-            } else {
-                Config::from_default_assets()
-            }
-        } else {
-            Config::from_default_assets()
-        }
+            .or_else(home::find_default_config_dir)
+            .map(|config_dir| config_dir.join(DEFAULT_CONFIG_FILE_NAME))
+            .filter(|path| path.exists())
     }
-}
-
-impl Config {
     pub fn theme_config(&self, opts: &Options) -> Option<Palette> {
         match &opts.theme {
             Some(theme_name) => self.themes.get_theme(theme_name).map(|theme| theme.palette),
@@ -178,12 +196,30 @@ impl Config {
         }
     }
     pub fn from_path(path: &PathBuf, default_config: Option<Config>) -> ConfigResult {
+        Config::from_path_with_includes(path, default_config, &mut vec![])
+    }
+    /// `include_stack` holds the files we are in the middle of including, so that we can report
+    /// include cycles rather than recursing forever
+    fn from_path_with_includes(
+        path: &PathBuf,
+        default_config: Option<Config>,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> ConfigResult {
         match File::open(path) {
             Ok(mut file) => {
                 let mut kdl_config = String::new();
                 file.read_to_string(&mut kdl_config)
                     .map_err(|e| ConfigError::IoPath(e, path.to_path_buf()))?;
-                match Config::from_kdl(&kdl_config, default_config) {
+                include_stack.push(fs::canonicalize(path).unwrap_or_else(|_| path.clone()));
+                let config = Config::merge_included_config_files(
+                    &kdl_config,
+                    path,
+                    default_config,
+                    include_stack,
+                )
+                .and_then(|base_config| Config::from_kdl(&kdl_config, base_config));
+                include_stack.pop();
+                match config {
                     Ok(config) => Ok(config),
                     Err(ConfigError::KdlDeserializationError(kdl_error)) => {
                         let error_message = match kdl_error.kind {
@@ -222,6 +258,42 @@ impl Config {
             Err(e) => Err(ConfigError::IoPath(e, path.into())),
         }
     }
+    /// Merges the files included by this config on top of `base_config`, in the order they are
+    /// included, so that the including file itself can then be merged on top of them.
+    fn merge_included_config_files(
+        kdl_config: &str,
+        path: &Path,
+        mut base_config: Option<Config>,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<Option<Config>, ConfigError> {
+        let config_dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        for (included_path, span) in Config::included_config_files(kdl_config, &config_dir)? {
+            let canonical_path =
+                fs::canonicalize(&included_path).unwrap_or_else(|_| included_path.clone());
+            if include_stack.contains(&canonical_path) {
+                return Err(ConfigError::new_kdl_error(
+                    format!(
+                        "{} is already being included, including it again would never end",
+                        included_path.display()
+                    ),
+                    span.offset(),
+                    span.len(),
+                ));
+            }
+            let included_config =
+                Config::from_path_with_includes(&included_path, base_config, include_stack)
+                    .map_err(|e| match e {
+                        ConfigError::IoPath(e, path) => ConfigError::new_kdl_error(
+                            format!("Failed to include {}: {}", path.display(), e),
+                            span.offset(),
+                            span.len(),
+                        ),
+                        e => e,
+                    })?;
+            base_config = Some(included_config);
+        }
+        Ok(base_config)
+    }
     pub fn merge(&mut self, other: Config) -> Result<(), ConfigError> {
         self.options = self.options.merge(other.options);
         self.keybinds.merge(other.keybinds.clone());
@@ -237,11 +309,12 @@ impl Config {
 mod config_test {
     use super::*;
     use crate::data::{InputMode, Palette, PaletteColor, PluginTag};
-    use crate::input::layout::RunPluginLocation;
+    use crate::input::layout::{PluginUserConfiguration, RunPlugin, RunPluginLocation};
     use crate::input::options::{Clipboard, OnForceClose};
     use crate::input::plugins::{PluginConfig, PluginType, PluginsConfig};
     use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
-    use std::collections::HashMap;
+    use crate::ipc::ColorDepth;
+    use std::collections::{BTreeMap, HashMap};
     use std::io::Write;
     use tempfile::tempdir;
 
@@ -295,6 +368,24 @@ mod config_test {
         assert_eq!(result.unwrap(), Config::from_default_assets().unwrap());
     }
 
+    #[test]
+    fn config_file_path_from_cli_args() {
+        let tmp = tempdir().unwrap();
+        let mut opts = CliArgs::default();
+        opts.config_dir = Some(tmp.path().to_path_buf());
+        assert_eq!(Config::config_file_path(&opts), None);
+        File::create(tmp.path().join(DEFAULT_CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(
+            Config::config_file_path(&opts),
+            Some(tmp.path().join(DEFAULT_CONFIG_FILE_NAME))
+        );
+        opts.config = Some(PathBuf::from("nonexistent.kdl"));
+        assert_eq!(
+            Config::config_file_path(&opts),
+            Some(PathBuf::from("nonexistent.kdl"))
+        );
+    }
+
     #[test]
     fn try_from_cli_args_default() {
         let opts = CliArgs::default();
@@ -302,6 +393,21 @@ mod config_test {
         assert_eq!(result.unwrap(), Config::from_default_assets().unwrap());
     }
 
+    #[test]
+    fn negative_scrollback_memory_budget_is_an_error() {
+        let config_contents = r#"
+            scrollback_memory_budget -1
+        "#;
+        match Config::from_kdl(config_contents, None) {
+            Err(ConfigError::KdlError(kdl_error)) => {
+                assert!(kdl_error
+                    .error_message
+                    .contains("Invalid value for scrollback_memory_budget"));
+            },
+            result => panic!("expected a config error, got: {:?}", result),
+        }
+    }
+
     #[test]
     fn can_define_options_in_configfile() {
         let config_contents = r#"
@@ -324,6 +430,7 @@ mod config_test {
             scrollback_editor "/path/to/my/scrollback-editor"
             session_name "my awesome session"
             attach_to_session true
+            color_depth "256"
         "#;
         let config = Config::from_kdl(config_contents, None).unwrap();
         assert_eq!(
@@ -421,6 +528,11 @@ mod config_test {
             Some(true),
             "Option set in config"
         );
+        assert_eq!(
+            config.options.color_depth,
+            Some(ColorDepth::Indexed256),
+            "Option set in config"
+        );
     }
 
     #[test]
@@ -649,6 +761,168 @@ mod config_test {
         );
     }
 
+    #[test]
+    fn can_define_plugin_aliases_in_configfile() {
+        let config_contents = r#"
+            plugins {
+                strider { path "strider"; }
+                my_finder location="file:/path/to/finder.wasm" {
+                    start_dir "/tmp"
+                    show_hidden true
+                }
+                my_strider location="zellij:strider" {
+                    cwd "/"
+                    sort "name"
+                }
+            }
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        let mut finder_configuration = BTreeMap::new();
+        finder_configuration.insert("start_dir".to_owned(), "/tmp".to_owned());
+        finder_configuration.insert("show_hidden".to_owned(), "true".to_owned());
+        assert_eq!(
+            config.plugins.0.get(&PluginTag::new("my_finder")),
+            Some(&PluginConfig {
+                path: PathBuf::from("/path/to/finder.wasm"),
+                run: PluginType::Pane(None),
+                location: RunPluginLocation::File(PathBuf::from("/path/to/finder.wasm")),
+                _allow_exec_host_cmd: false,
+                userspace_configuration: PluginUserConfiguration::new(finder_configuration),
+            }),
+            "Plugin alias defined in config"
+        );
+
+        let mut call_site_configuration = BTreeMap::new();
+        call_site_configuration.insert("start_dir".to_owned(), "/home".to_owned());
+        let resolved_finder = config
+            .plugins
+            .get(RunPlugin {
+                location: RunPluginLocation::parse("my_finder", None).unwrap(),
+                configuration: PluginUserConfiguration::new(call_site_configuration),
+                ..Default::default()
+            })
+            .unwrap();
+        let mut expected_finder_configuration = BTreeMap::new();
+        expected_finder_configuration.insert("start_dir".to_owned(), "/home".to_owned());
+        expected_finder_configuration.insert("show_hidden".to_owned(), "true".to_owned());
+        assert_eq!(
+            resolved_finder.location,
+            RunPluginLocation::File(PathBuf::from("/path/to/finder.wasm")),
+            "Alias resolved to its location"
+        );
+        assert_eq!(
+            resolved_finder.userspace_configuration,
+            PluginUserConfiguration::new(expected_finder_configuration),
+            "Call site configuration overrides alias configuration"
+        );
+
+        let resolved_strider = config
+            .plugins
+            .get(RunPlugin::from_url("zellij:my_strider").unwrap())
+            .unwrap();
+        let mut expected_strider_configuration = BTreeMap::new();
+        expected_strider_configuration.insert("sort".to_owned(), "name".to_owned());
+        assert_eq!(
+            resolved_strider.location,
+            RunPluginLocation::Zellij(PluginTag::new("strider")),
+            "Alias of a builtin plugin resolved to the builtin plugin"
+        );
+        assert_eq!(resolved_strider.path, PathBuf::from("strider"));
+        assert_eq!(
+            resolved_strider.userspace_configuration,
+            PluginUserConfiguration::new(expected_strider_configuration),
+            "Reserved words are not part of the alias configuration"
+        );
+    }
+
+    #[test]
+    fn config_files_are_merged_on_top_of_the_files_they_include() {
+        let tmp = tempdir().unwrap();
+        File::create(tmp.path().join("base.kdl"))
+            .unwrap()
+            .write_all(b"scroll_buffer_size 1\nmouse_mode false\ncopy_command \"base\"\n")
+            .unwrap();
+        File::create(tmp.path().join("machine.kdl"))
+            .unwrap()
+            .write_all(b"include \"base.kdl\"\nscroll_buffer_size 2\ncopy_command \"machine\"\n")
+            .unwrap();
+        File::create(tmp.path().join(DEFAULT_CONFIG_FILE_NAME))
+            .unwrap()
+            .write_all(b"include \"machine.kdl\"\nscroll_buffer_size 3\n")
+            .unwrap();
+        let config = Config::from_path(&tmp.path().join(DEFAULT_CONFIG_FILE_NAME), None).unwrap();
+        assert_eq!(config.options.scroll_buffer_size, Some(3));
+        assert_eq!(config.options.copy_command, Some("machine".to_owned()));
+        assert_eq!(config.options.mouse_mode, Some(false));
+    }
+
+    #[test]
+    fn include_cycles_and_missing_includes_are_errors_in_the_including_file() {
+        let tmp = tempdir().unwrap();
+        File::create(tmp.path().join("a.kdl"))
+            .unwrap()
+            .write_all(b"include \"b.kdl\"\n")
+            .unwrap();
+        File::create(tmp.path().join("b.kdl"))
+            .unwrap()
+            .write_all(b"include \"a.kdl\"\n")
+            .unwrap();
+        File::create(tmp.path().join("c.kdl"))
+            .unwrap()
+            .write_all(b"include \"nonexistent.kdl\"\n")
+            .unwrap();
+        match Config::from_path(&tmp.path().join("a.kdl"), None) {
+            Err(ConfigError::KdlError(kdl_error)) => {
+                assert!(kdl_error
+                    .error_message
+                    .contains("a.kdl is already being included"));
+                assert!(kdl_error.src.is_some());
+            },
+            result => panic!("expected an include cycle error, got: {:?}", result),
+        }
+        match Config::from_path(&tmp.path().join("c.kdl"), None) {
+            Err(ConfigError::KdlError(kdl_error)) => {
+                assert!(kdl_error.error_message.contains("Failed to include"));
+                assert_eq!(kdl_error.offset, Some(0));
+            },
+            result => panic!("expected a missing include error, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn environment_variables_are_interpolated_in_string_values() {
+        std::env::set_var("ZELLIJ_CONFIG_TEST_SHELL", "fish");
+        let config_contents = r#"
+            default_shell "/usr/bin/${ZELLIJ_CONFIG_TEST_SHELL}"
+            copy_command "echo ${1:-unset}"
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        assert_eq!(
+            config.options.default_shell,
+            Some(PathBuf::from("/usr/bin/fish"))
+        );
+        assert_eq!(
+            config.options.copy_command,
+            Some("echo ${1:-unset}".to_owned()),
+            "Shell expressions that are not variables are left as is"
+        );
+        let config_contents = r#"
+            default_shell "${ZELLIJ_CONFIG_TEST_UNSET_VARIABLE}"
+            copy_command "echo $${ZELLIJ_CONFIG_TEST_SHELL}"
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        assert_eq!(
+            config.options.default_shell,
+            Some(PathBuf::from("${ZELLIJ_CONFIG_TEST_UNSET_VARIABLE}")),
+            "Unset variables are left as is"
+        );
+        assert_eq!(
+            config.options.copy_command,
+            Some("echo ${ZELLIJ_CONFIG_TEST_SHELL}".to_owned()),
+            "$${ is an escaped ${"
+        );
+    }
+
     #[test]
     fn can_define_ui_configuration_in_configfile() {
         let config_contents = r#"
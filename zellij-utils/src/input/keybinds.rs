@@ -1,62 +1,138 @@
 use std::collections::{BTreeMap, HashMap};
 
 use super::actions::Action;
-use crate::data::{InputMode, Key, KeybindsVec};
+use crate::data::{InputMode, Key, KeyWithModifier, KeybindsVec};
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Used in the config struct
 #[derive(Clone, PartialEq, Deserialize, Serialize, Default)]
-pub struct Keybinds(pub HashMap<InputMode, HashMap<Key, Vec<Action>>>);
+pub struct Keybinds {
+    pub single_keys: HashMap<InputMode, HashMap<Key, Vec<Action>>>,
+    /// The multi-key sequences of each mode (eg. `Ctrl a` followed by `c`)
+    pub sequences: HashMap<InputMode, HashMap<Vec<Key>, Vec<Action>>>,
+}
+
+/// How the keys typed so far relate to the multi-key sequences bound in a mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeySequenceMatch {
+    Complete(Vec<Action>),
+    Partial, // the keys begin at least one sequence, so we should wait for the next one
+    NoMatch,
+}
 
 impl fmt::Debug for Keybinds {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut stable_sorted = BTreeMap::new();
-        for (mode, keybinds) in self.0.iter() {
+        for (mode, keybinds) in self.single_keys.iter() {
             let mut stable_sorted_mode_keybinds = BTreeMap::new();
             for (key, actions) in keybinds {
                 stable_sorted_mode_keybinds.insert(key, actions);
             }
             stable_sorted.insert(mode, stable_sorted_mode_keybinds);
         }
-        write!(f, "{:#?}", stable_sorted)
+        let mut stable_sorted_sequences = BTreeMap::new();
+        for (mode, key_sequences) in self.sequences.iter().filter(|(_, s)| !s.is_empty()) {
+            let stable_sorted_mode_sequences: BTreeMap<_, _> = key_sequences.iter().collect();
+            stable_sorted_sequences.insert(mode, stable_sorted_mode_sequences);
+        }
+        f.debug_struct("Keybinds")
+            .field("single_keys", &stable_sorted)
+            .field("sequences", &stable_sorted_sequences)
+            .finish()
     }
 }
 
 impl Keybinds {
     pub fn get_actions_for_key_in_mode(&self, mode: &InputMode, key: &Key) -> Option<&Vec<Action>> {
-        self.0
+        self.single_keys
             .get(mode)
             .and_then(|normal_mode_keybindings| normal_mode_keybindings.get(key))
     }
     pub fn get_actions_for_key_in_mode_or_default_action(
         &self,
         mode: &InputMode,
-        key: &Key,
+        key_with_modifier: &KeyWithModifier,
         raw_bytes: Vec<u8>,
+        is_kitty_keyboard_protocol: bool,
     ) -> Vec<Action> {
-        self.0
-            .get(mode)
-            .and_then(|normal_mode_keybindings| normal_mode_keybindings.get(key))
+        self.get_actions_for_key_in_mode(mode, &Key::from(*key_with_modifier))
             .cloned()
-            .unwrap_or_else(|| vec![self.default_action_for_mode(mode, raw_bytes)])
+            .unwrap_or_else(|| {
+                vec![self.default_action_for_mode(
+                    mode,
+                    Some(key_with_modifier),
+                    raw_bytes,
+                    is_kitty_keyboard_protocol,
+                )]
+            })
     }
     pub fn get_input_mode_mut(&mut self, input_mode: &InputMode) -> &mut HashMap<Key, Vec<Action>> {
-        self.0.entry(*input_mode).or_insert_with(HashMap::new)
+        self.single_keys
+            .entry(*input_mode)
+            .or_insert_with(HashMap::new)
+    }
+    pub fn get_key_sequences_for_mode_mut(
+        &mut self,
+        input_mode: &InputMode,
+    ) -> &mut HashMap<Vec<Key>, Vec<Action>> {
+        self.sequences
+            .entry(*input_mode)
+            .or_insert_with(HashMap::new)
     }
-    pub fn default_action_for_mode(&self, mode: &InputMode, raw_bytes: Vec<u8>) -> Action {
-        match *mode {
-            InputMode::Normal | InputMode::Locked => Action::Write(raw_bytes),
-            InputMode::RenameTab => Action::TabNameInput(raw_bytes),
-            InputMode::RenamePane => Action::PaneNameInput(raw_bytes),
-            InputMode::EnterSearch => Action::SearchInput(raw_bytes),
-            _ => Action::NoOp,
+    /// An exact match wins over a longer sequence beginning with the same keys, so that we never
+    /// wait for a key that might not come when we already know what to do. A key bound on its own
+    /// that also begins a sequence is a partial match here, whether to wait for the next key is up
+    /// to the caller (see the `prefer_key_sequences` option)
+    pub fn match_key_sequence(&self, mode: &InputMode, keys: &[Key]) -> KeySequenceMatch {
+        let key_sequences = match self.sequences.get(mode) {
+            Some(key_sequences) => key_sequences,
+            None => return KeySequenceMatch::NoMatch,
+        };
+        if let Some(actions) = key_sequences.get(keys) {
+            KeySequenceMatch::Complete(actions.clone())
+        } else if key_sequences
+            .keys()
+            .any(|key_sequence| key_sequence.starts_with(keys))
+        {
+            KeySequenceMatch::Partial
+        } else {
+            KeySequenceMatch::NoMatch
+        }
+    }
+    pub fn default_action_for_mode(
+        &self,
+        mode: &InputMode,
+        key_with_modifier: Option<&KeyWithModifier>,
+        raw_bytes: Vec<u8>,
+        is_kitty_keyboard_protocol: bool,
+    ) -> Action {
+        match (*mode, key_with_modifier) {
+            (InputMode::Normal | InputMode::Locked, Some(key_with_modifier)) => {
+                Action::WriteKey(*key_with_modifier, raw_bytes, is_kitty_keyboard_protocol)
+            },
+            (InputMode::Normal | InputMode::Locked, None) => Action::Write(raw_bytes),
+            (mode, key_with_modifier) => {
+                // our own inputs (eg. tab names) do not understand the kitty keyboard protocol
+                let raw_bytes = match key_with_modifier {
+                    Some(key_with_modifier) if is_kitty_keyboard_protocol => {
+                        key_with_modifier.serialize_non_kitty().unwrap_or_default()
+                    },
+                    _ => raw_bytes,
+                };
+                match mode {
+                    InputMode::RenameTab => Action::TabNameInput(raw_bytes),
+                    InputMode::RenamePane => Action::PaneNameInput(raw_bytes),
+                    InputMode::EnterSearch => Action::SearchInput(raw_bytes),
+                    _ => Action::NoOp,
+                }
+            },
         }
     }
     pub fn to_keybinds_vec(&self) -> KeybindsVec {
         let mut ret = vec![];
-        for (mode, mode_binds) in &self.0 {
+        for (mode, mode_binds) in &self.single_keys {
             let mut mode_binds_vec: Vec<(Key, Vec<Action>)> = vec![];
             for (key, actions) in mode_binds {
                 mode_binds_vec.push((key.clone(), actions.clone()));
@@ -66,15 +142,24 @@ impl Keybinds {
         ret
     }
     pub fn merge(&mut self, mut other: Keybinds) {
-        for (other_input_mode, mut other_input_mode_keybinds) in other.0.drain() {
+        for (other_input_mode, mut other_input_mode_keybinds) in other.single_keys.drain() {
+            // a key rebound on its own no longer begins the sequences it began before
+            self.get_key_sequences_for_mode_mut(&other_input_mode)
+                .retain(|key_sequence, _| {
+                    !other_input_mode_keybinds.contains_key(&key_sequence[0])
+                });
             let input_mode_keybinds = self
-                .0
+                .single_keys
                 .entry(other_input_mode)
                 .or_insert_with(|| Default::default());
             for (other_action, other_action_keybinds) in other_input_mode_keybinds.drain() {
                 input_mode_keybinds.insert(other_action, other_action_keybinds);
             }
         }
+        for (other_input_mode, other_key_sequences) in other.sequences.drain() {
+            self.get_key_sequences_for_mode_mut(&other_input_mode)
+                .extend(other_key_sequences);
+        }
     }
 }
 
@@ -313,7 +313,17 @@ impl Default for RunPluginLocation {
 
 impl RunPluginLocation {
     pub fn parse(location: &str, cwd: Option<PathBuf>) -> Result<Self, PluginsConfigError> {
-        let url = Url::parse(location)?;
+        let url = match Url::parse(location) {
+            Ok(url) => url,
+            Err(url::ParseError::RelativeUrlWithoutBase)
+                if !location.is_empty()
+                    && !location.contains(|c| matches!(c, '/' | '\\' | ':')) =>
+            {
+                // a bare name, referring to a plugin alias defined in the config
+                return Ok(Self::Zellij(PluginTag::new(location)));
+            },
+            Err(e) => return Err(e.into()),
+        };
 
         let decoded_path = percent_encoding::percent_decode_str(url.path()).decode_utf8_lossy();
 
@@ -483,6 +493,26 @@ impl FromStr for PercentOrFixed {
     }
 }
 
+/// Which of the bell, activity and silence monitors a layout asks for on a pane, those left
+/// unset fall back to the ones of its tab and then to the ones in the config
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PaneMonitors {
+    pub bell: Option<bool>,
+    pub activity: Option<bool>,
+    // seconds
+    pub silence: Option<u64>,
+}
+
+impl PaneMonitors {
+    pub fn or(self, other: PaneMonitors) -> Self {
+        PaneMonitors {
+            bell: self.bell.or(other.bell),
+            activity: self.activity.or(other.activity),
+            silence: self.silence.or(other.silence),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
 pub struct FloatingPaneLayout {
     pub name: Option<String>,
@@ -493,6 +523,7 @@ pub struct FloatingPaneLayout {
     pub run: Option<Run>,
     pub focus: Option<bool>,
     pub already_running: bool,
+    pub monitors: PaneMonitors,
     pub pane_initial_contents: Option<String>,
 }
 
@@ -518,6 +549,7 @@ impl From<&TiledPaneLayout> for FloatingPaneLayout {
             name: pane_layout.name.clone(),
             run: pane_layout.run.clone(),
             focus: pane_layout.focus,
+            monitors: pane_layout.monitors,
             ..Default::default()
         }
     }
@@ -538,6 +570,7 @@ pub struct TiledPaneLayout {
     pub exclude_from_sync: Option<bool>,
     pub run_instructions_to_ignore: Vec<Option<Run>>,
     pub hide_floating_panes: bool, // only relevant if this is the base layout
+    pub monitors: PaneMonitors,
     pub pane_initial_contents: Option<String>,
 }
 
@@ -706,6 +739,12 @@ impl TiledPaneLayout {
             child.add_cwd_to_layout(cwd);
         }
     }
+    pub fn add_monitors_to_layout(&mut self, monitors: PaneMonitors) {
+        self.monitors = self.monitors.or(monitors);
+        for child in self.children.iter_mut() {
+            child.add_monitors_to_layout(monitors);
+        }
+    }
     pub fn deepest_depth(&self) -> usize {
         let mut deepest_child_depth = 0;
         for child in self.children.iter() {
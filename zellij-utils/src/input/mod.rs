@@ -18,7 +18,10 @@ pub use not_wasm::*;
 #[cfg(not(target_family = "wasm"))]
 mod not_wasm {
     use crate::{
-        data::{CharOrArrow, Direction, InputMode, Key, ModeInfo, PluginCapabilities},
+        data::{
+            BareKey, CharOrArrow, Direction, InputMode, Key, KeyModifiers, KeyWithModifier,
+            ModeInfo, PluginCapabilities,
+        },
         envs,
         ipc::ClientAttributes,
     };
@@ -40,6 +43,8 @@ mod not_wasm {
             style: attributes.style,
             capabilities,
             session_name,
+            pending_key_sequence: vec![],
+            is_read_only: false,
         }
     }
 
@@ -119,4 +124,47 @@ mod not_wasm {
             _ => Key::Esc, // there are other keys we can implement here, but we might need additional terminal support to implement them, not just exhausting this enum
         }
     }
+
+    /// Like [`cast_termwiz_key`], but keeps all the modifiers termwiz was able to parse. Returns
+    /// None for keys we do not represent.
+    pub fn cast_termwiz_key_with_modifier(
+        event: KeyEvent,
+        raw_bytes: &[u8],
+    ) -> Option<KeyWithModifier> {
+        if raw_bytes == [8] {
+            // see cast_termwiz_key above
+            return Some(KeyWithModifier::new(BareKey::Char('h'), KeyModifiers::CTRL));
+        }
+        let mut key_modifiers = KeyModifiers::NONE;
+        for (termwiz_modifier, modifier) in [
+            (Modifiers::SHIFT, KeyModifiers::SHIFT),
+            (Modifiers::ALT, KeyModifiers::ALT),
+            (Modifiers::CTRL, KeyModifiers::CTRL),
+            (Modifiers::SUPER, KeyModifiers::SUPER),
+        ] {
+            if event.modifiers.intersects(termwiz_modifier) {
+                key_modifiers.insert(modifier);
+            }
+        }
+        let bare_key = match event.key {
+            KeyCode::Char(c) => BareKey::Char(c),
+            KeyCode::Backspace => BareKey::Backspace,
+            KeyCode::LeftArrow | KeyCode::ApplicationLeftArrow => BareKey::Left,
+            KeyCode::RightArrow | KeyCode::ApplicationRightArrow => BareKey::Right,
+            KeyCode::UpArrow | KeyCode::ApplicationUpArrow => BareKey::Up,
+            KeyCode::DownArrow | KeyCode::ApplicationDownArrow => BareKey::Down,
+            KeyCode::Home => BareKey::Home,
+            KeyCode::End => BareKey::End,
+            KeyCode::PageUp => BareKey::PageUp,
+            KeyCode::PageDown => BareKey::PageDown,
+            KeyCode::Tab => BareKey::Tab,
+            KeyCode::Delete => BareKey::Delete,
+            KeyCode::Insert => BareKey::Insert,
+            KeyCode::Function(n) => BareKey::F(n),
+            KeyCode::Escape => BareKey::Esc,
+            KeyCode::Enter => BareKey::Enter,
+            _ => return None,
+        };
+        Some(KeyWithModifier::new(bare_key, key_modifiers))
+    }
 }
@@ -1,6 +1,7 @@
 //! Handles cli and configuration options
 use crate::cli::Command;
 use crate::data::InputMode;
+use crate::ipc::ColorDepth;
 use clap::{ArgEnum, Args};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -151,6 +152,70 @@ pub struct Options {
     /// The interval at which to serialize sessions for resurrection (in seconds)
     #[clap(long, value_parser)]
     pub serialization_interval: Option<u64>,
+
+    /// Whether to enable the kitty keyboard protocol in terminals that support it, so that key
+    /// combinations such as Ctrl Shift t can be bound and passed on to applications
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub support_kitty_keyboard_protocol: Option<bool>,
+
+    /// How long to wait for the next key of a multi-key keybinding before passing on the keys
+    /// typed so far (in milliseconds)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub key_sequence_timeout: Option<u64>,
+
+    /// Whether a key bound on its own that also begins a multi-key keybinding should wait for the
+    /// next key rather than act right away
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub prefer_key_sequences: Option<bool>,
+
+    /// The theme to switch to when the terminal reports a light background color
+    #[clap(long, value_parser)]
+    pub light_theme: Option<String>,
+
+    /// The theme to switch to when the terminal reports a dark background color
+    #[clap(long, value_parser)]
+    pub dark_theme: Option<String>,
+
+    /// Whether the whole scrollback of panes (including styles) is serialized along with the
+    /// session, so that it is restored when the session is resurrected (only the newest
+    /// scrollback_lines_to_serialize lines if it is set), default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub serialize_pane_scrollback: Option<bool>,
+
+    /// The amount of memory (in megabytes) that the scrollback of all panes in the session may
+    /// take up together, once it is exceeded the oldest lines are dropped first, default is
+    /// unlimited (only bounded by scroll_buffer_size)
+    #[clap(long, value_parser)]
+    pub scrollback_memory_budget: Option<usize>,
+
+    /// Whether to flag panes (and their tabs) in which a bell rang while they were not focused,
+    /// default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub monitor_bell: Option<bool>,
+
+    /// Whether to flag panes (and their tabs) that printed something while they were not
+    /// focused, default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub monitor_activity: Option<bool>,
+
+    /// Flag panes (and their tabs) that printed nothing for this many seconds while they were
+    /// not focused, default is 0 (off)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub monitor_silence: Option<u64>,
+
+    /// How many colours the terminal can display (truecolor, 256 or 16), colours it does not
+    /// support are brought down to the nearest ones it does, default is detected from COLORTERM
+    /// and terminfo
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub color_depth: Option<ColorDepth>,
 }
 
 #[derive(ArgEnum, Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -223,6 +288,23 @@ impl Options {
             .or(self.scrollback_lines_to_serialize);
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
+        let support_kitty_keyboard_protocol = other
+            .support_kitty_keyboard_protocol
+            .or(self.support_kitty_keyboard_protocol);
+        let key_sequence_timeout = other.key_sequence_timeout.or(self.key_sequence_timeout);
+        let prefer_key_sequences = other.prefer_key_sequences.or(self.prefer_key_sequences);
+        let light_theme = other.light_theme.or_else(|| self.light_theme.clone());
+        let dark_theme = other.dark_theme.or_else(|| self.dark_theme.clone());
+        let serialize_pane_scrollback = other
+            .serialize_pane_scrollback
+            .or(self.serialize_pane_scrollback);
+        let scrollback_memory_budget = other
+            .scrollback_memory_budget
+            .or(self.scrollback_memory_budget);
+        let monitor_bell = other.monitor_bell.or(self.monitor_bell);
+        let monitor_activity = other.monitor_activity.or(self.monitor_activity);
+        let monitor_silence = other.monitor_silence.or(self.monitor_silence);
+        let color_depth = other.color_depth.or(self.color_depth);
 
         Options {
             simplified_ui,
@@ -250,6 +332,17 @@ impl Options {
             scrollback_lines_to_serialize,
             styled_underlines,
             serialization_interval,
+            support_kitty_keyboard_protocol,
+            key_sequence_timeout,
+            prefer_key_sequences,
+            light_theme,
+            dark_theme,
+            serialize_pane_scrollback,
+            scrollback_memory_budget,
+            monitor_bell,
+            monitor_activity,
+            monitor_silence,
+            color_depth,
         }
     }
 
@@ -302,6 +395,24 @@ impl Options {
             .or_else(|| self.scrollback_lines_to_serialize.clone());
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
+        let support_kitty_keyboard_protocol = other
+            .support_kitty_keyboard_protocol
+            .or(self.support_kitty_keyboard_protocol);
+        let key_sequence_timeout = other.key_sequence_timeout.or(self.key_sequence_timeout);
+        let prefer_key_sequences = other.prefer_key_sequences.or(self.prefer_key_sequences);
+        let light_theme = other.light_theme.or_else(|| self.light_theme.clone());
+        let dark_theme = other.dark_theme.or_else(|| self.dark_theme.clone());
+        let serialize_pane_scrollback = merge_bool(
+            other.serialize_pane_scrollback,
+            self.serialize_pane_scrollback,
+        );
+        let scrollback_memory_budget = other
+            .scrollback_memory_budget
+            .or(self.scrollback_memory_budget);
+        let monitor_bell = merge_bool(other.monitor_bell, self.monitor_bell);
+        let monitor_activity = merge_bool(other.monitor_activity, self.monitor_activity);
+        let monitor_silence = other.monitor_silence.or(self.monitor_silence);
+        let color_depth = other.color_depth.or(self.color_depth);
 
         Options {
             simplified_ui,
@@ -329,6 +440,17 @@ impl Options {
             scrollback_lines_to_serialize,
             styled_underlines,
             serialization_interval,
+            support_kitty_keyboard_protocol,
+            key_sequence_timeout,
+            prefer_key_sequences,
+            light_theme,
+            dark_theme,
+            serialize_pane_scrollback,
+            scrollback_memory_budget,
+            monitor_bell,
+            monitor_activity,
+            monitor_silence,
+            color_depth,
         }
     }
 
@@ -393,6 +515,17 @@ impl From<CliOptions> for Options {
             scrollback_lines_to_serialize: opts.scrollback_lines_to_serialize,
             styled_underlines: opts.styled_underlines,
             serialization_interval: opts.serialization_interval,
+            support_kitty_keyboard_protocol: opts.support_kitty_keyboard_protocol,
+            key_sequence_timeout: opts.key_sequence_timeout,
+            prefer_key_sequences: opts.prefer_key_sequences,
+            light_theme: opts.light_theme,
+            dark_theme: opts.dark_theme,
+            serialize_pane_scrollback: opts.serialize_pane_scrollback,
+            scrollback_memory_budget: opts.scrollback_memory_budget,
+            monitor_bell: opts.monitor_bell,
+            monitor_activity: opts.monitor_activity,
+            monitor_silence: opts.monitor_silence,
+            color_depth: opts.color_depth,
             ..Default::default()
         }
     }
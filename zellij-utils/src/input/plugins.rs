@@ -1,6 +1,6 @@
 //! Plugins configuration metadata
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -40,8 +40,33 @@ impl PluginsConfig {
     }
 
     /// Get plugin config from run configuration specified in layout files.
+    ///
+    /// `zellij:` locations (and bare names) are looked up in the `plugins` section of the config,
+    /// which holds both the builtin plugins and user defined aliases. The configuration of an
+    /// alias is merged with the configuration given in `run`, the latter taking precedence.
     pub fn get(&self, run: impl Borrow<RunPlugin>) -> Option<PluginConfig> {
-        let run = run.borrow();
+        let mut run = run.borrow().clone();
+        let mut visited_aliases = HashSet::new();
+        while let RunPluginLocation::Zellij(tag) = run.location.clone() {
+            let alias = self.0.get(&tag)?;
+            if alias.location == run.location {
+                // a builtin plugin rather than an alias
+                break;
+            }
+            if !visited_aliases.insert(tag.clone()) {
+                log::error!("Plugin alias {} refers back to itself", tag);
+                return None;
+            }
+            let mut configuration = alias.userspace_configuration.clone();
+            for (key, value) in run.configuration.inner() {
+                configuration.insert(key, value);
+            }
+            run = RunPlugin {
+                _allow_exec_host_cmd: run._allow_exec_host_cmd || alias._allow_exec_host_cmd,
+                location: alias.location.clone(),
+                configuration,
+            };
+        }
         match &run.location {
             RunPluginLocation::File(path) => Some(PluginConfig {
                 path: path.clone(),
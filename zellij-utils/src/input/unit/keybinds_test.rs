@@ -54,6 +54,225 @@ fn can_define_multiple_keybinds_for_same_action() {
     );
 }
 
+#[test]
+fn can_define_keybindings_with_any_combination_of_modifiers() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl Shift T" { NewTab; }
+                bind "Alt Ctrl h" { MoveFocusOrTab "Left"; }
+                bind "Super Enter" { NewPane; }
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let ctrl_shift_t = data::KeyWithModifier::new(
+        data::BareKey::Char('t'),
+        data::KeyModifiers::CTRL | data::KeyModifiers::SHIFT,
+    );
+    let ctrl_alt_h = data::KeyWithModifier::new(
+        data::BareKey::Char('h'),
+        data::KeyModifiers::ALT | data::KeyModifiers::CTRL,
+    );
+    let super_enter = data::KeyWithModifier::new(data::BareKey::Enter, data::KeyModifiers::SUPER);
+    let ctrl_g = data::KeyWithModifier::new(data::BareKey::Char('g'), data::KeyModifiers::CTRL);
+    let actions_for = |key_with_modifier: &data::KeyWithModifier| {
+        config
+            .keybinds
+            .get_actions_for_key_in_mode_or_default_action(
+                &InputMode::Normal,
+                key_with_modifier,
+                vec![],
+                true,
+            )
+    };
+    assert_eq!(
+        actions_for(&ctrl_shift_t),
+        vec![Action::NewTab(None, vec![], None, None, None)]
+    );
+    assert_eq!(
+        actions_for(&ctrl_alt_h),
+        vec![Action::MoveFocusOrTab(Direction::Left)]
+    );
+    assert_eq!(actions_for(&super_enter), vec![Action::NewPane(None, None)]);
+    assert_eq!(
+        config
+            .keybinds
+            .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Ctrl('g')),
+        Some(&vec![Action::SwitchToMode(InputMode::Locked)]),
+        "keys with a legacy representation are bound as before"
+    );
+    assert_eq!(
+        actions_for(&ctrl_g),
+        vec![Action::SwitchToMode(InputMode::Locked)]
+    );
+    let unbound_key =
+        data::KeyWithModifier::new(data::BareKey::Char('x'), data::KeyModifiers::CTRL);
+    assert_eq!(
+        config
+            .keybinds
+            .get_actions_for_key_in_mode_or_default_action(
+                &InputMode::Normal,
+                &unbound_key,
+                "\u{1b}[120;5u".as_bytes().to_vec(),
+                true,
+            ),
+        vec![Action::WriteKey(
+            unbound_key,
+            "\u{1b}[120;5u".as_bytes().to_vec(),
+            true
+        )],
+        "unbound keys are written to the pane which decides how to encode them"
+    );
+}
+
+#[test]
+fn can_define_key_sequences() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" {
+                    bind "c" { NewTab; }
+                    bind "n" "Right" { GoToNextTab; }
+                }
+                bind "g" {
+                    bind "g" { ScrollToTop; }
+                }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let match_in_normal_mode =
+        |keys: &[Key]| config.keybinds.match_key_sequence(&InputMode::Normal, keys);
+    assert_eq!(
+        match_in_normal_mode(&[Key::Ctrl('a')]),
+        KeySequenceMatch::Partial,
+        "Waiting for the next key"
+    );
+    assert_eq!(
+        match_in_normal_mode(&[Key::Ctrl('a'), Key::Char('c')]),
+        KeySequenceMatch::Complete(vec![Action::NewTab(None, vec![], None, None, None)]),
+    );
+    assert_eq!(
+        match_in_normal_mode(&[Key::Ctrl('a'), Key::Right]),
+        KeySequenceMatch::Complete(vec![Action::GoToNextTab]),
+        "Several keys can follow the same key"
+    );
+    assert_eq!(
+        match_in_normal_mode(&[Key::Char('g'), Key::Char('g')]),
+        KeySequenceMatch::Complete(vec![Action::ScrollToTop]),
+    );
+    assert_eq!(
+        match_in_normal_mode(&[Key::Ctrl('a'), Key::Char('x')]),
+        KeySequenceMatch::NoMatch,
+        "Unbound sequence"
+    );
+    assert_eq!(
+        config
+            .keybinds
+            .match_key_sequence(&InputMode::Pane, &[Key::Ctrl('a')]),
+        KeySequenceMatch::NoMatch,
+        "Sequences are bound per mode"
+    );
+    assert_eq!(
+        config
+            .keybinds
+            .get_actions_for_key_in_mode(&InputMode::Normal, &Key::Char('g')),
+        None,
+        "Beginning a sequence does not bind the key on its own"
+    );
+}
+
+#[test]
+fn unbinding_a_key_unbinds_the_sequences_it_begins() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" {
+                    bind "c" { NewTab; }
+                }
+                bind "g" {
+                    bind "g" { ScrollToTop; }
+                }
+                unbind "Ctrl a"
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    assert_eq!(
+        config
+            .keybinds
+            .match_key_sequence(&InputMode::Normal, &[Key::Ctrl('a')]),
+        KeySequenceMatch::NoMatch,
+        "Sequence unbound"
+    );
+    assert_eq!(
+        config
+            .keybinds
+            .match_key_sequence(&InputMode::Normal, &[Key::Char('g')]),
+        KeySequenceMatch::Partial,
+        "Other sequence still bound"
+    );
+}
+
+#[test]
+fn merging_a_key_bound_on_its_own_drops_the_sequences_it_began() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" {
+                    bind "c" { NewTab; }
+                }
+                bind "g" {
+                    bind "g" { ScrollToTop; }
+                }
+            }
+        }
+    "#;
+    let other_config_contents = r#"
+        keybinds {
+            normal {
+                bind "g" { ScrollToBottom; }
+            }
+        }
+    "#;
+    let mut keybinds = Config::from_kdl(config_contents, None).unwrap().keybinds;
+    let other_keybinds = Config::from_kdl(other_config_contents, None)
+        .unwrap()
+        .keybinds;
+    keybinds.merge(other_keybinds);
+    assert_eq!(
+        keybinds.match_key_sequence(&InputMode::Normal, &[Key::Char('g')]),
+        KeySequenceMatch::NoMatch,
+        "Sequence dropped"
+    );
+    assert_eq!(
+        keybinds.get_actions_for_key_in_mode(&InputMode::Normal, &Key::Char('g')),
+        Some(&vec![Action::ScrollToBottom]),
+    );
+    assert_eq!(
+        keybinds.match_key_sequence(&InputMode::Normal, &[Key::Ctrl('a')]),
+        KeySequenceMatch::Partial,
+        "Other sequence still bound"
+    );
+}
+
+#[test]
+fn error_received_on_key_bound_to_both_actions_and_following_keys() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "g" {
+                    bind "g" { ScrollToTop; }
+                    ScrollToBottom
+                }
+            }
+        }
+    "#;
+    assert!(Config::from_kdl(config_contents, None).is_err());
+}
+
 #[test]
 fn can_define_series_of_actions_for_same_keybinding() {
     let config_contents = r#"
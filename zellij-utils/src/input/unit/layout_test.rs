@@ -498,6 +498,43 @@ fn layout_with_command_panes_and_cwd_and_args() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_with_literal_variables_in_command_args() {
+    // eg. a shell expression meant for the command rather than for us
+    let kdl_layout = r#"
+        layout {
+            pane command="bash" {
+                args "-c" "echo ${ZELLIJ_LAYOUT_TEST_UNSET_VARIABLE} $${HOME} ${1:-default}"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![TiledPaneLayout {
+                    run: Some(Run::Command(RunCommand {
+                        command: PathBuf::from("bash"),
+                        args: vec![
+                            String::from("-c"),
+                            String::from(
+                                "echo ${ZELLIJ_LAYOUT_TEST_UNSET_VARIABLE} ${HOME} ${1:-default}",
+                            ),
+                        ],
+                        hold_on_close: true,
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            vec![],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
 #[test]
 fn layout_with_command_panes_and_close_on_exit() {
     let kdl_layout = r#"
@@ -1093,6 +1130,40 @@ fn layout_with_pane_excluded_from_sync() {
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn layout_with_pane_monitors() {
+    let kdl_layout = r#"
+        layout {
+            tab monitor_silence=30 {
+                pane monitor_bell=false monitor_activity=true
+                floating_panes {
+                    pane monitor_silence=5
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let (_tab_name, tiled_panes, floating_panes) = &layout.tabs[0];
+    assert_eq!(
+        tiled_panes.children[0].monitors,
+        PaneMonitors {
+            bell: Some(false),
+            activity: Some(true),
+            silence: Some(30),
+        },
+        "tab monitors apply to the panes that don't set them"
+    );
+    assert_eq!(
+        floating_panes[0].monitors,
+        PaneMonitors {
+            bell: None,
+            activity: None,
+            silence: Some(5),
+        },
+        "pane monitors override the ones of the tab"
+    );
+}
+
 #[test]
 fn cannot_define_tab_template_name_with_space() {
     let kdl_layout = r#"
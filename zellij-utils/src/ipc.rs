@@ -16,6 +16,7 @@ use std::{
     io::{self, Write},
     marker::PhantomData,
     os::unix::io::{AsRawFd, FromRawFd},
+    str::FromStr,
 };
 
 type SessionId = u64;
@@ -42,6 +43,29 @@ pub struct ClientAttributes {
     pub size: Size,
     pub style: Style,
     pub keybinds: Keybinds,
+    pub color_depth: ColorDepth,
+}
+
+/// How many colours the terminal of a client can display, so that the server can bring colours
+/// it does not support down to ones it does before rendering to it
+#[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Indexed256,
+    Ansi16,
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "truecolor" | "TrueColor" | "24bit" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Indexed256),
+            "16" => Ok(ColorDepth::Ansi16),
+            _ => Err(format!("No such color depth: {}", s)),
+        }
+    }
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -70,6 +94,7 @@ pub enum ClientToServerMsg {
     BackgroundColor(String),
     ForegroundColor(String),
     ColorRegisters(Vec<(usize, String)>),
+    TerminalSupportsKittyGraphics,
     TerminalResize(Size),
     NewClient(
         ClientAttributes,
@@ -83,6 +108,8 @@ pub enum ClientToServerMsg {
         Options,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane id to focus
+        bool,                // is read-only
+        Option<ClientId>,    // client whose focus a read-only client follows
     ),
     Action(Action, Option<u32>, Option<ClientId>), // u32 is the terminal id
     ClientExited,
@@ -103,8 +130,9 @@ pub enum ServerToClientMsg {
     Log(Vec<String>),
     LogError(Vec<String>),
     SwitchSession(ConnectToSession),
-    UnblockCliPipeInput(String),   // String -> pipe name
-    CliPipeOutput(String, String), // String -> pipe name, String -> Output
+    UnblockCliPipeInput(String),         // String -> pipe name
+    CliPipeOutput(String, String),       // String -> pipe name, String -> Output
+    Reconfigure(Keybinds, Box<Options>), // the session's config was reloaded
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
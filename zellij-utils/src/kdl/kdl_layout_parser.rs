@@ -2,9 +2,9 @@ use crate::input::{
     command::RunCommand,
     config::ConfigError,
     layout::{
-        FloatingPaneLayout, Layout, LayoutConstraint, PercentOrFixed, PluginUserConfiguration, Run,
-        RunPlugin, RunPluginLocation, SplitDirection, SplitSize, SwapFloatingLayout,
-        SwapTiledLayout, TiledPaneLayout,
+        FloatingPaneLayout, Layout, LayoutConstraint, PaneMonitors, PercentOrFixed,
+        PluginUserConfiguration, Run, RunPlugin, RunPluginLocation, SplitDirection, SplitSize,
+        SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
     },
 };
 
@@ -13,6 +13,7 @@ use kdl::*;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 
+use super::interpolate_kdl_document;
 use crate::{
     kdl_child_with_name, kdl_children_nodes, kdl_first_entry_as_bool, kdl_first_entry_as_i64,
     kdl_first_entry_as_string, kdl_get_bool_property_or_child_value,
@@ -102,6 +103,9 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "expanded"
             || property_name == "exclude_from_sync"
             || property_name == "contents_file"
+            || property_name == "monitor_bell"
+            || property_name == "monitor_activity"
+            || property_name == "monitor_silence"
     }
     fn is_a_valid_floating_pane_property(&self, property_name: &str) -> bool {
         property_name == "borderless"
@@ -119,6 +123,9 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "width"
             || property_name == "height"
             || property_name == "contents_file"
+            || property_name == "monitor_bell"
+            || property_name == "monitor_activity"
+            || property_name == "monitor_silence"
     }
     fn is_a_valid_tab_property(&self, property_name: &str) -> bool {
         property_name == "focus"
@@ -131,6 +138,9 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "min_panes"
             || property_name == "exact_panes"
             || property_name == "hide_floating_panes"
+            || property_name == "monitor_bell"
+            || property_name == "monitor_activity"
+            || property_name == "monitor_silence"
     }
     pub fn is_a_reserved_plugin_property(property_name: &str) -> bool {
         property_name == "location"
@@ -446,6 +456,7 @@ impl<'a> KdlLayoutParser<'a> {
                 cwd,
                 hold_on_close,
                 hold_on_start,
+                originating_plugin: None,
             }))),
             (None, Some(edit), Some(cwd)) => {
                 Ok(Some(Run::EditFile(cwd.join(edit), None, Some(cwd))))
@@ -523,6 +534,7 @@ impl<'a> KdlLayoutParser<'a> {
             kdl_get_bool_property_or_child_value_with_error!(kdl_node, "exclude_from_sync");
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
+        let monitors = self.parse_pane_monitors(kdl_node)?;
         let split_size = self.parse_split_size(kdl_node)?;
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
@@ -570,6 +582,7 @@ impl<'a> KdlLayoutParser<'a> {
             children,
             children_are_stacked,
             is_expanded_in_stack,
+            monitors,
             pane_initial_contents,
             ..Default::default()
         })
@@ -589,6 +602,7 @@ impl<'a> KdlLayoutParser<'a> {
             .map(|name| name.to_string());
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
+        let monitors = self.parse_pane_monitors(kdl_node)?;
         self.assert_no_mixed_children_and_properties(kdl_node)?;
         let pane_initial_contents = contents_file.and_then(|contents_file| {
             self.file_name.parent().and_then(|parent_folder| {
@@ -603,10 +617,34 @@ impl<'a> KdlLayoutParser<'a> {
             y,
             run,
             focus,
+            monitors,
             pane_initial_contents,
             ..Default::default()
         })
     }
+    fn parse_pane_monitors(&self, kdl_node: &KdlNode) -> Result<PaneMonitors, ConfigError> {
+        let bell = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "monitor_bell");
+        let activity =
+            kdl_get_bool_property_or_child_value_with_error!(kdl_node, "monitor_activity");
+        let silence = match kdl_get_int_property_or_child_value!(kdl_node, "monitor_silence") {
+            Some(silence) if silence < 0 => {
+                return Err(ConfigError::new_layout_kdl_error(
+                    format!(
+                        "monitor_silence should be a number of seconds, found {}",
+                        silence
+                    ),
+                    kdl_node.span().offset(),
+                    kdl_node.span().len(),
+                ));
+            },
+            silence => silence.map(|silence| silence as u64),
+        };
+        Ok(PaneMonitors {
+            bell,
+            activity,
+            silence,
+        })
+    }
     fn insert_children_to_pane_template(
         &self,
         kdl_node: &KdlNode,
@@ -700,6 +738,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
                 let exclude_from_sync =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "exclude_from_sync");
+                let monitors = self.parse_pane_monitors(kdl_node)?;
 
                 let external_children_index = if should_mark_external_children_index {
                     self.populate_external_children_index(kdl_node)?
@@ -739,6 +778,7 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(exclude_from_sync) = exclude_from_sync {
                     pane_template.exclude_from_sync = Some(exclude_from_sync);
                 }
+                pane_template.monitors = monitors.or(pane_template.monitors);
                 if let Some(split_size) = split_size {
                     pane_template.split_size = Some(split_size);
                 }
@@ -803,6 +843,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
+                let monitors = self.parse_pane_monitors(kdl_node)?;
                 self.assert_no_bare_attributes_in_pane_node_with_template(
                     &run,
                     &pane_template.run,
@@ -825,6 +866,7 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(name) = name {
                     pane_template.name = Some(name);
                 }
+                pane_template.monitors = monitors.or(pane_template.monitors);
                 let height = self.parse_percent_or_fixed(kdl_node, "height", false)?;
                 let width = self.parse_percent_or_fixed(kdl_node, "width", false)?;
                 let x = self.parse_percent_or_fixed(kdl_node, "x", true)?;
@@ -854,6 +896,7 @@ impl<'a> KdlLayoutParser<'a> {
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
+                let monitors = self.parse_pane_monitors(kdl_node)?;
                 self.assert_no_bare_attributes_in_pane_node_with_template(
                     &run,
                     &pane_template.run,
@@ -876,6 +919,7 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(name) = name {
                     pane_template.name = Some(name);
                 }
+                pane_template.monitors = monitors.or(pane_template.monitors);
                 let height = self.parse_percent_or_fixed(kdl_node, "height", false)?;
                 let width = self.parse_percent_or_fixed(kdl_node, "width", false)?;
                 let x = self.parse_percent_or_fixed(kdl_node, "x", true)?;
@@ -1039,6 +1083,7 @@ impl<'a> KdlLayoutParser<'a> {
         self.assert_legal_template_name(&template_name, kdl_node)?;
         let focus = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "focus");
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
+        let monitors = self.parse_pane_monitors(kdl_node)?;
 
         let is_floating = self.differentiate_pane_and_floating_pane_template(&kdl_node)?;
         let can_be_either_floating_or_tiled =
@@ -1051,6 +1096,7 @@ impl<'a> KdlLayoutParser<'a> {
                     PaneOrFloatingPane::Either(TiledPaneLayout {
                         focus,
                         run,
+                        monitors,
                         ..Default::default()
                     }),
                     kdl_node.clone(),
@@ -1073,6 +1119,7 @@ impl<'a> KdlLayoutParser<'a> {
                         width,
                         x,
                         y,
+                        monitors,
                         ..Default::default()
                     }),
                     kdl_node.clone(),
@@ -1111,6 +1158,7 @@ impl<'a> KdlLayoutParser<'a> {
                         children: pane_parts,
                         children_are_stacked,
                         is_expanded_in_stack,
+                        monitors,
                         ..Default::default()
                     }),
                     kdl_node.clone(),
@@ -1140,6 +1188,7 @@ impl<'a> KdlLayoutParser<'a> {
         let is_focused = kdl_get_bool_property_or_child_value!(kdl_node, "focus").unwrap_or(false);
         let hide_floating_panes =
             kdl_get_bool_property_or_child_value!(kdl_node, "hide_floating_panes").unwrap_or(false);
+        let tab_monitors = self.parse_pane_monitors(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
         let mut child_floating_panes = vec![];
         let children = match kdl_children_nodes!(kdl_node) {
@@ -1162,6 +1211,10 @@ impl<'a> KdlLayoutParser<'a> {
         if let Some(cwd_prefix) = &self.cwd_prefix(tab_cwd.as_ref())? {
             pane_layout.add_cwd_to_layout(&cwd_prefix);
         }
+        pane_layout.add_monitors_to_layout(tab_monitors);
+        for floating_pane in child_floating_panes.iter_mut() {
+            floating_pane.monitors = floating_pane.monitors.or(tab_monitors);
+        }
         Ok((is_focused, tab_name, pane_layout, child_floating_panes))
     }
     fn parse_child_pane_nodes_for_tab(
@@ -1537,6 +1590,7 @@ impl<'a> KdlLayoutParser<'a> {
             kdl_get_string_property_or_child_value!(kdl_node, "name").map(|s| s.to_string());
         let tab_cwd = self.parse_path(kdl_node, "cwd")?;
         let is_focused = kdl_get_bool_property_or_child_value!(kdl_node, "focus").unwrap_or(false);
+        let tab_monitors = self.parse_pane_monitors(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
         match kdl_children_nodes!(kdl_node) {
             Some(children) => {
@@ -1568,6 +1622,10 @@ impl<'a> KdlLayoutParser<'a> {
         if let Some(cwd_prefix) = self.cwd_prefix(tab_cwd.as_ref())? {
             tab_layout.add_cwd_to_layout(&cwd_prefix);
         }
+        tab_layout.add_monitors_to_layout(tab_monitors);
+        for floating_pane in tab_template_floating_panes.iter_mut() {
+            floating_pane.monitors = floating_pane.monitors.or(tab_monitors);
+        }
         tab_layout.external_children_index = None;
         Ok((
             is_focused,
@@ -2285,7 +2343,8 @@ impl<'a> KdlLayoutParser<'a> {
         raw_swap_layouts: &str,
         mut existing_layout: Layout,
     ) -> Result<Layout, ConfigError> {
-        let kdl_swap_layout: KdlDocument = raw_swap_layouts.parse()?;
+        let mut kdl_swap_layout: KdlDocument = raw_swap_layouts.parse()?;
+        interpolate_kdl_document(&mut kdl_swap_layout);
         let mut swap_tiled_layouts = vec![];
         let mut swap_floating_layouts = vec![];
 
@@ -2329,7 +2388,8 @@ impl<'a> KdlLayoutParser<'a> {
         Ok(existing_layout)
     }
     pub fn parse(&mut self) -> Result<Layout, ConfigError> {
-        let kdl_layout: KdlDocument = self.raw_layout.parse()?;
+        let mut kdl_layout: KdlDocument = self.raw_layout.parse()?;
+        interpolate_kdl_document(&mut kdl_layout);
         let layout_node = kdl_layout
             .nodes()
             .iter()
@@ -12,18 +12,21 @@ use crate::input::options::{Clipboard, OnForceClose, Options};
 use crate::input::permission::{GrantedPermission, PermissionCache};
 use crate::input::plugins::{PluginConfig, PluginTag, PluginType, PluginsConfig};
 use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
+use crate::ipc::ColorDepth;
 use kdl_layout_parser::KdlLayoutParser;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use strum::IntoEnumIterator;
 
-use miette::NamedSource;
+use miette::{NamedSource, SourceSpan};
 
-use kdl::{KdlDocument, KdlEntry, KdlNode};
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use crate::input::actions::{Action, SearchDirection, SearchOption};
+use crate::input::actions::{
+    Action, CopyModeMotion, CopyModeSelection, SearchDirection, SearchOption,
+};
 use crate::input::command::RunCommandAction;
 
 #[macro_export]
@@ -46,6 +49,8 @@ macro_rules! parse_kdl_action_arguments {
                 "ScrollDown" => Ok(Action::ScrollDown),
                 "ScrollToBottom" => Ok(Action::ScrollToBottom),
                 "ScrollToTop" => Ok(Action::ScrollToTop),
+                "ScrollToPreviousPrompt" => Ok(Action::ScrollToPreviousPrompt),
+                "ScrollToNextPrompt" => Ok(Action::ScrollToNextPrompt),
                 "PageScrollUp" => Ok(Action::PageScrollUp),
                 "PageScrollDown" => Ok(Action::PageScrollDown),
                 "HalfPageScrollUp" => Ok(Action::HalfPageScrollUp),
@@ -65,12 +70,16 @@ macro_rules! parse_kdl_action_arguments {
                 "UndoRenameTab" => Ok(Action::UndoRenameTab),
                 "Detach" => Ok(Action::Detach),
                 "Copy" => Ok(Action::Copy),
+                "CopyModeYank" => Ok(Action::CopyModeYank),
+                "SelectLastCommandOutput" => Ok(Action::SelectLastCommandOutput),
+                "CopyLastCommandOutput" => Ok(Action::CopyLastCommandOutput),
                 "Confirm" => Ok(Action::Confirm),
                 "Deny" => Ok(Action::Deny),
                 "ToggleMouseMode" => Ok(Action::ToggleMouseMode),
                 "PreviousSwapLayout" => Ok(Action::PreviousSwapLayout),
                 "NextSwapLayout" => Ok(Action::NextSwapLayout),
                 "Clear" => Ok(Action::ClearScreen),
+                "ReloadConfig" => Ok(Action::ReloadConfig),
                 _ => Err(ConfigError::new_kdl_error(
                     format!("Unsupported action: {:?}", $action_name),
                     $action_node.span().offset(),
@@ -512,6 +521,26 @@ impl Action {
                 })?;
                 Ok(Action::SearchToggleOption(toggle_option))
             },
+            "CopyModeMotion" => {
+                let motion = CopyModeMotion::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid copy mode motion: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::CopyModeMotion(motion))
+            },
+            "CopyModeToggleSelection" => {
+                let selection = CopyModeSelection::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid copy mode selection: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::CopyModeToggleSelection(selection))
+            },
             "Search" => {
                 let search_direction =
                     SearchDirection::from_str(string.as_str()).map_err(|_| {
@@ -524,6 +553,7 @@ impl Action {
                 Ok(Action::Search(search_direction))
             },
             "RenameSession" => Ok(Action::RenameSession(string)),
+            "SwitchTheme" => Ok(Action::SwitchTheme(string, None)),
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name),
                 action_node.span().offset(),
@@ -676,6 +706,12 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             "ScrollToTop" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "ScrollToPreviousPrompt" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ScrollToNextPrompt" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "PageScrollUp" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
@@ -722,6 +758,15 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             },
             "Detach" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
             "Copy" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
+            "CopyModeYank" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "SelectLastCommandOutput" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "CopyLastCommandOutput" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "Clear" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
             "Confirm" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
             "Deny" => parse_kdl_action_arguments!(action_name, action_arguments, kdl_action),
@@ -885,6 +930,16 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "CopyModeMotion" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "CopyModeToggleSelection" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             "Run" => {
                 let arguments = action_arguments.iter().copied();
                 let mut args = kdl_arguments_that_are_strings(arguments)?;
@@ -926,6 +981,7 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                     direction,
                     hold_on_close,
                     hold_on_start,
+                    originating_plugin: None,
                 };
                 let x = command_metadata
                     .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "x"))
@@ -1037,6 +1093,7 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             },
             "PreviousSwapLayout" => Ok(Action::PreviousSwapLayout),
             "NextSwapLayout" => Ok(Action::NextSwapLayout),
+            "ReloadConfig" => Ok(Action::ReloadConfig),
             "BreakPane" => Ok(Action::BreakPane),
             "BreakPaneRight" => Ok(Action::BreakPaneRight),
             "BreakPaneLeft" => Ok(Action::BreakPaneLeft),
@@ -1045,6 +1102,11 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "SwitchTheme" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name).into(),
                 kdl_action.span().offset(),
@@ -1532,6 +1594,57 @@ impl Options {
         let serialization_interval =
             kdl_property_first_arg_as_i64_or_error!(kdl_options, "serialization_interval")
                 .map(|(scroll_buffer_size, _entry)| scroll_buffer_size as u64);
+        let support_kitty_keyboard_protocol = kdl_property_first_arg_as_bool_or_error!(
+            kdl_options,
+            "support_kitty_keyboard_protocol"
+        )
+        .map(|(v, _)| v);
+        let key_sequence_timeout =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "key_sequence_timeout")
+                .map(|(timeout, _entry)| timeout as u64);
+        let prefer_key_sequences =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "prefer_key_sequences")
+                .map(|(v, _)| v);
+        let light_theme = kdl_property_first_arg_as_string_or_error!(kdl_options, "light_theme")
+            .map(|(theme, _entry)| theme.to_string());
+        let dark_theme = kdl_property_first_arg_as_string_or_error!(kdl_options, "dark_theme")
+            .map(|(theme, _entry)| theme.to_string());
+        let serialize_pane_scrollback =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "serialize_pane_scrollback")
+                .map(|(v, _)| v);
+        let scrollback_memory_budget = match kdl_property_first_arg_as_i64_or_error!(
+            kdl_options,
+            "scrollback_memory_budget"
+        ) {
+            Some((megabytes, entry)) => Some(usize::try_from(megabytes).map_err(|_| {
+                kdl_parsing_error!(
+                    format!(
+                        "Invalid value for scrollback_memory_budget: '{}'",
+                        megabytes
+                    ),
+                    entry
+                )
+            })?),
+            None => None,
+        };
+        let monitor_bell =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "monitor_bell").map(|(v, _)| v);
+        let monitor_activity =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "monitor_activity")
+                .map(|(v, _)| v);
+        let monitor_silence =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "monitor_silence")
+                .map(|(v, _)| v as u64);
+        let color_depth =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "color_depth") {
+                Some((string, entry)) => Some(ColorDepth::from_str(string).map_err(|_| {
+                    kdl_parsing_error!(
+                        format!("Invalid value for color_depth: '{}'", string),
+                        entry
+                    )
+                })?),
+                None => None,
+            };
         Ok(Options {
             simplified_ui,
             theme,
@@ -1558,6 +1671,17 @@ impl Options {
             scrollback_lines_to_serialize,
             styled_underlines,
             serialization_interval,
+            support_kitty_keyboard_protocol,
+            key_sequence_timeout,
+            prefer_key_sequences,
+            light_theme,
+            dark_theme,
+            serialize_pane_scrollback,
+            scrollback_memory_budget,
+            monitor_bell,
+            monitor_activity,
+            monitor_silence,
+            color_depth,
         })
     }
 }
@@ -1652,18 +1776,29 @@ impl EnvironmentVariables {
 impl Keybinds {
     fn bind_keys_in_block(
         block: &KdlNode,
-        input_mode_keybinds: &mut HashMap<Key, Vec<Action>>,
+        input_mode: &InputMode,
+        keybinds: &mut Keybinds,
         config_options: &Options,
     ) -> Result<(), ConfigError> {
         let all_nodes = kdl_children_nodes_or_error!(block, "no keybinding block for mode");
         let bind_nodes = all_nodes.iter().filter(|n| kdl_name!(n) == "bind");
         let unbind_nodes = all_nodes.iter().filter(|n| kdl_name!(n) == "unbind");
         for key_block in bind_nodes {
-            Keybinds::bind_actions_for_each_key(key_block, input_mode_keybinds, config_options)?;
+            if Keybinds::begins_key_sequence(key_block) {
+                let key_sequences = keybinds.get_key_sequences_for_mode_mut(input_mode);
+                Keybinds::bind_key_sequences(key_block, &[], key_sequences, config_options)?;
+            } else {
+                let input_mode_keybinds = keybinds.get_input_mode_mut(input_mode);
+                Keybinds::bind_actions_for_each_key(
+                    key_block,
+                    input_mode_keybinds,
+                    config_options,
+                )?;
+            }
         }
         // we loop a second time so that the unbinds always happen after the binds
         for key_block in unbind_nodes {
-            Keybinds::unbind_keys(key_block, input_mode_keybinds)?;
+            Keybinds::unbind_keys(key_block, input_mode, keybinds)?;
         }
         for key_block in all_nodes {
             if kdl_name!(key_block) != "bind" && kdl_name!(key_block) != "unbind" {
@@ -1703,8 +1838,12 @@ impl Keybinds {
                     if modes_to_exclude.contains(&mode) {
                         continue;
                     }
-                    let mut input_mode_keybinds = keybinds_from_config.get_input_mode_mut(&mode);
-                    Keybinds::bind_keys_in_block(block, &mut input_mode_keybinds, config_options)?;
+                    Keybinds::bind_keys_in_block(
+                        block,
+                        &mode,
+                        &mut keybinds_from_config,
+                        config_options,
+                    )?;
                 }
             }
             if kdl_name!(block) == "shared_among" {
@@ -1716,8 +1855,12 @@ impl Keybinds {
                     if !modes_to_include.contains(&mode) {
                         continue;
                     }
-                    let mut input_mode_keybinds = keybinds_from_config.get_input_mode_mut(&mode);
-                    Keybinds::bind_keys_in_block(block, &mut input_mode_keybinds, config_options)?;
+                    Keybinds::bind_keys_in_block(
+                        block,
+                        &mode,
+                        &mut keybinds_from_config,
+                        config_options,
+                    )?;
                 }
             }
         }
@@ -1729,9 +1872,13 @@ impl Keybinds {
             {
                 continue;
             }
-            let mut input_mode_keybinds =
-                Keybinds::input_mode_keybindings(mode, &mut keybinds_from_config)?;
-            Keybinds::bind_keys_in_block(mode, &mut input_mode_keybinds, config_options)?;
+            let input_mode = Keybinds::input_mode_keybindings(mode, &mut keybinds_from_config)?;
+            Keybinds::bind_keys_in_block(
+                mode,
+                &input_mode,
+                &mut keybinds_from_config,
+                config_options,
+            )?;
         }
         if let Some(global_unbind) = kdl_keybinds.children().and_then(|c| c.get("unbind")) {
             Keybinds::unbind_keys_in_all_modes(global_unbind, &mut keybinds_from_config)?;
@@ -1750,32 +1897,86 @@ impl Keybinds {
         }
         Ok(())
     }
-    fn unbind_keys(
+    // a key that begins a sequence (eg. `bind "Ctrl a" { bind "c" { NewTab; }; }`) has follow-up
+    // keys instead of actions
+    fn begins_key_sequence(key_block: &KdlNode) -> bool {
+        kdl_children_nodes!(key_block)
+            .map(|children| children.iter().any(|child| kdl_name!(child) == "bind"))
+            .unwrap_or(false)
+    }
+    fn bind_key_sequences(
         key_block: &KdlNode,
-        input_mode_keybinds: &mut HashMap<Key, Vec<Action>>,
+        preceding_keys: &[Key],
+        key_sequences: &mut HashMap<Vec<Key>, Vec<Action>>,
+        config_options: &Options,
     ) -> Result<(), ConfigError> {
         let keys: Vec<Key> = keys_from_kdl!(key_block);
+        let begins_key_sequence = Keybinds::begins_key_sequence(key_block);
+        let actions: Vec<Action> = if begins_key_sequence {
+            vec![]
+        } else {
+            actions_from_kdl!(key_block, config_options)
+        };
         for key in keys {
-            input_mode_keybinds.remove(&key);
+            let mut key_sequence = preceding_keys.to_vec();
+            key_sequence.push(key);
+            if !begins_key_sequence {
+                key_sequences.insert(key_sequence, actions.clone());
+                continue;
+            }
+            for next_key_block in kdl_children_nodes_or_error!(key_block, "no keys to follow") {
+                if kdl_name!(next_key_block) != "bind" {
+                    return Err(ConfigError::new_kdl_error(
+                        "A key followed by other keys cannot also be bound to actions".into(),
+                        next_key_block.span().offset(),
+                        next_key_block.span().len(),
+                    ));
+                }
+                Keybinds::bind_key_sequences(
+                    next_key_block,
+                    &key_sequence,
+                    key_sequences,
+                    config_options,
+                )?;
+            }
         }
         Ok(())
     }
+    fn unbind_keys(
+        key_block: &KdlNode,
+        input_mode: &InputMode,
+        keybinds: &mut Keybinds,
+    ) -> Result<(), ConfigError> {
+        let keys: Vec<Key> = keys_from_kdl!(key_block);
+        let input_mode_keybinds = keybinds.get_input_mode_mut(input_mode);
+        for key in &keys {
+            input_mode_keybinds.remove(key);
+        }
+        // unbinding a key also unbinds the sequences it begins
+        keybinds
+            .get_key_sequences_for_mode_mut(input_mode)
+            .retain(|key_sequence, _| !keys.contains(&key_sequence[0]));
+        Ok(())
+    }
     fn unbind_keys_in_all_modes(
         global_unbind: &KdlNode,
         keybinds_from_config: &mut Keybinds,
     ) -> Result<(), ConfigError> {
         let keys: Vec<Key> = keys_from_kdl!(global_unbind);
-        for mode in keybinds_from_config.0.values_mut() {
+        for mode in keybinds_from_config.single_keys.values_mut() {
             for key in &keys {
                 mode.remove(&key);
             }
         }
+        for key_sequences in keybinds_from_config.sequences.values_mut() {
+            key_sequences.retain(|key_sequence, _| !keys.contains(&key_sequence[0]));
+        }
         Ok(())
     }
-    fn input_mode_keybindings<'a>(
+    fn input_mode_keybindings(
         mode: &KdlNode,
-        keybinds_from_config: &'a mut Keybinds,
-    ) -> Result<&'a mut HashMap<Key, Vec<Action>>, ConfigError> {
+        keybinds_from_config: &mut Keybinds,
+    ) -> Result<InputMode, ConfigError> {
         let mode_name = kdl_name!(mode);
         let input_mode = InputMode::from_str(mode_name).map_err(|_| {
             ConfigError::new_kdl_error(
@@ -1788,15 +1989,19 @@ impl Keybinds {
         let clear_defaults_for_mode = kdl_arg_is_truthy!(mode, "clear-defaults");
         if clear_defaults_for_mode {
             input_mode_keybinds.clear();
+            keybinds_from_config
+                .get_key_sequences_for_mode_mut(&input_mode)
+                .clear();
         }
-        Ok(input_mode_keybinds)
+        Ok(input_mode)
     }
 }
 
 impl Config {
     pub fn from_kdl(kdl_config: &str, base_config: Option<Config>) -> Result<Config, ConfigError> {
         let mut config = base_config.unwrap_or_else(|| Config::default());
-        let kdl_config: KdlDocument = kdl_config.parse()?;
+        let mut kdl_config: KdlDocument = kdl_config.parse()?;
+        interpolate_kdl_document(&mut kdl_config);
 
         let config_options = Options::from_kdl(&kdl_config)?;
         config.options = config.options.merge(config_options);
@@ -1824,6 +2029,106 @@ impl Config {
         }
         Ok(config)
     }
+    /// The files included with `include "path.kdl"` at the top level of this config, in the order
+    /// they appear in. Relative paths are resolved against `config_dir`.
+    pub fn included_config_files(
+        kdl_config: &str,
+        config_dir: &Path,
+    ) -> Result<Vec<(PathBuf, SourceSpan)>, ConfigError> {
+        let mut kdl_config: KdlDocument = kdl_config.parse()?;
+        interpolate_kdl_document(&mut kdl_config);
+        let mut included_config_files = vec![];
+        for include in kdl_config
+            .nodes()
+            .iter()
+            .filter(|n| kdl_name!(n) == "include")
+        {
+            let included_path =
+                kdl_first_entry_as_string!(include).ok_or(ConfigError::new_kdl_error(
+                    "include must be given the path of a config file, eg. include \"base.kdl\""
+                        .into(),
+                    include.span().offset(),
+                    include.span().len(),
+                ))?;
+            included_config_files.push((config_dir.join(included_path), *include.span()));
+        }
+        Ok(included_config_files)
+    }
+}
+
+/// Expands `${VAR}` to the value of the environment variable `VAR` and a leading `~/` to the home
+/// directory in all the string values of the document (but not in node or property names).
+///
+/// Anything else is left as is, including `${VAR}` when `VAR` is not set (so that eg. a command
+/// in a layout can still use it in its own shell) and `$${` which is an escaped `${`.
+pub fn interpolate_kdl_document(kdl_document: &mut KdlDocument) {
+    for node in kdl_document.nodes_mut() {
+        for entry in node.entries_mut() {
+            let interpolated = entry.value().as_string().and_then(interpolate_string);
+            if let Some(interpolated) = interpolated {
+                entry.set_value(KdlValue::String(interpolated));
+            }
+        }
+        if let Some(children) = node.children_mut() {
+            interpolate_kdl_document(children);
+        }
+    }
+}
+
+/// Returns None if there is nothing to interpolate in this string
+fn interpolate_string(value: &str) -> Option<String> {
+    if !value.contains("${") && !value.starts_with("~/") {
+        return None;
+    }
+    let mut interpolated = String::new();
+    let mut rest = match value.strip_prefix("~/") {
+        Some(rest) => {
+            interpolated.push_str(&shellexpand::tilde("~/"));
+            rest
+        },
+        None => value,
+    };
+    while let Some(dollar_index) = rest.find('$') {
+        interpolated.push_str(&rest[..dollar_index]);
+        rest = &rest[dollar_index..];
+        if let Some(after_escape) = rest.strip_prefix("$${") {
+            interpolated.push_str("${");
+            rest = after_escape;
+            continue;
+        }
+        let variable_value = rest
+            .strip_prefix("${")
+            .and_then(|after_start| after_start.find('}').map(|end| &after_start[..end]))
+            .filter(|variable_name| is_environment_variable_name(variable_name))
+            .and_then(|variable_name| {
+                std::env::var(variable_name)
+                    .ok()
+                    .map(|variable_value| (variable_name, variable_value))
+            });
+        match variable_value {
+            Some((variable_name, variable_value)) => {
+                interpolated.push_str(&variable_value);
+                rest = &rest[variable_name.len() + 3..];
+            },
+            None => {
+                // an unset variable or not something we interpolate (eg. a shell expression such
+                // as ${1:-default}), leave it to whatever reads this value
+                interpolated.push('$');
+                rest = &rest[1..];
+            },
+        }
+    }
+    interpolated.push_str(rest);
+    Some(interpolated)
+}
+
+fn is_environment_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_')
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
 }
 
 impl PluginsConfig {
@@ -1834,6 +2139,38 @@ impl PluginsConfig {
         {
             let plugin_name = kdl_name!(plugin_config);
             let plugin_tag = PluginTag::new(plugin_name);
+            if let Some(location) =
+                kdl_get_string_property_or_child_value!(plugin_config, "location")
+            {
+                // an alias, eg. my_finder location="file:/path/to/plugin.wasm" { key "value"; }
+                let location = RunPluginLocation::parse(location, None).map_err(|e| {
+                    ConfigError::new_kdl_error(
+                        format!("Failed to parse plugin location {}: {}", location, e),
+                        plugin_config.span().offset(),
+                        plugin_config.span().len(),
+                    )
+                })?;
+                let path = match &location {
+                    RunPluginLocation::File(path) => path.clone(),
+                    RunPluginLocation::Zellij(tag) => PathBuf::from(tag.to_string()),
+                    RunPluginLocation::Remote(_) => PathBuf::new(),
+                };
+                let allow_exec_host_cmd =
+                    kdl_children_property_first_arg_as_bool!(plugin_config, "_allow_exec_host_cmd")
+                        .unwrap_or(false);
+                let configuration = parse_plugin_user_configuration(plugin_config)?;
+                plugins.insert(
+                    plugin_tag,
+                    PluginConfig {
+                        path,
+                        run: PluginType::Pane(None),
+                        location,
+                        _allow_exec_host_cmd: allow_exec_host_cmd,
+                        userspace_configuration: PluginUserConfiguration::new(configuration),
+                    },
+                );
+                continue;
+            }
             let path = kdl_children_property_first_arg_as_string!(plugin_config, "path")
                 .map(|path| PathBuf::from(path))
                 .ok_or(ConfigError::new_kdl_error(
@@ -2148,6 +2485,14 @@ impl TabInfo {
                     .ok_or(format!("Failed to parse tab {}", $name))?
             }};
         }
+        macro_rules! optional_bool_node {
+            ($name:expr) => {{
+                kdl_document
+                    .get($name)
+                    .and_then(|n| n.entries().iter().next())
+                    .and_then(|e| e.value().as_bool())
+            }};
+        }
 
         let position = int_node!("position", usize);
         let name = string_node!("name");
@@ -2169,6 +2514,10 @@ impl TabInfo {
         }
         let active_swap_layout_name = optional_string_node!("active_swap_layout_name");
         let is_swap_layout_dirty = bool_node!("is_swap_layout_dirty");
+        // alerts are only written when they are raised
+        let bell_alert = optional_bool_node!("bell_alert").unwrap_or(false);
+        let activity_alert = optional_bool_node!("activity_alert").unwrap_or(false);
+        let silence_alert = optional_bool_node!("silence_alert").unwrap_or(false);
         Ok(TabInfo {
             position,
             name,
@@ -2180,6 +2529,9 @@ impl TabInfo {
             other_focused_clients,
             active_swap_layout_name,
             is_swap_layout_dirty,
+            bell_alert,
+            activity_alert,
+            silence_alert,
         })
     }
     pub fn encode_to_kdl(&self) -> KdlDocument {
@@ -2231,6 +2583,18 @@ impl TabInfo {
         is_swap_layout_dirty.push(self.is_swap_layout_dirty);
         kdl_doucment.nodes_mut().push(is_swap_layout_dirty);
 
+        for (alert_name, is_alerted) in [
+            ("bell_alert", self.bell_alert),
+            ("activity_alert", self.activity_alert),
+            ("silence_alert", self.silence_alert),
+        ] {
+            if is_alerted {
+                let mut alert = KdlNode::new(alert_name);
+                alert.push(true);
+                kdl_doucment.nodes_mut().push(alert);
+            }
+        }
+
         kdl_doucment
     }
 }
@@ -2302,6 +2666,14 @@ impl PaneInfo {
                     .ok_or(format!("Failed to parse pane {}", $name))?
             }};
         }
+        macro_rules! optional_bool_node {
+            ($name:expr) => {{
+                kdl_document
+                    .get($name)
+                    .and_then(|n| n.entries().iter().next())
+                    .and_then(|e| e.value().as_bool())
+            }};
+        }
         macro_rules! string_node {
             ($name:expr) => {{
                 kdl_document
@@ -2358,6 +2730,19 @@ impl PaneInfo {
         let terminal_command = optional_string_node!("terminal_command");
         let plugin_url = optional_string_node!("plugin_url");
         let is_selectable = bool_node!("is_selectable");
+        let cwd = optional_string_node!("cwd").map(PathBuf::from);
+        let pid = optional_int_node!("pid", u32);
+        let foreground_process = kdl_document.get("foreground_process").map(|n| {
+            n.entries()
+                .iter()
+                .filter_map(|e| e.value().as_string())
+                .map(|s| s.to_owned())
+                .collect()
+        });
+        // alerts are only written when they are raised
+        let bell_alert = optional_bool_node!("bell_alert").unwrap_or(false);
+        let activity_alert = optional_bool_node!("activity_alert").unwrap_or(false);
+        let silence_alert = optional_bool_node!("silence_alert").unwrap_or(false);
 
         let pane_info = PaneInfo {
             id,
@@ -2382,6 +2767,12 @@ impl PaneInfo {
             terminal_command,
             plugin_url,
             is_selectable,
+            cwd,
+            pid,
+            foreground_process,
+            bell_alert,
+            activity_alert,
+            silence_alert,
         };
         Ok((tab_position, pane_info))
     }
@@ -2442,6 +2833,28 @@ impl PaneInfo {
             string_node!("plugin_url", plugin_url.to_string());
         }
         bool_node!("is_selectable", self.is_selectable);
+        if let Some(cwd) = &self.cwd {
+            string_node!("cwd", cwd.display().to_string());
+        }
+        if let Some(pid) = self.pid {
+            int_node!("pid", pid);
+        }
+        if let Some(foreground_process) = &self.foreground_process {
+            let mut foreground_process_node = KdlNode::new("foreground_process");
+            for part in foreground_process {
+                foreground_process_node.push(part.to_string());
+            }
+            kdl_doucment.nodes_mut().push(foreground_process_node);
+        }
+        if self.bell_alert {
+            bool_node!("bell_alert", true);
+        }
+        if self.activity_alert {
+            bool_node!("activity_alert", true);
+        }
+        if self.silence_alert {
+            bool_node!("silence_alert", true);
+        }
         kdl_doucment
     }
 }
@@ -2529,6 +2942,12 @@ fn serialize_and_deserialize_session_info_with_data() {
             terminal_command: Some("foo".to_owned()),
             plugin_url: None,
             is_selectable: true,
+            cwd: Some(PathBuf::from("/path/to/cwd")),
+            pid: Some(123),
+            foreground_process: Some(vec!["vim".to_owned(), "Cargo.toml".to_owned()]),
+            bell_alert: true,
+            activity_alert: false,
+            silence_alert: true,
         },
         PaneInfo {
             id: 1,
@@ -2553,6 +2972,12 @@ fn serialize_and_deserialize_session_info_with_data() {
             terminal_command: None,
             plugin_url: Some("i_am_a_fake_plugin".to_owned()),
             is_selectable: true,
+            cwd: None,
+            pid: None,
+            foreground_process: None,
+            bell_alert: false,
+            activity_alert: false,
+            silence_alert: false,
         },
     ];
     let mut panes = HashMap::new();
@@ -2571,6 +2996,9 @@ fn serialize_and_deserialize_session_info_with_data() {
                 other_focused_clients: vec![2, 3],
                 active_swap_layout_name: Some("BASE".to_owned()),
                 is_swap_layout_dirty: true,
+                bell_alert: false,
+                activity_alert: false,
+                silence_alert: false,
             },
             TabInfo {
                 position: 1,
@@ -2583,6 +3011,9 @@ fn serialize_and_deserialize_session_info_with_data() {
                 other_focused_clients: vec![2, 3],
                 active_swap_layout_name: None,
                 is_swap_layout_dirty: false,
+                bell_alert: true,
+                activity_alert: true,
+                silence_alert: true,
             },
         ],
         panes: PaneManifest { panes },
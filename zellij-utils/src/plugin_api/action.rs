@@ -1279,9 +1279,25 @@ impl TryFrom<Action> for ProtobufAction {
             | Action::NewInPlacePluginPane(..)
             | Action::Deny
             | Action::Copy
+            | Action::CopyModeMotion(..)
+            | Action::CopyModeToggleSelection(..)
+            | Action::CopyModeYank
+            | Action::ScrollToPreviousPrompt
+            | Action::ScrollToNextPrompt
+            | Action::SelectLastCommandOutput
+            | Action::CopyLastCommandOutput
             | Action::DumpLayout
+            | Action::PipePaneOutput(..)
             | Action::CliPipe { .. }
-            | Action::SkipConfirm(..) => Err("Unsupported action"),
+            | Action::SkipConfirm(..)
+            | Action::ActOnTarget(..)
+            | Action::ListTabs(..)
+            | Action::ListPanes(..)
+            | Action::ListClients(..)
+            | Action::ReloadConfig
+            | Action::SwitchTheme(..)
+            | Action::WriteKey(..)
+            | Action::PendingKeySequence(..) => Err("Unsupported action"),
         }
     }
 }
@@ -1293,6 +1309,7 @@ impl TryFrom<ProtobufSearchOption> for SearchOption {
             ProtobufSearchOption::CaseSensitivity => Ok(SearchOption::CaseSensitivity),
             ProtobufSearchOption::WholeWord => Ok(SearchOption::WholeWord),
             ProtobufSearchOption::Wrap => Ok(SearchOption::Wrap),
+            ProtobufSearchOption::Regex => Ok(SearchOption::Regex),
         }
     }
 }
@@ -1304,6 +1321,7 @@ impl TryFrom<SearchOption> for ProtobufSearchOption {
             SearchOption::CaseSensitivity => Ok(ProtobufSearchOption::CaseSensitivity),
             SearchOption::WholeWord => Ok(ProtobufSearchOption::WholeWord),
             SearchOption::Wrap => Ok(ProtobufSearchOption::Wrap),
+            SearchOption::Regex => Ok(ProtobufSearchOption::Regex),
         }
     }
 }
@@ -1372,6 +1390,7 @@ impl TryFrom<ProtobufRunCommandAction> for RunCommandAction {
             direction,
             hold_on_close,
             hold_on_start,
+            originating_plugin: None,
         })
     }
 }
@@ -14,8 +14,8 @@ pub use super::generated_api::api::{
     style::Style as ProtobufStyle,
 };
 use crate::data::{
-    CopyDestination, Event, EventType, InputMode, Key, LayoutInfo, ModeInfo, Mouse, PaneInfo,
-    PaneManifest, PermissionStatus, PluginCapabilities, SessionInfo, Style, TabInfo,
+    CopyDestination, Event, EventType, InputMode, Key, LayoutInfo, ModeInfo, Mouse, PaneId,
+    PaneInfo, PaneManifest, PermissionStatus, PluginCapabilities, SessionInfo, Style, TabInfo,
 };
 
 use crate::errors::prelude::*;
@@ -228,6 +228,79 @@ impl TryFrom<ProtobufEvent> for Event {
                 },
                 _ => Err("Malformed payload for the WebRequestResult Event"),
             },
+            Some(ProtobufEventType::PaneContents) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneContentsPayload(pane_contents_payload)) => {
+                    let pane_id = if pane_contents_payload.is_plugin {
+                        PaneId::Plugin(pane_contents_payload.pane_id)
+                    } else {
+                        PaneId::Terminal(pane_contents_payload.pane_id)
+                    };
+                    Ok(Event::PaneContents(pane_id, pane_contents_payload.lines))
+                },
+                _ => Err("Malformed payload for the PaneContents Event"),
+            },
+            Some(ProtobufEventType::PaneOutput) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneOutputPayload(pane_output_payload)) => {
+                    let pane_id = if pane_output_payload.is_plugin {
+                        PaneId::Plugin(pane_output_payload.pane_id)
+                    } else {
+                        PaneId::Terminal(pane_output_payload.pane_id)
+                    };
+                    Ok(Event::PaneOutput(pane_id, pane_output_payload.lines))
+                },
+                _ => Err("Malformed payload for the PaneOutput Event"),
+            },
+            Some(ProtobufEventType::PaneOpened) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneOpenedPayload(pane_id_payload)) => {
+                    Ok(Event::PaneOpened(pane_id_payload.into()))
+                },
+                _ => Err("Malformed payload for the PaneOpened Event"),
+            },
+            Some(ProtobufEventType::PaneClosed) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneClosedPayload(pane_id_payload)) => {
+                    Ok(Event::PaneClosed(pane_id_payload.into()))
+                },
+                _ => Err("Malformed payload for the PaneClosed Event"),
+            },
+            Some(ProtobufEventType::CommandPaneExited) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::CommandPaneExitedPayload(
+                    command_pane_exited_payload,
+                )) => {
+                    let pane_id = if command_pane_exited_payload.is_plugin {
+                        PaneId::Plugin(command_pane_exited_payload.pane_id)
+                    } else {
+                        PaneId::Terminal(command_pane_exited_payload.pane_id)
+                    };
+                    Ok(Event::CommandPaneExited(
+                        pane_id,
+                        command_pane_exited_payload.exit_code,
+                        command_pane_exited_payload
+                            .context
+                            .into_iter()
+                            .map(|c_i| (c_i.name, c_i.value))
+                            .collect(),
+                    ))
+                },
+                _ => Err("Malformed payload for the CommandPaneExited Event"),
+            },
+            Some(ProtobufEventType::PaneBell) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneBellPayload(pane_id_payload)) => {
+                    Ok(Event::PaneBell(pane_id_payload.into()))
+                },
+                _ => Err("Malformed payload for the PaneBell Event"),
+            },
+            Some(ProtobufEventType::PaneActivity) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneActivityPayload(pane_id_payload)) => {
+                    Ok(Event::PaneActivity(pane_id_payload.into()))
+                },
+                _ => Err("Malformed payload for the PaneActivity Event"),
+            },
+            Some(ProtobufEventType::PaneSilence) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::PaneSilencePayload(pane_id_payload)) => {
+                    Ok(Event::PaneSilence(pane_id_payload.into()))
+                },
+                _ => Err("Malformed payload for the PaneSilence Event"),
+            },
             None => Err("Unknown Protobuf Event"),
         }
     }
@@ -425,6 +498,75 @@ impl TryFrom<Event> for ProtobufEvent {
                     )),
                 })
             },
+            Event::PaneContents(pane_id, lines) => {
+                let (pane_id, is_plugin) = match pane_id {
+                    PaneId::Terminal(id) => (id, false),
+                    PaneId::Plugin(id) => (id, true),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::PaneContents as i32,
+                    payload: Some(event::Payload::PaneContentsPayload(PaneContentsPayload {
+                        pane_id,
+                        is_plugin,
+                        lines,
+                    })),
+                })
+            },
+            Event::PaneOutput(pane_id, lines) => {
+                let (pane_id, is_plugin) = match pane_id {
+                    PaneId::Terminal(id) => (id, false),
+                    PaneId::Plugin(id) => (id, true),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::PaneOutput as i32,
+                    payload: Some(event::Payload::PaneOutputPayload(PaneContentsPayload {
+                        pane_id,
+                        is_plugin,
+                        lines,
+                    })),
+                })
+            },
+            Event::PaneOpened(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PaneOpened as i32,
+                payload: Some(event::Payload::PaneOpenedPayload(pane_id.into())),
+            }),
+            Event::PaneClosed(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PaneClosed as i32,
+                payload: Some(event::Payload::PaneClosedPayload(pane_id.into())),
+            }),
+            Event::CommandPaneExited(pane_id, exit_code, context) => {
+                let (pane_id, is_plugin) = match pane_id {
+                    PaneId::Terminal(id) => (id, false),
+                    PaneId::Plugin(id) => (id, true),
+                };
+                let command_pane_exited_payload = CommandPaneExitedPayload {
+                    pane_id,
+                    is_plugin,
+                    exit_code,
+                    context: context
+                        .into_iter()
+                        .map(|(name, value)| ContextItem { name, value })
+                        .collect(),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::CommandPaneExited as i32,
+                    payload: Some(event::Payload::CommandPaneExitedPayload(
+                        command_pane_exited_payload,
+                    )),
+                })
+            },
+            Event::PaneBell(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PaneBell as i32,
+                payload: Some(event::Payload::PaneBellPayload(pane_id.into())),
+            }),
+            Event::PaneActivity(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PaneActivity as i32,
+                payload: Some(event::Payload::PaneActivityPayload(pane_id.into())),
+            }),
+            Event::PaneSilence(pane_id) => Ok(ProtobufEvent {
+                name: ProtobufEventType::PaneSilence as i32,
+                payload: Some(event::Payload::PaneSilencePayload(pane_id.into())),
+            }),
         }
     }
 }
@@ -681,6 +823,16 @@ impl TryFrom<ProtobufPaneInfo> for PaneInfo {
             terminal_command: protobuf_pane_info.terminal_command,
             plugin_url: protobuf_pane_info.plugin_url,
             is_selectable: protobuf_pane_info.is_selectable,
+            cwd: protobuf_pane_info.cwd.map(PathBuf::from),
+            pid: protobuf_pane_info.pid,
+            foreground_process: if protobuf_pane_info.foreground_process.is_empty() {
+                None
+            } else {
+                Some(protobuf_pane_info.foreground_process)
+            },
+            bell_alert: protobuf_pane_info.bell_alert,
+            activity_alert: protobuf_pane_info.activity_alert,
+            silence_alert: protobuf_pane_info.silence_alert,
         })
     }
 }
@@ -716,6 +868,12 @@ impl TryFrom<PaneInfo> for ProtobufPaneInfo {
             terminal_command: pane_info.terminal_command,
             plugin_url: pane_info.plugin_url,
             is_selectable: pane_info.is_selectable,
+            cwd: pane_info.cwd.map(|cwd| cwd.display().to_string()),
+            pid: pane_info.pid,
+            foreground_process: pane_info.foreground_process.unwrap_or_default(),
+            bell_alert: pane_info.bell_alert,
+            activity_alert: pane_info.activity_alert,
+            silence_alert: pane_info.silence_alert,
         })
     }
 }
@@ -738,6 +896,9 @@ impl TryFrom<ProtobufTabInfo> for TabInfo {
                 .collect(),
             active_swap_layout_name: protobuf_tab_info.active_swap_layout_name,
             is_swap_layout_dirty: protobuf_tab_info.is_swap_layout_dirty,
+            bell_alert: protobuf_tab_info.bell_alert,
+            activity_alert: protobuf_tab_info.activity_alert,
+            silence_alert: protobuf_tab_info.silence_alert,
         })
     }
 }
@@ -760,6 +921,9 @@ impl TryFrom<TabInfo> for ProtobufTabInfo {
                 .collect(),
             active_swap_layout_name: tab_info.active_swap_layout_name,
             is_swap_layout_dirty: tab_info.is_swap_layout_dirty,
+            bell_alert: tab_info.bell_alert,
+            activity_alert: tab_info.activity_alert,
+            silence_alert: tab_info.silence_alert,
         })
     }
 }
@@ -804,12 +968,16 @@ impl TryFrom<ProtobufModeUpdatePayload> for ModeInfo {
         let capabilities = PluginCapabilities {
             arrow_fonts: protobuf_mode_update_payload.arrow_fonts_support,
         };
+        let pending_key_sequence = protobuf_mode_update_payload.pending_key_sequence;
+        let is_read_only = protobuf_mode_update_payload.is_read_only;
         let mode_info = ModeInfo {
             mode: current_mode,
             keybinds,
             style,
             capabilities,
             session_name,
+            pending_key_sequence,
+            is_read_only,
         };
         Ok(mode_info)
     }
@@ -827,7 +995,11 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
             let mode: ProtobufInputMode = input_mode.try_into()?;
             let mut keybinds: Vec<ProtobufKeyBind> = vec![];
             for (key, actions) in input_mode_keybinds {
-                let protobuf_key: ProtobufKey = key.try_into()?;
+                // keys the plugin api cannot express at all (eg. F13) are not sent to plugins
+                let protobuf_key: ProtobufKey = match key.try_into() {
+                    Ok(protobuf_key) => protobuf_key,
+                    Err(_) => continue,
+                };
                 let mut protobuf_actions: Vec<ProtobufAction> = vec![];
                 for action in actions {
                     if let Ok(protobuf_action) = action.try_into() {
@@ -852,6 +1024,8 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
             keybinds: protobuf_input_mode_keybinds,
             arrow_fonts_support,
             session_name,
+            pending_key_sequence: mode_info.pending_key_sequence,
+            is_read_only: mode_info.is_read_only,
         })
     }
 }
@@ -909,6 +1083,14 @@ impl TryFrom<ProtobufEventType> for EventType {
             ProtobufEventType::SessionUpdate => EventType::SessionUpdate,
             ProtobufEventType::RunCommandResult => EventType::RunCommandResult,
             ProtobufEventType::WebRequestResult => EventType::WebRequestResult,
+            ProtobufEventType::PaneContents => EventType::PaneContents,
+            ProtobufEventType::PaneOutput => EventType::PaneOutput,
+            ProtobufEventType::PaneOpened => EventType::PaneOpened,
+            ProtobufEventType::PaneClosed => EventType::PaneClosed,
+            ProtobufEventType::CommandPaneExited => EventType::CommandPaneExited,
+            ProtobufEventType::PaneBell => EventType::PaneBell,
+            ProtobufEventType::PaneActivity => EventType::PaneActivity,
+            ProtobufEventType::PaneSilence => EventType::PaneSilence,
         })
     }
 }
@@ -936,10 +1118,43 @@ impl TryFrom<EventType> for ProtobufEventType {
             EventType::SessionUpdate => ProtobufEventType::SessionUpdate,
             EventType::RunCommandResult => ProtobufEventType::RunCommandResult,
             EventType::WebRequestResult => ProtobufEventType::WebRequestResult,
+            EventType::PaneContents => ProtobufEventType::PaneContents,
+            EventType::PaneOutput => ProtobufEventType::PaneOutput,
+            EventType::PaneOpened => ProtobufEventType::PaneOpened,
+            EventType::PaneClosed => ProtobufEventType::PaneClosed,
+            EventType::CommandPaneExited => ProtobufEventType::CommandPaneExited,
+            EventType::PaneBell => ProtobufEventType::PaneBell,
+            EventType::PaneActivity => ProtobufEventType::PaneActivity,
+            EventType::PaneSilence => ProtobufEventType::PaneSilence,
         })
     }
 }
 
+impl From<PaneIdPayload> for PaneId {
+    fn from(pane_id_payload: PaneIdPayload) -> Self {
+        if pane_id_payload.is_plugin {
+            PaneId::Plugin(pane_id_payload.pane_id)
+        } else {
+            PaneId::Terminal(pane_id_payload.pane_id)
+        }
+    }
+}
+
+impl From<PaneId> for PaneIdPayload {
+    fn from(pane_id: PaneId) -> Self {
+        match pane_id {
+            PaneId::Terminal(id) => PaneIdPayload {
+                pane_id: id,
+                is_plugin: false,
+            },
+            PaneId::Plugin(id) => PaneIdPayload {
+                pane_id: id,
+                is_plugin: true,
+            },
+        }
+    }
+}
+
 impl From<ProtobufResurrectableSession> for (String, Duration) {
     fn from(protobuf_resurrectable_session: ProtobufResurrectableSession) -> (String, Duration) {
         (
@@ -1005,6 +1220,20 @@ fn serialize_mode_update_event_with_non_default_values() {
                         ],
                     ),
                     (Key::Char('a'), vec![Action::WriteChars("foo".to_owned())]),
+                    (
+                        Key::WithModifiers(crate::data::KeyWithModifier::new(
+                            crate::data::BareKey::Char('t'),
+                            crate::data::KeyModifiers::CTRL | crate::data::KeyModifiers::SHIFT,
+                        )),
+                        vec![Action::NewTab(None, vec![], None, None, None)],
+                    ),
+                    (
+                        Key::WithModifiers(crate::data::KeyWithModifier::new(
+                            crate::data::BareKey::Enter,
+                            crate::data::KeyModifiers::SUPER,
+                        )),
+                        vec![Action::SwitchToMode(InputMode::Normal)],
+                    ),
                 ],
             ),
         ],
@@ -1035,6 +1264,8 @@ fn serialize_mode_update_event_with_non_default_values() {
         },
         capabilities: PluginCapabilities { arrow_fonts: false },
         session_name: Some("my awesome test session".to_owned()),
+        pending_key_sequence: vec!["Ctrl+a".to_owned()],
+        is_read_only: true,
     });
     let protobuf_event: ProtobufEvent = mode_update_event.clone().try_into().unwrap();
     let serialized_protobuf_event = protobuf_event.encode_to_vec();
@@ -1077,6 +1308,9 @@ fn serialize_tab_update_event_with_non_default_values() {
             other_focused_clients: vec![2, 3, 4],
             active_swap_layout_name: Some("my cool swap layout".to_owned()),
             is_swap_layout_dirty: false,
+            bell_alert: true,
+            activity_alert: false,
+            silence_alert: true,
         },
         TabInfo {
             position: 1,
@@ -1089,6 +1323,9 @@ fn serialize_tab_update_event_with_non_default_values() {
             other_focused_clients: vec![1, 5, 111],
             active_swap_layout_name: None,
             is_swap_layout_dirty: true,
+            bell_alert: false,
+            activity_alert: true,
+            silence_alert: false,
         },
         TabInfo::default(),
     ]);
@@ -1238,6 +1475,94 @@ fn serialize_visible_event() {
     );
 }
 
+#[test]
+fn serialize_pane_contents_event() {
+    use prost::Message;
+    let pane_contents_event = Event::PaneContents(
+        PaneId::Terminal(1),
+        vec!["$ cargo test".to_owned(), "test result: ok".to_owned()],
+    );
+    let protobuf_event: ProtobufEvent = pane_contents_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        pane_contents_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_pane_output_event() {
+    use prost::Message;
+    let pane_output_event = Event::PaneOutput(
+        PaneId::Terminal(1),
+        vec!["test tests::it_works ... FAILED".to_owned()],
+    );
+    let protobuf_event: ProtobufEvent = pane_output_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        pane_output_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_pane_closed_event() {
+    use prost::Message;
+    let pane_closed_event = Event::PaneClosed(PaneId::Plugin(2));
+    let protobuf_event: ProtobufEvent = pane_closed_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        pane_closed_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_pane_monitor_events() {
+    use prost::Message;
+    for pane_monitor_event in [
+        Event::PaneBell(PaneId::Terminal(1)),
+        Event::PaneActivity(PaneId::Plugin(2)),
+        Event::PaneSilence(PaneId::Terminal(3)),
+    ] {
+        let protobuf_event: ProtobufEvent = pane_monitor_event.clone().try_into().unwrap();
+        let serialized_protobuf_event = protobuf_event.encode_to_vec();
+        let deserialized_protobuf_event: ProtobufEvent =
+            Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+        let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+        assert_eq!(
+            pane_monitor_event, deserialized_event,
+            "Event properly serialized/deserialized without change"
+        );
+    }
+}
+
+#[test]
+fn serialize_command_pane_exited_event() {
+    use prost::Message;
+    let mut context = std::collections::BTreeMap::new();
+    context.insert("test_suite".to_owned(), "unit".to_owned());
+    let command_pane_exited_event = Event::CommandPaneExited(PaneId::Terminal(3), Some(1), context);
+    let protobuf_event: ProtobufEvent = command_pane_exited_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        command_pane_exited_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
 #[test]
 fn serialize_custom_message_event() {
     use prost::Message;
@@ -1347,6 +1672,9 @@ fn serialize_session_update_event_with_non_default_values() {
             other_focused_clients: vec![2, 3, 4],
             active_swap_layout_name: Some("my cool swap layout".to_owned()),
             is_swap_layout_dirty: false,
+            bell_alert: true,
+            activity_alert: false,
+            silence_alert: true,
         },
         TabInfo {
             position: 1,
@@ -1359,6 +1687,9 @@ fn serialize_session_update_event_with_non_default_values() {
             other_focused_clients: vec![1, 5, 111],
             active_swap_layout_name: None,
             is_swap_layout_dirty: true,
+            bell_alert: false,
+            activity_alert: true,
+            silence_alert: false,
         },
         TabInfo::default(),
     ];
@@ -1387,6 +1718,12 @@ fn serialize_session_update_event_with_non_default_values() {
             terminal_command: Some("foo".to_owned()),
             plugin_url: None,
             is_selectable: true,
+            cwd: Some(PathBuf::from("/path/to/cwd")),
+            pid: Some(123),
+            foreground_process: Some(vec!["vim".to_owned(), "Cargo.toml".to_owned()]),
+            bell_alert: true,
+            activity_alert: false,
+            silence_alert: false,
         },
         PaneInfo {
             id: 1,
@@ -1411,6 +1748,12 @@ fn serialize_session_update_event_with_non_default_values() {
             terminal_command: None,
             plugin_url: Some("i_am_a_fake_plugin".to_owned()),
             is_selectable: true,
+            cwd: None,
+            pid: None,
+            foreground_process: None,
+            bell_alert: false,
+            activity_alert: true,
+            silence_alert: true,
         },
     ];
     panes.insert(0, panes_list);
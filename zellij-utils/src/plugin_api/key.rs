@@ -2,13 +2,25 @@ pub use super::generated_api::api::key::{
     key::{KeyModifier, MainKey, NamedKey},
     Key as ProtobufKey,
 };
-use crate::data::{CharOrArrow, Direction, Key};
+use crate::data::{BareKey, CharOrArrow, Direction, Key, KeyModifiers, KeyWithModifier};
 
 use std::convert::TryFrom;
 
 impl TryFrom<ProtobufKey> for Key {
     type Error = &'static str;
     fn try_from(protobuf_key: ProtobufKey) -> Result<Self, &'static str> {
+        if let Some(key_modifiers) = protobuf_key.key_modifiers {
+            let bare_key = match protobuf_key.main_key.ok_or("invalid key")? {
+                MainKey::Char(codepoint) => {
+                    BareKey::Char(char::from_u32(codepoint as u32).ok_or("invalid key")?)
+                },
+                MainKey::Key(key_index) => {
+                    named_key_to_bare_key(NamedKey::from_i32(key_index).ok_or("invalid key")?)
+                },
+            };
+            let key_modifiers = KeyModifiers::from_bits_truncate(key_modifiers as u8);
+            return Ok(Key::from(KeyWithModifier::new(bare_key, key_modifiers)));
+        }
         let key_modifier = parse_optional_modifier(&protobuf_key);
         match key_modifier {
             Some(KeyModifier::Ctrl) => {
@@ -39,46 +51,57 @@ impl TryFrom<Key> for ProtobufKey {
         match key {
             Key::PageDown => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::PageDown as i32)),
             }),
             Key::PageUp => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::PageUp as i32)),
             }),
             Key::Left => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::LeftArrow as i32)),
             }),
             Key::Down => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::DownArrow as i32)),
             }),
             Key::Up => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::UpArrow as i32)),
             }),
             Key::Right => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::RightArrow as i32)),
             }),
             Key::Home => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::Home as i32)),
             }),
             Key::End => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::End as i32)),
             }),
             Key::Backspace => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::Backspace as i32)),
             }),
             Key::Delete => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::Delete as i32)),
             }),
             Key::Insert => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::Insert as i32)),
             }),
             Key::F(index) => {
@@ -99,11 +122,13 @@ impl TryFrom<Key> for ProtobufKey {
                 };
                 Ok(ProtobufKey {
                     modifier: None,
+                    key_modifiers: None,
                     main_key,
                 })
             },
             Key::Char(character) => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Char((character as u8) as i32)),
             }),
             Key::Alt(char_or_arrow) => {
@@ -122,27 +147,43 @@ impl TryFrom<Key> for ProtobufKey {
                 };
                 Ok(ProtobufKey {
                     modifier: Some(KeyModifier::Alt as i32),
+                    key_modifiers: None,
                     main_key: Some(main_key),
                 })
             },
             Key::Ctrl(character) => Ok(ProtobufKey {
                 modifier: Some(KeyModifier::Ctrl as i32),
+                key_modifiers: None,
                 main_key: Some(MainKey::Char((character as u8) as i32)),
             }),
             Key::BackTab => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::Tab as i32)),
             }),
             Key::Null => {
                 Ok(ProtobufKey {
                     modifier: None,
+                    key_modifiers: None,
                     main_key: None, // TODO: does this break deserialization?
                 })
             },
             Key::Esc => Ok(ProtobufKey {
                 modifier: None,
+                key_modifiers: None,
                 main_key: Some(MainKey::Key(NamedKey::Esc as i32)),
             }),
+            Key::WithModifiers(key_with_modifier) => {
+                let main_key = match key_with_modifier.bare_key {
+                    BareKey::Char(character) => MainKey::Char(character as i32),
+                    bare_key => MainKey::Key(bare_key_to_named_key(bare_key)? as i32),
+                };
+                Ok(ProtobufKey {
+                    modifier: None,
+                    main_key: Some(main_key),
+                    key_modifiers: Some(key_with_modifier.key_modifiers.bits() as u32),
+                })
+            },
         }
     }
 }
@@ -218,5 +259,69 @@ fn named_key_to_key(named_key: NamedKey) -> Key {
         NamedKey::F12 => Key::F(12),
         NamedKey::Tab => Key::BackTab,
         NamedKey::Esc => Key::Esc,
+        NamedKey::Enter => Key::Char('\n'),
+    }
+}
+
+fn named_key_to_bare_key(named_key: NamedKey) -> BareKey {
+    match named_key {
+        NamedKey::PageDown => BareKey::PageDown,
+        NamedKey::PageUp => BareKey::PageUp,
+        NamedKey::LeftArrow => BareKey::Left,
+        NamedKey::DownArrow => BareKey::Down,
+        NamedKey::UpArrow => BareKey::Up,
+        NamedKey::RightArrow => BareKey::Right,
+        NamedKey::Home => BareKey::Home,
+        NamedKey::End => BareKey::End,
+        NamedKey::Backspace => BareKey::Backspace,
+        NamedKey::Delete => BareKey::Delete,
+        NamedKey::Insert => BareKey::Insert,
+        NamedKey::F1 => BareKey::F(1),
+        NamedKey::F2 => BareKey::F(2),
+        NamedKey::F3 => BareKey::F(3),
+        NamedKey::F4 => BareKey::F(4),
+        NamedKey::F5 => BareKey::F(5),
+        NamedKey::F6 => BareKey::F(6),
+        NamedKey::F7 => BareKey::F(7),
+        NamedKey::F8 => BareKey::F(8),
+        NamedKey::F9 => BareKey::F(9),
+        NamedKey::F10 => BareKey::F(10),
+        NamedKey::F11 => BareKey::F(11),
+        NamedKey::F12 => BareKey::F(12),
+        NamedKey::Tab => BareKey::Tab,
+        NamedKey::Esc => BareKey::Esc,
+        NamedKey::Enter => BareKey::Enter,
+    }
+}
+
+fn bare_key_to_named_key(bare_key: BareKey) -> Result<NamedKey, &'static str> {
+    match bare_key {
+        BareKey::PageDown => Ok(NamedKey::PageDown),
+        BareKey::PageUp => Ok(NamedKey::PageUp),
+        BareKey::Left => Ok(NamedKey::LeftArrow),
+        BareKey::Down => Ok(NamedKey::DownArrow),
+        BareKey::Up => Ok(NamedKey::UpArrow),
+        BareKey::Right => Ok(NamedKey::RightArrow),
+        BareKey::Home => Ok(NamedKey::Home),
+        BareKey::End => Ok(NamedKey::End),
+        BareKey::Backspace => Ok(NamedKey::Backspace),
+        BareKey::Delete => Ok(NamedKey::Delete),
+        BareKey::Insert => Ok(NamedKey::Insert),
+        BareKey::F(1) => Ok(NamedKey::F1),
+        BareKey::F(2) => Ok(NamedKey::F2),
+        BareKey::F(3) => Ok(NamedKey::F3),
+        BareKey::F(4) => Ok(NamedKey::F4),
+        BareKey::F(5) => Ok(NamedKey::F5),
+        BareKey::F(6) => Ok(NamedKey::F6),
+        BareKey::F(7) => Ok(NamedKey::F7),
+        BareKey::F(8) => Ok(NamedKey::F8),
+        BareKey::F(9) => Ok(NamedKey::F9),
+        BareKey::F(10) => Ok(NamedKey::F10),
+        BareKey::F(11) => Ok(NamedKey::F11),
+        BareKey::F(12) => Ok(NamedKey::F12),
+        BareKey::Tab => Ok(NamedKey::Tab),
+        BareKey::Esc => Ok(NamedKey::Esc),
+        BareKey::Enter => Ok(NamedKey::Enter),
+        BareKey::F(_) | BareKey::Char(_) => Err("Unsupported key"),
     }
 }
@@ -6,22 +6,22 @@ pub use super::generated_api::api::{
         plugin_command::Payload, CliPipeOutputPayload, CommandName, ContextItem, EnvVariable,
         ExecCmdPayload, FixedOrPercent as ProtobufFixedOrPercent,
         FixedOrPercentValue as ProtobufFixedOrPercentValue,
-        FloatingPaneCoordinates as ProtobufFloatingPaneCoordinates, HttpVerb as ProtobufHttpVerb,
-        IdAndNewName, KillSessionsPayload, MessageToPluginPayload, MovePayload,
-        NewPluginArgs as ProtobufNewPluginArgs, OpenCommandPanePayload, OpenFilePayload,
-        PaneId as ProtobufPaneId, PaneType as ProtobufPaneType,
+        FloatingPaneCoordinates as ProtobufFloatingPaneCoordinates, GetPaneContentsPayload,
+        HttpVerb as ProtobufHttpVerb, IdAndNewName, KillSessionsPayload, MessageToPluginPayload,
+        MovePayload, NewPluginArgs as ProtobufNewPluginArgs, OpenCommandPanePayload,
+        OpenFilePayload, PaneId as ProtobufPaneId, PaneType as ProtobufPaneType,
         PluginCommand as ProtobufPluginCommand, PluginMessagePayload,
-        RequestPluginPermissionPayload, ResizePayload, RunCommandPayload, SetTimeoutPayload,
-        SubscribePayload, SwitchSessionPayload, SwitchTabToPayload, UnsubscribePayload,
-        WebRequestPayload,
+        RequestPluginPermissionPayload, ResizePayload, RunCommandPayload, SetThemePayload,
+        SetTimeoutPayload, SubscribePayload, SwitchSessionPayload, SwitchTabToPayload,
+        UnsubscribePayload, WebRequestPayload,
     },
     plugin_permission::PermissionType as ProtobufPermissionType,
     resize::ResizeAction as ProtobufResizeAction,
 };
 
 use crate::data::{
-    ConnectToSession, FloatingPaneCoordinates, HttpVerb, MessageToPlugin, NewPluginArgs, PaneId,
-    PermissionType, PluginCommand,
+    ClientId, ConnectToSession, FloatingPaneCoordinates, HttpVerb, MessageToPlugin, NewPluginArgs,
+    PaneId, PermissionType, PluginCommand,
 };
 use crate::input::layout::SplitSize;
 
@@ -276,10 +276,16 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
             },
             Some(CommandName::OpenCommandPane) => match protobuf_plugin_command.payload {
                 Some(Payload::OpenCommandPanePayload(command_to_run_payload)) => {
+                    let context: BTreeMap<String, String> = command_to_run_payload
+                        .context
+                        .into_iter()
+                        .map(|e| (e.name, e.value))
+                        .collect();
                     match command_to_run_payload.command_to_run {
-                        Some(command_to_run) => {
-                            Ok(PluginCommand::OpenCommandPane(command_to_run.try_into()?))
-                        },
+                        Some(command_to_run) => Ok(PluginCommand::OpenCommandPane(
+                            command_to_run.try_into()?,
+                            context,
+                        )),
                         None => Err("Malformed open open command pane payload"),
                     }
                 },
@@ -290,10 +296,16 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
                     let floating_pane_coordinates = command_to_run_payload
                         .floating_pane_coordinates
                         .map(|f| f.into());
+                    let context: BTreeMap<String, String> = command_to_run_payload
+                        .context
+                        .into_iter()
+                        .map(|e| (e.name, e.value))
+                        .collect();
                     match command_to_run_payload.command_to_run {
                         Some(command_to_run) => Ok(PluginCommand::OpenCommandPaneFloating(
                             command_to_run.try_into()?,
                             floating_pane_coordinates,
+                            context,
                         )),
                         None => Err("Malformed open command pane floating payload"),
                     }
@@ -717,9 +729,15 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
             },
             Some(CommandName::OpenCommandInPlace) => match protobuf_plugin_command.payload {
                 Some(Payload::OpenCommandPaneInPlacePayload(command_to_run_payload)) => {
+                    let context: BTreeMap<String, String> = command_to_run_payload
+                        .context
+                        .into_iter()
+                        .map(|e| (e.name, e.value))
+                        .collect();
                     match command_to_run_payload.command_to_run {
                         Some(command_to_run) => Ok(PluginCommand::OpenCommandPaneInPlace(
                             command_to_run.try_into()?,
+                            context,
                         )),
                         None => Err("Malformed open command pane in-place payload"),
                     }
@@ -854,6 +872,40 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
                 },
                 _ => Err("Mismatched payload for PipeOutput"),
             },
+            Some(CommandName::GetPaneContents) => match protobuf_plugin_command.payload {
+                Some(Payload::GetPaneContentsPayload(GetPaneContentsPayload {
+                    pane_id: Some(pane_id),
+                    include_scrollback,
+                    with_ansi,
+                })) => Ok(PluginCommand::GetPaneContents {
+                    pane_id: pane_id.try_into()?,
+                    include_scrollback,
+                    with_ansi,
+                }),
+                _ => Err("Mismatched payload for GetPaneContents"),
+            },
+            Some(CommandName::WatchPane) => match protobuf_plugin_command.payload {
+                Some(Payload::WatchPanePayload(pane_id)) => {
+                    Ok(PluginCommand::WatchPane(pane_id.try_into()?))
+                },
+                _ => Err("Mismatched payload for WatchPane"),
+            },
+            Some(CommandName::SetTheme) => match protobuf_plugin_command.payload {
+                Some(Payload::SetThemePayload(SetThemePayload {
+                    theme_name,
+                    client_id,
+                })) => Ok(PluginCommand::SetTheme(
+                    theme_name,
+                    client_id.map(|c| c as ClientId),
+                )),
+                _ => Err("Mismatched payload for SetTheme"),
+            },
+            Some(CommandName::UnwatchPane) => match protobuf_plugin_command.payload {
+                Some(Payload::UnwatchPanePayload(pane_id)) => {
+                    Ok(PluginCommand::UnwatchPane(pane_id.try_into()?))
+                },
+                _ => Err("Mismatched payload for UnwatchPane"),
+            },
             None => Err("Unrecognized plugin command"),
         }
     }
@@ -925,20 +977,36 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                     })),
                 })
             },
-            PluginCommand::OpenCommandPane(command_to_run) => Ok(ProtobufPluginCommand {
-                name: CommandName::OpenCommandPane as i32,
-                payload: Some(Payload::OpenCommandPanePayload(OpenCommandPanePayload {
-                    command_to_run: Some(command_to_run.try_into()?),
-                    floating_pane_coordinates: None,
-                })),
-            }),
-            PluginCommand::OpenCommandPaneFloating(command_to_run, floating_pane_coordinates) => {
+            PluginCommand::OpenCommandPane(command_to_run, context) => {
+                let context: Vec<_> = context
+                    .into_iter()
+                    .map(|(name, value)| ContextItem { name, value })
+                    .collect();
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::OpenCommandPane as i32,
+                    payload: Some(Payload::OpenCommandPanePayload(OpenCommandPanePayload {
+                        command_to_run: Some(command_to_run.try_into()?),
+                        floating_pane_coordinates: None,
+                        context,
+                    })),
+                })
+            },
+            PluginCommand::OpenCommandPaneFloating(
+                command_to_run,
+                floating_pane_coordinates,
+                context,
+            ) => {
+                let context: Vec<_> = context
+                    .into_iter()
+                    .map(|(name, value)| ContextItem { name, value })
+                    .collect();
                 Ok(ProtobufPluginCommand {
                     name: CommandName::OpenCommandPaneFloating as i32,
                     payload: Some(Payload::OpenCommandPaneFloatingPayload(
                         OpenCommandPanePayload {
                             command_to_run: Some(command_to_run.try_into()?),
                             floating_pane_coordinates: floating_pane_coordinates.map(|f| f.into()),
+                            context,
                         },
                     )),
                 })
@@ -1236,15 +1304,22 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                     floating_pane_coordinates: None,
                 })),
             }),
-            PluginCommand::OpenCommandPaneInPlace(command_to_run) => Ok(ProtobufPluginCommand {
-                name: CommandName::OpenCommandInPlace as i32,
-                payload: Some(Payload::OpenCommandPaneInPlacePayload(
-                    OpenCommandPanePayload {
-                        command_to_run: Some(command_to_run.try_into()?),
-                        floating_pane_coordinates: None,
-                    },
-                )),
-            }),
+            PluginCommand::OpenCommandPaneInPlace(command_to_run, context) => {
+                let context: Vec<_> = context
+                    .into_iter()
+                    .map(|(name, value)| ContextItem { name, value })
+                    .collect();
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::OpenCommandInPlace as i32,
+                    payload: Some(Payload::OpenCommandPaneInPlacePayload(
+                        OpenCommandPanePayload {
+                            command_to_run: Some(command_to_run.try_into()?),
+                            floating_pane_coordinates: None,
+                            context,
+                        },
+                    )),
+                })
+            },
             PluginCommand::RunCommand(command_line, env_variables, cwd, context) => {
                 let env_variables: Vec<_> = env_variables
                     .into_iter()
@@ -1356,6 +1431,33 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                     session_names,
                 })),
             }),
+            PluginCommand::GetPaneContents {
+                pane_id,
+                include_scrollback,
+                with_ansi,
+            } => Ok(ProtobufPluginCommand {
+                name: CommandName::GetPaneContents as i32,
+                payload: Some(Payload::GetPaneContentsPayload(GetPaneContentsPayload {
+                    pane_id: Some(pane_id.try_into()?),
+                    include_scrollback,
+                    with_ansi,
+                })),
+            }),
+            PluginCommand::WatchPane(pane_id) => Ok(ProtobufPluginCommand {
+                name: CommandName::WatchPane as i32,
+                payload: Some(Payload::WatchPanePayload(pane_id.try_into()?)),
+            }),
+            PluginCommand::SetTheme(theme_name, client_id) => Ok(ProtobufPluginCommand {
+                name: CommandName::SetTheme as i32,
+                payload: Some(Payload::SetThemePayload(SetThemePayload {
+                    theme_name,
+                    client_id: client_id.map(|c| c as u32),
+                })),
+            }),
+            PluginCommand::UnwatchPane(pane_id) => Ok(ProtobufPluginCommand {
+                name: CommandName::UnwatchPane as i32,
+                payload: Some(Payload::UnwatchPanePayload(pane_id.try_into()?)),
+            }),
         }
     }
 }
@@ -24,6 +24,7 @@ impl TryFrom<ProtobufPermissionType> for PermissionType {
             ProtobufPermissionType::MessageAndLaunchOtherPlugins => {
                 Ok(PermissionType::MessageAndLaunchOtherPlugins)
             },
+            ProtobufPermissionType::ReadPaneContents => Ok(PermissionType::ReadPaneContents),
         }
     }
 }
@@ -49,6 +50,7 @@ impl TryFrom<PermissionType> for ProtobufPermissionType {
             PermissionType::MessageAndLaunchOtherPlugins => {
                 Ok(ProtobufPermissionType::MessageAndLaunchOtherPlugins)
             },
+            PermissionType::ReadPaneContents => Ok(ProtobufPermissionType::ReadPaneContents),
         }
     }
 }
@@ -13,7 +13,7 @@ use std::path::PathBuf;
 use crate::{
     input::layout::PluginUserConfiguration,
     input::layout::{
-        FloatingPaneLayout, Layout, PercentOrFixed, Run, SplitDirection, SplitSize,
+        FloatingPaneLayout, Layout, PaneMonitors, PercentOrFixed, Run, SplitDirection, SplitSize,
         SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
     },
     pane_size::{Constraint, PaneGeom},
@@ -49,6 +49,7 @@ pub struct TabLayoutManifest {
     pub floating_panes: Vec<PaneLayoutManifest>,
     pub is_focused: bool,
     pub hide_floating_panes: bool,
+    pub monitors: PaneMonitors,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -59,6 +60,7 @@ pub struct PaneLayoutManifest {
     pub is_borderless: bool,
     pub title: Option<String>,
     pub is_focused: bool,
+    pub monitors: PaneMonitors,
     pub pane_contents: Option<String>,
 }
 
@@ -99,6 +101,7 @@ fn stringify_tab(
     tab_name: String,
     is_focused: bool,
     hide_floating_panes: bool,
+    monitors: &PaneMonitors,
     tiled_panes: &Vec<PaneLayoutManifest>,
     floating_panes: &Vec<PaneLayoutManifest>,
     pane_contents: &mut BTreeMap<String, String>,
@@ -122,6 +125,9 @@ fn stringify_tab(
             if hide_floating_panes {
                 tab_attributes.push(format!("hide_floating_panes=true"));
             }
+            for (monitor, value) in stringify_monitors(monitors) {
+                tab_attributes.push(format!("{}={}", monitor, value));
+            }
             kdl_string.push_str(&kdl_string_from_tab(
                 &tiled_panes,
                 &floating_panes_layout,
@@ -360,6 +366,9 @@ fn stringify_tiled_layout_attributes(
         };
         kdl_string.push_str(&format!(" split_direction=\"{direction}\""));
     }
+    for (monitor, value) in stringify_monitors(&layout.monitors) {
+        kdl_string.push_str(&format!(" {}={}", monitor, value));
+    }
 }
 
 fn stringify_floating_layout_attributes(layout: &FloatingPaneLayout, kdl_string: &mut String) {
@@ -399,6 +408,23 @@ fn stringify_floating_layout_attributes(layout: &FloatingPaneLayout, kdl_string:
         },
         None => {},
     }
+    for (monitor, value) in stringify_monitors(&layout.monitors) {
+        kdl_string.push_str(&indent(&format!("{} {}\n", monitor, value), INDENT));
+    }
+}
+
+fn stringify_monitors(monitors: &PaneMonitors) -> Vec<(&'static str, String)> {
+    let mut stringified = vec![];
+    if let Some(bell) = monitors.bell {
+        stringified.push(("monitor_bell", bell.to_string()));
+    }
+    if let Some(activity) = monitors.activity {
+        stringified.push(("monitor_activity", activity.to_string()));
+    }
+    if let Some(silence) = monitors.silence {
+        stringified.push(("monitor_silence", silence.to_string()));
+    }
+    stringified
 }
 
 fn stringify_start_suspended(command: &Option<String>, kdl_string: &mut String) {
@@ -529,6 +555,7 @@ fn stringify_multiple_tabs(
             tab_name.clone(),
             tab_layout_manifest.is_focused,
             hide_floating_panes,
+            &tab_layout_manifest.monitors,
             &tiled_panes,
             &floating_panes,
             pane_contents,
@@ -597,6 +624,7 @@ fn tiled_pane_layout_from_manifest(
             )
         })
         .unwrap_or((None, false, false, None, None, None));
+    let monitors = manifest.map(|g| g.monitors).unwrap_or_default();
     TiledPaneLayout {
         split_size,
         run,
@@ -604,6 +632,7 @@ fn tiled_pane_layout_from_manifest(
         is_expanded_in_stack,
         name,
         focus,
+        monitors,
         pane_initial_contents,
         ..Default::default()
     }
@@ -693,6 +722,7 @@ fn get_floating_panes_layout_from_panegeoms(
                 run,
                 focus: Some(m.is_focused),
                 already_running: false,
+                monitors: m.monitors,
                 pane_initial_contents: m.pane_contents.clone(),
             }
         })
@@ -1123,6 +1153,80 @@ mod tests {
 }"#]]
         .assert_eq(&kdl.0);
     }
+    #[test]
+    fn monitors() {
+        let mut tiled_panes: Vec<PaneLayoutManifest> = PANEGEOMS_JSON[0]
+            .iter()
+            .map(|pg| parse_panegeom_from_json(pg))
+            .map(|geom| PaneLayoutManifest {
+                geom,
+                ..Default::default()
+            })
+            .collect();
+        tiled_panes[0].monitors = PaneMonitors {
+            bell: Some(false),
+            activity: None,
+            silence: Some(30),
+        };
+        let floating_pane = PaneLayoutManifest {
+            geom: parse_panegeom_from_json(PANEGEOMS_JSON[1][1]),
+            monitors: PaneMonitors {
+                bell: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes,
+            floating_panes: vec![floating_pane],
+            monitors: PaneMonitors {
+                activity: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"layout {
+    tab name="Tab #1" monitor_activity=true {
+        pane size=1
+        pane monitor_bell=false monitor_silence=30
+        pane size=2
+        floating_panes {
+            pane {
+                height 20
+                width 50
+                x 0
+                y 26
+                monitor_bell true
+            }
+        }
+    }
+}"#]]
+        .assert_eq(&kdl.0);
+
+        let layout = Layout::from_kdl(&kdl.0, "layout_file_name".into(), None, None).unwrap();
+        let (_tab_name, tiled_panes, floating_panes) = layout.tabs().remove(0);
+        assert_eq!(
+            tiled_panes.children[1].monitors,
+            PaneMonitors {
+                bell: Some(false),
+                activity: Some(true),
+                silence: Some(30),
+            }
+        );
+        assert_eq!(
+            floating_panes[0].monitors,
+            PaneMonitors {
+                bell: Some(true),
+                activity: Some(true),
+                silence: None,
+            }
+        );
+    }
     // utility functions
     fn parse_panegeom_from_json(data_str: &str) -> PaneGeom {
         //
@@ -342,6 +342,28 @@ pub struct Setup {
     pub generate_auto_start: Option<String>,
 }
 
+fn apply_themes_to_config(
+    config: &mut Config,
+    cli_config_options: Option<Options>,
+    cli_args: &CliArgs,
+) -> Result<Options, ConfigError> {
+    let config_options = match cli_config_options {
+        Some(cli_config_options) => config.options.merge(cli_config_options),
+        None => config.options.clone(),
+    };
+
+    config.themes = config.themes.merge(get_default_themes());
+
+    let user_theme_dir = config_options.theme_dir.clone().or_else(|| {
+        get_theme_dir(cli_args.config_dir.clone().or_else(find_default_config_dir))
+            .filter(|dir| dir.exists())
+    });
+    if let Some(user_theme_dir) = user_theme_dir {
+        config.themes = config.themes.merge(Themes::from_dir(user_theme_dir)?);
+    }
+    Ok(config_options)
+}
+
 impl Setup {
     /// Entrypoint from main
     /// Merges options from the config file and the command line options
@@ -357,12 +379,7 @@ impl Setup {
         // note that this can potentially exit the process
         Setup::handle_setup_commands(cli_args);
         let config = Config::try_from(cli_args)?;
-        let cli_config_options: Option<Options> =
-            if let Some(Command::Options(options)) = cli_args.command.clone() {
-                Some(options.into())
-            } else {
-                None
-            };
+        let cli_config_options = Setup::cli_config_options(cli_args);
         let mut config_without_layout = config.clone();
         let (layout, mut config) =
             Setup::parse_layout_and_override_config(cli_config_options.as_ref(), config, cli_args)?;
@@ -371,27 +388,6 @@ impl Setup {
             apply_themes_to_config(&mut config, cli_config_options.clone(), cli_args)?;
         let config_options_without_layout =
             apply_themes_to_config(&mut config_without_layout, cli_config_options, cli_args)?;
-        fn apply_themes_to_config(
-            config: &mut Config,
-            cli_config_options: Option<Options>,
-            cli_args: &CliArgs,
-        ) -> Result<Options, ConfigError> {
-            let config_options = match cli_config_options {
-                Some(cli_config_options) => config.options.merge(cli_config_options),
-                None => config.options.clone(),
-            };
-
-            config.themes = config.themes.merge(get_default_themes());
-
-            let user_theme_dir = config_options.theme_dir.clone().or_else(|| {
-                get_theme_dir(cli_args.config_dir.clone().or_else(find_default_config_dir))
-                    .filter(|dir| dir.exists())
-            });
-            if let Some(user_theme_dir) = user_theme_dir {
-                config.themes = config.themes.merge(Themes::from_dir(user_theme_dir)?);
-            }
-            Ok(config_options)
-        }
 
         if let Some(Command::Setup(ref setup)) = &cli_args.command {
             setup
@@ -413,6 +409,31 @@ impl Setup {
         ))
     }
 
+    /// Re-reads the config of a running session the way [`Setup::from_cli_args`] read it when the
+    /// session started, without the overrides of its layout other than the themes it defines
+    pub fn reload_config(cli_args: &CliArgs) -> Result<(Config, Options), ConfigError> {
+        let mut config = Config::try_from(cli_args)?;
+        let cli_config_options = Setup::cli_config_options(cli_args);
+        match Setup::parse_layout_and_override_config(
+            cli_config_options.as_ref(),
+            config.clone(),
+            cli_args,
+        ) {
+            Ok((_layout, config_with_layout)) => config.themes = config_with_layout.themes,
+            Err(e) => log::error!("Failed to read the themes of the layout: {}", e),
+        }
+        let config_options = apply_themes_to_config(&mut config, cli_config_options, cli_args)?;
+        Ok((config, config_options))
+    }
+
+    fn cli_config_options(cli_args: &CliArgs) -> Option<Options> {
+        if let Some(Command::Options(options)) = cli_args.command.clone() {
+            Some(options.into())
+        } else {
+            None
+        }
+    }
+
     /// General setup helpers
     pub fn from_cli(&self) -> Result<()> {
         if self.clean {